@@ -0,0 +1,260 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::{vietnam_offset, Exchange, PriceDepth, TickData, TradeSide};
+
+/// Periodic call auction sessions on HOSE/HNX. UPCOM trades continuously only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AuctionSession {
+    /// Opening auction, 09:00-09:15 (HOSE only).
+    Ato,
+    /// Closing auction, 14:30-14:45.
+    Atc,
+}
+
+impl AuctionSession {
+    /// Parses the price board's session code.
+    pub fn from_code(code: &str) -> Option<AuctionSession> {
+        match code.trim().to_uppercase().as_str() {
+            "ATO" => Some(AuctionSession::Ato),
+            "ATC" => Some(AuctionSession::Atc),
+            _ => None,
+        }
+    }
+
+    /// Auction window `[start, match_time]` in exchange time.
+    pub fn window(&self) -> (NaiveTime, NaiveTime) {
+        match self {
+            AuctionSession::Ato => (NaiveTime::from_hms_opt(9, 0, 0).unwrap(), NaiveTime::from_hms_opt(9, 15, 0).unwrap()),
+            AuctionSession::Atc => (NaiveTime::from_hms_opt(14, 30, 0).unwrap(), NaiveTime::from_hms_opt(14, 45, 0).unwrap()),
+        }
+    }
+
+    /// Whether `exchange` runs this auction.
+    pub fn applies_to(&self, exchange: Exchange) -> bool {
+        matches!(
+            (self, exchange),
+            (AuctionSession::Ato, Exchange::Hose) | (AuctionSession::Atc, Exchange::Hose | Exchange::Hnx)
+        )
+    }
+}
+
+/// The single print an auction produced: one price, the total matched volume.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuctionPrint {
+    pub session: AuctionSession,
+    pub time: DateTime<Utc>,
+    pub price: f64,
+    pub volume: u64,
+}
+
+/// Auction state for one symbol on one trading day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuctionData {
+    pub symbol: String,
+    pub date: NaiveDate,
+    /// Session the board reports right now, if an auction is running.
+    pub current_session: Option<AuctionSession>,
+    /// Indicative matching price published during a running auction.
+    pub indicative_price: Option<f64>,
+    pub indicative_volume: Option<u64>,
+    pub ato: Option<AuctionPrint>,
+    pub atc: Option<AuctionPrint>,
+}
+
+/// Finds the print `session` produced among one day's matched trades.
+///
+/// The auction matches at a single price at the end of its window, so the
+/// result is every trade stamped at the match time: their volumes summed,
+/// price taken from the first. Provider feeds often stamp these prints a few
+/// seconds late, hence the 59-second tolerance.
+pub fn find_auction_print(ticks: &[TickData], session: AuctionSession) -> Option<AuctionPrint> {
+    let (_, match_time) = session.window();
+    let tolerance = chrono::Duration::seconds(59);
+
+    let mut prints = ticks.iter().filter(|tick| {
+        let local = tick.time.with_timezone(&vietnam_offset()).time();
+        local >= match_time && local <= match_time + tolerance
+    });
+
+    let first = prints.next()?;
+    let volume = first.volume + prints.filter(|tick| tick.price == first.price).map(|tick| tick.volume).sum::<u64>();
+
+    Some(AuctionPrint {
+        session,
+        time: first.time,
+        price: first.price,
+        volume,
+    })
+}
+
+/// One price-board sample for a symbol. While an auction runs, `price` and
+/// `volume` are the indicative match; otherwise the last continuous match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuctionSnapshot {
+    pub symbol: String,
+    pub time: DateTime<Utc>,
+    pub session: Option<AuctionSession>,
+    pub price: Option<f64>,
+    pub volume: Option<u64>,
+    /// Previous session's close.
+    pub reference_price: Option<f64>,
+    pub depth: Option<PriceDepth>,
+}
+
+/// Order imbalance of a running auction at one sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuctionImbalance {
+    pub symbol: String,
+    pub time: DateTime<Utc>,
+    pub session: AuctionSession,
+    pub indicative_price: f64,
+    pub indicative_volume: Option<u64>,
+    /// Last continuous price before the auction, or the reference price if
+    /// none was observed.
+    pub pre_auction_price: Option<f64>,
+    /// Indicative price over `pre_auction_price`, minus one.
+    pub drift: Option<f64>,
+    /// Indicative price change since the previous sample of this auction.
+    pub price_change: Option<f64>,
+    /// Displayed buy interest the indicative price would leave unmatched,
+    /// minus the sell interest; see [`unmatched_volume`].
+    pub unmatched_volume: Option<i64>,
+}
+
+impl AuctionImbalance {
+    /// Side with excess interest, which tends to push the match further.
+    pub fn side(&self) -> TradeSide {
+        match self.unmatched_volume {
+            Some(volume) if volume > 0 => TradeSide::Buy,
+            Some(volume) if volume < 0 => TradeSide::Sell,
+            _ => TradeSide::Unknown,
+        }
+    }
+}
+
+/// Bid volume priced at or above `price` minus ask volume at or below it,
+/// over the displayed levels. During an auction the board shows orders left
+/// after the indicative match, so a positive value is unfilled demand.
+pub fn unmatched_volume(depth: &PriceDepth, price: f64) -> i64 {
+    let bids: u64 = depth.bids.iter().filter(|level| level.price >= price).map(|level| level.volume).sum();
+    let asks: u64 = depth.asks.iter().filter(|level| level.price <= price).map(|level| level.volume).sum();
+    bids as i64 - asks as i64
+}
+
+/// Turns successive snapshots into imbalance readings, remembering each
+/// symbol's last continuous price and last indicative price. Feed it samples
+/// from before the auction too, so drift is measured from the pre-auction
+/// price rather than yesterday's close.
+#[derive(Debug, Clone, Default)]
+pub struct ImbalanceTracker {
+    last_continuous: HashMap<String, f64>,
+    last_indicative: HashMap<(String, AuctionSession), f64>,
+}
+
+impl ImbalanceTracker {
+    pub fn new() -> Self {
+        ImbalanceTracker::default()
+    }
+
+    /// Reading for `snapshot`, or `None` outside an auction or before an
+    /// indicative price is published.
+    pub fn update(&mut self, snapshot: &AuctionSnapshot) -> Option<AuctionImbalance> {
+        let Some(session) = snapshot.session else {
+            if let Some(price) = snapshot.price {
+                self.last_continuous.insert(snapshot.symbol.clone(), price);
+            }
+            return None;
+        };
+        let indicative_price = snapshot.price.filter(|price| *price > 0.0)?;
+        let pre_auction_price = self.last_continuous.get(&snapshot.symbol).copied().or(snapshot.reference_price);
+        let previous = self.last_indicative.insert((snapshot.symbol.clone(), session), indicative_price);
+        Some(AuctionImbalance {
+            symbol: snapshot.symbol.clone(),
+            time: snapshot.time,
+            session,
+            indicative_price,
+            indicative_volume: snapshot.volume,
+            pre_auction_price,
+            drift: pre_auction_price.filter(|price| *price > 0.0).map(|price| indicative_price / price - 1.0),
+            price_change: previous.map(|previous| indicative_price - previous),
+            unmatched_volume: snapshot.depth.as_ref().map(|depth| unmatched_volume(depth, indicative_price)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn tick(hour: u32, minute: u32, second: u32, price: f64, volume: u64) -> TickData {
+        // Exchange time is UTC+7
+        TickData {
+            time: Utc.with_ymd_and_hms(2024, 6, 3, hour - 7, minute, second).unwrap(),
+            price,
+            volume,
+            side: TradeSide::Unknown,
+            id: None,
+        }
+    }
+
+    #[test]
+    fn test_find_auction_prints() {
+        let ticks = vec![
+            tick(9, 15, 2, 25_000.0, 3_000),
+            tick(9, 15, 2, 25_000.0, 2_000),
+            tick(9, 16, 10, 25_050.0, 100),
+            tick(14, 29, 50, 25_300.0, 500),
+            tick(14, 45, 5, 25_400.0, 90_000),
+        ];
+
+        let ato = find_auction_print(&ticks, AuctionSession::Ato).unwrap();
+        assert_eq!(ato.price, 25_000.0);
+        assert_eq!(ato.volume, 5_000);
+
+        let atc = find_auction_print(&ticks, AuctionSession::Atc).unwrap();
+        assert_eq!(atc.volume, 90_000);
+        assert!(find_auction_print(&ticks[2..4], AuctionSession::Atc).is_none());
+    }
+
+    #[test]
+    fn test_imbalance_tracker() {
+        use crate::models::DepthLevel;
+        let level = |price: f64, volume: u64| DepthLevel { price, volume, orders: None };
+        let snapshot = |minute: u32, session: Option<AuctionSession>, price: f64, depth: Option<PriceDepth>| AuctionSnapshot {
+            symbol: "FPT".to_string(),
+            time: Utc.with_ymd_and_hms(2024, 6, 3, 7, minute, 0).unwrap(),
+            session,
+            price: Some(price),
+            volume: Some(10_000),
+            reference_price: Some(99_000.0),
+            depth,
+        };
+        let depth = PriceDepth {
+            symbol: "FPT".to_string(),
+            time: Utc::now(),
+            bids: vec![level(101_000.0, 5_000), level(100_500.0, 2_000)],
+            asks: vec![level(101_000.0, 1_000), level(101_500.0, 4_000)],
+        };
+
+        let mut tracker = ImbalanceTracker::new();
+        assert!(tracker.update(&snapshot(29, None, 100_000.0, None)).is_none());
+        let first = tracker.update(&snapshot(31, Some(AuctionSession::Atc), 101_000.0, Some(depth))).unwrap();
+        assert_eq!((first.pre_auction_price, first.price_change, first.unmatched_volume), (Some(100_000.0), None, Some(4_000)));
+        assert!((first.drift.unwrap() - 0.01).abs() < 1e-12);
+        assert_eq!(first.side(), TradeSide::Buy);
+
+        let second = tracker.update(&snapshot(32, Some(AuctionSession::Atc), 101_500.0, None)).unwrap();
+        assert_eq!((second.price_change, second.side()), (Some(500.0), TradeSide::Unknown));
+    }
+
+    #[test]
+    fn test_session_rules() {
+        assert_eq!(AuctionSession::from_code("atc"), Some(AuctionSession::Atc));
+        assert_eq!(AuctionSession::from_code("LO"), None);
+        assert!(!AuctionSession::Ato.applies_to(Exchange::Hnx));
+        assert!(!AuctionSession::Atc.applies_to(Exchange::Upcom));
+    }
+}
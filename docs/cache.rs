@@ -0,0 +1,209 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::models::Language;
+
+#[derive(Debug)]
+pub enum CacheError {
+    Io(std::io::Error),
+    Serialization(serde_json::Error),
+}
+
+impl From<std::io::Error> for CacheError {
+    fn from(error: std::io::Error) -> Self {
+        CacheError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for CacheError {
+    fn from(error: serde_json::Error) -> Self {
+        CacheError::Serialization(error)
+    }
+}
+
+/// Kinds of cached responses, each with its own TTL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CacheKind {
+    DailyHistory,
+    IntradayHistory,
+    CompanyInfo,
+    News,
+}
+
+impl CacheKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheKind::DailyHistory => "daily_history",
+            CacheKind::IntradayHistory => "intraday_history",
+            CacheKind::CompanyInfo => "company_info",
+            CacheKind::News => "news",
+        }
+    }
+
+    /// Daily/weekly/monthly candles vs. everything finer.
+    pub fn for_interval(interval: &str) -> Self {
+        match interval {
+            "1D" | "1W" | "1M" => CacheKind::DailyHistory,
+            _ => CacheKind::IntradayHistory,
+        }
+    }
+
+    fn default_ttl(&self) -> Duration {
+        match self {
+            CacheKind::DailyHistory => Duration::hours(6),
+            CacheKind::IntradayHistory => Duration::minutes(1),
+            CacheKind::CompanyInfo => Duration::days(1),
+            CacheKind::News => Duration::minutes(15),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Entry<T> {
+    stored_at: DateTime<Utc>,
+    value: T,
+}
+
+/// JSON-file response cache laid out as `<root>/<kind>/<key>.json`. A zero
+/// TTL disables caching for that kind.
+pub struct DiskCache {
+    root: PathBuf,
+    ttls: HashMap<CacheKind, Duration>,
+}
+
+impl DiskCache {
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, CacheError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        Ok(DiskCache { root, ttls: HashMap::new() })
+    }
+
+    pub fn with_ttl(mut self, kind: CacheKind, ttl: Duration) -> Self {
+        self.ttls.insert(kind, ttl);
+        self
+    }
+
+    pub fn ttl(&self, kind: CacheKind) -> Duration {
+        self.ttls.get(&kind).copied().unwrap_or_else(|| kind.default_ttl())
+    }
+
+    fn path_for(&self, kind: CacheKind, key: &str) -> PathBuf {
+        let file: String = key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        self.root.join(kind.as_str()).join(format!("{}.json", file))
+    }
+
+    /// The cached value for `key` if it is younger than the kind's TTL.
+    /// Unreadable entries count as misses.
+    pub fn get<T: DeserializeOwned>(&self, kind: CacheKind, key: &str) -> Option<T> {
+        let ttl = self.ttl(kind);
+        if ttl <= Duration::zero() {
+            return None;
+        }
+        let text = fs::read_to_string(self.path_for(kind, key)).ok()?;
+        let entry: Entry<T> = serde_json::from_str(&text).ok()?;
+        (Utc::now() - entry.stored_at < ttl).then_some(entry.value)
+    }
+
+    pub fn put<T: Serialize>(&self, kind: CacheKind, key: &str, value: &T) -> Result<(), CacheError> {
+        if self.ttl(kind) <= Duration::zero() {
+            return Ok(());
+        }
+        let path = self.path_for(kind, key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(&Entry { stored_at: Utc::now(), value })?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Every readable entry of `kind` keyed by file stem, regardless of age.
+    pub fn entries<T: DeserializeOwned>(&self, kind: CacheKind) -> Vec<(String, T)> {
+        let Ok(dir) = fs::read_dir(self.root.join(kind.as_str())) else {
+            return Vec::new();
+        };
+        let mut entries: Vec<(String, T)> = dir
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                let entry: Entry<T> = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+                Some((path.file_stem()?.to_str()?.to_string(), entry.value))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+
+    pub fn invalidate(&self, kind: CacheKind, key: &str) -> Result<(), CacheError> {
+        match fs::remove_file(self.path_for(kind, key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes every entry, or only those of `kind`.
+    pub fn clear(&self, kind: Option<CacheKind>) -> Result<(), CacheError> {
+        let kinds = match kind {
+            Some(kind) => vec![kind],
+            None => vec![CacheKind::DailyHistory, CacheKind::IntradayHistory, CacheKind::CompanyInfo, CacheKind::News],
+        };
+        for kind in kinds {
+            match fs::remove_dir_all(self.root.join(kind.as_str())) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cache key for a history request.
+pub fn history_key(provider: &str, symbol: &str, start: &str, end: Option<&str>, interval: &str) -> String {
+    format!("{}_{}_{}_{}_{}", provider, symbol.to_uppercase(), interval, start, end.unwrap_or("latest"))
+}
+
+/// Cache key for company info in `language`.
+pub fn company_key(provider: &str, symbol: &str, language: Language) -> String {
+    format!("{}_{}_{}", provider, symbol.to_uppercase(), language.code())
+}
+
+/// Cache key for the first news page of `symbol`.
+pub fn news_key(provider: &str, symbol: &str, page_size: u32) -> String {
+    format!("{}_{}_{}", provider, symbol.to_uppercase(), page_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disk_cache_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::open(dir.path()).unwrap().with_ttl(CacheKind::IntradayHistory, Duration::zero());
+        let key = history_key("vci", "fpt", "2024-01-01", None, "1D");
+
+        assert_eq!(cache.get::<Vec<u32>>(CacheKind::DailyHistory, &key), None);
+        cache.put(CacheKind::DailyHistory, &key, &vec![1, 2, 3]).unwrap();
+        assert_eq!(cache.get::<Vec<u32>>(CacheKind::DailyHistory, &key), Some(vec![1, 2, 3]));
+
+        // Zero TTL never stores
+        cache.put(CacheKind::IntradayHistory, &key, &vec![1]).unwrap();
+        assert_eq!(cache.get::<Vec<u32>>(CacheKind::IntradayHistory, &key), None);
+
+        cache.invalidate(CacheKind::DailyHistory, &key).unwrap();
+        assert_eq!(cache.get::<Vec<u32>>(CacheKind::DailyHistory, &key), None);
+
+        assert_ne!(news_key("tcbs", "fpt", 10), news_key("tcbs", "FPT", 50));
+        assert_ne!(company_key("vci", "FPT", Language::Vietnamese), company_key("vci", "FPT", Language::English));
+    }
+}
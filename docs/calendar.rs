@@ -0,0 +1,192 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc, Weekday};
+use std::collections::BTreeSet;
+
+use crate::models::vietnam_offset;
+
+/// First day of the lunar new year in Vietnam, 2000-2030.
+pub(crate) const TET_DATES: &[(i32, u32, u32)] = &[
+    (2000, 2, 5), (2001, 1, 24), (2002, 2, 12), (2003, 2, 1), (2004, 1, 22), (2005, 2, 9),
+    (2006, 1, 29), (2007, 2, 17), (2008, 2, 7), (2009, 1, 26), (2010, 2, 14), (2011, 2, 3),
+    (2012, 1, 23), (2013, 2, 10), (2014, 1, 31), (2015, 2, 19), (2016, 2, 8), (2017, 1, 28),
+    (2018, 2, 16), (2019, 2, 5), (2020, 1, 25), (2021, 2, 12), (2022, 2, 1), (2023, 1, 22),
+    (2024, 2, 10), (2025, 1, 29), (2026, 2, 17), (2027, 2, 6), (2028, 1, 26), (2029, 2, 13),
+    (2030, 2, 3),
+];
+
+pub fn tet_date(year: i32) -> Option<NaiveDate> {
+    TET_DATES.iter().find(|(y, _, _)| *y == year).and_then(|&(y, m, d)| NaiveDate::from_ymd_opt(y, m, d))
+}
+
+/// Exchange closures announced by HOSE/HNX besides Tet and the fixed-date
+/// holidays: Hung Kings day and compensatory days off. Extend with
+/// [`MarketCalendar::with_holidays`] for years not listed here.
+const ANNOUNCED_CLOSURES: &[(i32, u32, u32)] = &[
+    (2023, 1, 2), (2023, 5, 2), (2023, 5, 3), (2023, 9, 1), (2023, 9, 4),
+    (2024, 4, 18), (2024, 9, 3),
+    (2025, 4, 7), (2025, 5, 2), (2025, 9, 1),
+    (2026, 4, 27),
+];
+
+/// Weekdays the exchanges close for Tet in `year`: from two days before the
+/// lunar new year to four days after, as announced every year since 2023.
+pub fn tet_closures(year: i32) -> Vec<NaiveDate> {
+    let Some(tet) = tet_date(year) else {
+        return Vec::new();
+    };
+    (tet - Duration::days(2)).iter_days()
+        .take_while(|date| *date <= tet + Duration::days(4))
+        .filter(|date| !matches!(date.weekday(), Weekday::Sat | Weekday::Sun))
+        .collect()
+}
+
+/// Trading-day calendar for the Vietnamese exchanges: weekends, fixed-date
+/// public holidays (1/1, 30/4, 1/5, 2/9), Tet and announced closures.
+#[derive(Debug, Clone)]
+pub struct MarketCalendar {
+    extra_holidays: BTreeSet<NaiveDate>,
+}
+
+impl Default for MarketCalendar {
+    fn default() -> Self {
+        MarketCalendar {
+            extra_holidays: ANNOUNCED_CLOSURES.iter()
+                .filter_map(|&(y, m, d)| NaiveDate::from_ymd_opt(y, m, d))
+                .chain(TET_DATES.iter().flat_map(|&(year, _, _)| tet_closures(year)))
+                .collect(),
+        }
+    }
+}
+
+impl MarketCalendar {
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.extra_holidays.extend(holidays);
+        self
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        matches!((date.month(), date.day()), (1, 1) | (4, 30) | (5, 1) | (9, 2)) || self.extra_holidays.contains(&date)
+    }
+
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.is_holiday(date)
+    }
+
+    /// Trading days in `[from, to]`, oldest first.
+    pub fn trading_days_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        from.iter_days()
+            .take_while(|date| *date <= to)
+            .filter(|date| self.is_trading_day(*date))
+            .collect()
+    }
+
+    /// The trading day `n` sessions before `date` (`n = 0` gives the latest
+    /// trading day on or before `date`).
+    pub fn nth_trading_day_before(&self, date: NaiveDate, n: usize) -> NaiveDate {
+        let mut current = date;
+        while !self.is_trading_day(current) {
+            current -= Duration::days(1);
+        }
+        for _ in 0..n {
+            current -= Duration::days(1);
+            while !self.is_trading_day(current) {
+                current -= Duration::days(1);
+            }
+        }
+        current
+    }
+
+    /// Date of the latest session that has opened by `now`: today from the
+    /// 9:00 open on trading days, otherwise the previous trading day.
+    pub fn session_date(&self, now: DateTime<Utc>) -> NaiveDate {
+        let local = now.with_timezone(&vietnam_offset());
+        let mut date = local.date_naive();
+        if local.time() < NaiveTime::from_hms_opt(9, 0, 0).unwrap() {
+            date -= Duration::days(1);
+        }
+        self.nth_trading_day_before(date, 0)
+    }
+
+    /// Splits `[from, to]` into consecutive date ranges of at most
+    /// `max_trading_days` trading days each, covering the whole span.
+    pub fn chunk_range(&self, from: NaiveDate, to: NaiveDate, max_trading_days: usize) -> Vec<(NaiveDate, NaiveDate)> {
+        let days = self.trading_days_between(from, to);
+        let mut chunks = Vec::new();
+        let mut chunk_start = from;
+        for group in days.chunks(max_trading_days.max(1)) {
+            let chunk_end = *group.last().unwrap();
+            chunks.push((chunk_start, chunk_end));
+            chunk_start = chunk_end + Duration::days(1);
+        }
+        match chunks.last_mut() {
+            Some(last) => last.1 = to,
+            None => chunks.push((from, to)),
+        }
+        chunks
+    }
+}
+
+/// [`MarketCalendar::trading_days_between`] on the default calendar.
+pub fn trading_days_between(from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    MarketCalendar::default().trading_days_between(from, to)
+}
+
+/// [`MarketCalendar::nth_trading_day_before`] on the default calendar.
+pub fn nth_trading_day_before(date: NaiveDate, n: usize) -> NaiveDate {
+    MarketCalendar::default().nth_trading_day_before(date, n)
+}
+
+/// [`MarketCalendar::session_date`] on the default calendar.
+pub fn session_date(now: DateTime<Utc>) -> NaiveDate {
+    MarketCalendar::default().session_date(now)
+}
+
+/// [`MarketCalendar::chunk_range`] on the default calendar.
+pub fn chunk_range(from: NaiveDate, to: NaiveDate, max_trading_days: usize) -> Vec<(NaiveDate, NaiveDate)> {
+    MarketCalendar::default().chunk_range(from, to, max_trading_days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_trading_days_skip_tet() {
+        // Tet 2024: closed Thu 8 Feb through Wed 14 Feb
+        let days = trading_days_between(date(2024, 2, 5), date(2024, 2, 16));
+        assert_eq!(days, vec![date(2024, 2, 5), date(2024, 2, 6), date(2024, 2, 7), date(2024, 2, 15), date(2024, 2, 16)]);
+        assert_eq!(nth_trading_day_before(date(2024, 2, 15), 1), date(2024, 2, 7));
+        assert_eq!(nth_trading_day_before(date(2024, 2, 11), 0), date(2024, 2, 7));
+        // Sunday, Thursday before the open and Thursday after it
+        let at = |d: u32, hour: u32| date(2024, 2, d).and_hms_opt(hour, 0, 0).unwrap().and_local_timezone(vietnam_offset()).unwrap().with_timezone(&Utc);
+        assert_eq!(session_date(at(11, 12)), date(2024, 2, 7));
+        assert_eq!(session_date(at(15, 8)), date(2024, 2, 7));
+        assert_eq!(session_date(at(15, 10)), date(2024, 2, 15));
+        // Tet 2023, 2025 and 2026 as announced
+        assert_eq!(trading_days_between(date(2023, 1, 19), date(2023, 1, 27)), vec![date(2023, 1, 19), date(2023, 1, 27)]);
+        assert_eq!(trading_days_between(date(2025, 1, 24), date(2025, 2, 3)), vec![date(2025, 1, 24), date(2025, 2, 3)]);
+        assert_eq!(trading_days_between(date(2026, 2, 13), date(2026, 2, 23)), vec![date(2026, 2, 13), date(2026, 2, 23)]);
+    }
+
+    #[test]
+    fn test_fixed_and_custom_holidays() {
+        let calendar = MarketCalendar::default().with_holidays([date(2030, 6, 3)]);
+        assert!(!calendar.is_trading_day(date(2030, 9, 2)));
+        assert!(!calendar.is_trading_day(date(2030, 6, 3)));
+        assert!(calendar.is_trading_day(date(2030, 6, 4)));
+    }
+
+    #[test]
+    fn test_chunk_range_covers_span() {
+        let chunks = chunk_range(date(2024, 2, 3), date(2024, 2, 18), 2);
+        assert_eq!(chunks, vec![
+            (date(2024, 2, 3), date(2024, 2, 6)),
+            (date(2024, 2, 7), date(2024, 2, 15)),
+            (date(2024, 2, 16), date(2024, 2, 18)),
+        ]);
+        assert_eq!(chunk_range(date(2024, 2, 10), date(2024, 2, 11), 5), vec![(date(2024, 2, 10), date(2024, 2, 11))]);
+    }
+}
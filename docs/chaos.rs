@@ -0,0 +1,116 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Failure injection for resilience testing. Each probability is applied
+/// independently per HTTP attempt, before the real request is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChaosConfig {
+    pub delay_probability: f64,
+    /// Injected delays are uniform in `0..max_delay`.
+    pub max_delay: Duration,
+    /// Attempt fails as if the server answered 503.
+    pub server_error_probability: f64,
+    /// Attempt "succeeds" with a payload of the wrong shape.
+    pub malformed_probability: f64,
+    /// Fixed seed for reproducible runs.
+    pub seed: Option<u64>,
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        ChaosConfig {
+            delay_probability: 0.0,
+            max_delay: Duration::from_secs(2),
+            server_error_probability: 0.0,
+            malformed_probability: 0.0,
+            seed: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChaosFault {
+    ServerError(u16),
+    Malformed(Value),
+}
+
+pub struct Chaos {
+    config: ChaosConfig,
+    rng: Mutex<StdRng>,
+}
+
+impl Chaos {
+    pub fn new(config: ChaosConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Chaos { config, rng: Mutex::new(rng) }
+    }
+
+    pub fn config(&self) -> &ChaosConfig {
+        &self.config
+    }
+
+    /// Rolls the dice for one attempt: sleeps for any injected delay and
+    /// returns the fault to simulate, if any.
+    pub async fn inject(&self) -> Option<ChaosFault> {
+        let (delay, fault) = self.roll();
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+        fault
+    }
+
+    fn roll(&self) -> (Option<Duration>, Option<ChaosFault>) {
+        let mut rng = self.rng.lock().unwrap();
+        let delay = rng.gen_bool(self.config.delay_probability.clamp(0.0, 1.0))
+            .then(|| self.config.max_delay.mul_f64(rng.gen::<f64>()));
+
+        let fault = if rng.gen_bool(self.config.server_error_probability.clamp(0.0, 1.0)) {
+            Some(ChaosFault::ServerError(503))
+        } else if rng.gen_bool(self.config.malformed_probability.clamp(0.0, 1.0)) {
+            Some(ChaosFault::Malformed(malformed_payload(rng.gen_range(0..3))))
+        } else {
+            None
+        };
+        (delay, fault)
+    }
+}
+
+/// Payloads providers have been seen to return instead of data.
+fn malformed_payload(kind: u32) -> Value {
+    match kind {
+        0 => Value::String("<html><body>502 Bad Gateway</body></html>".to_string()),
+        1 => serde_json::json!({}),
+        _ => serde_json::json!([{ "t": "not-a-timestamp", "o": null }]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_rolls_are_reproducible() {
+        let config = ChaosConfig {
+            delay_probability: 0.5,
+            server_error_probability: 0.3,
+            malformed_probability: 0.3,
+            seed: Some(42),
+            ..Default::default()
+        };
+        let a = Chaos::new(config.clone());
+        let b = Chaos::new(config);
+        let rolls_a: Vec<_> = (0..50).map(|_| a.roll()).collect();
+        let rolls_b: Vec<_> = (0..50).map(|_| b.roll()).collect();
+        assert_eq!(rolls_a, rolls_b);
+        assert!(rolls_a.iter().any(|(_, fault)| matches!(fault, Some(ChaosFault::ServerError(503)))));
+
+        let quiet = Chaos::new(ChaosConfig::default());
+        assert!((0..50).all(|_| quiet.roll() == (None, None)));
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CircuitState {
+    /// Requests flow normally.
+    Closed,
+    /// Requests fail fast until the cooldown elapses.
+    Open,
+    /// Cooldown elapsed; one trial request decides whether to close again.
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitConfig {
+    /// Consecutive failed requests that open the circuit; a request counts
+    /// once, after its last retry.
+    pub failure_threshold: u32,
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitConfig {
+    fn default() -> Self {
+        CircuitConfig { failure_threshold: 5, cooldown: Duration::from_secs(30) }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Endpoint {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    trial_started: Option<Instant>,
+}
+
+impl Endpoint {
+    fn state(&self, config: &CircuitConfig, now: Instant) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened) if now.duration_since(opened) < config.cooldown => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+}
+
+/// Per-endpoint circuit breaker. Share one behind an `Arc` to give several
+/// clients a common view of which endpoints are failing.
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    config: CircuitConfig,
+    endpoints: Mutex<HashMap<String, Endpoint>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitConfig) -> Self {
+        CircuitBreaker { config, endpoints: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn config(&self) -> CircuitConfig {
+        self.config
+    }
+
+    /// Admits a request to `endpoint`, or returns how long until it may be
+    /// tried again. In the half-open state only one trial is admitted at a
+    /// time; a trial that never reports back expires after one cooldown.
+    pub fn try_acquire(&self, endpoint: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let Some(state) = endpoints.get_mut(endpoint) else {
+            return Ok(());
+        };
+        match state.state(&self.config, now) {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => Err(self.config.cooldown - now.duration_since(state.opened_at.unwrap())),
+            CircuitState::HalfOpen => match state.trial_started {
+                Some(started) if now.duration_since(started) < self.config.cooldown => {
+                    Err(self.config.cooldown - now.duration_since(started))
+                }
+                _ => {
+                    state.trial_started = Some(now);
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    pub fn record_success(&self, endpoint: &str) {
+        self.endpoints.lock().unwrap().remove(endpoint);
+    }
+
+    pub fn record_failure(&self, endpoint: &str) {
+        let now = Instant::now();
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let state = endpoints.entry(endpoint.to_string()).or_default();
+        state.consecutive_failures += 1;
+        // A failed trial reopens immediately
+        if state.trial_started.take().is_some() || state.consecutive_failures >= self.config.failure_threshold.max(1) {
+            if state.opened_at.is_none() || state.state(&self.config, now) == CircuitState::HalfOpen {
+                tracing::warn!("Circuit opened for {} after {} consecutive failures", endpoint, state.consecutive_failures);
+            }
+            state.opened_at = Some(now);
+        }
+    }
+
+    pub fn state(&self, endpoint: &str) -> CircuitState {
+        self.endpoints.lock().unwrap().get(endpoint).map_or(CircuitState::Closed, |state| state.state(&self.config, Instant::now()))
+    }
+
+    /// Endpoints with recent failures and their state; endpoints not listed
+    /// are closed.
+    pub fn states(&self) -> BTreeMap<String, CircuitState> {
+        let now = Instant::now();
+        self.endpoints.lock().unwrap().iter().map(|(endpoint, state)| (endpoint.clone(), state.state(&self.config, now))).collect()
+    }
+
+    pub fn consecutive_failures(&self, endpoint: &str) -> u32 {
+        self.endpoints.lock().unwrap().get(endpoint).map_or(0, |state| state.consecutive_failures)
+    }
+
+    /// Closes `endpoint`'s circuit, or every circuit with `None`.
+    pub fn reset(&self, endpoint: Option<&str>) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        match endpoint {
+            Some(endpoint) => {
+                endpoints.remove(endpoint);
+            }
+            None => endpoints.clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_breaker_lifecycle() {
+        let breaker = CircuitBreaker::new(CircuitConfig { failure_threshold: 2, cooldown: Duration::from_secs(10) });
+        let endpoint = "stock-insight/v1/stock/bars-long-term";
+
+        breaker.record_failure(endpoint);
+        assert_eq!(breaker.state(endpoint), CircuitState::Closed);
+        breaker.record_failure(endpoint);
+        assert_eq!(breaker.state(endpoint), CircuitState::Open);
+        assert_eq!(breaker.try_acquire(endpoint), Err(Duration::from_secs(10)));
+        assert!(breaker.try_acquire("other").is_ok());
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(breaker.state(endpoint), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire(endpoint).is_ok());
+        // Only one trial at a time
+        assert!(breaker.try_acquire(endpoint).is_err());
+        breaker.record_failure(endpoint);
+        assert_eq!(breaker.state(endpoint), CircuitState::Open);
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert!(breaker.try_acquire(endpoint).is_ok());
+        breaker.record_success(endpoint);
+        assert_eq!(breaker.state(endpoint), CircuitState::Closed);
+        assert!(breaker.states().is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// Deduplicates concurrent identical requests: the first caller for a key
+/// runs the request, later callers wait for its result instead of firing
+/// their own. Keys are forgotten as soon as the request completes, so this
+/// never serves stale data.
+pub struct Coalescer<K, V> {
+    inflight: Mutex<HashMap<K, watch::Receiver<Option<V>>>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for Coalescer<K, V> {
+    fn default() -> Self {
+        Coalescer { inflight: Mutex::new(HashMap::new()) }
+    }
+}
+
+/// Removes the leader's entry even if its future is dropped mid-flight, so
+/// waiting callers can take over.
+struct InflightGuard<'a, K: Eq + Hash, V> {
+    inflight: &'a Mutex<HashMap<K, watch::Receiver<Option<V>>>>,
+    key: &'a K,
+}
+
+impl<K: Eq + Hash, V> Drop for InflightGuard<'_, K, V> {
+    fn drop(&mut self) {
+        self.inflight.lock().unwrap().remove(self.key);
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Coalescer<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of requests currently in flight.
+    pub fn pending(&self) -> usize {
+        self.inflight.lock().unwrap().len()
+    }
+
+    /// Runs `request` unless one is already in flight for `key`, in which
+    /// case its result is shared. If the running caller is cancelled, one of
+    /// the waiting callers runs its own `request` instead.
+    pub async fn run<F: Future<Output = V>>(&self, key: K, request: F) -> V {
+        let mut request = Some(request);
+        loop {
+            let waiting = {
+                let mut inflight = self.inflight.lock().unwrap();
+                match inflight.get(&key) {
+                    Some(receiver) => Err(receiver.clone()),
+                    None => {
+                        let (sender, receiver) = watch::channel(None);
+                        inflight.insert(key.clone(), receiver);
+                        Ok(sender)
+                    }
+                }
+            };
+
+            match waiting {
+                Ok(sender) => {
+                    let guard = InflightGuard { inflight: &self.inflight, key: &key };
+                    // Only the first pass can lead; later passes follow a new leader or lead with the untouched request
+                    let value = request.take().expect("request already consumed").await;
+                    drop(guard);
+                    let _ = sender.send(Some(value.clone()));
+                    return value;
+                }
+                Err(mut receiver) => {
+                    if let Ok(value) = receiver.wait_for(|value| value.is_some()).await {
+                        return value.clone().expect("checked by wait_for");
+                    }
+                    // Leader was dropped before finishing; try again
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_concurrent_calls_share_one_request() {
+        let coalescer: Arc<Coalescer<String, u32>> = Arc::new(Coalescer::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let request = |calls: Arc<AtomicUsize>| async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            42
+        };
+
+        let results = futures::future::join_all((0..5).map(|_| coalescer.run("FPT".to_string(), request(calls.clone())))).await;
+        assert_eq!(results, vec![42; 5]);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(coalescer.pending(), 0);
+
+        // A cancelled leader hands over to a waiting caller
+        let leader = tokio::spawn({
+            let (coalescer, calls) = (coalescer.clone(), calls.clone());
+            async move { coalescer.run("FPT".to_string(), request(calls)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let follower = tokio::spawn({
+            let (coalescer, calls) = (coalescer.clone(), calls.clone());
+            async move { coalescer.run("FPT".to_string(), request(calls)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        leader.abort();
+        assert_eq!(follower.await.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+}
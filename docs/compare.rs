@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tcbs::FinancialStatement;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetricCategory {
+    Valuation,
+    Growth,
+    Profitability,
+}
+
+/// Metrics included in a comparison: (TCBS ratio key, label, category).
+/// Keys are the snake_case names produced by `TcbsClient::financial_ratios`.
+pub const COMPARISON_METRICS: [(&str, &str, MetricCategory); 11] = [
+    ("price_to_earning", "P/E", MetricCategory::Valuation),
+    ("price_to_book", "P/B", MetricCategory::Valuation),
+    ("value_before_ebitda", "EV/EBITDA", MetricCategory::Valuation),
+    ("earning_per_share", "EPS", MetricCategory::Valuation),
+    ("eps_change", "EPS growth", MetricCategory::Growth),
+    ("book_value_per_share_change", "BVPS growth", MetricCategory::Growth),
+    ("roe", "ROE", MetricCategory::Profitability),
+    ("roa", "ROA", MetricCategory::Profitability),
+    ("gross_profit_margin", "Gross margin", MetricCategory::Profitability),
+    ("operating_profit_margin", "Operating margin", MetricCategory::Profitability),
+    ("post_tax_margin", "Net margin", MetricCategory::Profitability),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricRow {
+    pub key: String,
+    pub label: String,
+    pub category: MetricCategory,
+    /// One value per symbol, in [`ComparisonMatrix::symbols`] order.
+    pub values: Vec<Option<f64>>,
+}
+
+/// Metrics aligned across symbols. Each column uses the newest period any
+/// symbol reported; a symbol without that period falls back to its own latest
+/// earlier one, recorded in `periods` (`None` when it has no data at all).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonMatrix {
+    pub symbols: Vec<String>,
+    pub target_period: Option<String>,
+    pub periods: Vec<Option<String>>,
+    pub rows: Vec<MetricRow>,
+}
+
+impl ComparisonMatrix {
+    pub fn value(&self, symbol: &str, key: &str) -> Option<f64> {
+        let column = self.symbols.iter().position(|s| s.eq_ignore_ascii_case(symbol))?;
+        self.rows.iter().find(|row| row.key == key)?.values[column]
+    }
+
+    pub fn rows_in(&self, category: MetricCategory) -> impl Iterator<Item = &MetricRow> {
+        self.rows.iter().filter(move |row| row.category == category)
+    }
+}
+
+/// Builds the matrix from each symbol's ratio statements. Period labels must
+/// sort chronologically as strings ("2024", "2024-Q3").
+pub fn build_matrix(statements: &[(String, Vec<FinancialStatement>)]) -> ComparisonMatrix {
+    let target_period = statements.iter()
+        .flat_map(|(_, periods)| periods.iter().map(|statement| statement.period.clone()))
+        .max();
+
+    let chosen: Vec<Option<&FinancialStatement>> = statements.iter()
+        .map(|(_, periods)| {
+            periods.iter()
+                .filter(|statement| target_period.as_ref().is_some_and(|target| &statement.period <= target))
+                .max_by(|a, b| a.period.cmp(&b.period))
+        })
+        .collect();
+
+    let rows = COMPARISON_METRICS.iter()
+        .map(|&(key, label, category)| MetricRow {
+            key: key.to_string(),
+            label: label.to_string(),
+            category,
+            values: chosen.iter()
+                .map(|statement| statement.and_then(|s| s.data.get(key).copied()).filter(|v| v.is_finite()))
+                .collect(),
+        })
+        .collect();
+
+    ComparisonMatrix {
+        symbols: statements.iter().map(|(symbol, _)| symbol.to_uppercase()).collect(),
+        target_period,
+        periods: chosen.iter().map(|statement| statement.map(|s| s.period.clone())).collect(),
+        rows,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement(period: &str, values: &[(&str, f64)]) -> FinancialStatement {
+        FinancialStatement {
+            period: period.to_string(),
+            data: values.iter().map(|&(k, v)| (k.to_string(), v)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_matrix_aligns_periods() {
+        let matrix = build_matrix(&[
+            ("HPG".to_string(), vec![statement("2024-Q2", &[("roe", 0.10)]), statement("2024-Q3", &[("roe", 0.12), ("price_to_earning", 15.0)])]),
+            ("hsg".to_string(), vec![statement("2024-Q2", &[("roe", 0.05)])]),
+            ("NKG".to_string(), vec![]),
+        ]);
+
+        assert_eq!(matrix.target_period.as_deref(), Some("2024-Q3"));
+        assert_eq!(matrix.periods, vec![Some("2024-Q3".to_string()), Some("2024-Q2".to_string()), None]);
+        assert_eq!(matrix.value("HPG", "roe"), Some(0.12));
+        assert_eq!(matrix.value("HSG", "roe"), Some(0.05));
+        assert_eq!(matrix.value("HSG", "price_to_earning"), None);
+        assert_eq!(matrix.value("NKG", "roe"), None);
+        assert_eq!(matrix.rows_in(MetricCategory::Valuation).count(), 4);
+    }
+}
@@ -0,0 +1,314 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::{vietnam_offset, Ohlcv};
+use crate::tcbs;
+use crate::vci::{self, CapitalRaiseKind, CorporateEvent};
+
+/// What a corporate action does to a holding. Share ratios are new shares
+/// per share held on the record date (0.2 for a 20% / 5:1 issue).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ActionKind {
+    CashDividend { amount_per_share: f64 },
+    StockDividend { ratio: f64 },
+    BonusShares { ratio: f64 },
+    /// `ratio` is shares after per share before (2.0 for a 2-for-1 split).
+    Split { ratio: f64 },
+    /// `price` is the subscription price in VND, when announced.
+    RightsIssue { ratio: f64, price: Option<f64> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorporateAction {
+    pub symbol: String,
+    pub kind: ActionKind,
+    /// First session the shares trade without the entitlement.
+    pub ex_date: NaiveDate,
+    pub record_date: Option<NaiveDate>,
+    pub payment_date: Option<NaiveDate>,
+    pub title: String,
+}
+
+impl CorporateAction {
+    pub fn is_dividend(&self) -> bool {
+        matches!(self.kind, ActionKind::CashDividend { .. } | ActionKind::StockDividend { .. })
+    }
+
+    /// Multiplier for prices before the ex-date so they are comparable with
+    /// prices after it, given the last close before the ex-date. Cash uses
+    /// `(P - D) / P`, share issues `1 / (1 + r)` and rights issues the
+    /// theoretical ex-rights price over `P`.
+    pub fn price_factor(&self, prior_close: f64) -> f64 {
+        if prior_close <= 0.0 {
+            return 1.0;
+        }
+        let factor = match self.kind {
+            ActionKind::CashDividend { amount_per_share } => (prior_close - amount_per_share) / prior_close,
+            ActionKind::StockDividend { ratio } | ActionKind::BonusShares { ratio } => 1.0 / (1.0 + ratio),
+            ActionKind::Split { ratio } => 1.0 / ratio,
+            ActionKind::RightsIssue { ratio, price } => match price {
+                Some(price) if price < prior_close => (prior_close + ratio * price) / ((1.0 + ratio) * prior_close),
+                _ => 1.0,
+            },
+        };
+        if factor.is_finite() && factor > 0.0 { factor } else { 1.0 }
+    }
+
+    /// Multiplier for volumes before the ex-date: share issues and splits
+    /// grow the share count; cash and rights leave it alone.
+    pub fn volume_factor(&self) -> f64 {
+        match self.kind {
+            ActionKind::StockDividend { ratio } | ActionKind::BonusShares { ratio } => 1.0 + ratio,
+            ActionKind::Split { ratio } => ratio,
+            ActionKind::CashDividend { .. } | ActionKind::RightsIssue { .. } => 1.0,
+        }
+    }
+}
+
+/// Cumulative back-adjustment for one bar.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AdjustmentFactor {
+    pub price: f64,
+    pub volume: f64,
+}
+
+/// Factors for bars given as `(time, close)` in time order. Each action
+/// scales every bar dated (exchange time) before its ex-date, using the
+/// unadjusted close of the last such bar. Actions outside the span of the
+/// bars change nothing.
+pub fn adjustment_factors(bars: &[(DateTime<Utc>, f64)], actions: &[CorporateAction]) -> Vec<AdjustmentFactor> {
+    let dates: Vec<NaiveDate> = bars.iter().map(|(time, _)| time.with_timezone(&vietnam_offset()).date_naive()).collect();
+    let mut factors = vec![AdjustmentFactor { price: 1.0, volume: 1.0 }; bars.len()];
+    for action in actions {
+        let ex_index = dates.partition_point(|date| *date < action.ex_date);
+        if ex_index == 0 || ex_index == bars.len() {
+            continue;
+        }
+        let price = action.price_factor(bars[ex_index - 1].1);
+        let volume = action.volume_factor();
+        for factor in &mut factors[..ex_index] {
+            factor.price *= price;
+            factor.volume *= volume;
+        }
+    }
+    factors
+}
+
+/// Bar types [`back_adjust`] can scale: the shared model and each
+/// provider's own OHLCV row.
+pub trait AdjustableBar {
+    fn time(&self) -> DateTime<Utc>;
+    fn close(&self) -> f64;
+    /// Open, high, low and close.
+    fn prices_mut(&mut self) -> [&mut f64; 4];
+    fn volume_mut(&mut self) -> &mut u64;
+}
+
+impl AdjustableBar for Ohlcv {
+    fn time(&self) -> DateTime<Utc> {
+        self.time
+    }
+
+    fn close(&self) -> f64 {
+        self.close
+    }
+
+    fn prices_mut(&mut self) -> [&mut f64; 4] {
+        [&mut self.open, &mut self.high, &mut self.low, &mut self.close]
+    }
+
+    fn volume_mut(&mut self) -> &mut u64 {
+        &mut self.volume
+    }
+}
+
+impl AdjustableBar for vci::OhlcvData {
+    fn time(&self) -> DateTime<Utc> {
+        self.time
+    }
+
+    fn close(&self) -> f64 {
+        self.close
+    }
+
+    fn prices_mut(&mut self) -> [&mut f64; 4] {
+        [&mut self.open, &mut self.high, &mut self.low, &mut self.close]
+    }
+
+    fn volume_mut(&mut self) -> &mut u64 {
+        &mut self.volume
+    }
+}
+
+impl AdjustableBar for tcbs::OhlcvData {
+    fn time(&self) -> DateTime<Utc> {
+        self.time
+    }
+
+    fn close(&self) -> f64 {
+        self.close
+    }
+
+    fn prices_mut(&mut self) -> [&mut f64; 4] {
+        [&mut self.open, &mut self.high, &mut self.low, &mut self.close]
+    }
+
+    fn volume_mut(&mut self) -> &mut u64 {
+        &mut self.volume
+    }
+}
+
+/// Back-adjusts `bars` (time order) in place for `actions`.
+pub fn back_adjust<B: AdjustableBar>(bars: &mut [B], actions: &[CorporateAction]) {
+    let closes: Vec<(DateTime<Utc>, f64)> = bars.iter().map(|bar| (bar.time(), bar.close())).collect();
+    for (bar, factor) in bars.iter_mut().zip(adjustment_factors(&closes, actions)) {
+        for price in bar.prices_mut() {
+            *price *= factor.price;
+        }
+        let volume = bar.volume_mut();
+        *volume = (*volume as f64 * factor.volume).round() as u64;
+    }
+}
+
+/// Types a VCI `OrganizationEvents` row. Issuances that don't dilute
+/// holders (placements, ESOP, conversions) and non-financial events are `None`.
+pub fn from_event(event: &CorporateEvent) -> Option<CorporateAction> {
+    let ex_date = event.effective_date()?;
+    let ratio = event.ratio.filter(|ratio| *ratio > 0.0);
+    let title = event.title.to_lowercase();
+    let kind = if event.is_cash_dividend() {
+        let amount_per_share = event.value.filter(|value| *value > 0.0).or_else(|| ratio.map(|ratio| ratio * vci::PAR_VALUE_VND))?;
+        ActionKind::CashDividend { amount_per_share }
+    } else if title.contains("chia tách") || title.contains("tách cổ phiếu") {
+        ActionKind::Split { ratio: 1.0 + ratio? }
+    } else {
+        if event.event_code.as_deref().is_some_and(|code| code.eq_ignore_ascii_case("AIS")) {
+            return None;
+        }
+        match CapitalRaiseKind::classify(&event.title) {
+            CapitalRaiseKind::StockDividend => ActionKind::StockDividend { ratio: ratio? },
+            CapitalRaiseKind::BonusShares => ActionKind::BonusShares { ratio: ratio? },
+            CapitalRaiseKind::RightsIssue => ActionKind::RightsIssue { ratio: ratio?, price: event.value.filter(|value| *value > 0.0) },
+            _ => return None,
+        }
+    };
+    Some(CorporateAction {
+        symbol: event.symbol.to_uppercase(),
+        kind,
+        ex_date,
+        record_date: vci::parse_event_date(&event.record_date),
+        payment_date: vci::parse_event_date(&event.issue_date),
+        title: event.title.clone(),
+    })
+}
+
+/// Typed actions from VCI events, oldest ex-date first, with duplicate
+/// announcements of the same action collapsed.
+pub fn from_events(events: &[CorporateEvent]) -> Vec<CorporateAction> {
+    let mut actions: Vec<CorporateAction> = events.iter().filter_map(from_event).collect();
+    actions.sort_by_key(|action| action.ex_date);
+    actions.dedup_by(|a, b| a.ex_date == b.ex_date && a.kind == b.kind);
+    actions
+}
+
+/// Parses a TCBS `dividend-payment-histories` row (`exerciseDate` as
+/// dd/mm/yy, `cashDividendPercentage` as a fraction of par, `issueMethod`
+/// "cash" or "share").
+pub(crate) fn parse_tcbs_dividend(symbol: &str, row: &Value) -> Option<CorporateAction> {
+    let date = row.get("exerciseDate")?.as_str()?;
+    let ex_date = NaiveDate::parse_from_str(date, "%d/%m/%y")
+        .or_else(|_| NaiveDate::parse_from_str(date, "%d/%m/%Y"))
+        .ok()?;
+    let ratio = row.get("cashDividendPercentage").and_then(|v| v.as_f64()).filter(|ratio| *ratio > 0.0)?;
+    let method = row.get("issueMethod").and_then(|v| v.as_str()).unwrap_or("cash");
+    let (kind, title) = if method.eq_ignore_ascii_case("share") {
+        (ActionKind::StockDividend { ratio }, format!("Stock dividend {:.0}%", ratio * 100.0))
+    } else {
+        (ActionKind::CashDividend { amount_per_share: ratio * vci::PAR_VALUE_VND }, format!("Cash dividend {:.0}%", ratio * 100.0))
+    };
+    Some(CorporateAction { symbol: symbol.to_uppercase(), kind, ex_date, record_date: None, payment_date: None, title })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(code: &str, title: &str, exright: &str, ratio: Option<f64>, value: Option<f64>) -> CorporateEvent {
+        CorporateEvent {
+            symbol: "HPG".to_string(),
+            title: title.to_string(),
+            event_code: Some(code.to_string()),
+            event_name: None,
+            public_date: None,
+            record_date: None,
+            exright_date: Some(exright.to_string()),
+            issue_date: None,
+            ratio,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_types_vci_events() {
+        let events = vec![
+            event("ISS", "Phát hành cổ phiếu trả cổ tức tỷ lệ 10:1", "2024-06-20", Some(0.1), None),
+            event("DIV", "Trả cổ tức bằng tiền mặt đợt 1", "2024-03-05", Some(0.05), None),
+            event("ISS", "Phát hành cổ phiếu riêng lẻ", "2024-04-01", Some(0.1), None),
+            event("ISS", "Phát hành quyền mua cho cổ đông hiện hữu", "2024-08-01", Some(0.5), Some(10_000.0)),
+            event("AGME", "Đại hội cổ đông thường niên", "2024-04-20", None, None),
+        ];
+        let actions = from_events(&events);
+        assert_eq!(actions.len(), 3);
+        assert_eq!(actions[0].kind, ActionKind::CashDividend { amount_per_share: 500.0 });
+        assert_eq!(actions[1].kind, ActionKind::StockDividend { ratio: 0.1 });
+        assert_eq!(actions[2].kind, ActionKind::RightsIssue { ratio: 0.5, price: Some(10_000.0) });
+
+        assert_eq!(actions[0].price_factor(25_000.0), 0.98);
+        assert!((actions[1].price_factor(25_000.0) - 1.0 / 1.1).abs() < 1e-12);
+        assert!((actions[2].price_factor(25_000.0) - 20_000.0 / 25_000.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_back_adjust_removes_ex_date_gaps() {
+        use chrono::TimeZone;
+        let bar = |day: u32, close: f64, volume: u64| Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 6, day, 2, 0, 0).unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume,
+            symbol: None,
+            breakdown: None,
+            futures: None,
+        };
+        let action = |day: u32, kind: ActionKind| CorporateAction {
+            symbol: "HPG".to_string(),
+            kind,
+            ex_date: NaiveDate::from_ymd_opt(2024, 6, day).unwrap(),
+            record_date: None,
+            payment_date: None,
+            title: String::new(),
+        };
+        let mut bars = vec![bar(3, 22_000.0, 100), bar(4, 20_000.0, 100), bar(5, 19_000.0, 200), bar(6, 19_000.0, 200)];
+        let actions = vec![
+            action(5, ActionKind::CashDividend { amount_per_share: 1_000.0 }),
+            action(4, ActionKind::BonusShares { ratio: 0.1 }),
+            action(20, ActionKind::Split { ratio: 2.0 }),
+        ];
+        back_adjust(&mut bars, &actions);
+        let closes: Vec<f64> = bars.iter().map(|bar| (bar.close * 1e6).round() / 1e6).collect();
+        assert_eq!(closes, vec![19_000.0, 19_000.0, 19_000.0, 19_000.0]);
+        assert_eq!(bars.iter().map(|bar| bar.volume).collect::<Vec<_>>(), vec![110, 100, 200, 200]);
+    }
+
+    #[test]
+    fn test_parse_tcbs_dividend() {
+        let cash = serde_json::json!({"exerciseDate": "05/06/24", "cashYear": 2023, "cashDividendPercentage": 0.1, "issueMethod": "cash"});
+        let share = serde_json::json!({"exerciseDate": "20/06/24", "cashDividendPercentage": 0.2, "issueMethod": "share"});
+        let cash = parse_tcbs_dividend("fpt", &cash).unwrap();
+        assert_eq!((cash.ex_date, cash.kind), (NaiveDate::from_ymd_opt(2024, 6, 5).unwrap(), ActionKind::CashDividend { amount_per_share: 1000.0 }));
+        assert_eq!(parse_tcbs_dividend("fpt", &share).unwrap().kind, ActionKind::StockDividend { ratio: 0.2 });
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::calendar;
+use crate::models::Ohlcv;
+use crate::provider::{ProviderError, StockDataProvider};
+use crate::store::LocalStore;
+
+/// Daily returns on the market calendar. A return is `None` when either
+/// its day or the previous trading day has no bar (suspension, not yet
+/// listed), so gaps never show up as zero or multi-day returns.
+pub fn aligned_returns(bars: &[Ohlcv], days: &[NaiveDate]) -> Vec<Option<f64>> {
+    let closes: HashMap<NaiveDate, f64> = bars.iter().filter(|b| b.close > 0.0).map(|b| (b.time.date_naive(), b.close)).collect();
+    days.windows(2)
+        .map(|pair| Some(closes.get(&pair[1])? / closes.get(&pair[0])? - 1.0))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorrelationMatrix {
+    pub symbols: Vec<String>,
+    /// Pearson correlation, `None` where a pair shares fewer than two returns.
+    pub correlation: Vec<Vec<Option<f64>>>,
+    /// Sample covariance of daily returns.
+    pub covariance: Vec<Vec<Option<f64>>>,
+    /// Days each pair had returns for.
+    pub observations: Vec<Vec<usize>>,
+}
+
+impl CorrelationMatrix {
+    /// Builds the matrix from daily bars over `[from, to]`, using pairwise
+    /// complete observations.
+    pub fn from_series(series: &BTreeMap<String, Vec<Ohlcv>>, from: NaiveDate, to: NaiveDate) -> Self {
+        let days = calendar::trading_days_between(from, to);
+        let symbols: Vec<String> = series.keys().cloned().collect();
+        let returns: Vec<Vec<Option<f64>>> = series.values().map(|bars| aligned_returns(bars, &days)).collect();
+
+        let n = symbols.len();
+        let mut matrix = CorrelationMatrix {
+            symbols,
+            correlation: vec![vec![None; n]; n],
+            covariance: vec![vec![None; n]; n],
+            observations: vec![vec![0; n]; n],
+        };
+        for i in 0..n {
+            for j in i..n {
+                let pairs: Vec<(f64, f64)> = returns[i].iter().zip(&returns[j]).filter_map(|(a, b)| Some(((*a)?, (*b)?))).collect();
+                let (cov, corr) = pair_stats(&pairs).unzip();
+                for (a, b) in [(i, j), (j, i)] {
+                    matrix.covariance[a][b] = cov;
+                    matrix.correlation[a][b] = corr.flatten();
+                    matrix.observations[a][b] = pairs.len();
+                }
+            }
+        }
+        matrix
+    }
+
+    pub fn get(&self, a: &str, b: &str) -> Option<f64> {
+        let index = |symbol: &str| self.symbols.iter().position(|s| s.eq_ignore_ascii_case(symbol));
+        self.correlation[index(a)?][index(b)?]
+    }
+}
+
+/// (covariance, correlation) of paired samples; correlation is `None` when
+/// either side has no variance.
+fn pair_stats(pairs: &[(f64, f64)]) -> Option<(f64, Option<f64>)> {
+    if pairs.len() < 2 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let (mean_a, mean_b) = (pairs.iter().map(|p| p.0).sum::<f64>() / n, pairs.iter().map(|p| p.1).sum::<f64>() / n);
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (a, b) in pairs {
+        cov += (a - mean_a) * (b - mean_b);
+        var_a += (a - mean_a).powi(2);
+        var_b += (b - mean_b).powi(2);
+    }
+    let correlation = (var_a > 0.0 && var_b > 0.0).then(|| cov / (var_a.sqrt() * var_b.sqrt()));
+    Some((cov / (n - 1.0), correlation))
+}
+
+/// Correlation of daily returns for `symbols` over `[from, to]`. Series the
+/// store already covers are read locally; the rest are fetched.
+pub async fn correlation_matrix(
+    provider: &dyn StockDataProvider,
+    store: Option<&LocalStore>,
+    symbols: &[&str],
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<CorrelationMatrix, ProviderError> {
+    let last_trading_day = calendar::trading_days_between(from, to).last().copied();
+    let mut series = BTreeMap::new();
+    for symbol in symbols {
+        let symbol = symbol.to_uppercase();
+        let local = store.and_then(|store| store.read(&symbol, "1D").ok()).unwrap_or_default();
+        let covered = match (local.first(), local.last(), last_trading_day) {
+            (Some(first), Some(last), Some(needed)) => first.time.date_naive() <= from && last.time.date_naive() >= needed,
+            _ => false,
+        };
+        let bars = if covered { local } else { provider.get_history(&symbol, from, Some(to), "1D").await? };
+        series.insert(symbol, bars);
+    }
+    Ok(CorrelationMatrix::from_series(&series, from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn bars(closes: &[(u32, f64)]) -> Vec<Ohlcv> {
+        closes
+            .iter()
+            .map(|&(day, close)| Ohlcv {
+                time: Utc.with_ymd_and_hms(2024, 6, day, 0, 0, 0).unwrap(),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 0,
+                symbol: None,
+                breakdown: None,
+                futures: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_correlation_skips_missing_days() {
+        let from = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 7).unwrap();
+        let series = BTreeMap::from([
+            ("HPG".to_string(), bars(&[(3, 100.0), (4, 102.0), (5, 101.0), (6, 104.0), (7, 103.0)])),
+            // Suspended on the 6th: returns for the 6th and 7th are dropped
+            ("HSG".to_string(), bars(&[(3, 50.0), (4, 51.0), (5, 50.5), (7, 51.5)])),
+            ("MIR".to_string(), bars(&[(3, 10.0), (4, 9.8), (5, 9.9), (6, 9.6), (7, 9.7)])),
+        ]);
+        let matrix = CorrelationMatrix::from_series(&series, from, to);
+
+        assert_eq!(matrix.observations[0][1], 2);
+        assert!((matrix.get("HPG", "HSG").unwrap() - 1.0).abs() < 1e-9);
+        assert!(matrix.get("HPG", "MIR").unwrap() < -0.9);
+        assert!((matrix.get("MIR", "MIR").unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(matrix.covariance[0][2], matrix.covariance[2][0]);
+    }
+}
@@ -0,0 +1,260 @@
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::calendar;
+
+/// Rolling VN30 futures codes ("VN30F1M", ...) accepted by the providers in
+/// place of a dated contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Tenor {
+    OneMonth,
+    TwoMonth,
+    OneQuarter,
+    TwoQuarter,
+}
+
+impl Tenor {
+    pub const ALL: [Tenor; 4] = [Tenor::OneMonth, Tenor::TwoMonth, Tenor::OneQuarter, Tenor::TwoQuarter];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Tenor::OneMonth => "VN30F1M",
+            Tenor::TwoMonth => "VN30F2M",
+            Tenor::OneQuarter => "VN30F1Q",
+            Tenor::TwoQuarter => "VN30F2Q",
+        }
+    }
+
+    /// The dated contract this code refers to on `as_of`. Front-month rolls
+    /// the day after expiry; the quarterly codes are the next two quarter
+    /// months after the two monthly contracts.
+    pub fn contract(&self, as_of: NaiveDate) -> FuturesContract {
+        let mut front = month_start(as_of);
+        if as_of > expiry_of(front) {
+            front = next_month(front);
+        }
+        let second = next_month(front);
+        let month = match self {
+            Tenor::OneMonth => front,
+            Tenor::TwoMonth => second,
+            Tenor::OneQuarter | Tenor::TwoQuarter => {
+                let mut quarter = next_month(second);
+                while !quarter.month().is_multiple_of(3) {
+                    quarter = next_month(quarter);
+                }
+                if *self == Tenor::TwoQuarter {
+                    quarter = (0..3).fold(quarter, |month, _| next_month(month));
+                }
+                quarter
+            }
+        };
+        FuturesContract::of_month("VN30", month)
+    }
+}
+
+impl std::str::FromStr for Tenor {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_uppercase();
+        Tenor::ALL.into_iter()
+            .find(|tenor| tenor.code() == value)
+            .ok_or_else(|| format!("Unknown futures tenor: {}", value))
+    }
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.day0() as i64)
+}
+
+fn next_month(first: NaiveDate) -> NaiveDate {
+    month_start(first + Duration::days(32))
+}
+
+/// Last trading day of the contract month starting on `first`: the third
+/// Thursday, or the trading day before it when that is a holiday.
+fn expiry_of(first: NaiveDate) -> NaiveDate {
+    let offset = (Weekday::Thu.num_days_from_monday() + 7 - first.weekday().num_days_from_monday()) % 7;
+    let third_thursday = first + Duration::days(offset as i64 + 14);
+    calendar::nth_trading_day_before(third_thursday, 0)
+}
+
+/// [`expiry_of`] for a contract month; `None` when `month` is not 1-12.
+pub fn expiry_date(year: i32, month: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1).map(expiry_of)
+}
+
+/// A dated index futures contract.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FuturesContract {
+    /// Legacy exchange code, e.g. "VN30F2406".
+    pub symbol: String,
+    pub underlying: String,
+    pub year: i32,
+    pub month: u32,
+    /// Last trading day; the contract settles against the underlying close.
+    pub expiry: NaiveDate,
+}
+
+impl FuturesContract {
+    /// `None` when `month` is not 1-12.
+    pub fn new(underlying: &str, year: i32, month: u32) -> Option<Self> {
+        NaiveDate::from_ymd_opt(year, month, 1).map(|first| Self::of_month(underlying, first))
+    }
+
+    fn of_month(underlying: &str, first: NaiveDate) -> Self {
+        FuturesContract {
+            symbol: format!("{}F{:02}{:02}", underlying.to_uppercase(), first.year() % 100, first.month()),
+            underlying: underlying.to_uppercase(),
+            year: first.year(),
+            month: first.month(),
+            expiry: expiry_of(first),
+        }
+    }
+
+    /// Parses legacy dated codes ("VN30F2406", "VN100F2412"). Rolling codes
+    /// need a date; see [`Tenor::contract`].
+    pub fn parse(symbol: &str) -> Option<Self> {
+        let symbol = symbol.trim().to_uppercase();
+        let (underlying, date) = symbol.rsplit_once('F')?;
+        if underlying.is_empty() || date.len() != 4 || !date.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let (year, month): (i32, u32) = (date[..2].parse().ok()?, date[2..].parse().ok()?);
+        FuturesContract::new(underlying, 2000 + year, month)
+    }
+
+    /// Trading days left until expiry, counting `as_of` and the expiry day.
+    pub fn days_to_expiry(&self, as_of: NaiveDate) -> usize {
+        calendar::trading_days_between(as_of, self.expiry).len()
+    }
+}
+
+/// The four listed VN30 contracts on `as_of`, by tenor.
+pub fn active_contracts(as_of: NaiveDate) -> Vec<(Tenor, FuturesContract)> {
+    Tenor::ALL.into_iter().map(|tenor| (tenor, tenor.contract(as_of))).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WarrantKind {
+    Call,
+    Put,
+}
+
+/// Covered warrant terms from the price board listing info.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoveredWarrant {
+    pub symbol: String,
+    pub underlying: String,
+    pub issuer: Option<String>,
+    pub kind: WarrantKind,
+    /// Exercise price in VND.
+    pub exercise_price: f64,
+    /// Warrants needed for one underlying share ("2:1" is 2.0).
+    pub exercise_ratio: f64,
+    pub first_trading_date: Option<NaiveDate>,
+    pub last_trading_date: Option<NaiveDate>,
+    pub maturity_date: Option<NaiveDate>,
+}
+
+impl CoveredWarrant {
+    /// Value per warrant if exercised at `underlying_price`.
+    pub fn intrinsic_value(&self, underlying_price: f64) -> f64 {
+        let payoff = match self.kind {
+            WarrantKind::Call => underlying_price - self.exercise_price,
+            WarrantKind::Put => self.exercise_price - underlying_price,
+        };
+        (payoff / self.exercise_ratio).max(0.0)
+    }
+
+    /// Underlying price at which a warrant bought at `warrant_price` breaks
+    /// even at maturity.
+    pub fn break_even(&self, warrant_price: f64) -> f64 {
+        match self.kind {
+            WarrantKind::Call => self.exercise_price + warrant_price * self.exercise_ratio,
+            WarrantKind::Put => self.exercise_price - warrant_price * self.exercise_ratio,
+        }
+    }
+}
+
+fn parse_date(value: Option<&Value>) -> Option<NaiveDate> {
+    let text = value?.as_str()?;
+    let date = text.split('T').next()?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(date, "%Y%m%d"))
+        .or_else(|_| NaiveDate::parse_from_str(date, "%d/%m/%Y"))
+        .ok()
+}
+
+/// Accepts "2:1", "2" or a number.
+fn parse_ratio(value: Option<&Value>) -> Option<f64> {
+    let value = value?;
+    if let Some(number) = value.as_f64() {
+        return Some(number);
+    }
+    let text = value.as_str()?.trim();
+    match text.split_once(':') {
+        Some((warrants, shares)) => Some(warrants.trim().parse::<f64>().ok()? / shares.trim().parse::<f64>().ok()?),
+        None => text.parse().ok(),
+    }
+}
+
+/// Parses one price-board row; `None` unless it carries warrant terms.
+pub(crate) fn parse_covered_warrant(row: &Value) -> Option<CoveredWarrant> {
+    let listing = row.get("listingInfo").unwrap_or(row);
+    let text = |key: &str| listing.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let number = |key: &str| listing.get(key).and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()));
+    let kind = match text("coveredWarrantType").as_deref().map(str::to_uppercase).as_deref() {
+        Some("P") | Some("PUT") => WarrantKind::Put,
+        _ => WarrantKind::Call,
+    };
+    Some(CoveredWarrant {
+        symbol: text("symbol")?.to_uppercase(),
+        underlying: text("underlyingSymbol")?.to_uppercase(),
+        issuer: text("issuerName").or_else(|| text("issuer")),
+        kind,
+        exercise_price: number("exercisePrice")?,
+        exercise_ratio: parse_ratio(listing.get("exerciseRatio")).filter(|ratio| *ratio > 0.0)?,
+        first_trading_date: parse_date(listing.get("firstTradingDate")),
+        last_trading_date: parse_date(listing.get("lastTradingDate")),
+        maturity_date: parse_date(listing.get("maturityDate")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contract_expiry_and_roll() {
+        assert_eq!(expiry_date(2024, 6), NaiveDate::from_ymd_opt(2024, 6, 20));
+        assert_eq!(expiry_date(2024, 13), None);
+        assert!(FuturesContract::parse("VN30F2413").is_none());
+        let contract = FuturesContract::parse("vn30f2406").unwrap();
+        assert_eq!((contract.underlying.as_str(), contract.year, contract.month), ("VN30", 2024, 6));
+        assert!(FuturesContract::parse("FPT").is_none());
+
+        let on_expiry = NaiveDate::from_ymd_opt(2024, 6, 20).unwrap();
+        assert_eq!(Tenor::OneMonth.contract(on_expiry).symbol, "VN30F2406");
+        let after = on_expiry + Duration::days(1);
+        let symbols: Vec<String> = active_contracts(after).into_iter().map(|(_, c)| c.symbol).collect();
+        assert_eq!(symbols, vec!["VN30F2407", "VN30F2408", "VN30F2409", "VN30F2412"]);
+        assert_eq!("vn30f2m".parse::<Tenor>(), Ok(Tenor::TwoMonth));
+    }
+
+    #[test]
+    fn test_parse_covered_warrant() {
+        let row = serde_json::json!({"listingInfo": {
+            "symbol": "CHPG2406", "underlyingSymbol": "HPG", "issuerName": "KIS",
+            "exercisePrice": 28000, "exerciseRatio": "2:1",
+            "maturityDate": "2024-12-30", "lastTradingDate": "2024-12-26"
+        }});
+        let warrant = parse_covered_warrant(&row).unwrap();
+        assert_eq!(warrant.exercise_ratio, 2.0);
+        assert_eq!(warrant.intrinsic_value(30000.0), 1000.0);
+        assert_eq!(warrant.break_even(1500.0), 31000.0);
+        assert_eq!(warrant.maturity_date, NaiveDate::from_ymd_opt(2024, 12, 30));
+        assert!(parse_covered_warrant(&serde_json::json!({"listingInfo": {"symbol": "HPG"}})).is_none());
+    }
+}
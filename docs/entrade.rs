@@ -0,0 +1,418 @@
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use futures::stream::{BoxStream, StreamExt};
+use reqwest::{Client, Error as ReqwestError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+
+use crate::calendar;
+use crate::circuit::CircuitBreaker;
+use crate::failover::Provider;
+use crate::models::{is_futures_symbol, vietnam_offset, DateRange, Index, Interval, Ohlcv};
+use crate::rate_limit::RateLimiter;
+use crate::resample;
+use crate::retry::{RequestError, Requester, RetryPolicy};
+use crate::stats::{self, ClientStats, LatencyHistogram, UsageReport};
+
+/// Stock prices come in thousand VND; they are scaled to VND to match the
+/// VCI and TCBS clients. Index points and futures prices are left as is.
+const STOCK_PRICE_SCALE: f64 = 1_000.0;
+
+#[derive(Debug)]
+pub enum EntradeError {
+    Http(ReqwestError),
+    Serialization(serde_json::Error),
+    InvalidInterval(String),
+    InvalidDateRange(String),
+    InvalidResponse(String),
+    /// The endpoint family has no equivalent for this request.
+    Unsupported(String),
+    /// The endpoint's circuit breaker is open; retry after the given delay.
+    CircuitOpen { endpoint: String, retry_in: StdDuration },
+    /// Non-success HTTP status, after any retries the policy allows.
+    Status(reqwest::StatusCode),
+    NoData,
+}
+
+impl From<ReqwestError> for EntradeError {
+    fn from(error: ReqwestError) -> Self {
+        EntradeError::Http(error)
+    }
+}
+
+impl From<RequestError> for EntradeError {
+    fn from(error: RequestError) -> Self {
+        match error {
+            RequestError::Status(status) => EntradeError::Status(status),
+            RequestError::Http(error) => EntradeError::Http(error),
+            RequestError::CircuitOpen { endpoint, retry_in } => EntradeError::CircuitOpen { endpoint, retry_in },
+        }
+    }
+}
+
+impl From<serde_json::Error> for EntradeError {
+    fn from(error: serde_json::Error) -> Self {
+        EntradeError::Serialization(error)
+    }
+}
+
+/// Chart series family, selecting the `ohlcs/<market>` endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Market {
+    Stock,
+    Index,
+    Derivative,
+}
+
+impl Market {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Market::Stock => "stock",
+            Market::Index => "index",
+            Market::Derivative => "derivative",
+        }
+    }
+
+    /// Indices by any provider's name, futures by their `VN30F`/`41I1`-style
+    /// codes, everything else as a stock.
+    pub fn for_symbol(symbol: &str) -> Self {
+        if Index::from_symbol(symbol).is_some() {
+            Market::Index
+        } else if is_futures_symbol(symbol) {
+            Market::Derivative
+        } else {
+            Market::Stock
+        }
+    }
+}
+
+/// Parses the chart API's column arrays (`t`, `o`, `h`, `l`, `c`, `v`).
+pub fn parse_ohlcs(data: &Value, symbol: &str, market: Market) -> Result<Vec<Ohlcv>, EntradeError> {
+    let column = |key: &str| {
+        data.get(key).and_then(|v| v.as_array()).ok_or_else(|| EntradeError::InvalidResponse(format!("Missing key: {}", key)))
+    };
+    let (times, opens, highs, lows, closes, volumes) = (column("t")?, column("o")?, column("h")?, column("l")?, column("c")?, column("v")?);
+    if [opens.len(), highs.len(), lows.len(), closes.len(), volumes.len()].iter().any(|&len| len != times.len()) {
+        return Err(EntradeError::InvalidResponse("Inconsistent array lengths".to_string()));
+    }
+
+    let scale = if market == Market::Stock { STOCK_PRICE_SCALE } else { 1.0 };
+    let price = |values: &[Value], i: usize| values[i].as_f64().unwrap_or(0.0) * scale;
+    let mut bars = Vec::with_capacity(times.len());
+    for i in 0..times.len() {
+        let timestamp = times[i].as_i64().ok_or_else(|| EntradeError::InvalidResponse(format!("Invalid timestamp at index {}", i)))?;
+        let time = Utc.timestamp_opt(timestamp, 0).single().ok_or_else(|| EntradeError::InvalidResponse(format!("Timestamp out of range: {}", timestamp)))?;
+        bars.push(Ohlcv {
+            time,
+            open: price(opens, i),
+            high: price(highs, i),
+            low: price(lows, i),
+            close: price(closes, i),
+            volume: volumes[i].as_f64().unwrap_or(0.0).max(0.0) as u64,
+            symbol: Some(symbol.to_uppercase()),
+            breakdown: None,
+            futures: None,
+        });
+    }
+    bars.sort_by_key(|bar| bar.time);
+    bars.dedup_by_key(|bar| bar.time);
+    Ok(bars)
+}
+
+/// DNSE/Entrade chart API client. Serves minute bars further back than
+/// TCBS, for stocks, indices and VN30 futures.
+pub struct EntradeClient {
+    client: Client,
+    base_url: String,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Arc<CircuitBreaker>,
+    stats: Arc<ClientStats>,
+}
+
+/// Configures an [`EntradeClient`]: HTTP timeouts, proxy, base URL and
+/// request budget. An injected `reqwest::Client` takes precedence over the
+/// timeout and proxy settings.
+pub struct EntradeClientBuilder {
+    rate_limit_per_minute: u32,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    timeout: StdDuration,
+    connect_timeout: Option<StdDuration>,
+    proxy: Option<String>,
+    http_client: Option<Client>,
+    base_url: Option<String>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl Default for EntradeClientBuilder {
+    fn default() -> Self {
+        EntradeClientBuilder {
+            rate_limit_per_minute: 6,
+            rate_limiter: None,
+            timeout: StdDuration::from_secs(30),
+            connect_timeout: None,
+            proxy: None,
+            http_client: None,
+            base_url: None,
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: None,
+        }
+    }
+}
+
+impl EntradeClientBuilder {
+    pub fn rate_limit(mut self, per_minute: u32) -> Self {
+        self.rate_limit_per_minute = per_minute;
+        self
+    }
+
+    /// Shared request budget; overrides [`Self::rate_limit`].
+    pub fn rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: StdDuration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: StdDuration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes all traffic through `url`, e.g. `http://proxy.corp:3128`.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Points the client at another host, e.g. a mock server in tests.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    pub fn build(self) -> Result<EntradeClient, EntradeError> {
+        let client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder().timeout(self.timeout);
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(proxy) = &self.proxy {
+                    builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+                }
+                builder.build()?
+            }
+        };
+        Ok(EntradeClient {
+            client,
+            base_url: self.base_url.unwrap_or_else(|| "https://services.entrade.com.vn".to_string()),
+            rate_limiter: self.rate_limiter.unwrap_or_else(|| Arc::new(RateLimiter::new(self.rate_limit_per_minute))),
+            retry_policy: self.retry_policy,
+            circuit_breaker: self.circuit_breaker.unwrap_or_default(),
+            stats: Arc::new(ClientStats::new()),
+        })
+    }
+}
+
+impl EntradeClient {
+    pub fn new(rate_limit_per_minute: u32) -> Result<Self, EntradeError> {
+        Self::builder().rate_limit(rate_limit_per_minute).build()
+    }
+
+    pub fn builder() -> EntradeClientBuilder {
+        EntradeClientBuilder::default()
+    }
+
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Shares `breaker` with this client. By default each client has its
+    /// own breaker opening after 5 consecutive failures for 30s.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = breaker;
+        self
+    }
+
+    pub fn circuit_breaker(&self) -> Arc<CircuitBreaker> {
+        Arc::clone(&self.circuit_breaker)
+    }
+
+    /// Latency histograms per endpoint for every request attempt so far.
+    pub fn stats(&self) -> std::collections::BTreeMap<String, LatencyHistogram> {
+        self.stats.snapshot()
+    }
+
+    /// Requests sent per day and endpoint.
+    pub fn usage_report(&self) -> UsageReport {
+        self.stats.usage(Provider::Entrade)
+    }
+
+    async fn get_json(&self, url: &str, params: &[(&str, String)]) -> Result<Value, EntradeError> {
+        let endpoint = stats::endpoint_key(url);
+        let requester = Requester {
+            policy: &self.retry_policy,
+            rate_limiter: &self.rate_limiter,
+            stats: &self.stats,
+            circuit_breaker: Some(&self.circuit_breaker),
+            chaos: None,
+        };
+        let build = || self.client.get(url).query(params).header("Accept", "application/json");
+        Ok(requester.send_json(&endpoint, &endpoint, build).await?)
+    }
+
+    /// Bars of `symbol` in `market` between two instants.
+    pub async fn get_bars(&self, market: Market, symbol: &str, from: DateTime<Utc>, to: DateTime<Utc>, interval: Interval) -> Result<Vec<Ohlcv>, EntradeError> {
+        let Some(resolution) = interval.entrade_resolution() else {
+            let daily = Box::pin(self.get_bars(market, symbol, from, to, Interval::D1)).await?;
+            return Ok(resample::resample(&daily, interval.as_str()));
+        };
+        let index = Index::from_symbol(symbol).filter(|_| market == Market::Index);
+        let code = index.map_or_else(|| symbol.to_uppercase(), |index| index.entrade_code().to_string());
+        let url = format!("{}/chart-api/v2/ohlcs/{}", self.base_url, market.as_str());
+        let params = [
+            ("symbol", code),
+            ("resolution", resolution.to_string()),
+            ("from", from.timestamp().to_string()),
+            ("to", to.timestamp().to_string()),
+        ];
+        let data = self.get_json(&url, &params).await?;
+        parse_ohlcs(&data, index.map_or(symbol, |index| index.as_str()), market)
+    }
+
+    /// Bars for `[start, end]` (`YYYY-MM-DD`, exchange dates) at `interval`,
+    /// with the market picked from the symbol.
+    pub async fn get_history(&self, symbol: &str, start: NaiveDate, end: Option<NaiveDate>, interval: impl AsRef<str>) -> Result<Vec<Ohlcv>, EntradeError> {
+        let interval: Interval = interval.as_ref().parse().map_err(EntradeError::InvalidInterval)?;
+        let range = DateRange::through(start, end).map_err(EntradeError::InvalidDateRange)?;
+        self.get_history_range(symbol, range, interval).await
+    }
+
+    pub async fn get_history_range(&self, symbol: &str, range: DateRange, interval: Interval) -> Result<Vec<Ohlcv>, EntradeError> {
+        let offset = vietnam_offset();
+        let from = offset.from_local_datetime(&range.start.and_hms_opt(0, 0, 0).unwrap()).unwrap().with_timezone(&Utc);
+        let to = offset.from_local_datetime(&range.end.and_hms_opt(23, 59, 59).unwrap()).unwrap().with_timezone(&Utc);
+        self.get_bars(Market::for_symbol(symbol), symbol, from, to, interval).await
+    }
+
+    /// History of a market index, labelled with its canonical symbol.
+    pub async fn get_index_history(&self, index: Index, start: NaiveDate, end: Option<NaiveDate>, interval: impl AsRef<str>) -> Result<Vec<Ohlcv>, EntradeError> {
+        self.get_history(index.as_str(), start, end, interval).await
+    }
+
+    /// The most recent bar at `interval`, including the one still forming.
+    pub async fn latest_bar(&self, symbol: &str, interval: Interval) -> Result<Option<Ohlcv>, EntradeError> {
+        let now = Utc::now();
+        let today = now.with_timezone(&vietnam_offset()).date_naive();
+        let first = calendar::nth_trading_day_before(today, if interval.is_intraday() { 3 } else { 30 });
+        let from = now - Duration::days((today - first).num_days() + 1);
+        Ok(self.get_bars(Market::for_symbol(symbol), symbol, from, now, interval).await?.pop())
+    }
+
+    /// Near-real-time bars of `symbol`, polled every `poll_interval`. Each
+    /// update of the forming bar is emitted, including its final state; the
+    /// poller stops once the stream is dropped.
+    pub fn subscribe_bars(self: Arc<Self>, symbol: &str, interval: Interval, poll_interval: StdDuration) -> BoxStream<'static, Ohlcv> {
+        let symbol = symbol.to_uppercase();
+        let (tx, rx) = mpsc::channel(64);
+        tokio::spawn(async move {
+            let mut last: Option<Ohlcv> = None;
+            loop {
+                match self.latest_bar(&symbol, interval).await {
+                    Ok(Some(bar)) if last.as_ref() != Some(&bar) => {
+                        last = Some(bar.clone());
+                        if tx.send(bar).await.is_err() {
+                            return;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Entrade bar poll failed for {}: {:?}", symbol, e),
+                }
+                tokio::select! {
+                    _ = sleep(poll_interval) => {}
+                    _ = tx.closed() => return,
+                }
+            }
+        });
+        futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|bar| (bar, rx)) }).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ohlcs_scales_stock_prices() {
+        let data = serde_json::json!({
+            "t": [1717984800, 1717984860],
+            "o": [25.5, 25.6], "h": [25.7, 25.6], "l": [25.4, 25.5], "c": [25.6, 25.55],
+            "v": [12000, 8000],
+            "nextTime": 0
+        });
+        let bars = parse_ohlcs(&data, "hpg", Market::Stock).unwrap();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].close, 25_600.0);
+        assert_eq!(bars[1].volume, 8000);
+        assert_eq!(bars[0].symbol.as_deref(), Some("HPG"));
+
+        let index = parse_ohlcs(&data, "VNINDEX", Market::Index).unwrap();
+        assert_eq!(index[0].close, 25.6);
+        assert_eq!(Market::for_symbol("vn30f2406"), Market::Derivative);
+        assert_eq!(Market::for_symbol("VN30"), Market::Index);
+        assert!(parse_ohlcs(&serde_json::json!({"t": [1]}), "HPG", Market::Stock).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_open_breaker_short_circuits_requests() {
+        let breaker = Arc::new(CircuitBreaker::new(crate::circuit::CircuitConfig {
+            failure_threshold: 1,
+            cooldown: StdDuration::from_secs(60),
+        }));
+        breaker.record_failure("chart-api/v2/ohlcs/stock");
+        let client = EntradeClient::builder().base_url("http://127.0.0.1:9").circuit_breaker(breaker).build().unwrap();
+        let to = Utc::now();
+        let result = client.get_bars(Market::Stock, "HPG", to - Duration::days(1), to, Interval::M1).await;
+        assert!(matches!(result, Err(EntradeError::CircuitOpen { .. })));
+    }
+}
@@ -0,0 +1,266 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::calendar;
+use crate::correlation::aligned_returns;
+use crate::index_membership::MembershipPeriod;
+use crate::models::Ohlcv;
+use crate::provider::{ProviderError, StockDataProvider};
+use crate::vci::CorporateEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum StudyEventType {
+    ExDividend,
+    Earnings,
+    IndexInclusion,
+    IndexExclusion,
+}
+
+/// An event to study. `date` is the announcement or effective date; when it
+/// falls on a non-trading day the next session counts as day 0.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct StudyEvent {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub event_type: StudyEventType,
+}
+
+impl StudyEvent {
+    pub fn new(symbol: &str, date: NaiveDate, event_type: StudyEventType) -> Self {
+        StudyEvent { symbol: symbol.to_uppercase(), date, event_type }
+    }
+}
+
+/// Cash-dividend ex-dates from VCI corporate events.
+pub fn ex_dividend_events(events: &[CorporateEvent]) -> Vec<StudyEvent> {
+    events
+        .iter()
+        .filter(|event| event.is_cash_dividend())
+        .filter_map(|event| {
+            let date = NaiveDate::parse_from_str(event.exright_date.as_deref()?.get(..10)?, "%Y-%m-%d").ok()?;
+            Some(StudyEvent::new(&event.symbol, date, StudyEventType::ExDividend))
+        })
+        .collect()
+}
+
+/// Inclusion and exclusion dates from recorded index membership.
+pub fn index_events(periods: &[MembershipPeriod], index: &str) -> Vec<StudyEvent> {
+    periods
+        .iter()
+        .filter(|period| period.index.eq_ignore_ascii_case(index))
+        .flat_map(|period| {
+            let inclusion = StudyEvent::new(&period.symbol, period.start, StudyEventType::IndexInclusion);
+            let exclusion = period.end.map(|end| StudyEvent::new(&period.symbol, end, StudyEventType::IndexExclusion));
+            std::iter::once(inclusion).chain(exclusion)
+        })
+        .collect()
+}
+
+/// Event window `[-pre, +post]` in sessions, and a market-model estimation
+/// window of `estimation` sessions ending `gap` sessions before the event
+/// window. With `estimation == 0` abnormal returns are simply market-adjusted.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EventStudyConfig {
+    pub pre: usize,
+    pub post: usize,
+    pub estimation: usize,
+    pub gap: usize,
+}
+
+impl Default for EventStudyConfig {
+    fn default() -> Self {
+        EventStudyConfig { pre: 5, post: 10, estimation: 120, gap: 10 }
+    }
+}
+
+impl EventStudyConfig {
+    fn lookback(&self) -> usize {
+        self.pre + self.gap + self.estimation + 1
+    }
+
+    /// Calendar range of daily bars needed to study `events`.
+    pub fn data_range(&self, events: &[StudyEvent]) -> Option<(NaiveDate, NaiveDate)> {
+        let first = events.iter().map(|e| e.date).min()?;
+        let last = events.iter().map(|e| e.date).max()?;
+        // Holidays can stretch a session count well past 7/5 calendar days
+        Some((calendar::nth_trading_day_before(first, self.lookback()), last + Duration::days(self.post as i64 * 2 + 14)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventStudyResult {
+    /// Session offsets from the event, `-pre..=post`.
+    pub offsets: Vec<i64>,
+    /// Average abnormal return (AAR) per offset.
+    pub average_abnormal: Vec<f64>,
+    /// Cumulative average abnormal return (CAAR) per offset.
+    pub cumulative: Vec<f64>,
+    /// Cross-sectional t-statistic of the AAR; `None` with fewer than two events.
+    pub t_stats: Vec<Option<f64>>,
+    pub events_used: Vec<StudyEvent>,
+    /// Events without enough data around them.
+    pub skipped: Vec<StudyEvent>,
+}
+
+impl EventStudyResult {
+    pub fn abnormal_at(&self, offset: i64) -> Option<f64> {
+        self.offsets.iter().position(|o| *o == offset).map(|i| self.average_abnormal[i])
+    }
+}
+
+/// Abnormal returns of one event over the window, or `None` when any window
+/// return or too much of the estimation period is missing.
+fn abnormal_returns(stock: &[Option<f64>], market: &[Option<f64>], day0: usize, config: &EventStudyConfig) -> Option<Vec<f64>> {
+    // Return index `k` is the return into trading day `k + 1`
+    let window_start = day0.checked_sub(config.pre + 1)?;
+    let window_end = window_start + config.pre + config.post;
+
+    let (alpha, beta) = if config.estimation == 0 {
+        (0.0, 1.0)
+    } else {
+        let end = window_start.checked_sub(config.gap)?;
+        let start = end.checked_sub(config.estimation)?;
+        let pairs: Vec<(f64, f64)> = (start..end).filter_map(|k| Some(((*market.get(k)?)?, (*stock.get(k)?)?))).collect();
+        if pairs.len() * 2 < config.estimation {
+            return None;
+        }
+        let n = pairs.len() as f64;
+        let (mean_m, mean_s) = (pairs.iter().map(|p| p.0).sum::<f64>() / n, pairs.iter().map(|p| p.1).sum::<f64>() / n);
+        let var: f64 = pairs.iter().map(|p| (p.0 - mean_m).powi(2)).sum();
+        let cov: f64 = pairs.iter().map(|p| (p.0 - mean_m) * (p.1 - mean_s)).sum();
+        let beta = if var > 0.0 { cov / var } else { 1.0 };
+        (mean_s - beta * mean_m, beta)
+    };
+
+    (window_start..=window_end)
+        .map(|k| Some((*stock.get(k)?)? - alpha - beta * (*market.get(k)?)?))
+        .collect()
+}
+
+/// Market-model event study over daily bars. `series` maps symbols to their
+/// bars; `benchmark` is the market index (usually VNINDEX).
+pub fn event_study(
+    events: &[StudyEvent],
+    series: &BTreeMap<String, Vec<Ohlcv>>,
+    benchmark: &[Ohlcv],
+    config: EventStudyConfig,
+) -> EventStudyResult {
+    let offsets: Vec<i64> = (-(config.pre as i64)..=config.post as i64).collect();
+    let mut result = EventStudyResult {
+        average_abnormal: vec![0.0; offsets.len()],
+        cumulative: vec![0.0; offsets.len()],
+        t_stats: vec![None; offsets.len()],
+        offsets,
+        events_used: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let Some((from, to)) = config.data_range(events) else {
+        return result;
+    };
+    let days = calendar::trading_days_between(from, to);
+    let market = aligned_returns(benchmark, &days);
+
+    let mut returns_by_symbol: BTreeMap<&str, Vec<Option<f64>>> = BTreeMap::new();
+    let mut samples: Vec<Vec<f64>> = Vec::new();
+    for event in events {
+        let abnormal = series.get(&event.symbol).and_then(|bars| {
+            let stock = returns_by_symbol.entry(&event.symbol).or_insert_with(|| aligned_returns(bars, &days));
+            let day0 = days.iter().position(|day| *day >= event.date)?;
+            abnormal_returns(stock, &market, day0, &config)
+        });
+        match abnormal {
+            Some(abnormal) => {
+                samples.push(abnormal);
+                result.events_used.push(event.clone());
+            }
+            None => result.skipped.push(event.clone()),
+        }
+    }
+    if samples.is_empty() {
+        return result;
+    }
+
+    let n = samples.len() as f64;
+    let mut cumulative = 0.0;
+    for i in 0..result.offsets.len() {
+        let mean = samples.iter().map(|s| s[i]).sum::<f64>() / n;
+        cumulative += mean;
+        result.average_abnormal[i] = mean;
+        result.cumulative[i] = cumulative;
+        if samples.len() > 1 {
+            let sd = (samples.iter().map(|s| (s[i] - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+            result.t_stats[i] = (sd > 0.0).then(|| mean / (sd / n.sqrt()));
+        }
+    }
+    result
+}
+
+/// Fetches daily history for every event symbol and the benchmark, then
+/// runs [`event_study`].
+pub async fn run_event_study(
+    provider: &dyn StockDataProvider,
+    events: &[StudyEvent],
+    benchmark: &str,
+    config: EventStudyConfig,
+) -> Result<EventStudyResult, ProviderError> {
+    let Some((from, to)) = config.data_range(events) else {
+        return Ok(event_study(events, &BTreeMap::new(), &[], config));
+    };
+    let benchmark_bars = provider.get_history(benchmark, from, Some(to), "1D").await?;
+    let mut series = BTreeMap::new();
+    for symbol in events.iter().map(|e| e.symbol.clone()).collect::<BTreeSet<_>>() {
+        let bars = provider.get_history(&symbol, from, Some(to), "1D").await?;
+        series.insert(symbol, bars);
+    }
+    Ok(event_study(events, &series, &benchmark_bars, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn bars(days: &[NaiveDate], close: impl Fn(usize) -> f64) -> Vec<Ohlcv> {
+        days.iter()
+            .enumerate()
+            .map(|(i, day)| {
+                let close = close(i);
+                Ohlcv {
+                    time: Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0).unwrap()),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 0,
+                    symbol: None,
+                    breakdown: None,
+                    futures: None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_event_study_market_adjusted() {
+        let days = calendar::trading_days_between(NaiveDate::from_ymd_opt(2024, 5, 2).unwrap(), NaiveDate::from_ymd_opt(2024, 7, 31).unwrap());
+        let event_day = days.iter().position(|d| *d == NaiveDate::from_ymd_opt(2024, 6, 10).unwrap()).unwrap();
+        // Market flat; the stock jumps 5% on the event day and is flat otherwise
+        let market = bars(&days, |_| 1000.0);
+        let stock = bars(&days, |i| if i >= event_day { 105.0 } else { 100.0 });
+        let series = BTreeMap::from([("FPT".to_string(), stock)]);
+        let events = vec![
+            // Saturday: day 0 rolls to Monday the 10th
+            StudyEvent::new("fpt", NaiveDate::from_ymd_opt(2024, 6, 8).unwrap(), StudyEventType::Earnings),
+            StudyEvent::new("VNM", NaiveDate::from_ymd_opt(2024, 6, 10).unwrap(), StudyEventType::Earnings),
+        ];
+        let config = EventStudyConfig { pre: 2, post: 3, estimation: 0, gap: 0 };
+
+        let result = event_study(&events, &series, &market, config);
+        assert_eq!(result.events_used.len(), 1);
+        assert_eq!(result.skipped[0].symbol, "VNM");
+        assert!((result.abnormal_at(0).unwrap() - 0.05).abs() < 1e-9);
+        assert_eq!(result.abnormal_at(-1), Some(0.0));
+        assert!((result.cumulative.last().unwrap() - 0.05).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::models::{Interval, Ohlcv, Quote};
+
+/// Something that happened inside the crate that other components may react to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    QuoteUpdated(Quote),
+    BarClosed {
+        interval: Interval,
+        bar: Ohlcv,
+    },
+    AlertFired {
+        name: String,
+        symbol: String,
+        message: String,
+        time: DateTime<Utc>,
+    },
+    /// A store or export sync for `symbol` finished, writing `bars` bars.
+    SyncCompleted {
+        symbol: String,
+        interval: Interval,
+        bars: usize,
+        time: DateTime<Utc>,
+    },
+    /// A provider is failing or slow; `provider` is "vci" or "tcbs".
+    ProviderDegraded {
+        provider: String,
+        reason: String,
+        time: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    QuoteUpdated,
+    BarClosed,
+    AlertFired,
+    SyncCompleted,
+    ProviderDegraded,
+}
+
+impl Event {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::QuoteUpdated(_) => EventKind::QuoteUpdated,
+            Event::BarClosed { .. } => EventKind::BarClosed,
+            Event::AlertFired { .. } => EventKind::AlertFired,
+            Event::SyncCompleted { .. } => EventKind::SyncCompleted,
+            Event::ProviderDegraded { .. } => EventKind::ProviderDegraded,
+        }
+    }
+
+    /// Symbol the event is about, if any.
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            Event::QuoteUpdated(quote) => Some(&quote.symbol),
+            Event::BarClosed { bar, .. } => bar.symbol.as_deref(),
+            Event::AlertFired { symbol, .. } | Event::SyncCompleted { symbol, .. } => Some(symbol),
+            Event::ProviderDegraded { .. } => None,
+        }
+    }
+}
+
+/// In-process publish/subscribe bus. Cloning shares the same bus; every
+/// subscriber sees every event published after it subscribed.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl EventBus {
+    /// `capacity` events are buffered per slow subscriber before it skips ahead.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        EventBus { sender }
+    }
+
+    /// Returns how many subscribers received the event.
+    pub fn publish(&self, event: Event) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    pub fn subscribe(&self) -> BoxStream<'static, Event> {
+        let receiver = self.sender.subscribe();
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Event subscriber lagged, skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Only events of the given kinds.
+    pub fn subscribe_to(&self, kinds: &[EventKind]) -> BoxStream<'static, Event> {
+        let kinds = kinds.to_vec();
+        self.subscribe()
+            .filter(move |event| futures::future::ready(kinds.contains(&event.kind())))
+            .boxed()
+    }
+
+    /// Publishes every item of `items` as an event until the stream ends.
+    pub fn forward<S, T, F>(&self, items: S, to_event: F) -> tokio::task::JoinHandle<()>
+    where
+        S: Stream<Item = T> + Send + 'static,
+        T: Send + 'static,
+        F: Fn(T) -> Event + Send + 'static,
+    {
+        let bus = self.clone();
+        tokio::spawn(async move {
+            let mut items = Box::pin(items);
+            while let Some(item) = items.next().await {
+                bus.publish(to_event(item));
+            }
+        })
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn degraded() -> Event {
+        Event::ProviderDegraded {
+            provider: "vci".to_string(),
+            reason: "timeout".to_string(),
+            time: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filtered_subscription() {
+        let bus = EventBus::default();
+        let mut alerts = bus.subscribe_to(&[EventKind::AlertFired]);
+        let mut all = bus.subscribe();
+
+        bus.publish(degraded());
+        bus.publish(Event::AlertFired {
+            name: "breakout".to_string(),
+            symbol: "FPT".to_string(),
+            message: "above 120".to_string(),
+            time: Utc::now(),
+        });
+
+        assert_eq!(all.next().await.unwrap().kind(), EventKind::ProviderDegraded);
+        let alert = alerts.next().await.unwrap();
+        assert_eq!(alert.symbol(), Some("FPT"));
+    }
+}
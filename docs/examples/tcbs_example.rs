@@ -1,11 +1,12 @@
-use vietnam_stock_clients::{TcbsClient, TcbsError};
+use chrono::NaiveDate;
+use vietnam_stock_clients::{Interval, TcbsClient, TcbsError};
 
 #[tokio::main]
 async fn main() -> Result<(), TcbsError> {
     println!("TCBS Client Example");
     println!("===================");
 
-    let mut client = TcbsClient::new(true, 6)?;
+    let client = TcbsClient::new(true, 6)?;
     let test_symbol = "VCI";
 
     // 1. Test company info
@@ -75,7 +76,7 @@ async fn main() -> Result<(), TcbsError> {
     println!("\n📈 Historical Data for {}", test_symbol);
     println!("{}", "-".repeat(40));
     
-    match client.get_history(test_symbol, "2025-08-01", Some("2025-08-13"), "1D", 365).await {
+    match client.get_history(test_symbol, NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(), NaiveDate::from_ymd_opt(2025, 8, 13), Interval::D1, 365).await {
         Ok(data) => {
             let data_count = data.len();
             println!("✅ Success! Retrieved {} data points", data_count);
@@ -99,32 +100,27 @@ async fn main() -> Result<(), TcbsError> {
 
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
-    // 4. Batch Historical Data (COMMENTED OUT - NOT EFFICIENT)
-    // println!("\n📊 Batch Historical Data (3 symbols - latest day)");
-    // println!("{}", "-".repeat(40));
-    // 
-    // let test_symbols = vec!["VCI".to_string(), "TCB".to_string(), "FPT".to_string()];
-    // match client.get_batch_history(&test_symbols, "2025-08-14", Some("2025-08-14"), "1D", 365).await {
-    //     Ok(batch_data) => {
-    //         println!("✅ Batch request successful for {} symbols!", test_symbols.len());
-    //         println!("📈 Latest closing prices:");
-    //         println!("{}", "-".repeat(40));
-    //         
-    //         for symbol in &test_symbols {
-    //             if let Some(Some(data)) = batch_data.get(symbol) {
-    //                 if let Some(latest) = data.last() {
-    //                     println!("  {}: {:.0} VND", symbol, latest.close);
-    //                 }
-    //             } else {
-    //                 println!("  {}: ❌ No data", symbol);
-    //             }
-    //         }
-    //     }
-    //     Err(e) => println!("❌ Batch request failed: {:?}", e),
-    // }
-    println!("\n📊 Step 4: Batch Historical Data (SKIPPED)");
+    // 4. Batch Historical Data
+    println!("\n📊 Batch Historical Data (3 symbols - latest day)");
     println!("{}", "-".repeat(40));
-    println!("❌ Batch history method commented out - it just calls single history 10 times, not efficient.");
+
+    let test_symbols = vec!["VCI".to_string(), "TCB".to_string(), "FPT".to_string()];
+    match client.get_batch_history(&test_symbols, "2025-08-14", Some("2025-08-14"), "1D", 365, 3).await {
+        Ok(batch_data) => {
+            println!("✅ Batch request successful for {} symbols!", test_symbols.len());
+            println!("📈 Latest closing prices:");
+            println!("{}", "-".repeat(40));
+
+            for symbol in &test_symbols {
+                match batch_data.get(symbol) {
+                    Some(Ok(data)) if !data.is_empty() => println!("  {}: {:.0} VND", symbol, data.last().unwrap().close),
+                    Some(Err(e)) => println!("  {}: ❌ {:?}", symbol, e),
+                    _ => println!("  {}: ❌ No data", symbol),
+                }
+            }
+        }
+        Err(e) => println!("❌ Batch request failed: {:?}", e),
+    }
 
     println!("\n{}", "=".repeat(60));
     println!("✅ TCBS CLIENT EXAMPLE COMPLETED");
@@ -1,11 +1,12 @@
-use vietnam_stock_clients::{VciClient, VciError};
+use chrono::NaiveDate;
+use vietnam_stock_clients::{Interval, VciClient, VciError};
 
 #[tokio::main]
 async fn main() -> Result<(), VciError> {
     println!("VCI Client Example");
     println!("==================");
 
-    let mut client = VciClient::new(true, 6)?;
+    let client = VciClient::new(true, 6)?;
     let test_symbol = "VCI";
 
     // 1. Test company info
@@ -39,7 +40,7 @@ async fn main() -> Result<(), VciError> {
     println!("\n📈 Historical Data for {}", test_symbol);
     println!("{}", "-".repeat(40));
     
-    match client.get_history(test_symbol, "2025-08-01", Some("2025-08-13"), "1D").await {
+    match client.get_history(test_symbol, NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(), NaiveDate::from_ymd_opt(2025, 8, 13), Interval::D1).await {
         Ok(data) => {
             let data_count = data.len();
             println!("✅ Success! Retrieved {} data points", data_count);
@@ -0,0 +1,483 @@
+use chrono::{NaiveDate, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::models::{vietnam_offset, Ohlcv};
+use crate::provenance::Provenance;
+
+/// Lean stores equity prices as integers scaled by 10,000 ("deci-cents").
+const LEAN_PRICE_SCALE: f64 = 10_000.0;
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Zip(zip::result::ZipError),
+    UnsupportedInterval(String),
+    NoData,
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(error: std::io::Error) -> Self {
+        ExportError::Io(error)
+    }
+}
+
+impl From<zip::result::ZipError> for ExportError {
+    fn from(error: zip::result::ZipError) -> Self {
+        ExportError::Zip(error)
+    }
+}
+
+fn is_intraday(interval: &str) -> bool {
+    matches!(interval, "1m" | "5m" | "15m" | "30m" | "1H")
+}
+
+fn lean_price(price: f64) -> i64 {
+    (price * LEAN_PRICE_SCALE).round() as i64
+}
+
+fn write_zip_entry(path: &Path, entry_name: &str, contents: &str) -> Result<(), ExportError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut zip = ZipWriter::new(fs::File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file(entry_name, options)?;
+    zip.write_all(contents.as_bytes())?;
+    zip.finish()?;
+    Ok(())
+}
+
+/// Writes `<file>.provenance.json` next to each exported file and returns
+/// the sidecar paths. The exporters call this themselves when given a
+/// provenance record.
+pub fn write_provenance(paths: &[PathBuf], provenance: &Provenance) -> Result<Vec<PathBuf>, ExportError> {
+    let json = serde_json::to_string_pretty(provenance).map_err(std::io::Error::other)?;
+    paths.iter()
+        .map(|path| {
+            let mut name = path.file_name().unwrap_or_default().to_os_string();
+            name.push(".provenance.json");
+            let sidecar = path.with_file_name(name);
+            fs::write(&sidecar, &json)?;
+            Ok(sidecar)
+        })
+        .collect()
+}
+
+/// Writes bars in QuantConnect Lean's equity layout under `data_dir`
+/// (the folder Lean's `data-folder` points at) and returns the written files.
+///
+/// - `1D` → `equity/<market>/daily/<symbol>.zip`
+/// - `1H` → `equity/<market>/hour/<symbol>.zip`
+/// - `1m` → `equity/<market>/minute/<symbol>/<yyyyMMdd>_trade.zip`, one per day
+///
+/// Intraday times are written in exchange time (UTC+7), as Lean expects.
+pub fn export_lean(
+    data_dir: impl AsRef<Path>,
+    market: &str,
+    symbol: &str,
+    interval: &str,
+    bars: &[Ohlcv],
+    provenance: Option<&Provenance>,
+) -> Result<Vec<PathBuf>, ExportError> {
+    if bars.is_empty() {
+        return Err(ExportError::NoData);
+    }
+
+    let paths = write_lean(data_dir.as_ref(), market, symbol, interval, bars)?;
+    if let Some(provenance) = provenance {
+        write_provenance(&paths, provenance)?;
+    }
+    Ok(paths)
+}
+
+fn write_lean(data_dir: &Path, market: &str, symbol: &str, interval: &str, bars: &[Ohlcv]) -> Result<Vec<PathBuf>, ExportError> {
+    let symbol = symbol.to_lowercase();
+    let market_dir = data_dir.join("equity").join(market.to_lowercase());
+
+    match interval {
+        "1D" | "1H" => {
+            let resolution = if interval == "1D" { "daily" } else { "hour" };
+            let mut csv = String::new();
+            for bar in bars {
+                let stamp = if interval == "1D" {
+                    bar.time.with_timezone(&vietnam_offset()).format("%Y%m%d 00:00").to_string()
+                } else {
+                    bar.time.with_timezone(&vietnam_offset()).format("%Y%m%d %H:%M").to_string()
+                };
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    stamp,
+                    lean_price(bar.open),
+                    lean_price(bar.high),
+                    lean_price(bar.low),
+                    lean_price(bar.close),
+                    bar.volume
+                ));
+            }
+
+            let path = market_dir.join(resolution).join(format!("{}.zip", symbol));
+            write_zip_entry(&path, &format!("{}.csv", symbol), &csv)?;
+            Ok(vec![path])
+        }
+        "1m" => {
+            let mut days: BTreeMap<NaiveDate, String> = BTreeMap::new();
+            for bar in bars {
+                let local = bar.time.with_timezone(&vietnam_offset());
+                let millis = local.num_seconds_from_midnight() as u64 * 1000;
+                days.entry(local.date_naive()).or_default().push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    millis,
+                    lean_price(bar.open),
+                    lean_price(bar.high),
+                    lean_price(bar.low),
+                    lean_price(bar.close),
+                    bar.volume
+                ));
+            }
+
+            let symbol_dir = market_dir.join("minute").join(&symbol);
+            let mut paths = Vec::new();
+            for (day, csv) in days {
+                let day = day.format("%Y%m%d").to_string();
+                let path = symbol_dir.join(format!("{}_trade.zip", day));
+                write_zip_entry(&path, &format!("{}_{}_minute_trade.csv", day, symbol), &csv)?;
+                paths.push(path);
+            }
+            Ok(paths)
+        }
+        _ => Err(ExportError::UnsupportedInterval(interval.to_string())),
+    }
+}
+
+/// Writes `<dir>/<SYMBOL>.csv` in the column order read by Backtrader's
+/// `GenericCSVData` defaults (`datetime,open,high,low,close,volume,openinterest`,
+/// `dtformat='%Y-%m-%d %H:%M:%S'`).
+pub fn export_backtrader(
+    dir: impl AsRef<Path>,
+    symbol: &str,
+    interval: &str,
+    bars: &[Ohlcv],
+    provenance: Option<&Provenance>,
+) -> Result<PathBuf, ExportError> {
+    if bars.is_empty() {
+        return Err(ExportError::NoData);
+    }
+
+    fs::create_dir_all(dir.as_ref())?;
+    let path = dir.as_ref().join(format!("{}.csv", symbol.to_uppercase()));
+    let mut writer = BufWriter::new(fs::File::create(&path)?);
+    writeln!(writer, "datetime,open,high,low,close,volume,openinterest")?;
+    for bar in bars {
+        let stamp = if is_intraday(interval) {
+            bar.time.with_timezone(&vietnam_offset()).format("%Y-%m-%d %H:%M:%S").to_string()
+        } else {
+            bar.time.with_timezone(&vietnam_offset()).format("%Y-%m-%d 00:00:00").to_string()
+        };
+        writeln!(writer, "{},{},{},{},{},{},0", stamp, bar.open, bar.high, bar.low, bar.close, bar.volume)?;
+    }
+    writer.flush()?;
+    if let Some(provenance) = provenance {
+        write_provenance(std::slice::from_ref(&path), provenance)?;
+    }
+    Ok(path)
+}
+
+/// Writes a zipline `csvdir` bundle file: `<dir>/daily/<SYMBOL>.csv` for `1D`
+/// or `<dir>/minute/<SYMBOL>.csv` for `1m`, with columns
+/// `date,open,high,low,close,volume,dividend,split`. Minute timestamps are UTC.
+pub fn export_zipline(
+    dir: impl AsRef<Path>,
+    symbol: &str,
+    interval: &str,
+    bars: &[Ohlcv],
+    provenance: Option<&Provenance>,
+) -> Result<PathBuf, ExportError> {
+    if bars.is_empty() {
+        return Err(ExportError::NoData);
+    }
+
+    let frequency = match interval {
+        "1D" => "daily",
+        "1m" => "minute",
+        _ => return Err(ExportError::UnsupportedInterval(interval.to_string())),
+    };
+
+    let bundle_dir = dir.as_ref().join(frequency);
+    fs::create_dir_all(&bundle_dir)?;
+    let path = bundle_dir.join(format!("{}.csv", symbol.to_uppercase()));
+    let mut writer = BufWriter::new(fs::File::create(&path)?);
+    writeln!(writer, "date,open,high,low,close,volume,dividend,split")?;
+    for bar in bars {
+        let local = bar.time.with_timezone(&vietnam_offset());
+        let stamp = if frequency == "daily" {
+            local.format("%Y-%m-%d").to_string()
+        } else {
+            local.format("%Y-%m-%d %H:%M:%S").to_string()
+        };
+        writeln!(writer, "{},{},{},{},{},{},0.0,1.0", stamp, bar.open, bar.high, bar.low, bar.close, bar.volume)?;
+    }
+    writer.flush()?;
+    if let Some(provenance) = provenance {
+        write_provenance(std::slice::from_ref(&path), provenance)?;
+    }
+    Ok(path)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetaTraderFormat {
+    /// History Center import: `YYYY.MM.DD,HH:MM,open,high,low,close,volume`, no header.
+    Mt4,
+    /// Custom-symbol bar import: tab-separated with `<DATE>\t<TIME>...` header.
+    Mt5,
+}
+
+/// MetaTrader timeframe in minutes, which MT4 uses in history file names.
+fn metatrader_period(interval: &str) -> Option<u32> {
+    match interval {
+        "1m" => Some(1),
+        "5m" => Some(5),
+        "15m" => Some(15),
+        "30m" => Some(30),
+        "1H" => Some(60),
+        "1D" => Some(1440),
+        "1W" => Some(10080),
+        "1M" => Some(43200),
+        _ => None,
+    }
+}
+
+/// Writes `<dir>/<SYMBOL><period>.csv` (e.g. `FPT1440.csv`, `FPT1.csv`) ready
+/// for MetaTrader's bar import. Intraday bars are stamped in exchange time;
+/// daily and coarser bars use their trading date at `00:00`.
+pub fn export_metatrader(
+    dir: impl AsRef<Path>,
+    symbol: &str,
+    interval: &str,
+    bars: &[Ohlcv],
+    format: MetaTraderFormat,
+    provenance: Option<&Provenance>,
+) -> Result<PathBuf, ExportError> {
+    if bars.is_empty() {
+        return Err(ExportError::NoData);
+    }
+    let period = metatrader_period(interval)
+        .ok_or_else(|| ExportError::UnsupportedInterval(interval.to_string()))?;
+
+    fs::create_dir_all(dir.as_ref())?;
+    let path = dir.as_ref().join(format!("{}{}.csv", symbol.to_uppercase(), period));
+    let mut writer = BufWriter::new(fs::File::create(&path)?);
+    if format == MetaTraderFormat::Mt5 {
+        writeln!(writer, "<DATE>\t<TIME>\t<OPEN>\t<HIGH>\t<LOW>\t<CLOSE>\t<TICKVOL>\t<VOL>\t<SPREAD>")?;
+    }
+
+    for bar in bars {
+        let (date, time) = if is_intraday(interval) {
+            let local = bar.time.with_timezone(&vietnam_offset());
+            (local.format("%Y.%m.%d").to_string(), local.format("%H:%M:%S").to_string())
+        } else {
+            (bar.time.with_timezone(&vietnam_offset()).format("%Y.%m.%d").to_string(), "00:00:00".to_string())
+        };
+
+        match format {
+            MetaTraderFormat::Mt4 => writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                date, &time[..5], bar.open, bar.high, bar.low, bar.close, bar.volume
+            )?,
+            MetaTraderFormat::Mt5 => writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t0",
+                date, time, bar.open, bar.high, bar.low, bar.close, bar.volume, bar.volume
+            )?,
+        }
+    }
+    writer.flush()?;
+    if let Some(provenance) = provenance {
+        write_provenance(std::slice::from_ref(&path), provenance)?;
+    }
+    Ok(path)
+}
+
+/// Candlestick point in lightweight-charts' `CandlestickData` shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LwCandle {
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+/// Histogram point in lightweight-charts' `HistogramData` shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LwVolume {
+    pub time: i64,
+    pub value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightweightChartsOptions {
+    /// Lightweight-charts renders `UTCTimestamp` as UTC wall-clock. When set,
+    /// times are shifted by +7h so intraday bars display in exchange time.
+    pub exchange_time: bool,
+    pub up_color: Option<String>,
+    pub down_color: Option<String>,
+    /// Copied onto the series so the chart can show the attribution.
+    pub provenance: Option<Provenance>,
+}
+
+impl Default for LightweightChartsOptions {
+    fn default() -> Self {
+        LightweightChartsOptions {
+            exchange_time: true,
+            up_color: Some("#26a69a".to_string()),
+            down_color: Some("#ef5350".to_string()),
+            provenance: None,
+        }
+    }
+}
+
+/// Candle and volume series ready for `series.setData(...)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightweightSeries {
+    pub candles: Vec<LwCandle>,
+    pub volume: Vec<LwVolume>,
+    /// From [`LightweightChartsOptions::provenance`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+/// Converts bars to lightweight-charts series. Times are UTC seconds, sorted
+/// ascending and deduplicated (the library rejects repeated timestamps).
+pub fn to_lightweight_charts(bars: &[Ohlcv], options: &LightweightChartsOptions) -> LightweightSeries {
+    let shift = if options.exchange_time { crate::models::VIETNAM_UTC_OFFSET_SECS as i64 } else { 0 };
+
+    let mut by_time: BTreeMap<i64, &Ohlcv> = BTreeMap::new();
+    for bar in bars {
+        by_time.insert(bar.time.timestamp() + shift, bar);
+    }
+
+    let mut series = LightweightSeries {
+        candles: Vec::with_capacity(by_time.len()),
+        volume: Vec::with_capacity(by_time.len()),
+        provenance: options.provenance.clone(),
+    };
+    for (time, bar) in by_time {
+        series.candles.push(LwCandle {
+            time,
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+        });
+        let color = if bar.close >= bar.open { &options.up_color } else { &options.down_color };
+        series.volume.push(LwVolume {
+            time,
+            value: bar.volume as f64,
+            color: color.clone(),
+        });
+    }
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::io::Read;
+
+    fn bar(hour: u32, minute: u32, close: f64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap(),
+            open: close,
+            high: close + 100.0,
+            low: close - 100.0,
+            close,
+            volume: 1000,
+            symbol: Some("FPT".to_string()),
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_lean_minute_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        // 02:15 UTC is 09:15 in Hanoi
+        let provenance = crate::provenance::Licensing::default().provenance(crate::failover::Provider::Vci, Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap());
+        let paths = export_lean(dir.path(), "vietnam", "FPT", "1m", &[bar(2, 15, 110500.0)], Some(&provenance)).unwrap();
+        assert_eq!(paths, vec![dir.path().join("equity/vietnam/minute/fpt/20240304_trade.zip")]);
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&paths[0]).unwrap()).unwrap();
+        let mut contents = String::new();
+        archive.by_name("20240304_fpt_minute_trade.csv").unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "33300000,1105000000,1106000000,1104000000,1105000000,1000\n");
+
+        let sidecar = dir.path().join("equity/vietnam/minute/fpt/20240304_trade.zip.provenance.json");
+        let written: Provenance = serde_json::from_str(&fs::read_to_string(sidecar).unwrap()).unwrap();
+        assert_eq!(written, provenance);
+    }
+
+    #[test]
+    fn test_zipline_intervals() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = export_zipline(dir.path(), "FPT", "1H", &[bar(2, 0, 1.0)], None);
+        assert!(matches!(result, Err(ExportError::UnsupportedInterval(_))));
+
+        let daily = Ohlcv { time: Utc.with_ymd_and_hms(2024, 3, 3, 17, 0, 0).unwrap(), ..bar(0, 0, 100.0) };
+        let path = export_zipline(dir.path(), "FPT", "1D", &[daily], None).unwrap();
+        assert!(fs::read_to_string(path).unwrap().lines().nth(1).unwrap().starts_with("2024-03-04,"));
+        let path = export_zipline(dir.path(), "FPT", "1m", &[bar(2, 15, 100.0)], None).unwrap();
+        assert!(fs::read_to_string(path).unwrap().lines().nth(1).unwrap().starts_with("2024-03-04 09:15:00,"));
+    }
+
+    #[test]
+    fn test_lightweight_charts_shape() {
+        let mut down = bar(3, 0, 90.0);
+        down.open = 95.0;
+        let bars = vec![down, bar(2, 0, 100.0), bar(2, 0, 101.0)];
+        let options = LightweightChartsOptions { exchange_time: false, ..Default::default() };
+        let series = to_lightweight_charts(&bars, &options);
+
+        assert_eq!(series.candles.len(), 2);
+        assert_eq!(series.candles[0].time, bars[1].time.timestamp());
+        assert_eq!(series.candles[0].close, 101.0);
+        assert_eq!(series.volume[1].color.as_deref(), Some("#ef5350"));
+
+        let json = serde_json::to_value(&series.candles[0]).unwrap();
+        assert_eq!(json.as_object().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_metatrader_minute_layout() {
+        let dir = tempfile::tempdir().unwrap();
+        let bars = [bar(2, 15, 100.0)];
+
+        let mt4 = export_metatrader(dir.path(), "fpt", "1m", &bars, MetaTraderFormat::Mt4, None).unwrap();
+        assert_eq!(mt4, dir.path().join("FPT1.csv"));
+        assert_eq!(fs::read_to_string(&mt4).unwrap(), "2024.03.04,09:15,100,200,0,100,1000\n");
+
+        // Daily bars are stamped at exchange midnight, 17:00 UTC the day before
+        let daily = [Ohlcv { time: Utc.with_ymd_and_hms(2024, 3, 3, 17, 0, 0).unwrap(), ..bar(0, 0, 100.0) }];
+        let mt5 = export_metatrader(dir.path(), "FPT", "1D", &daily, MetaTraderFormat::Mt5, None).unwrap();
+        let contents = fs::read_to_string(&mt5).unwrap();
+        assert_eq!(contents.lines().nth(1).unwrap(), "2024.03.04\t00:00:00\t100\t200\t0\t100\t1000\t1000\t0");
+    }
+
+    #[test]
+    fn test_backtrader_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let daily = Ohlcv { time: Utc.with_ymd_and_hms(2024, 3, 3, 17, 0, 0).unwrap(), ..bar(0, 0, 100.0) };
+        let path = export_backtrader(dir.path(), "fpt", "1D", &[daily], None).unwrap();
+        let contents = fs::read_to_string(path).unwrap();
+        assert_eq!(contents.lines().nth(1).unwrap(), "2024-03-04 00:00:00,100,200,0,100,1000,0");
+    }
+}
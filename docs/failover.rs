@@ -0,0 +1,244 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use tokio::task::JoinHandle;
+
+use crate::calendar;
+use crate::models::{vietnam_offset, Ohlcv};
+use crate::quality::{self, SourcedBar};
+use crate::shutdown::{self, ShutdownToken};
+use crate::tcbs::{TcbsClient, TcbsError};
+use crate::vci::{VciClient, VciError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Provider {
+    Vci,
+    Tcbs,
+    Entrade,
+    /// Fundamentals only; not a price-history source.
+    Fireant,
+}
+
+impl Provider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Provider::Vci => "vci",
+            Provider::Tcbs => "tcbs",
+            Provider::Entrade => "entrade",
+            Provider::Fireant => "fireant",
+        }
+    }
+
+    /// The fallback in a VCI/TCBS failover pair; Entrade and FireAnt fall
+    /// back to VCI.
+    pub fn other(&self) -> Provider {
+        match self {
+            Provider::Vci => Provider::Tcbs,
+            Provider::Tcbs | Provider::Entrade | Provider::Fireant => Provider::Vci,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FailoverError {
+    Vci(VciError),
+    Tcbs(TcbsError),
+    /// Both providers failed; errors are in primary-then-fallback order.
+    AllFailed(Box<FailoverError>, Box<FailoverError>),
+    /// The provider is not part of the failover pair.
+    Unsupported(Provider),
+}
+
+impl From<VciError> for FailoverError {
+    fn from(error: VciError) -> Self {
+        FailoverError::Vci(error)
+    }
+}
+
+impl From<TcbsError> for FailoverError {
+    fn from(error: TcbsError) -> Self {
+        FailoverError::Tcbs(error)
+    }
+}
+
+/// Last-bar timestamps seen by one freshness sample.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FreshnessSample {
+    pub time: DateTime<Utc>,
+    pub vci_last_bar: Option<DateTime<Utc>>,
+    pub tcbs_last_bar: Option<DateTime<Utc>>,
+    pub chosen: Provider,
+}
+
+/// History client that tries a primary provider and falls back to the
+/// other. With freshness routing the primary is re-chosen by periodically
+/// comparing how recent each provider's bars are for a reference symbol.
+pub struct FailoverClient {
+    vci: Arc<VciClient>,
+    tcbs: Arc<TcbsClient>,
+    primary: RwLock<Provider>,
+    reference_symbol: String,
+    last_sample: RwLock<Option<FreshnessSample>>,
+}
+
+impl FailoverClient {
+    pub fn new(vci: Arc<VciClient>, tcbs: Arc<TcbsClient>) -> Self {
+        FailoverClient {
+            vci,
+            tcbs,
+            primary: RwLock::new(Provider::Vci),
+            reference_symbol: "VNM".to_string(),
+            last_sample: RwLock::new(None),
+        }
+    }
+
+    pub fn with_primary(self, provider: Provider) -> Self {
+        *self.primary.write().unwrap() = provider;
+        self
+    }
+
+    /// Liquid symbol sampled by freshness routing. Defaults to VNM.
+    pub fn with_reference_symbol(mut self, symbol: &str) -> Self {
+        self.reference_symbol = symbol.to_uppercase();
+        self
+    }
+
+    pub fn primary(&self) -> Provider {
+        *self.primary.read().unwrap()
+    }
+
+    pub fn last_sample(&self) -> Option<FreshnessSample> {
+        self.last_sample.read().unwrap().clone()
+    }
+
+    /// Fetches from the primary provider, falling back to the other on error
+    /// or an empty result. Returns the provider that answered.
+    pub async fn get_history(&self, symbol: &str, start: NaiveDate, end: Option<NaiveDate>, interval: &str) -> Result<(Provider, Vec<Ohlcv>), FailoverError> {
+        let primary = self.primary();
+        let first = self.fetch_from(primary, symbol, start, end, interval).await;
+        match first {
+            Ok(bars) if !bars.is_empty() => return Ok((primary, bars)),
+            _ => tracing::debug!("{} returned no history for {}, trying {}", primary.as_str(), symbol, primary.other().as_str()),
+        }
+
+        match self.fetch_from(primary.other(), symbol, start, end, interval).await {
+            Ok(bars) => Ok((primary.other(), bars)),
+            Err(second) => match first {
+                Ok(bars) => Ok((primary, bars)),
+                Err(first) => Err(FailoverError::AllFailed(Box::new(first), Box::new(second))),
+            },
+        }
+    }
+
+    /// Daily history from the primary provider with any missing trading days
+    /// filled from the other provider. Filled rows are marked with their origin.
+    pub async fn get_daily_gap_filled(&self, symbol: &str, start: NaiveDate, end: Option<NaiveDate>) -> Result<Vec<SourcedBar>, FailoverError> {
+        let (origin, bars) = self.get_history(symbol, start, end, "1D").await?;
+        let today = Utc::now().with_timezone(&vietnam_offset()).date_naive();
+        let missing = quality::missing_trading_days(&bars, start, end.unwrap_or(today));
+        let (Some(first), Some(last)) = (missing.first(), missing.last()) else {
+            return Ok(quality::fill_gaps(bars, origin, &[], origin.other(), &[]));
+        };
+
+        // One request spanning the gaps; fill_gaps keeps only the missing days
+        let alternate = match self.fetch_from(origin.other(), symbol, *first, Some(*last), "1D").await {
+            Ok(alternate) => alternate,
+            Err(e) => {
+                tracing::warn!("Gap fill from {} failed for {}: {:?}", origin.other().as_str(), symbol, e);
+                Vec::new()
+            }
+        };
+        Ok(quality::fill_gaps(bars, origin, &alternate, origin.other(), &missing))
+    }
+
+    async fn fetch_from(&self, provider: Provider, symbol: &str, start: NaiveDate, end: Option<NaiveDate>, interval: &str) -> Result<Vec<Ohlcv>, FailoverError> {
+        match provider {
+            Provider::Vci => {
+                let bars = self.vci.get_history(symbol, start, end, interval).await?;
+                Ok(bars.into_iter().map(Ohlcv::from).collect())
+            }
+            Provider::Tcbs => {
+                let days = count_back_days(start, end);
+                let bars = self.tcbs.get_history(symbol, start, end, interval, days).await?;
+                Ok(bars.into_iter().map(Ohlcv::from).collect())
+            }
+            Provider::Entrade | Provider::Fireant => Err(FailoverError::Unsupported(provider)),
+        }
+    }
+
+    /// Samples recent 1-minute bars of the reference symbol from both
+    /// providers and routes to whichever has the later last bar.
+    pub async fn sample_freshness(&self) -> FreshnessSample {
+        let today = Utc::now().with_timezone(&vietnam_offset()).date_naive();
+        let start = calendar::nth_trading_day_before(today, 2);
+        let symbol = self.reference_symbol.as_str();
+
+        let (vci, tcbs) = tokio::join!(
+            self.fetch_from(Provider::Vci, symbol, start, None, "1m"),
+            self.fetch_from(Provider::Tcbs, symbol, start, None, "1m"),
+        );
+        let last_bar = |bars: Result<Vec<Ohlcv>, FailoverError>| bars.ok().and_then(|bars| bars.iter().map(|bar| bar.time).max());
+        let vci_last_bar = last_bar(vci);
+        let tcbs_last_bar = last_bar(tcbs);
+
+        let chosen = fresher(self.primary(), vci_last_bar, tcbs_last_bar);
+        if chosen != self.primary() {
+            tracing::info!("Routing history requests to {} (vci: {:?}, tcbs: {:?})", chosen.as_str(), vci_last_bar, tcbs_last_bar);
+        }
+        *self.primary.write().unwrap() = chosen;
+
+        let sample = FreshnessSample { time: Utc::now(), vci_last_bar, tcbs_last_bar, chosen };
+        *self.last_sample.write().unwrap() = Some(sample.clone());
+        sample
+    }
+
+    /// Re-samples freshness every `every`. Stops once `shutdown` is
+    /// signalled, or when the handle is aborted.
+    pub fn spawn_freshness_routing(self: &Arc<Self>, every: std::time::Duration, mut shutdown: Option<ShutdownToken>) -> JoinHandle<()> {
+        let client = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown::signalled(&mut shutdown) => return,
+                }
+                client.sample_freshness().await;
+            }
+        })
+    }
+}
+
+/// Provider with the later last bar; ties and double failures keep `current`.
+pub fn fresher(current: Provider, vci_last_bar: Option<DateTime<Utc>>, tcbs_last_bar: Option<DateTime<Utc>>) -> Provider {
+    match (vci_last_bar, tcbs_last_bar) {
+        (Some(vci), Some(tcbs)) if vci > tcbs => Provider::Vci,
+        (Some(vci), Some(tcbs)) if tcbs > vci => Provider::Tcbs,
+        (Some(_), None) => Provider::Vci,
+        (None, Some(_)) => Provider::Tcbs,
+        _ => current,
+    }
+}
+
+/// TCBS needs an explicit bar count; trading days in range covers daily bars.
+pub(crate) fn count_back_days(start: NaiveDate, end: Option<NaiveDate>) -> u32 {
+    let today = Utc::now().with_timezone(&vietnam_offset()).date_naive();
+    calendar::trading_days_between(start, end.unwrap_or(today)).len() as u32 + 10
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_fresher_provider() {
+        let earlier = Utc.with_ymd_and_hms(2024, 6, 3, 7, 0, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2024, 6, 3, 7, 5, 0).unwrap();
+
+        assert_eq!(fresher(Provider::Vci, Some(earlier), Some(later)), Provider::Tcbs);
+        assert_eq!(fresher(Provider::Tcbs, Some(later), None), Provider::Vci);
+        assert_eq!(fresher(Provider::Tcbs, Some(later), Some(later)), Provider::Tcbs);
+        assert_eq!(fresher(Provider::Vci, None, None), Provider::Vci);
+    }
+}
@@ -0,0 +1,533 @@
+use chrono::{Datelike, Utc};
+use reqwest::{Client, Error as ReqwestError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::circuit::CircuitBreaker;
+use crate::failover::Provider;
+use crate::rate_limit::RateLimiter;
+use crate::retry::{RequestError, Requester, RetryPolicy};
+use crate::statements::Financials;
+use crate::stats::{self, ClientStats, LatencyHistogram, UsageReport};
+use crate::tcbs::{FinancialInfo, FinancialStatement};
+use crate::text;
+use crate::valuation::{self, PerShareFundamentals};
+
+/// Environment variable holding the FireAnt bearer token.
+pub const TOKEN_ENV: &str = "FIREANT_TOKEN";
+
+/// FireAnt ratio fields mapped to the TCBS keys used across the crate, with
+/// the factor that converts FireAnt percentages to TCBS fractions.
+const RATIO_ALIASES: [(&str, &str, f64); 12] = [
+    ("basicEPS", "earning_per_share", 1.0),
+    ("bookValuePerShare", "book_value_per_share", 1.0),
+    ("pe", "price_to_earning", 1.0),
+    ("pb", "price_to_book", 1.0),
+    ("evebitda", "value_before_ebitda", 1.0),
+    ("roe", "roe", 0.01),
+    ("roa", "roa", 0.01),
+    ("grossMargin", "gross_profit_margin", 0.01),
+    ("operatingMargin", "operating_profit_margin", 0.01),
+    ("netMargin", "post_tax_margin", 0.01),
+    ("epsGrowth", "eps_change", 0.01),
+    ("bookValuePerShareGrowth", "book_value_per_share_change", 0.01),
+];
+
+/// Statement line names (normalized, numbering stripped) mapped to the TCBS
+/// keys other modules read.
+const LINE_ALIASES: [(&str, &str); 37] = [
+    // Income statement
+    ("doanh thu thuan", "revenue"),
+    ("doanh thu thuan ve ban hang va cung cap dich vu", "revenue"),
+    ("gia von hang ban", "cost_of_good_sold"),
+    ("loi nhuan gop", "gross_profit"),
+    ("loi nhuan gop ve ban hang va cung cap dich vu", "gross_profit"),
+    ("loi nhuan thuan tu hoat dong kinh doanh", "operation_profit"),
+    ("chi phi lai vay", "interest_expense"),
+    ("trong do: chi phi lai vay", "interest_expense"),
+    ("tong loi nhuan ke toan truoc thue", "pre_tax_profit"),
+    ("loi nhuan sau thue thu nhap doanh nghiep", "post_tax_profit"),
+    ("loi nhuan sau thue cua co dong cua cong ty me", "share_holder_income"),
+    // Balance sheet
+    ("tien va cac khoan tuong duong tien", "cash"),
+    ("dau tu tai chinh ngan han", "short_invest"),
+    ("cac khoan dau tu tai chinh ngan han", "short_invest"),
+    ("cac khoan phai thu ngan han", "short_receivable"),
+    ("hang ton kho", "inventory"),
+    ("tai san ngan han", "short_asset"),
+    ("tai san co dinh", "fixed_asset"),
+    ("tai san dai han", "long_asset"),
+    ("tong cong tai san", "asset"),
+    ("vay ngan han", "short_debt"),
+    ("vay va no thue tai chinh ngan han", "short_debt"),
+    ("vay dai han", "long_debt"),
+    ("vay va no thue tai chinh dai han", "long_debt"),
+    ("no phai tra", "debt"),
+    ("von chu so huu", "equity"),
+    ("von gop cua chu so huu", "capital"),
+    ("von dau tu cua chu so huu", "capital"),
+    ("loi nhuan sau thue chua phan phoi", "un_distributed_income"),
+    ("loi ich co dong khong kiem soat", "minor_share_holder_profit"),
+    ("loi ich cua co dong thieu so", "minor_share_holder_profit"),
+    // Cash flow
+    ("luu chuyen tien thuan tu hoat dong kinh doanh", "from_sale"),
+    ("luu chuyen tien thuan tu hoat dong dau tu", "from_invest"),
+    ("luu chuyen tien thuan tu hoat dong tai chinh", "from_financial"),
+    ("tien chi de mua sam, xay dung tscd va cac tai san dai han khac", "invest_cost"),
+    ("tien chi de mua sam xay dung tscd va cac tai san dai han khac", "invest_cost"),
+    ("mua sam tscd", "invest_cost"),
+];
+
+#[derive(Debug)]
+pub enum FireantError {
+    Http(ReqwestError),
+    Serialization(serde_json::Error),
+    InvalidResponse(String),
+    /// No bearer token was given or found in [`TOKEN_ENV`].
+    MissingToken,
+    /// The endpoint's circuit breaker is open; retry after the given delay.
+    CircuitOpen { endpoint: String, retry_in: Duration },
+    /// Non-success HTTP status, after any retries the policy allows.
+    Status(reqwest::StatusCode),
+    NoData,
+}
+
+impl From<ReqwestError> for FireantError {
+    fn from(error: ReqwestError) -> Self {
+        FireantError::Http(error)
+    }
+}
+
+impl From<RequestError> for FireantError {
+    fn from(error: RequestError) -> Self {
+        match error {
+            RequestError::Status(reqwest::StatusCode::UNAUTHORIZED) => FireantError::MissingToken,
+            RequestError::Status(status) => FireantError::Status(status),
+            RequestError::Http(error) => FireantError::Http(error),
+            RequestError::CircuitOpen { endpoint, retry_in } => FireantError::CircuitOpen { endpoint, retry_in },
+        }
+    }
+}
+
+impl From<serde_json::Error> for FireantError {
+    fn from(error: serde_json::Error) -> Self {
+        FireantError::Serialization(error)
+    }
+}
+
+/// `type` parameter of the full financial report endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportType {
+    BalanceSheet,
+    IncomeStatement,
+    CashFlowDirect,
+    CashFlowIndirect,
+}
+
+impl ReportType {
+    fn code(&self) -> u8 {
+        match self {
+            ReportType::BalanceSheet => 1,
+            ReportType::IncomeStatement => 2,
+            ReportType::CashFlowDirect => 3,
+            ReportType::CashFlowIndirect => 4,
+        }
+    }
+}
+
+/// "YYYY-Qn" for quarters and "YYYY" for years, matching TCBS labels.
+fn period_label(year: i64, quarter: i64) -> String {
+    if quarter > 0 {
+        format!("{}-Q{}", year, quarter)
+    } else {
+        year.to_string()
+    }
+}
+
+/// Line name without the leading "I.", "1." or "a)" numbering some
+/// reports carry.
+fn strip_numbering(normalized: &str) -> &str {
+    match normalized.split_once(' ') {
+        Some((label, rest)) if label.len() > 1
+            && label.ends_with(['.', ')'])
+            && label[..label.len() - 1].chars().all(|ch| ch.is_ascii_digit() || "ivxl".contains(ch) || label.len() == 2) => rest,
+        _ => normalized,
+    }
+}
+
+/// Snake_case key for a statement line, preferring the TCBS name when known.
+fn line_key(name: &str) -> String {
+    let normalized = text::normalize(name);
+    let normalized = strip_numbering(&normalized);
+    if let Some((_, key)) = LINE_ALIASES.iter().find(|(line, _)| *line == normalized) {
+        return key.to_string();
+    }
+    normalized
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn camel_to_snake(name: &str) -> String {
+    let mut result = String::new();
+    for ch in name.chars() {
+        if ch.is_uppercase() && !result.is_empty() {
+            result.push('_');
+        }
+        result.extend(ch.to_lowercase());
+    }
+    result
+}
+
+/// Pivots the line-oriented full report (one row per line item, each with a
+/// `values` array across periods) into one statement per period, oldest first.
+pub fn parse_full_report(data: &Value) -> Result<Vec<FinancialStatement>, FireantError> {
+    let rows = data.as_array().ok_or_else(|| FireantError::InvalidResponse("Expected an array of report lines".to_string()))?;
+    let mut periods: HashMap<(i64, i64), HashMap<String, f64>> = HashMap::new();
+    for row in rows {
+        let Some(name) = row.get("name").and_then(|v| v.as_str()) else { continue };
+        let key = line_key(name);
+        for value in row.get("values").and_then(|v| v.as_array()).into_iter().flatten() {
+            let (Some(year), Some(amount)) = (value.get("year").and_then(|v| v.as_i64()), value.get("value").and_then(|v| v.as_f64())) else {
+                continue;
+            };
+            let quarter = value.get("quarter").and_then(|v| v.as_i64()).unwrap_or(0);
+            periods.entry((year, quarter)).or_default().entry(key.clone()).or_insert(amount);
+        }
+    }
+    let mut keys: Vec<(i64, i64)> = periods.keys().copied().collect();
+    keys.sort();
+    Ok(keys.into_iter()
+        .map(|(year, quarter)| FinancialStatement { period: period_label(year, quarter), data: periods.remove(&(year, quarter)).unwrap_or_default() })
+        .collect())
+}
+
+/// Converts period-oriented ratio rows to statements keyed like TCBS
+/// `financialratio`, so valuation and comparison code reads them unchanged.
+pub fn parse_ratios(data: &Value) -> Result<Vec<FinancialStatement>, FireantError> {
+    let rows = data.as_array().ok_or_else(|| FireantError::InvalidResponse("Expected an array of periods".to_string()))?;
+    let mut statements: Vec<(i64, i64, FinancialStatement)> = rows.iter()
+        .filter_map(|row| {
+            let year = row.get("year").and_then(|v| v.as_i64())?;
+            let quarter = row.get("quarter").and_then(|v| v.as_i64()).unwrap_or(0);
+            let data = row.as_object()?
+                .iter()
+                .filter(|(key, _)| key.as_str() != "year" && key.as_str() != "quarter")
+                .filter_map(|(key, value)| {
+                    let value = value.as_f64()?;
+                    Some(match RATIO_ALIASES.iter().find(|(source, _, _)| source == key) {
+                        Some((_, target, scale)) => (target.to_string(), value * scale),
+                        None => (camel_to_snake(key), value),
+                    })
+                })
+                .collect();
+            Some((year, quarter, FinancialStatement { period: period_label(year, quarter), data }))
+        })
+        .collect();
+    statements.sort_by_key(|(year, quarter, _)| (*year, *quarter));
+    Ok(statements.into_iter().map(|(_, _, statement)| statement).collect())
+}
+
+/// FireAnt (restv2.fireant.vn) client for long-run fundamentals, typically
+/// 10+ years of quarterly statements and ratios. Requires a bearer token.
+pub struct FireantClient {
+    client: Client,
+    base_url: String,
+    token: String,
+    rate_limiter: Arc<RateLimiter>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Arc<CircuitBreaker>,
+    stats: Arc<ClientStats>,
+}
+
+/// Configures a [`FireantClient`]: bearer token, HTTP timeouts, proxy, base
+/// URL and request budget. An injected `reqwest::Client` takes precedence
+/// over the timeout and proxy settings.
+pub struct FireantClientBuilder {
+    token: String,
+    rate_limit_per_minute: u32,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    timeout: Duration,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    http_client: Option<Client>,
+    base_url: Option<String>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl Default for FireantClientBuilder {
+    fn default() -> Self {
+        FireantClientBuilder {
+            token: String::new(),
+            rate_limit_per_minute: 6,
+            rate_limiter: None,
+            timeout: Duration::from_secs(30),
+            connect_timeout: None,
+            proxy: None,
+            http_client: None,
+            base_url: None,
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: None,
+        }
+    }
+}
+
+impl FireantClientBuilder {
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = token.into();
+        self
+    }
+
+    pub fn rate_limit(mut self, per_minute: u32) -> Self {
+        self.rate_limit_per_minute = per_minute;
+        self
+    }
+
+    /// Shared request budget; overrides [`Self::rate_limit`].
+    pub fn rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes all traffic through `url`, e.g. `http://proxy.corp:3128`.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Points the client at another host, e.g. a mock server in tests.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Fails with [`FireantError::MissingToken`] when no token was set.
+    pub fn build(self) -> Result<FireantClient, FireantError> {
+        if self.token.trim().is_empty() {
+            return Err(FireantError::MissingToken);
+        }
+        let client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder().timeout(self.timeout);
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(proxy) = &self.proxy {
+                    builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+                }
+                builder.build()?
+            }
+        };
+        Ok(FireantClient {
+            client,
+            base_url: self.base_url.unwrap_or_else(|| "https://restv2.fireant.vn".to_string()),
+            token: self.token,
+            rate_limiter: self.rate_limiter.unwrap_or_else(|| Arc::new(RateLimiter::new(self.rate_limit_per_minute))),
+            retry_policy: self.retry_policy,
+            circuit_breaker: self.circuit_breaker.unwrap_or_default(),
+            stats: Arc::new(ClientStats::new()),
+        })
+    }
+}
+
+impl FireantClient {
+    pub fn new(token: impl Into<String>, rate_limit_per_minute: u32) -> Result<Self, FireantError> {
+        Self::builder().token(token).rate_limit(rate_limit_per_minute).build()
+    }
+
+    pub fn builder() -> FireantClientBuilder {
+        FireantClientBuilder::default()
+    }
+
+    /// Reads the token from [`TOKEN_ENV`].
+    pub fn from_env(rate_limit_per_minute: u32) -> Result<Self, FireantError> {
+        let token = std::env::var(TOKEN_ENV).map_err(|_| FireantError::MissingToken)?;
+        Self::new(token, rate_limit_per_minute)
+    }
+
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Shares `breaker` with this client. By default each client has its
+    /// own breaker opening after 5 consecutive failures for 30s.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = breaker;
+        self
+    }
+
+    pub fn circuit_breaker(&self) -> Arc<CircuitBreaker> {
+        Arc::clone(&self.circuit_breaker)
+    }
+
+    /// Latency histograms per endpoint for every request attempt so far.
+    pub fn stats(&self) -> std::collections::BTreeMap<String, LatencyHistogram> {
+        self.stats.snapshot()
+    }
+
+    /// Requests sent per day and endpoint, to keep under the token's quota.
+    pub fn usage_report(&self) -> UsageReport {
+        self.stats.usage(Provider::Fireant)
+    }
+
+    async fn get_json(&self, url: &str, params: &[(&str, String)]) -> Result<Value, FireantError> {
+        let endpoint = stats::endpoint_key(url);
+        let requester = Requester {
+            policy: &self.retry_policy,
+            rate_limiter: &self.rate_limiter,
+            stats: &self.stats,
+            circuit_breaker: Some(&self.circuit_breaker),
+            chaos: None,
+        };
+        let build = || self.client.get(url).query(params).bearer_auth(&self.token).header("Accept", "application/json");
+        Ok(requester.send_json(&endpoint, &endpoint, build).await?)
+    }
+
+    /// Up to `limit` periods of one statement, oldest first. `period` is
+    /// "year" or "quarter", as for the TCBS statement methods.
+    pub async fn financial_report(&self, symbol: &str, report: ReportType, period: &str, limit: u32) -> Result<Vec<FinancialStatement>, FireantError> {
+        let url = format!("{}/symbols/{}/full-financial-reports", self.base_url, symbol.to_uppercase());
+        let quarter = if period == "year" { 0 } else { 4 };
+        let params = [
+            ("type", report.code().to_string()),
+            ("year", Utc::now().year().to_string()),
+            ("quarter", quarter.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        let statements = parse_full_report(&self.get_json(&url, &params).await?)?;
+        if statements.is_empty() {
+            return Err(FireantError::NoData);
+        }
+        Ok(statements)
+    }
+
+    /// Ratio history (EPS, BVPS, P/E, P/B, ROE, margins) keyed like TCBS
+    /// `financial_ratios`, oldest first.
+    pub async fn financial_ratios(&self, symbol: &str, period: &str, count: u32) -> Result<Vec<FinancialStatement>, FireantError> {
+        let url = format!("{}/symbols/{}/financial-data", self.base_url, symbol.to_uppercase());
+        let kind = if period == "year" { "Y" } else { "Q" };
+        let params = [("type", kind.to_string()), ("count", count.to_string())];
+        let statements = parse_ratios(&self.get_json(&url, &params).await?)?;
+        if statements.is_empty() {
+            return Err(FireantError::NoData);
+        }
+        Ok(statements)
+    }
+
+    /// Point-in-time per-share figures over the last `quarters` quarters.
+    pub async fn fundamentals(&self, symbol: &str, quarters: u32) -> Result<Vec<PerShareFundamentals>, FireantError> {
+        let ratios = self.financial_ratios(symbol, "quarter", quarters).await?;
+        Ok(valuation::fundamentals_from_ratios(&ratios))
+    }
+
+    /// All statements and ratios in the same shape as
+    /// [`TcbsClient::financial_info`](crate::tcbs::TcbsClient::financial_info).
+    /// The four sections are fetched concurrently; those that fail are left
+    /// as `None`.
+    pub async fn financial_info(&self, symbol: &str, period: &str, limit: u32) -> FinancialInfo {
+        let (balance_sheet, income_statement, cash_flow, ratios) = tokio::join!(
+            self.financial_report(symbol, ReportType::BalanceSheet, period, limit),
+            self.financial_report(symbol, ReportType::IncomeStatement, period, limit),
+            self.financial_report(symbol, ReportType::CashFlowIndirect, period, limit),
+            self.financial_ratios(symbol, period, limit),
+        );
+        FinancialInfo {
+            symbol: symbol.to_uppercase(),
+            period: period.to_string(),
+            balance_sheet: balance_sheet.ok(),
+            income_statement: income_statement.ok(),
+            cash_flow: cash_flow.ok(),
+            ratios: ratios.ok(),
+        }
+    }
+
+    /// Statements mapped onto the provider-independent structs, in the
+    /// same shape as [`TcbsClient::financials`](crate::tcbs::TcbsClient::financials).
+    pub async fn financials(&self, symbol: &str, period: &str, limit: u32) -> Financials {
+        Financials::from_info(&self.financial_info(symbol, period, limit).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_report_pivots_periods() {
+        let data = serde_json::json!([
+            {"id": 1, "name": "Doanh thu thuần", "values": [
+                {"period": "Q2/2024", "year": 2024, "quarter": 2, "value": 200.0},
+                {"period": "Q1/2024", "year": 2024, "quarter": 1, "value": 150.0}
+            ]},
+            {"id": 2, "name": "Chi phí bán hàng", "values": [{"year": 2024, "quarter": 2, "value": -20.0}]}
+        ]);
+        let statements = parse_full_report(&data).unwrap();
+        assert_eq!(statements.iter().map(|s| s.period.as_str()).collect::<Vec<_>>(), vec!["2024-Q1", "2024-Q2"]);
+        assert_eq!(statements[1].data["revenue"], 200.0);
+        assert_eq!(statements[1].data["chi_phi_ban_hang"], -20.0);
+    }
+
+    #[test]
+    fn test_parse_ratios_maps_to_fundamentals() {
+        let data = serde_json::json!([
+            {"year": 2024, "quarter": 1, "basicEPS": 2000.0, "bookValuePerShare": 20000.0, "roe": 10.0, "dividendYield": 3.5},
+            {"year": 2023, "quarter": 4, "basicEPS": 1800.0, "bookValuePerShare": 19000.0}
+        ]);
+        let ratios = parse_ratios(&data).unwrap();
+        assert_eq!(ratios[0].period, "2023-Q4");
+        assert_eq!(ratios[1].data["roe"], 0.1);
+        assert_eq!(ratios[1].data["dividend_yield"], 3.5);
+
+        let fundamentals = valuation::fundamentals_from_ratios(&ratios);
+        assert_eq!(fundamentals[1].eps, Some(2000.0));
+        assert_eq!(fundamentals[1].book_value_per_share, Some(20000.0));
+        assert!(FireantClient::new(" ", 6).is_err());
+    }
+}
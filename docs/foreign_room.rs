@@ -0,0 +1,314 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::models::vietnam_offset;
+use crate::store::StoreError;
+
+const CSV_HEADER: &str = "ticker,time,current_room,total_room,foreign_buy_volume,foreign_sell_volume";
+
+/// Remaining foreign ownership room for a symbol at one point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignRoomSnapshot {
+    pub symbol: String,
+    pub time: DateTime<Utc>,
+    /// Shares foreigners can still buy.
+    pub current_room: u64,
+    /// Foreign ownership limit in shares.
+    pub total_room: u64,
+    pub foreign_buy_volume: u64,
+    pub foreign_sell_volume: u64,
+}
+
+impl ForeignRoomSnapshot {
+    /// Fraction of the foreign limit already held, `0.0..=1.0`.
+    pub fn used_ratio(&self) -> Option<f64> {
+        if self.total_room == 0 {
+            return None;
+        }
+        Some(1.0 - self.current_room as f64 / self.total_room as f64)
+    }
+
+    /// True when remaining room is at or below `threshold` of the limit.
+    pub fn is_tight(&self, threshold: f64) -> bool {
+        self.total_room > 0 && (self.current_room as f64 / self.total_room as f64) <= threshold
+    }
+}
+
+/// Per-day summary of intraday room snapshots (dates in exchange time).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyForeignRoom {
+    pub date: NaiveDate,
+    pub open_room: u64,
+    pub close_room: u64,
+    pub min_room: u64,
+    pub total_room: u64,
+    /// Close minus open; negative when foreigners absorbed room.
+    pub change: i64,
+    pub samples: usize,
+}
+
+/// Records room snapshots per symbol as they are sampled, optionally
+/// appending them to `<dir>/<TICKER>.csv` so history survives restarts.
+pub struct ForeignRoomTracker {
+    history: HashMap<String, Vec<ForeignRoomSnapshot>>,
+    dir: Option<PathBuf>,
+}
+
+impl ForeignRoomTracker {
+    pub fn new() -> Self {
+        ForeignRoomTracker {
+            history: HashMap::new(),
+            dir: None,
+        }
+    }
+
+    /// Opens a persistent tracker, loading any snapshots already on disk.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut history: HashMap<String, Vec<ForeignRoomSnapshot>> = HashMap::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("csv") {
+                continue;
+            }
+            let reader = BufReader::new(fs::File::open(&path)?);
+            for (line_no, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line_no == 0 || line.trim().is_empty() {
+                    continue;
+                }
+                let snapshot = parse_record(&line).ok_or_else(|| {
+                    StoreError::InvalidRecord(format!("{}:{}: {}", path.display(), line_no + 1, line))
+                })?;
+                history.entry(snapshot.symbol.clone()).or_default().push(snapshot);
+            }
+        }
+        for snapshots in history.values_mut() {
+            snapshots.sort_by_key(|s| s.time);
+        }
+
+        Ok(ForeignRoomTracker { history, dir: Some(dir) })
+    }
+
+    /// Records a snapshot. Unchanged consecutive samples are skipped so
+    /// frequent polling of a quiet symbol doesn't bloat the history.
+    pub fn record(&mut self, snapshot: ForeignRoomSnapshot) -> Result<bool, StoreError> {
+        let symbol = snapshot.symbol.to_uppercase();
+        let snapshots = self.history.entry(symbol.clone()).or_default();
+        if let Some(last) = snapshots.last() {
+            if last.current_room == snapshot.current_room
+                && last.total_room == snapshot.total_room
+                && last.time.date_naive() == snapshot.time.date_naive()
+            {
+                return Ok(false);
+            }
+        }
+
+        if let Some(dir) = &self.dir {
+            let path = dir.join(format!("{}.csv", symbol));
+            let is_new = !path.exists();
+            let mut file = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+            if is_new {
+                writeln!(file, "{}", CSV_HEADER)?;
+            }
+            writeln!(
+                file,
+                "{},{},{},{},{},{}",
+                symbol,
+                snapshot.time.format("%Y-%m-%d %H:%M:%S"),
+                snapshot.current_room,
+                snapshot.total_room,
+                snapshot.foreign_buy_volume,
+                snapshot.foreign_sell_volume
+            )?;
+        }
+
+        snapshots.push(snapshot);
+        Ok(true)
+    }
+
+    pub fn history(&self, symbol: &str) -> &[ForeignRoomSnapshot] {
+        self.history.get(&symbol.to_uppercase()).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn latest(&self, symbol: &str) -> Option<&ForeignRoomSnapshot> {
+        self.history(symbol).last()
+    }
+
+    /// Collapses the intraday history of `symbol` into one row per trading day.
+    pub fn daily(&self, symbol: &str) -> Vec<DailyForeignRoom> {
+        let mut days: BTreeMap<NaiveDate, DailyForeignRoom> = BTreeMap::new();
+        for snapshot in self.history(symbol) {
+            let date = snapshot.time.with_timezone(&vietnam_offset()).date_naive();
+            days.entry(date)
+                .and_modify(|day| {
+                    day.close_room = snapshot.current_room;
+                    day.min_room = day.min_room.min(snapshot.current_room);
+                    day.total_room = snapshot.total_room;
+                    day.change = day.close_room as i64 - day.open_room as i64;
+                    day.samples += 1;
+                })
+                .or_insert(DailyForeignRoom {
+                    date,
+                    open_room: snapshot.current_room,
+                    close_room: snapshot.current_room,
+                    min_room: snapshot.current_room,
+                    total_room: snapshot.total_room,
+                    change: 0,
+                    samples: 1,
+                });
+        }
+        days.into_values().collect()
+    }
+
+    /// Symbols whose latest remaining room is at or below `threshold` of the limit.
+    pub fn tight_symbols(&self, threshold: f64) -> Vec<&ForeignRoomSnapshot> {
+        let mut tight: Vec<&ForeignRoomSnapshot> = self.history.values()
+            .filter_map(|snapshots| snapshots.last())
+            .filter(|snapshot| snapshot.is_tight(threshold))
+            .collect();
+        tight.sort_by(|a, b| a.symbol.cmp(&b.symbol));
+        tight
+    }
+}
+
+impl Default for ForeignRoomTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One day of foreign investor trading in a symbol. Values are in VND.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignTradingDay {
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub buy_volume: u64,
+    pub buy_value: f64,
+    pub sell_volume: u64,
+    pub sell_value: f64,
+    /// Remaining room at the close, when the provider reports it.
+    pub current_room: Option<u64>,
+    pub total_room: Option<u64>,
+}
+
+impl ForeignTradingDay {
+    pub fn net_volume(&self) -> i64 {
+        self.buy_volume as i64 - self.sell_volume as i64
+    }
+
+    pub fn net_value(&self) -> f64 {
+        self.buy_value - self.sell_value
+    }
+}
+
+/// Parses a daily foreign-flow row, accepting the VCI (`foreignBuyVolumeTotal`,
+/// ...) and TCBS (`buyForeignQuantity`, ...) field names.
+pub(crate) fn parse_foreign_trading_day(symbol: &str, row: &serde_json::Value) -> Option<ForeignTradingDay> {
+    let number = |keys: &[&str]| keys.iter().find_map(|key| {
+        let value = row.get(*key)?;
+        value.as_f64().or_else(|| value.as_str()?.replace(',', "").parse().ok())
+    });
+    let date_text = ["tradingDate", "dateReport", "date"].iter().find_map(|key| row.get(*key)?.as_str())?;
+    let date_part = date_text.split('T').next()?;
+    let date = NaiveDate::parse_from_str(date_part, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(date_part, "%d/%m/%Y"))
+        .ok()?;
+    Some(ForeignTradingDay {
+        symbol: symbol.to_uppercase(),
+        date,
+        buy_volume: number(&["foreignBuyVolumeTotal", "buyForeignQuantity", "foreignBuyVolume"])? as u64,
+        buy_value: number(&["foreignBuyValueTotal", "buyForeignValue", "foreignBuyValue"]).unwrap_or(0.0),
+        sell_volume: number(&["foreignSellVolumeTotal", "sellForeignQuantity", "foreignSellVolume"])? as u64,
+        sell_value: number(&["foreignSellValueTotal", "sellForeignValue", "foreignSellValue"]).unwrap_or(0.0),
+        current_room: number(&["foreignCurrentRoom", "currentRoom"]).map(|room| room as u64),
+        total_room: number(&["foreignTotalRoom", "totalRoom"]).map(|room| room as u64),
+    })
+}
+
+/// Keeps rows within `[start, end]`, oldest first, one per date.
+pub(crate) fn clip_foreign_trading(mut days: Vec<ForeignTradingDay>, start: NaiveDate, end: NaiveDate) -> Vec<ForeignTradingDay> {
+    days.retain(|day| day.date >= start && day.date <= end);
+    days.sort_by_key(|day| day.date);
+    days.dedup_by_key(|day| day.date);
+    days
+}
+
+fn parse_record(line: &str) -> Option<ForeignRoomSnapshot> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() < 6 {
+        return None;
+    }
+    Some(ForeignRoomSnapshot {
+        symbol: fields[0].to_string(),
+        time: NaiveDateTime::parse_from_str(fields[1], "%Y-%m-%d %H:%M:%S").ok()?.and_utc(),
+        current_room: fields[2].parse().ok()?,
+        total_room: fields[3].parse().ok()?,
+        foreign_buy_volume: fields[4].parse().ok()?,
+        foreign_sell_volume: fields[5].parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn snapshot(hour: u32, current_room: u64) -> ForeignRoomSnapshot {
+        ForeignRoomSnapshot {
+            symbol: "FPT".to_string(),
+            time: Utc.with_ymd_and_hms(2024, 6, 3, hour, 0, 0).unwrap(),
+            current_room,
+            total_room: 1_000_000,
+            foreign_buy_volume: 0,
+            foreign_sell_volume: 0,
+        }
+    }
+
+    #[test]
+    fn test_daily_summary_and_dedup() {
+        let mut tracker = ForeignRoomTracker::new();
+        assert!(tracker.record(snapshot(2, 50_000)).unwrap());
+        assert!(!tracker.record(snapshot(3, 50_000)).unwrap());
+        tracker.record(snapshot(4, 10_000)).unwrap();
+        tracker.record(snapshot(7, 20_000)).unwrap();
+
+        let daily = tracker.daily("fpt");
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].min_room, 10_000);
+        assert_eq!(daily[0].change, -30_000);
+        assert_eq!(tracker.tight_symbols(0.01).len(), 0);
+        assert_eq!(tracker.tight_symbols(0.05).len(), 1);
+    }
+
+    #[test]
+    fn test_persistence_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tracker = ForeignRoomTracker::open(dir.path()).unwrap();
+        tracker.record(snapshot(2, 50_000)).unwrap();
+        tracker.record(snapshot(4, 40_000)).unwrap();
+
+        let reopened = ForeignRoomTracker::open(dir.path()).unwrap();
+        assert_eq!(reopened.history("FPT"), tracker.history("FPT"));
+    }
+
+    #[test]
+    fn test_parse_foreign_trading_both_providers() {
+        let vci = serde_json::json!({"tradingDate": "2024-06-03T00:00:00", "foreignBuyVolumeTotal": 1200, "foreignBuyValueTotal": 3.0e10,
+            "foreignSellVolumeTotal": 2000, "foreignSellValueTotal": 5.0e10, "foreignCurrentRoom": 500000});
+        let tcbs = serde_json::json!({"dateReport": "04/06/2024", "buyForeignQuantity": "3,000", "buyForeignValue": 1.0e9, "sellForeignQuantity": 0});
+        let days: Vec<ForeignTradingDay> = [tcbs, vci].iter().filter_map(|row| parse_foreign_trading_day("hpg", row)).collect();
+        let days = clip_foreign_trading(days, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), NaiveDate::from_ymd_opt(2024, 6, 30).unwrap());
+        assert_eq!(days[0].date, NaiveDate::from_ymd_opt(2024, 6, 3).unwrap());
+        assert_eq!(days[0].net_volume(), -800);
+        assert_eq!(days[0].net_value(), -2.0e10);
+        assert_eq!(days[0].current_room, Some(500000));
+        assert_eq!((days[1].buy_volume, days[1].current_room), (3000, None));
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::tcbs::FinancialStatement;
+
+/// One quarter of reported results with growth against the same quarter a
+/// year earlier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrowthPoint {
+    pub period: String,
+    pub revenue: Option<f64>,
+    pub net_profit: Option<f64>,
+    /// Fractional change, e.g. `0.12` for +12%.
+    pub revenue_yoy: Option<f64>,
+    pub earnings_yoy: Option<f64>,
+}
+
+/// Forward estimate for a future fiscal year.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EarningsEstimate {
+    pub period: String,
+    pub revenue: Option<f64>,
+    pub net_profit: Option<f64>,
+    pub eps: Option<f64>,
+}
+
+impl EarningsEstimate {
+    /// Fractional revision of net profit from an earlier estimate of the same period.
+    pub fn revision_from(&self, previous: &EarningsEstimate) -> Option<f64> {
+        growth(self.net_profit?, previous.net_profit?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrowthProfile {
+    pub symbol: String,
+    /// Oldest first.
+    pub quarterly: Vec<GrowthPoint>,
+    /// Empty when the provider has no estimates for the symbol.
+    pub estimates: Vec<EarningsEstimate>,
+}
+
+impl GrowthProfile {
+    pub fn latest(&self) -> Option<&GrowthPoint> {
+        self.quarterly.last()
+    }
+}
+
+fn growth(current: f64, previous: f64) -> Option<f64> {
+    (previous != 0.0).then(|| (current - previous) / previous.abs())
+}
+
+/// Splits a "YYYY-Qn" label into (year, quarter).
+fn parse_quarter(label: &str) -> Option<(i32, u32)> {
+    let (year, quarter) = label.split_once("-Q")?;
+    Some((year.parse().ok()?, quarter.parse().ok()?))
+}
+
+/// Builds the quarterly series from income statements. Net profit prefers
+/// profit attributable to shareholders over total post-tax profit.
+pub fn quarterly_growth(statements: &[FinancialStatement]) -> Vec<GrowthPoint> {
+    let mut quarters: Vec<((i32, u32), GrowthPoint)> = statements.iter()
+        .filter_map(|statement| {
+            let key = parse_quarter(&statement.period)?;
+            let point = GrowthPoint {
+                period: format!("{}-Q{}", key.0, key.1),
+                revenue: statement.data.get("revenue").copied(),
+                net_profit: statement.data.get("share_holder_income")
+                    .or_else(|| statement.data.get("post_tax_profit"))
+                    .copied(),
+                revenue_yoy: None,
+                earnings_yoy: None,
+            };
+            Some((key, point))
+        })
+        .collect();
+    quarters.sort_by_key(|(key, _)| *key);
+    quarters.dedup_by_key(|(key, _)| *key);
+
+    let by_quarter: HashMap<(i32, u32), GrowthPoint> = quarters.iter().cloned().collect();
+    quarters.into_iter()
+        .map(|((year, quarter), mut point)| {
+            if let Some(prior) = by_quarter.get(&(year - 1, quarter)) {
+                point.revenue_yoy = point.revenue.zip(prior.revenue).and_then(|(c, p)| growth(c, p));
+                point.earnings_yoy = point.net_profit.zip(prior.net_profit).and_then(|(c, p)| growth(c, p));
+            }
+            point
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement(period: &str, revenue: f64, profit: f64) -> FinancialStatement {
+        FinancialStatement {
+            period: period.to_string(),
+            data: [("revenue".to_string(), revenue), ("share_holder_income".to_string(), profit)].into(),
+        }
+    }
+
+    #[test]
+    fn test_quarterly_growth_matches_same_quarter() {
+        let series = quarterly_growth(&[
+            statement("2024-Q1", 120.0, 15.0),
+            statement("2023-Q1", 100.0, -10.0),
+            statement("2023-Q2", 90.0, 0.0),
+            statement("2024-Q2", 99.0, 5.0),
+        ]);
+
+        assert_eq!(series.len(), 4);
+        assert_eq!(series[0].period, "2023-Q1");
+        assert_eq!(series[0].revenue_yoy, None);
+        let q1 = &series[2];
+        assert!((q1.revenue_yoy.unwrap() - 0.2).abs() < 1e-9);
+        assert!((q1.earnings_yoy.unwrap() - 2.5).abs() < 1e-9);
+        assert_eq!(series[3].earnings_yoy, None);
+    }
+}
@@ -0,0 +1,213 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::store::StoreError;
+
+const CSV_HEADER: &str = "index,symbol,start,end";
+
+/// A continuous stretch of index membership. `end` is the first date the
+/// symbol was no longer a member; `None` while it still is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MembershipPeriod {
+    pub index: String,
+    pub symbol: String,
+    pub start: NaiveDate,
+    pub end: Option<NaiveDate>,
+}
+
+impl MembershipPeriod {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && self.end.is_none_or(|end| date < end)
+    }
+}
+
+/// Entries and exits found by [`IndexMembershipHistory::record_constituents`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MembershipChange {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Point-in-time index membership (VN30, VNMidCap, HNX30, ...). Built by
+/// recording constituent snapshots as they are observed, optionally seeded
+/// with known historical periods, and persisted to a single CSV file.
+pub struct IndexMembershipHistory {
+    periods: Vec<MembershipPeriod>,
+    path: Option<PathBuf>,
+}
+
+impl IndexMembershipHistory {
+    pub fn new() -> Self {
+        IndexMembershipHistory {
+            periods: Vec::new(),
+            path: None,
+        }
+    }
+
+    /// Opens a persistent history at `path`, loading it when it exists.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let path = path.as_ref().to_path_buf();
+        let mut periods = Vec::new();
+        if path.exists() {
+            let reader = BufReader::new(fs::File::open(&path)?);
+            for (line_no, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line_no == 0 || line.trim().is_empty() {
+                    continue;
+                }
+                periods.push(parse_record(&line).ok_or_else(|| {
+                    StoreError::InvalidRecord(format!("{}:{}: {}", path.display(), line_no + 1, line))
+                })?);
+            }
+        }
+        Ok(IndexMembershipHistory { periods, path: Some(path) })
+    }
+
+    pub fn periods(&self) -> &[MembershipPeriod] {
+        &self.periods
+    }
+
+    /// Adds a known historical period, e.g. from an exchange rebalancing notice.
+    pub fn add_period(&mut self, period: MembershipPeriod) -> Result<(), StoreError> {
+        self.periods.push(MembershipPeriod {
+            index: period.index.to_uppercase(),
+            symbol: period.symbol.to_uppercase(),
+            ..period
+        });
+        self.save()
+    }
+
+    /// Reconciles the constituents observed on `date` with the open periods:
+    /// new symbols start a period, missing ones have theirs closed.
+    pub fn record_constituents(&mut self, index: &str, date: NaiveDate, symbols: &[String]) -> Result<MembershipChange, StoreError> {
+        let index = index.to_uppercase();
+        let observed: BTreeSet<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+        let mut change = MembershipChange::default();
+
+        for period in self.periods.iter_mut().filter(|p| p.index == index && p.end.is_none()) {
+            if !observed.contains(&period.symbol) && date > period.start {
+                period.end = Some(date);
+                change.removed.push(period.symbol.clone());
+            }
+        }
+
+        let current: BTreeSet<String> = self.members_open(&index);
+        for symbol in observed.difference(&current) {
+            self.periods.push(MembershipPeriod {
+                index: index.clone(),
+                symbol: symbol.clone(),
+                start: date,
+                end: None,
+            });
+            change.added.push(symbol.clone());
+        }
+
+        if !change.added.is_empty() || !change.removed.is_empty() {
+            self.save()?;
+        }
+        Ok(change)
+    }
+
+    /// Constituents of `index` on `date`, sorted.
+    pub fn members_as_of(&self, index: &str, date: NaiveDate) -> Vec<String> {
+        let index = index.to_uppercase();
+        let members: BTreeSet<String> = self.periods.iter()
+            .filter(|p| p.index == index && p.contains(date))
+            .map(|p| p.symbol.clone())
+            .collect();
+        members.into_iter().collect()
+    }
+
+    pub fn is_member(&self, index: &str, symbol: &str, date: NaiveDate) -> bool {
+        self.periods.iter().any(|p| {
+            p.index.eq_ignore_ascii_case(index) && p.symbol.eq_ignore_ascii_case(symbol) && p.contains(date)
+        })
+    }
+
+    fn members_open(&self, index: &str) -> BTreeSet<String> {
+        self.periods.iter()
+            .filter(|p| p.index == index && p.end.is_none())
+            .map(|p| p.symbol.clone())
+            .collect()
+    }
+
+    fn save(&self) -> Result<(), StoreError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(fs::File::create(path)?);
+        writeln!(writer, "{}", CSV_HEADER)?;
+        for period in &self.periods {
+            writeln!(
+                writer,
+                "{},{},{},{}",
+                period.index,
+                period.symbol,
+                period.start.format("%Y-%m-%d"),
+                period.end.map(|end| end.format("%Y-%m-%d").to_string()).unwrap_or_default()
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Default for IndexMembershipHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_record(line: &str) -> Option<MembershipPeriod> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() < 4 {
+        return None;
+    }
+    let end = match fields[3] {
+        "" => None,
+        date => Some(NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?),
+    };
+    Some(MembershipPeriod {
+        index: fields[0].to_string(),
+        symbol: fields[1].to_string(),
+        start: NaiveDate::parse_from_str(fields[2], "%Y-%m-%d").ok()?,
+        end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn symbols(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_point_in_time_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("membership.csv");
+        let mut history = IndexMembershipHistory::open(&path).unwrap();
+
+        history.record_constituents("vn30", date(1, 2), &symbols(&["FPT", "PDR"])).unwrap();
+        let change = history.record_constituents("VN30", date(2, 5), &symbols(&["FPT", "BVH"])).unwrap();
+        assert_eq!(change.added, vec!["BVH".to_string()]);
+        assert_eq!(change.removed, vec!["PDR".to_string()]);
+
+        let reopened = IndexMembershipHistory::open(&path).unwrap();
+        assert_eq!(reopened.members_as_of("VN30", date(1, 15)), symbols(&["FPT", "PDR"]));
+        assert_eq!(reopened.members_as_of("VN30", date(2, 5)), symbols(&["BVH", "FPT"]));
+        assert!(!reopened.is_member("VN30", "PDR", date(3, 1)));
+    }
+}
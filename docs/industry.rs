@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::vci::IndustryListing;
+
+/// One node of the ICB tree. Codes are four digits, mostly one significant
+/// digit per level: "8000" (industry) > "8300" (supersector) > "8350"
+/// (sector) > "8355" (subsector). Oil & Gas breaks the pattern ("0001" >
+/// "0500"), so parents are resolved against the tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IcbIndustry {
+    pub code: String,
+    /// 1 (broadest) to 4.
+    pub level: u8,
+    pub name: String,
+    pub en_name: Option<String>,
+}
+
+/// The ICB classification as published by the provider.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndustryTree {
+    industries: BTreeMap<String, IcbIndustry>,
+}
+
+impl IndustryTree {
+    pub fn new(industries: Vec<IcbIndustry>) -> Self {
+        IndustryTree { industries: industries.into_iter().map(|industry| (industry.code.clone(), industry)).collect() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.industries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.industries.is_empty()
+    }
+
+    pub fn get(&self, code: &str) -> Option<&IcbIndustry> {
+        self.industries.get(code)
+    }
+
+    /// Every node at `level`, in code order.
+    pub fn level(&self, level: u8) -> Vec<&IcbIndustry> {
+        self.industries.values().filter(|industry| industry.level == level).collect()
+    }
+
+    /// Enclosing node of `code`: the node one level up sharing the longest
+    /// code prefix, at least one digit per level above. `None` at level 1.
+    pub fn parent(&self, code: &str) -> Option<&IcbIndustry> {
+        let industry = self.get(code)?;
+        let keep = (industry.level as usize).checked_sub(1).filter(|&keep| keep > 0)?;
+        self.industries.values()
+            .filter(|candidate| candidate.level + 1 == industry.level)
+            .map(|candidate| (common_prefix(&candidate.code, code), candidate))
+            .filter(|(shared, _)| *shared >= keep)
+            .max_by_key(|(shared, _)| *shared)
+            .map(|(_, candidate)| candidate)
+    }
+
+    /// Direct children of `code`.
+    pub fn children(&self, code: &str) -> Vec<&IcbIndustry> {
+        self.industries.values()
+            .filter(|industry| self.parent(&industry.code).is_some_and(|parent| parent.code == code))
+            .collect()
+    }
+
+    /// `code` and its ancestors, level 1 first.
+    pub fn path(&self, code: &str) -> Vec<&IcbIndustry> {
+        let mut path = Vec::new();
+        let mut current = self.get(code);
+        while let Some(industry) = current {
+            path.push(industry);
+            current = self.parent(&industry.code);
+        }
+        path.reverse();
+        path
+    }
+}
+
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
+}
+
+/// Symbols grouped by their ICB code at `level` (1-4), for sector
+/// dashboards. Listings without a code at that level are left out.
+pub fn group_by_level(listings: &[IndustryListing], level: u8) -> BTreeMap<String, Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for listing in listings {
+        if let Some(code) = listing.icb_code(level) {
+            groups.entry(code.to_string()).or_default().push(listing.symbol.clone());
+        }
+    }
+    for symbols in groups.values_mut() {
+        symbols.sort();
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(code: &str, level: u8, name: &str) -> IcbIndustry {
+        IcbIndustry { code: code.to_string(), level, name: name.to_string(), en_name: None }
+    }
+
+    #[test]
+    fn test_tree_and_grouping() {
+        let tree = IndustryTree::new(vec![
+            node("8000", 1, "Tài chính"),
+            node("8300", 2, "Ngân hàng"),
+            node("8350", 3, "Ngân hàng"),
+            node("8355", 4, "Ngân hàng"),
+            node("8700", 2, "Dịch vụ tài chính"),
+            node("0001", 1, "Dầu khí"),
+            node("0500", 2, "Dầu khí"),
+            node("0530", 3, "Sản xuất dầu khí"),
+        ]);
+        assert_eq!(tree.path("8355").iter().map(|industry| industry.code.as_str()).collect::<Vec<_>>(), vec!["8000", "8300", "8350", "8355"]);
+        assert_eq!(tree.children("8000").len(), 2);
+        assert_eq!(tree.level(2)[2].name, "Dịch vụ tài chính");
+        assert!(tree.parent("8000").is_none());
+        assert_eq!(tree.path("0530").iter().map(|industry| industry.code.as_str()).collect::<Vec<_>>(), vec!["0001", "0500", "0530"]);
+        assert_eq!(tree.children("0001").len(), 1);
+
+        let listing = |symbol: &str, code: &str| IndustryListing {
+            symbol: symbol.to_string(),
+            organ_name: None,
+            icb_name2: None,
+            icb_name3: None,
+            icb_name4: None,
+            icb_codes: [Some("8000".to_string()), Some(code.to_string()), None, None],
+        };
+        let groups = group_by_level(&[listing("VCB", "8300"), listing("SSI", "8700"), listing("ACB", "8300")], 2);
+        assert_eq!(groups["8300"], vec!["ACB", "VCB"]);
+        assert_eq!(group_by_level(&[listing("VCB", "8300")], 3).len(), 0);
+    }
+}
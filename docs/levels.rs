@@ -0,0 +1,251 @@
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{vietnam_offset, Ohlcv};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PivotMethod {
+    /// Floor-trader pivots: P = (H + L + C) / 3.
+    Classic,
+    /// Support/resistance at 0.382, 0.618 and 1.0 of the range from P.
+    Fibonacci,
+}
+
+/// Pivot levels for the next session, derived from one completed bar.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PivotLevels {
+    pub pivot: f64,
+    pub r1: f64,
+    pub r2: f64,
+    pub r3: f64,
+    pub s1: f64,
+    pub s2: f64,
+    pub s3: f64,
+}
+
+impl PivotLevels {
+    /// Levels from highest to lowest, paired with their names.
+    pub fn levels(&self) -> [(&'static str, f64); 7] {
+        [("R3", self.r3), ("R2", self.r2), ("R1", self.r1), ("P", self.pivot), ("S1", self.s1), ("S2", self.s2), ("S3", self.s3)]
+    }
+}
+
+/// Pivots from the previous period's bar: the prior day for daily levels,
+/// or a weekly/monthly bar (see `resample`) for longer ones.
+pub fn pivot_points(prior: &Ohlcv, method: PivotMethod) -> PivotLevels {
+    let (high, low, close) = (prior.high, prior.low, prior.close);
+    let pivot = (high + low + close) / 3.0;
+    let range = high - low;
+    match method {
+        PivotMethod::Classic => PivotLevels {
+            pivot,
+            r1: 2.0 * pivot - low,
+            r2: pivot + range,
+            r3: high + 2.0 * (pivot - low),
+            s1: 2.0 * pivot - high,
+            s2: pivot - range,
+            s3: low - 2.0 * (high - pivot),
+        },
+        PivotMethod::Fibonacci => PivotLevels {
+            pivot,
+            r1: pivot + 0.382 * range,
+            r2: pivot + 0.618 * range,
+            r3: pivot + range,
+            s1: pivot - 0.382 * range,
+            s2: pivot - 0.618 * range,
+            s3: pivot - range,
+        },
+    }
+}
+
+/// High/low/close of the last completed day, week and month before the
+/// final bar of a daily series.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriorLevels {
+    pub day_high: f64,
+    pub day_low: f64,
+    pub day_close: f64,
+    pub week_high: Option<f64>,
+    pub week_low: Option<f64>,
+    pub month_high: Option<f64>,
+    pub month_low: Option<f64>,
+}
+
+/// Prior-period levels relative to the last bar of `bars` (daily, oldest
+/// first). Weeks and months follow exchange dates.
+pub fn prior_levels(bars: &[Ohlcv]) -> Option<PriorLevels> {
+    let [.., previous, last] = bars else { return None };
+    let date = |bar: &Ohlcv| bar.time.with_timezone(&vietnam_offset()).date_naive();
+    let today = date(last);
+    let week = |d: chrono::NaiveDate| (d.iso_week().year(), d.iso_week().week());
+    let month = |d: chrono::NaiveDate| (d.year(), d.month());
+
+    let extremes = |in_period: &dyn Fn(&Ohlcv) -> bool| {
+        let mut period = bars.iter().filter(|bar| in_period(bar)).peekable();
+        period.peek()?;
+        let (high, low) = period.fold((f64::MIN, f64::MAX), |(high, low), bar| (high.max(bar.high), low.min(bar.low)));
+        Some((high, low))
+    };
+    let prior_week = bars.iter().rev().map(date).find(|d| week(*d) < week(today)).map(week);
+    let prior_month = bars.iter().rev().map(date).find(|d| month(*d) < month(today)).map(month);
+    let week_levels = prior_week.and_then(|w| extremes(&|bar| week(date(bar)) == w));
+    let month_levels = prior_month.and_then(|m| extremes(&|bar| month(date(bar)) == m));
+
+    Some(PriorLevels {
+        day_high: previous.high,
+        day_low: previous.low,
+        day_close: previous.close,
+        week_high: week_levels.map(|(high, _)| high),
+        week_low: week_levels.map(|(_, low)| low),
+        month_high: month_levels.map(|(high, _)| high),
+        month_low: month_levels.map(|(_, low)| low),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwingKind {
+    High,
+    Low,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SwingPoint {
+    pub time: DateTime<Utc>,
+    pub price: f64,
+    pub kind: SwingKind,
+}
+
+/// Bars whose high (low) is strictly above (below) the `strength` bars on
+/// each side. The last `strength` bars can't be confirmed yet and are skipped.
+pub fn swing_points(bars: &[Ohlcv], strength: usize) -> Vec<SwingPoint> {
+    let strength = strength.max(1);
+    if bars.len() < 2 * strength + 1 {
+        return Vec::new();
+    }
+    let mut points = Vec::new();
+    for i in strength..bars.len() - strength {
+        let neighbours = bars[i - strength..i].iter().chain(&bars[i + 1..=i + strength]);
+        let (mut is_high, mut is_low) = (true, true);
+        for other in neighbours {
+            is_high &= bars[i].high > other.high;
+            is_low &= bars[i].low < other.low;
+        }
+        if is_high {
+            points.push(SwingPoint { time: bars[i].time, price: bars[i].high, kind: SwingKind::High });
+        }
+        if is_low {
+            points.push(SwingPoint { time: bars[i].time, price: bars[i].low, kind: SwingKind::Low });
+        }
+    }
+    points
+}
+
+/// A price zone where swing points cluster.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Level {
+    /// Mean price of the clustered swing points.
+    pub price: f64,
+    pub touches: usize,
+    pub last_touch: DateTime<Utc>,
+}
+
+/// Groups swing points whose prices lie within `tolerance` (a fraction,
+/// e.g. 0.01) of a cluster's mean. Sorted by price, lowest first.
+pub fn support_resistance(bars: &[Ohlcv], strength: usize, tolerance: f64) -> Vec<Level> {
+    let mut points = swing_points(bars, strength);
+    points.sort_by(|a, b| a.price.total_cmp(&b.price));
+    let mut levels: Vec<(f64, Vec<SwingPoint>)> = Vec::new();
+    for point in points {
+        match levels.last_mut() {
+            Some((mean, members)) if (point.price - *mean).abs() <= tolerance * *mean => {
+                members.push(point);
+                *mean = members.iter().map(|p| p.price).sum::<f64>() / members.len() as f64;
+            }
+            _ => levels.push((point.price, vec![point])),
+        }
+    }
+    levels.into_iter()
+        .map(|(price, members)| Level {
+            price,
+            touches: members.len(),
+            last_touch: members.iter().map(|p| p.time).max().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Closest level below and above `price`.
+pub fn nearest(levels: &[Level], price: f64) -> (Option<&Level>, Option<&Level>) {
+    let support = levels.iter().filter(|level| level.price <= price).max_by(|a, b| a.price.total_cmp(&b.price));
+    let resistance = levels.iter().filter(|level| level.price > price).min_by(|a, b| a.price.total_cmp(&b.price));
+    (support, resistance)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Crossing {
+    Above,
+    Below,
+}
+
+/// Whether a move from `previous` to `current` crossed `level`, for alert
+/// conditions.
+pub fn crossing(level: f64, previous: f64, current: f64) -> Option<Crossing> {
+    if previous < level && current >= level {
+        Some(Crossing::Above)
+    } else if previous > level && current <= level {
+        Some(Crossing::Below)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn bar(month: u32, day: u32, high: f64, low: f64, close: f64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, month, day, 0, 0, 0).unwrap(),
+            open: close,
+            high,
+            low,
+            close,
+            volume: 0,
+            symbol: None,
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_pivots_and_prior_levels() {
+        let levels = pivot_points(&bar(6, 3, 110.0, 90.0, 100.0), PivotMethod::Classic);
+        assert_eq!((levels.pivot, levels.r1, levels.s1, levels.r2, levels.s3), (100.0, 110.0, 90.0, 120.0, 70.0));
+        let fib = pivot_points(&bar(6, 3, 110.0, 90.0, 100.0), PivotMethod::Fibonacci);
+        assert!((fib.r1 - 107.64).abs() < 1e-9);
+
+        // Fri 31 May, then Mon-Wed 3-5 June.
+        let bars = vec![bar(5, 30, 99.0, 95.0, 97.0), bar(5, 31, 101.0, 96.0, 100.0), bar(6, 3, 103.0, 98.0, 102.0), bar(6, 4, 104.0, 97.0, 99.0), bar(6, 5, 100.0, 98.0, 99.0)];
+        let prior = prior_levels(&bars).unwrap();
+        assert_eq!((prior.day_high, prior.day_low), (104.0, 97.0));
+        assert_eq!((prior.week_high, prior.week_low), (Some(101.0), Some(95.0)));
+        assert_eq!(prior.month_high, Some(101.0));
+    }
+
+    #[test]
+    fn test_swings_cluster_into_levels() {
+        let highs = [10.0, 12.0, 15.0, 12.0, 11.0, 13.0, 15.1, 13.0, 12.0, 11.0, 9.0];
+        let bars: Vec<Ohlcv> = highs.iter().enumerate().map(|(i, &h)| bar(6, i as u32 + 1, h, h - 2.0, h - 1.0)).collect();
+        let swings = swing_points(&bars, 2);
+        assert_eq!(swings.iter().filter(|s| s.kind == SwingKind::High).count(), 2);
+
+        let levels = support_resistance(&bars, 2, 0.01);
+        let top = levels.iter().find(|level| level.touches == 2).unwrap();
+        assert!((top.price - 15.05).abs() < 1e-9);
+        let (support, resistance) = nearest(&levels, 14.0);
+        assert!(support.is_some_and(|level| level.price < 14.0));
+        assert_eq!(resistance.map(|level| level.touches), Some(2));
+        assert_eq!(crossing(top.price, 14.9, 15.2), Some(Crossing::Above));
+        assert_eq!(crossing(top.price, 14.0, 14.5), None);
+    }
+}
@@ -1,5 +1,76 @@
 pub mod vci;
 pub mod tcbs;
+pub mod models;
+pub mod rate_limit;
+pub mod resample;
+pub mod store;
+pub mod export;
+pub mod udf;
+pub mod market_rules;
+pub mod auction;
+pub mod foreign_room;
+pub mod compare;
+pub mod growth;
+pub mod text;
+pub mod metadata;
+pub mod index_membership;
+pub mod universe;
+pub mod sector;
+pub mod streaming;
+pub mod session;
+pub mod calendar;
+pub mod valuation;
+pub mod events;
+pub mod stats;
+pub mod failover;
+pub mod chaos;
+pub mod testing;
+pub mod range;
+pub mod quality;
+pub mod liquidity;
+pub mod spread;
+pub mod profile;
+pub mod shutdown;
+pub mod preflight;
+pub mod pagination;
+pub mod watchlist;
+pub mod portfolio;
+pub mod provider;
+pub mod risk;
+pub mod rebalance;
+pub mod stress;
+pub mod correlation;
+pub mod pairs;
+pub mod seasonality;
+pub mod event_study;
+pub mod coalesce;
+pub mod retry;
+pub mod circuit;
+pub mod news_feed;
+pub mod screener;
+pub mod ranking;
+pub mod entrade;
+pub mod ta;
+pub mod fireant;
+pub mod patterns;
+pub mod derivatives;
+pub mod levels;
+pub mod proprietary;
+pub mod rotation;
+pub mod corporate_actions;
+pub mod money_flow;
+pub mod volatility;
+pub mod limit_moves;
+pub mod symbol_search;
+pub mod provenance;
+pub mod industry;
+pub mod statements;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "cache")]
+pub mod search;
+#[cfg(feature = "sentiment")]
+pub mod sentiment;
 
 pub use vci::{VciClient, VciError};
 pub use tcbs::{TcbsClient, TcbsError};
@@ -7,6 +78,10 @@ pub use tcbs::{TcbsClient, TcbsError};
 // Re-export common types
 pub use vci::{OhlcvData as VciOhlcvData, CompanyInfo as VciCompanyInfo};
 pub use tcbs::{OhlcvData as TcbsOhlcvData, CompanyInfo as TcbsCompanyInfo};
+pub use models::{DateParam, DateRange, Exchange, Index, Interval, Language, Ohlcv, PriceAdjustment, Quote, SecurityType, TradingStatus, VolumeBreakdown};
+pub use store::{LocalStore, StoreError};
+pub use provider::{ProviderError, StockDataProvider};
+pub use retry::RetryPolicy;
 
 #[cfg(test)]
 mod tests {
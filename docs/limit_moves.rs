@@ -0,0 +1,231 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::corporate_actions::CorporateAction;
+use crate::market_rules;
+use crate::models::{vietnam_offset, Exchange, Ohlcv, Quote};
+
+/// Side of the daily price band a close pinned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LimitHit {
+    /// Closed at the ceiling ("trần").
+    Ceiling,
+    /// Closed at the floor ("sàn").
+    Floor,
+}
+
+/// Whether `close` sits on the band around `reference`, allowing half a tick
+/// for providers that report rounded prices.
+pub fn limit_hit(close: f64, reference: f64, exchange: Exchange) -> Option<LimitHit> {
+    if reference <= 0.0 {
+        return None;
+    }
+    let (floor, ceiling) = market_rules::price_limits(reference, exchange);
+    let tolerance = market_rules::tick_size(close, exchange) / 2.0;
+    if close >= ceiling - tolerance {
+        Some(LimitHit::Ceiling)
+    } else if close <= floor + tolerance {
+        Some(LimitHit::Floor)
+    } else {
+        None
+    }
+}
+
+/// Live check against the band the board publishes, which also covers
+/// first-day and post-suspension bands.
+pub fn quote_limit_hit(quote: &Quote) -> Option<LimitHit> {
+    match (quote.ceiling_price, quote.floor_price) {
+        (Some(ceiling), _) if ceiling > 0.0 && quote.price >= ceiling => Some(LimitHit::Ceiling),
+        (_, Some(floor)) if floor > 0.0 && quote.price <= floor => Some(LimitHit::Floor),
+        _ => None,
+    }
+}
+
+/// Band reference of each daily bar: the previous close, moved to the
+/// exchange's ex-rights reference on the ex-date of any of `actions`. UPCOM
+/// references the previous session's average matched price, which daily
+/// bars don't carry, so UPCOM bars (and the first bar) get `None`.
+pub fn reference_prices(bars: &[Ohlcv], exchange: Exchange, actions: &[CorporateAction]) -> Vec<Option<f64>> {
+    let mut references = vec![None; bars.len()];
+    if exchange == Exchange::Upcom {
+        return references;
+    }
+    for (i, pair) in bars.windows(2).enumerate() {
+        let date = session_date(&pair[1]);
+        let previous = session_date(&pair[0]);
+        let reference = actions.iter()
+            .filter(|action| action.ex_date > previous && action.ex_date <= date)
+            .fold(pair[0].close, |reference, action| reference * action.price_factor(reference));
+        references[i + 1] = Some(reference);
+    }
+    references
+}
+
+/// Limit closes of daily bars against `references`, one per bar. Bars must
+/// be unadjusted and in VND: adjusted history moves the closes off the
+/// exchange's band.
+pub fn limit_days_with(bars: &[Ohlcv], exchange: Exchange, references: &[Option<f64>]) -> Vec<Option<LimitHit>> {
+    bars.iter()
+        .zip(references.iter().copied().chain(std::iter::repeat(None)))
+        .map(|(bar, reference)| limit_hit(bar.close, reference?, exchange))
+        .collect()
+}
+
+/// Limit closes of daily bars with references from [`reference_prices`].
+pub fn limit_days(bars: &[Ohlcv], exchange: Exchange, actions: &[CorporateAction]) -> Vec<Option<LimitHit>> {
+    limit_days_with(bars, exchange, &reference_prices(bars, exchange, actions))
+}
+
+/// Run of consecutive limit closes on the same side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LimitStreak {
+    pub symbol: String,
+    pub hit: LimitHit,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub sessions: usize,
+}
+
+fn session_date(bar: &Ohlcv) -> NaiveDate {
+    bar.time.with_timezone(&vietnam_offset()).date_naive()
+}
+
+/// Every streak of at least `min_sessions` consecutive limit closes, with
+/// ex-dates in `actions` moving the reference. Empty for UPCOM (see
+/// [`reference_prices`]).
+pub fn limit_streaks(symbol: &str, bars: &[Ohlcv], exchange: Exchange, actions: &[CorporateAction], min_sessions: usize) -> Vec<LimitStreak> {
+    let mut streaks: Vec<LimitStreak> = Vec::new();
+    let mut current: Option<LimitStreak> = None;
+    for (bar, hit) in bars.iter().zip(limit_days(bars, exchange, actions)) {
+        let date = session_date(bar);
+        match (current.as_mut(), hit) {
+            (Some(streak), Some(hit)) if streak.hit == hit => {
+                streak.end = date;
+                streak.sessions += 1;
+            }
+            (_, hit) => {
+                streaks.extend(current.take().filter(|streak| streak.sessions >= min_sessions.max(1)));
+                current = hit.map(|hit| LimitStreak { symbol: symbol.to_uppercase(), hit, start: date, end: date, sessions: 1 });
+            }
+        }
+    }
+    streaks.extend(current.filter(|streak| streak.sessions >= min_sessions.max(1)));
+    streaks
+}
+
+/// Streak running into the latest bar, if the last close was at a limit.
+pub fn current_streak(symbol: &str, bars: &[Ohlcv], exchange: Exchange, actions: &[CorporateAction]) -> Option<LimitStreak> {
+    let last = session_date(bars.last()?);
+    limit_streaks(symbol, bars, exchange, actions, 1).pop().filter(|streak| streak.end == last)
+}
+
+/// Market-wide count of symbols closing at each limit on one session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LimitBreadth {
+    pub date: NaiveDate,
+    pub ceiling: usize,
+    pub floor: usize,
+    /// Symbols with a bar that session.
+    pub traded: usize,
+}
+
+/// Per-session ceiling/floor counts over daily `history`, oldest first,
+/// with each symbol's `actions` moving its reference on ex-dates. Symbols
+/// missing from `exchanges` are skipped, since the band depends on the
+/// exchange, and so are UPCOM symbols (see [`reference_prices`]).
+pub fn limit_breadth(
+    history: &HashMap<String, Vec<Ohlcv>>,
+    exchanges: &HashMap<String, Exchange>,
+    actions: &HashMap<String, Vec<CorporateAction>>,
+) -> Vec<LimitBreadth> {
+    let mut days: BTreeMap<NaiveDate, LimitBreadth> = BTreeMap::new();
+    for (symbol, bars) in history {
+        let Some(&exchange) = exchanges.get(symbol).filter(|exchange| **exchange != Exchange::Upcom) else {
+            continue;
+        };
+        let actions = actions.get(symbol).map(Vec::as_slice).unwrap_or_default();
+        for (bar, hit) in bars.iter().zip(limit_days(bars, exchange, actions)).skip(1) {
+            let date = session_date(bar);
+            let day = days.entry(date).or_insert_with(|| LimitBreadth { date, ..Default::default() });
+            day.traded += 1;
+            match hit {
+                Some(LimitHit::Ceiling) => day.ceiling += 1,
+                Some(LimitHit::Floor) => day.floor += 1,
+                None => {}
+            }
+        }
+    }
+    days.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::corporate_actions::ActionKind;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn bars(closes: &[f64]) -> Vec<Ohlcv> {
+        closes.iter()
+            .enumerate()
+            .map(|(i, &close)| Ohlcv {
+                time: Utc.with_ymd_and_hms(2024, 6, 3, 2, 0, 0).unwrap() + Duration::days(i as i64),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 1_000,
+                symbol: None,
+                breakdown: None,
+                futures: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_limit_streaks() {
+        // A 2,000 dividend ex on the last day takes the reference to 20,000, ceiling 21,400
+        let ex_dividend = CorporateAction {
+            symbol: "HPG".to_string(),
+            kind: ActionKind::CashDividend { amount_per_share: 2_000.0 },
+            ex_date: NaiveDate::from_ymd_opt(2024, 6, 4).unwrap(),
+            record_date: None,
+            payment_date: None,
+            title: String::new(),
+        };
+        let ex_day = bars(&[22_000.0, 21_400.0]);
+        assert_eq!(limit_days(&ex_day, Exchange::Hose, &[]), vec![None, None]);
+        assert_eq!(limit_days(&ex_day, Exchange::Hose, &[ex_dividend]), vec![None, Some(LimitHit::Ceiling)]);
+
+        // HOSE +/-7%: 20,000 -> 21,400 -> 22,850 (ceiling 22,850) -> 22,000 -> 20,500 (floor 20,500)
+        let bars = bars(&[20_000.0, 21_400.0, 22_850.0, 22_000.0, 20_500.0]);
+        assert_eq!(limit_days(&bars, Exchange::Hose, &[]), vec![None, Some(LimitHit::Ceiling), Some(LimitHit::Ceiling), None, Some(LimitHit::Floor)]);
+        assert!(limit_days(&bars, Exchange::Upcom, &[]).iter().all(Option::is_none));
+
+        let streaks = limit_streaks("hpg", &bars, Exchange::Hose, &[], 2);
+        assert_eq!(streaks.len(), 1);
+        assert_eq!((streaks[0].hit, streaks[0].sessions, streaks[0].start), (LimitHit::Ceiling, 2, NaiveDate::from_ymd_opt(2024, 6, 4).unwrap()));
+
+        let current = current_streak("hpg", &bars, Exchange::Hose, &[]).unwrap();
+        assert_eq!((current.hit, current.sessions), (LimitHit::Floor, 1));
+        assert_eq!(limit_hit(21_400.0, 20_000.0, Exchange::Hnx), None);
+    }
+
+    #[test]
+    fn test_limit_breadth() {
+        let history = HashMap::from([
+            ("AAA".to_string(), bars(&[10_000.0, 10_700.0])),
+            ("BBB".to_string(), bars(&[10_000.0, 9_300.0])),
+            ("CCC".to_string(), bars(&[10_000.0, 10_100.0])),
+            ("DDD".to_string(), bars(&[10_000.0, 10_700.0])),
+        ]);
+        let exchanges = HashMap::from([
+            ("AAA".to_string(), Exchange::Hose),
+            ("BBB".to_string(), Exchange::Hose),
+            ("CCC".to_string(), Exchange::Hnx),
+        ]);
+        let breadth = limit_breadth(&history, &exchanges, &HashMap::new());
+        assert_eq!(breadth.len(), 1);
+        assert_eq!((breadth[0].ceiling, breadth[0].floor, breadth[0].traded), (1, 1, 3));
+    }
+}
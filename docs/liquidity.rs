@@ -0,0 +1,83 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::{vietnam_offset, TickData};
+
+/// Traded volume and value ("GTGD") for one bar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiquidityBar {
+    pub time: DateTime<Utc>,
+    pub volume: u64,
+    /// Traded value in VND.
+    pub value: Option<f64>,
+    /// True when `value` was approximated from price × volume because the
+    /// provider did not report it.
+    pub value_estimated: bool,
+    /// Number of matched trades, when known.
+    pub trades: Option<u64>,
+}
+
+impl LiquidityBar {
+    /// Average shares per matched trade.
+    pub fn avg_trade_size(&self) -> Option<f64> {
+        self.trades.filter(|&trades| trades > 0).map(|trades| self.volume as f64 / trades as f64)
+    }
+
+    /// Average VND per matched trade.
+    pub fn avg_trade_value(&self) -> Option<f64> {
+        Some(self.value? / self.trades.filter(|&trades| trades > 0)? as f64)
+    }
+
+    /// Value-weighted average price.
+    pub fn vwap(&self) -> Option<f64> {
+        (self.volume > 0).then(|| self.value.map(|value| value / self.volume as f64)).flatten()
+    }
+}
+
+/// Daily liquidity (exchange-time days) from matched trades, with exact
+/// trade counts.
+pub fn daily_from_ticks(ticks: &[TickData]) -> Vec<LiquidityBar> {
+    let mut days: BTreeMap<chrono::NaiveDate, LiquidityBar> = BTreeMap::new();
+    for tick in ticks {
+        let date = tick.time.with_timezone(&vietnam_offset()).date_naive();
+        let day = days.entry(date).or_insert_with(|| LiquidityBar {
+            time: vietnam_offset()
+                .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+                .unwrap()
+                .with_timezone(&Utc),
+            volume: 0,
+            value: Some(0.0),
+            value_estimated: false,
+            trades: Some(0),
+        });
+        day.volume += tick.volume;
+        day.value = day.value.map(|value| value + tick.price * tick.volume as f64);
+        day.trades = day.trades.map(|trades| trades + 1);
+    }
+    days.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TradeSide;
+
+    #[test]
+    fn test_daily_from_ticks() {
+        let tick = |hour: u32, price: f64, volume: u64| TickData {
+            time: Utc.with_ymd_and_hms(2024, 6, 3, hour, 0, 0).unwrap(),
+            price,
+            volume,
+            side: TradeSide::Buy,
+            id: None,
+        };
+        let days = daily_from_ticks(&[tick(2, 100.0, 300), tick(3, 110.0, 100), tick(20, 105.0, 200)]);
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].trades, Some(2));
+        assert_eq!(days[0].avg_trade_size(), Some(200.0));
+        assert_eq!(days[0].vwap(), Some(102.5));
+        assert_eq!(days[1].avg_trade_value(), Some(21_000.0));
+    }
+}
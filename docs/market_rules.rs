@@ -0,0 +1,160 @@
+use crate::models::Exchange;
+
+/// Kind of security, since tick tables differ between stocks and funds/warrants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityKind {
+    Stock,
+    /// ETFs, closed-end funds and covered warrants.
+    FundOrWarrant,
+}
+
+/// Minimum price increment for a stock on `exchange` at `price`.
+///
+/// HOSE uses a stepped table (10 / 50 / 100 VND below 10,000 / 50,000 /
+/// above); HNX and UPCOM use a flat 100 VND.
+pub fn tick_size(price: f64, exchange: Exchange) -> f64 {
+    tick_size_for(price, exchange, SecurityKind::Stock)
+}
+
+pub fn tick_size_for(price: f64, exchange: Exchange, kind: SecurityKind) -> f64 {
+    match (exchange, kind) {
+        (Exchange::Hose, SecurityKind::FundOrWarrant) => 10.0,
+        (Exchange::Hose, SecurityKind::Stock) => {
+            if price < 10_000.0 {
+                10.0
+            } else if price < 50_000.0 {
+                50.0
+            } else {
+                100.0
+            }
+        }
+        (Exchange::Hnx, SecurityKind::FundOrWarrant) | (Exchange::Upcom, SecurityKind::FundOrWarrant) => 1.0,
+        (Exchange::Hnx, SecurityKind::Stock) | (Exchange::Upcom, SecurityKind::Stock) => 100.0,
+    }
+}
+
+/// Rounds `price` to the nearest valid tick for a stock on `exchange`.
+pub fn round_to_tick(price: f64, exchange: Exchange) -> f64 {
+    let tick = tick_size(price, exchange);
+    (price / tick).round() * tick
+}
+
+fn floor_to_tick(price: f64, exchange: Exchange) -> f64 {
+    let tick = tick_size(price, exchange);
+    // Epsilon guards against 25_700.0 / 50.0 landing at 513.999...
+    ((price / tick) + 1e-9).floor() * tick
+}
+
+fn ceil_to_tick(price: f64, exchange: Exchange) -> f64 {
+    let tick = tick_size(price, exchange);
+    ((price / tick) - 1e-9).ceil() * tick
+}
+
+/// True when `price` sits exactly on the tick grid for `exchange`.
+pub fn is_valid_tick(price: f64, exchange: Exchange) -> bool {
+    (round_to_tick(price, exchange) - price).abs() < 1e-6
+}
+
+/// Standard board lot in shares. Odd lots (1-99) trade in a separate book.
+pub fn board_lot(exchange: Exchange) -> u64 {
+    match exchange {
+        Exchange::Hose | Exchange::Hnx | Exchange::Upcom => 100,
+    }
+}
+
+/// Maximum quantity per order, where the exchange caps it.
+pub fn max_order_quantity(exchange: Exchange) -> Option<u64> {
+    match exchange {
+        Exchange::Hose => Some(500_000),
+        Exchange::Hnx | Exchange::Upcom => None,
+    }
+}
+
+/// Rounds a share quantity down to a whole number of board lots.
+pub fn round_to_lot(quantity: u64, exchange: Exchange) -> u64 {
+    let lot = board_lot(exchange);
+    quantity / lot * lot
+}
+
+/// Daily price band as a fraction of the reference price.
+pub fn price_band_pct(exchange: Exchange) -> f64 {
+    match exchange {
+        Exchange::Hose => 0.07,
+        Exchange::Hnx => 0.10,
+        Exchange::Upcom => 0.15,
+    }
+}
+
+/// Wider band applied on a stock's first trading day or after a suspension
+/// of 25 sessions or more.
+pub fn first_day_band_pct(exchange: Exchange) -> f64 {
+    match exchange {
+        Exchange::Hose => 0.20,
+        Exchange::Hnx => 0.30,
+        Exchange::Upcom => 0.40,
+    }
+}
+
+/// Ceiling and floor prices for a session, as `(floor, ceiling)`.
+///
+/// The ceiling is rounded down and the floor rounded up to the tick grid.
+/// When rounding collapses a limit onto the reference price (very low-priced
+/// stocks), the exchange widens it by one tick.
+pub fn price_limits(reference: f64, exchange: Exchange) -> (f64, f64) {
+    price_limits_with_band(reference, exchange, price_band_pct(exchange))
+}
+
+pub fn price_limits_with_band(reference: f64, exchange: Exchange, band: f64) -> (f64, f64) {
+    let mut ceiling = floor_to_tick(reference * (1.0 + band), exchange);
+    let mut floor = ceil_to_tick(reference * (1.0 - band), exchange);
+
+    if ceiling <= reference {
+        ceiling = reference + tick_size(reference, exchange);
+    }
+    if floor >= reference {
+        floor = (reference - tick_size(reference, exchange)).max(tick_size(reference, exchange));
+    }
+
+    (floor, ceiling)
+}
+
+/// True when `price` is within `[floor, ceiling]` for the given reference.
+pub fn within_band(price: f64, reference: f64, exchange: Exchange) -> bool {
+    let (floor, ceiling) = price_limits(reference, exchange);
+    price >= floor - 1e-6 && price <= ceiling + 1e-6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hose_tick_table() {
+        assert_eq!(tick_size(9_990.0, Exchange::Hose), 10.0);
+        assert_eq!(tick_size(25_000.0, Exchange::Hose), 50.0);
+        assert_eq!(tick_size(120_000.0, Exchange::Hose), 100.0);
+        assert_eq!(tick_size(120_000.0, Exchange::Hnx), 100.0);
+        assert_eq!(round_to_tick(25_020.0, Exchange::Hose), 25_000.0);
+        assert_eq!(round_to_tick(25_030.0, Exchange::Hose), 25_050.0);
+        assert!(is_valid_tick(25_050.0, Exchange::Hose));
+        assert!(!is_valid_tick(25_060.0, Exchange::Hose));
+    }
+
+    #[test]
+    fn test_price_limits() {
+        // 24,000 * 1.07 = 25,680 -> 25,650; 24,000 * 0.93 = 22,320 -> 22,350
+        assert_eq!(price_limits(24_000.0, Exchange::Hose), (22_350.0, 25_650.0));
+        assert_eq!(price_limits(10_000.0, Exchange::Hnx), (9_000.0, 11_000.0));
+        assert_eq!(price_limits(10_000.0, Exchange::Upcom), (8_500.0, 11_500.0));
+        // Rounding would collapse onto the reference; widen by one tick
+        assert_eq!(price_limits(500.0, Exchange::Hnx), (400.0, 600.0));
+        assert!(within_band(25_650.0, 24_000.0, Exchange::Hose));
+        assert!(!within_band(25_700.0, 24_000.0, Exchange::Hose));
+    }
+
+    #[test]
+    fn test_round_to_lot() {
+        assert_eq!(round_to_lot(1_250, Exchange::Hose), 1_200);
+        assert_eq!(round_to_lot(99, Exchange::Upcom), 0);
+    }
+}
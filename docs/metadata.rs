@@ -0,0 +1,169 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use crate::market_rules;
+use crate::models::{Exchange, Language};
+use crate::shutdown::{self, ShutdownToken};
+use crate::vci::{CompanyInfo, CompanySection, VciClient, VciError};
+
+/// Static per-symbol facts used to enrich quotes and ticks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolMetadata {
+    pub symbol: String,
+    pub exchange: Option<Exchange>,
+    pub full_name: Option<String>,
+    pub industry: Option<String>,
+    pub board_lot: u64,
+    /// Daily price band as a fraction of the reference price.
+    pub price_band_pct: Option<f64>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl SymbolMetadata {
+    pub fn from_company_info(info: &CompanyInfo, fetched_at: DateTime<Utc>) -> Self {
+        let exchange = info.exchange.as_deref().and_then(|e| e.parse::<Exchange>().ok());
+        SymbolMetadata {
+            symbol: info.symbol.to_uppercase(),
+            exchange,
+            full_name: info.company_name.clone(),
+            industry: info.industry.clone(),
+            board_lot: exchange.map(market_rules::board_lot).unwrap_or(100),
+            price_band_pct: exchange.map(market_rules::price_band_pct),
+            fetched_at,
+        }
+    }
+}
+
+/// In-memory metadata cache. Lookups never wait on the network once a symbol
+/// has been seen: stale entries are returned as-is while a background task
+/// refreshes them.
+pub struct MetadataCache {
+    client: Arc<VciClient>,
+    ttl: Duration,
+    entries: RwLock<HashMap<String, SymbolMetadata>>,
+    refreshing: Mutex<HashSet<String>>,
+}
+
+impl MetadataCache {
+    pub fn new(client: Arc<VciClient>, ttl: Duration) -> Arc<Self> {
+        Arc::new(MetadataCache {
+            client,
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+            refreshing: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Cached entry only, without any network access.
+    pub fn cached(&self, symbol: &str) -> Option<SymbolMetadata> {
+        self.entries.read().unwrap().get(&symbol.to_uppercase()).cloned()
+    }
+
+    pub fn insert(&self, metadata: SymbolMetadata) {
+        self.entries.write().unwrap().insert(metadata.symbol.clone(), metadata);
+    }
+
+    pub fn symbols(&self) -> Vec<String> {
+        self.entries.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Returns cached metadata, scheduling a background refresh when stale.
+    /// Only the first lookup of a symbol goes to the network inline.
+    pub async fn get(self: &Arc<Self>, symbol: &str) -> Result<SymbolMetadata, VciError> {
+        let symbol = symbol.to_uppercase();
+        if let Some(metadata) = self.cached(&symbol) {
+            if Utc::now() - metadata.fetched_at >= self.ttl {
+                self.spawn_refresh(symbol);
+            }
+            return Ok(metadata);
+        }
+        self.refresh(&symbol).await
+    }
+
+    /// Fetches and stores fresh metadata for `symbol`.
+    pub async fn refresh(&self, symbol: &str) -> Result<SymbolMetadata, VciError> {
+        let info = self.client.company_info_with(symbol, Language::Vietnamese, &[CompanySection::Listing, CompanySection::Price]).await?;
+        let metadata = SymbolMetadata::from_company_info(&info, Utc::now());
+        self.insert(metadata.clone());
+        Ok(metadata)
+    }
+
+    fn spawn_refresh(self: &Arc<Self>, symbol: String) {
+        if !self.refreshing.lock().unwrap().insert(symbol.clone()) {
+            return; // Already in flight
+        }
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(e) = cache.refresh(&symbol).await {
+                tracing::warn!("Metadata refresh failed for {}: {:?}", symbol, e);
+            }
+            cache.refreshing.lock().unwrap().remove(&symbol);
+        });
+    }
+
+    /// Periodically refreshes every cached symbol whose entry has expired.
+    /// Stops once `shutdown` is signalled, or when the handle is aborted.
+    pub fn spawn_refresh_loop(self: &Arc<Self>, every: std::time::Duration, mut shutdown: Option<ShutdownToken>) -> JoinHandle<()> {
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = shutdown::signalled(&mut shutdown) => return,
+                }
+                let now = Utc::now();
+                let stale: Vec<String> = cache.entries.read().unwrap()
+                    .values()
+                    .filter(|metadata| now - metadata.fetched_at >= cache.ttl)
+                    .map(|metadata| metadata.symbol.clone())
+                    .collect();
+                for symbol in stale {
+                    if let Err(e) = cache.refresh(&symbol).await {
+                        tracing::warn!("Metadata refresh failed for {}: {:?}", symbol, e);
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(exchange: &str) -> CompanyInfo {
+        CompanyInfo {
+            symbol: "acb".to_string(),
+            company_name: Some("Ngân hàng TMCP Á Châu".to_string()),
+            exchange: Some(exchange.to_string()),
+            industry: Some("Ngân hàng".to_string()),
+            company_type: None,
+            established_year: None,
+            employees: None,
+            market_cap: None,
+            current_price: None,
+            outstanding_shares: None,
+            company_profile: None,
+            website: None,
+            shareholders: Vec::new(),
+            officers: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_lookup_skips_network() {
+        let client = Arc::new(VciClient::new(false, 6).unwrap());
+        let cache = MetadataCache::new(client, Duration::hours(12));
+        cache.insert(SymbolMetadata::from_company_info(&info("HSX"), Utc::now()));
+
+        let metadata = cache.get("ACB").await.unwrap();
+        assert_eq!(metadata.exchange, Some(Exchange::Hose));
+        assert_eq!(metadata.board_lot, 100);
+        assert_eq!(metadata.price_band_pct, Some(market_rules::price_band_pct(Exchange::Hose)));
+        assert_eq!(cache.symbols(), vec!["ACB".to_string()]);
+    }
+}
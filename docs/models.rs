@@ -0,0 +1,896 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Seconds east of UTC for Vietnamese exchange time (ICT, no DST).
+pub const VIETNAM_UTC_OFFSET_SECS: i32 = 7 * 3600;
+
+/// Fixed offset for Vietnamese exchange time.
+pub fn vietnam_offset() -> FixedOffset {
+    FixedOffset::east_opt(VIETNAM_UTC_OFFSET_SECS).unwrap()
+}
+
+/// Calendar date accepted by history methods: a `NaiveDate`, any `DateTime`
+/// (taken as its date in exchange time) or a `YYYY-MM-DD` string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateParam {
+    Date(NaiveDate),
+    Text(String),
+}
+
+impl DateParam {
+    pub fn resolve(&self) -> Result<NaiveDate, String> {
+        match self {
+            DateParam::Date(date) => Ok(*date),
+            DateParam::Text(text) => NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", text)),
+        }
+    }
+}
+
+impl From<NaiveDate> for DateParam {
+    fn from(date: NaiveDate) -> Self {
+        DateParam::Date(date)
+    }
+}
+
+impl<Tz: TimeZone> From<DateTime<Tz>> for DateParam {
+    fn from(time: DateTime<Tz>) -> Self {
+        DateParam::Date(time.with_timezone(&vietnam_offset()).date_naive())
+    }
+}
+
+impl From<&str> for DateParam {
+    fn from(text: &str) -> Self {
+        DateParam::Text(text.to_string())
+    }
+}
+
+impl From<String> for DateParam {
+    fn from(text: String) -> Self {
+        DateParam::Text(text)
+    }
+}
+
+/// `YYYY-MM-DD`, the date form provider endpoints and cache keys use.
+pub(crate) fn iso_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Validated inclusive date range with `start <= end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    pub fn new(start: impl Into<DateParam>, end: impl Into<DateParam>) -> Result<Self, String> {
+        let (start, end) = (start.into().resolve()?, end.into().resolve()?);
+        if start > end {
+            return Err(format!("Start date {} is after end date {}", start, end));
+        }
+        Ok(DateRange { start, end })
+    }
+
+    /// From `start` through today in exchange time.
+    pub fn since(start: impl Into<DateParam>) -> Result<Self, String> {
+        DateRange::new(start, Utc::now().with_timezone(&vietnam_offset()).date_naive())
+    }
+
+    /// The string form history methods take, with `None` meaning today.
+    pub fn parse(start: &str, end: Option<&str>) -> Result<Self, String> {
+        match end {
+            Some(end) => DateRange::new(start, end),
+            None => DateRange::since(start),
+        }
+    }
+
+    /// [`Self::parse`] for dates already typed.
+    pub fn through(start: NaiveDate, end: Option<NaiveDate>) -> Result<Self, String> {
+        match end {
+            Some(end) => DateRange::new(start, end),
+            None => DateRange::since(start),
+        }
+    }
+
+    pub fn start_str(&self) -> String {
+        iso_date(self.start)
+    }
+
+    pub fn end_str(&self) -> String {
+        iso_date(self.end)
+    }
+}
+
+/// Provider-neutral OHLCV bar shared by the storage and export layers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ohlcv {
+    pub time: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: u64,
+    pub symbol: Option<String>,
+    /// Matched vs put-through split of `volume`, when the source reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<VolumeBreakdown>,
+    /// Derivative-only fields, present on futures bars.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub futures: Option<FuturesFields>,
+}
+
+impl Ohlcv {
+    pub fn with_breakdown(mut self, breakdown: VolumeBreakdown) -> Self {
+        self.breakdown = Some(breakdown);
+        self
+    }
+
+    /// Sets `basis` from the underlying index close at the same bar time.
+    pub fn with_basis(mut self, underlying_close: f64) -> Self {
+        let futures = self.futures.get_or_insert_with(FuturesFields::default);
+        futures.basis = Some(self.close - underlying_close);
+        self
+    }
+}
+
+/// Open interest, settlement and basis of a futures bar.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct FuturesFields {
+    pub open_interest: Option<u64>,
+    pub settlement_price: Option<f64>,
+    /// Futures close minus the underlying index close.
+    pub basis: Option<f64>,
+}
+
+/// True for index futures codes, both the legacy "VN30F2406" style and the
+/// KRX-era "41I1F7000" style.
+pub fn is_futures_symbol(symbol: &str) -> bool {
+    let symbol = symbol.trim().to_uppercase();
+    let legacy = ["VN30F", "VN100F", "GB05F", "GB10F"].iter().any(|prefix| symbol.starts_with(prefix));
+    let krx = symbol.len() == 9 && symbol.starts_with("41") && symbol.as_bytes()[4] == b'F';
+    legacy || krx
+}
+
+/// Daily volume and value split into order-matched ("khớp lệnh") and
+/// put-through ("thỏa thuận") trades.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct VolumeBreakdown {
+    pub matched_volume: u64,
+    pub matched_value: Option<f64>,
+    pub put_through_volume: u64,
+    pub put_through_value: Option<f64>,
+}
+
+impl VolumeBreakdown {
+    pub fn total_volume(&self) -> u64 {
+        self.matched_volume + self.put_through_volume
+    }
+
+    /// Share of volume traded by put-through, `0.0..=1.0`.
+    pub fn put_through_ratio(&self) -> Option<f64> {
+        let total = self.total_volume();
+        (total > 0).then(|| self.put_through_volume as f64 / total as f64)
+    }
+
+    /// Sum of two periods. Values stay known only if known in both.
+    pub fn combine(&self, other: &VolumeBreakdown) -> VolumeBreakdown {
+        let add = |a: Option<f64>, b: Option<f64>| a.zip(b).map(|(a, b)| a + b);
+        VolumeBreakdown {
+            matched_volume: self.matched_volume + other.matched_volume,
+            matched_value: add(self.matched_value, other.matched_value),
+            put_through_volume: self.put_through_volume + other.put_through_volume,
+            put_through_value: add(self.put_through_value, other.put_through_value),
+        }
+    }
+}
+
+/// Sets each daily bar's breakdown from `days`, matched on the exchange
+/// date. Bars without an entry keep theirs.
+pub fn attach_breakdowns(bars: &mut [Ohlcv], days: &std::collections::BTreeMap<NaiveDate, VolumeBreakdown>) {
+    for bar in bars {
+        if let Some(breakdown) = days.get(&bar.time.with_timezone(&vietnam_offset()).date_naive()) {
+            bar.breakdown = Some(*breakdown);
+        }
+    }
+}
+
+impl From<crate::vci::OhlcvData> for Ohlcv {
+    fn from(bar: crate::vci::OhlcvData) -> Self {
+        Ohlcv {
+            time: bar.time,
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+            symbol: bar.symbol,
+            breakdown: None,
+            futures: None,
+        }
+    }
+}
+
+impl From<crate::tcbs::OhlcvData> for Ohlcv {
+    fn from(bar: crate::tcbs::OhlcvData) -> Self {
+        Ohlcv {
+            time: bar.time,
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+            symbol: bar.symbol,
+            breakdown: None,
+            futures: None,
+        }
+    }
+}
+
+/// Bar interval. Converts to and from the crate's string notation
+/// ("1m", "5m", "15m", "30m", "1H", "1D", "1W", "1M").
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Interval {
+    M1,
+    M5,
+    M15,
+    M30,
+    H1,
+    D1,
+    W1,
+    MN1,
+}
+
+impl Interval {
+    pub const ALL: [Interval; 8] = [
+        Interval::M1,
+        Interval::M5,
+        Interval::M15,
+        Interval::M30,
+        Interval::H1,
+        Interval::D1,
+        Interval::W1,
+        Interval::MN1,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::M1 => "1m",
+            Interval::M5 => "5m",
+            Interval::M15 => "15m",
+            Interval::M30 => "30m",
+            Interval::H1 => "1H",
+            Interval::D1 => "1D",
+            Interval::W1 => "1W",
+            Interval::MN1 => "1M",
+        }
+    }
+
+    pub fn is_intraday(&self) -> bool {
+        matches!(self, Interval::M1 | Interval::M5 | Interval::M15 | Interval::M30 | Interval::H1)
+    }
+
+    /// VCI gap-chart `timeFrame`. VCI serves minute, hour and day bars only;
+    /// coarser intervals are resampled client-side from those.
+    pub fn vci_time_frame(&self) -> &'static str {
+        match self {
+            Interval::M1 | Interval::M5 | Interval::M15 | Interval::M30 => "ONE_MINUTE",
+            Interval::H1 => "ONE_HOUR",
+            Interval::D1 | Interval::W1 | Interval::MN1 => "ONE_DAY",
+        }
+    }
+
+    /// TCBS bars `resolution`.
+    pub fn tcbs_resolution(&self) -> &'static str {
+        match self {
+            Interval::M1 => "1",
+            Interval::M5 => "5",
+            Interval::M15 => "15",
+            Interval::M30 => "30",
+            Interval::H1 => "60",
+            Interval::D1 => "D",
+            Interval::W1 => "W",
+            Interval::MN1 => "M",
+        }
+    }
+
+    /// DNSE/Entrade chart `resolution`. Monthly bars are not served and are
+    /// resampled from daily ones.
+    pub fn entrade_resolution(&self) -> Option<&'static str> {
+        match self {
+            Interval::M1 => Some("1"),
+            Interval::M5 => Some("5"),
+            Interval::M15 => Some("15"),
+            Interval::M30 => Some("30"),
+            Interval::H1 => Some("1H"),
+            Interval::D1 => Some("1D"),
+            Interval::W1 => Some("1W"),
+            Interval::MN1 => None,
+        }
+    }
+
+    /// Fixed bar length in seconds for intraday intervals.
+    pub fn duration_secs(&self) -> Option<i64> {
+        match self {
+            Interval::M1 => Some(60),
+            Interval::M5 => Some(300),
+            Interval::M15 => Some(900),
+            Interval::M30 => Some(1800),
+            Interval::H1 => Some(3600),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Interval {
+    type Err = String;
+
+    /// Case-sensitive: "1m" is one minute, "1M" one month.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Interval::ALL.into_iter()
+            .find(|interval| interval.as_str() == value.trim())
+            .ok_or_else(|| {
+                let expected: Vec<&str> = Interval::ALL.iter().map(|interval| interval.as_str()).collect();
+                format!("Unsupported interval '{}'; expected one of {}", value, expected.join(", "))
+            })
+    }
+}
+
+/// Lets history methods take either an [`Interval`] or its string form.
+impl AsRef<str> for Interval {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Whether history prices are returned as traded or back-adjusted for
+/// dividends, bonus issues, splits and rights issues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PriceAdjustment {
+    #[default]
+    Unadjusted,
+    /// Bars before each ex-date are scaled so the series has no gap there;
+    /// the latest prices match what traded.
+    Adjusted,
+}
+
+/// Market index with a history series. Each provider spells some of these
+/// differently; [`Index::as_str`] is the crate's canonical symbol and the
+/// one set on returned bars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Index {
+    VnIndex,
+    Vn30,
+    HnxIndex,
+    Hnx30,
+    UpcomIndex,
+}
+
+impl Index {
+    pub const ALL: [Index; 5] = [Index::VnIndex, Index::Vn30, Index::HnxIndex, Index::Hnx30, Index::UpcomIndex];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Index::VnIndex => "VNINDEX",
+            Index::Vn30 => "VN30",
+            Index::HnxIndex => "HNXINDEX",
+            Index::Hnx30 => "HNX30",
+            Index::UpcomIndex => "UPCOMINDEX",
+        }
+    }
+
+    /// Symbol in the VCI gap-chart request.
+    pub fn vci_code(&self) -> &'static str {
+        match self {
+            Index::HnxIndex => "HNXIndex",
+            Index::UpcomIndex => "HNXUpcomIndex",
+            other => other.as_str(),
+        }
+    }
+
+    /// `ticker` in the TCBS bars request.
+    pub fn tcbs_code(&self) -> &'static str {
+        match self {
+            Index::HnxIndex => "HNXIndex",
+            Index::UpcomIndex => "UPCOM",
+            other => other.as_str(),
+        }
+    }
+
+    /// `symbol` in the Entrade index chart request.
+    pub fn entrade_code(&self) -> &'static str {
+        match self {
+            Index::HnxIndex => "HNX",
+            Index::UpcomIndex => "UPCOM",
+            other => other.as_str(),
+        }
+    }
+
+    /// The index named by `symbol` in any provider's spelling, if it is one.
+    pub fn from_symbol(symbol: &str) -> Option<Index> {
+        symbol.parse().ok()
+    }
+}
+
+impl std::fmt::Display for Index {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Index {
+    type Err = String;
+
+    /// Case-insensitive; accepts canonical and provider-specific names.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "VNINDEX" | "VN-INDEX" => Ok(Index::VnIndex),
+            "VN30" | "VN30INDEX" => Ok(Index::Vn30),
+            "HNXINDEX" | "HNX-INDEX" | "HNX" => Ok(Index::HnxIndex),
+            "HNX30" => Ok(Index::Hnx30),
+            "UPCOMINDEX" | "HNXUPCOMINDEX" | "UPCOM" => Ok(Index::UpcomIndex),
+            other => Err(format!("Unknown index: {}", other)),
+        }
+    }
+}
+
+/// Language for provider text fields. English falls back to Vietnamese
+/// wherever the provider has no translation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    Vietnamese,
+    English,
+}
+
+impl Language {
+    /// Provider language code ("vi" or "en").
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Vietnamese => "vi",
+            Language::English => "en",
+        }
+    }
+}
+
+/// Latest price-board quote for a symbol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub symbol: String,
+    pub time: DateTime<Utc>,
+    pub price: f64,
+    pub reference_price: Option<f64>,
+    pub ceiling_price: Option<f64>,
+    pub floor_price: Option<f64>,
+    pub open: Option<f64>,
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+    /// Accumulated session volume.
+    pub volume: u64,
+    /// Accumulated session traded value in VND.
+    pub value: Option<f64>,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+}
+
+impl Quote {
+    pub fn change(&self) -> Option<f64> {
+        self.reference_price.map(|reference| self.price - reference)
+    }
+
+    pub fn change_pct(&self) -> Option<f64> {
+        self.reference_price
+            .filter(|&reference| reference > 0.0)
+            .map(|reference| (self.price - reference) / reference * 100.0)
+    }
+}
+
+/// One price level of the order book.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthLevel {
+    pub price: f64,
+    pub volume: u64,
+    /// Resting orders at this price, when the provider reports it.
+    pub orders: Option<u32>,
+}
+
+/// Top of the order book as shown on the price board (usually three levels).
+/// Bids are best (highest) first, asks best (lowest) first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceDepth {
+    pub symbol: String,
+    pub time: DateTime<Utc>,
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+}
+
+impl PriceDepth {
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.asks.first()?.price - self.bids.first()?.price)
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.asks.first()?.price + self.bids.first()?.price) / 2.0)
+    }
+
+    /// Bid share of displayed volume in `[0, 1]`; above 0.5 leans buy.
+    pub fn imbalance(&self) -> Option<f64> {
+        let bid: u64 = self.bids.iter().map(|l| l.volume).sum();
+        let ask: u64 = self.asks.iter().map(|l| l.volume).sum();
+        (bid + ask > 0).then(|| bid as f64 / (bid + ask) as f64)
+    }
+}
+
+/// Index level sample (VNINDEX, VN30, HNXIndex, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexTick {
+    pub symbol: String,
+    pub time: DateTime<Utc>,
+    pub value: f64,
+    pub reference_value: Option<f64>,
+    /// Accumulated session volume, when reported.
+    pub volume: Option<u64>,
+}
+
+/// Aggressor side of a matched trade, as reported by the providers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+    /// Auction prints and trades the provider could not attribute.
+    Unknown,
+}
+
+impl TradeSide {
+    /// Parses provider side codes: VCI `b`/`s`, TCBS `BU`/`SD`.
+    pub fn from_code(code: &str) -> TradeSide {
+        match code.trim().to_uppercase().as_str() {
+            "B" | "BU" | "BUY" => TradeSide::Buy,
+            "S" | "SD" | "SELL" => TradeSide::Sell,
+            _ => TradeSide::Unknown,
+        }
+    }
+}
+
+/// A single matched trade ("khớp lệnh").
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TickData {
+    pub time: DateTime<Utc>,
+    pub price: f64,
+    pub volume: u64,
+    pub side: TradeSide,
+    pub id: Option<String>,
+}
+
+/// Vietnamese stock exchange a security is listed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Exchange {
+    Hose,
+    Hnx,
+    Upcom,
+}
+
+impl Exchange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Exchange::Hose => "HOSE",
+            Exchange::Hnx => "HNX",
+            Exchange::Upcom => "UPCOM",
+        }
+    }
+}
+
+impl std::str::FromStr for Exchange {
+    type Err = String;
+
+    /// Accepts the names used across providers ("HOSE"/"HSX", "HNX", "UPCOM").
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "HOSE" | "HSX" => Ok(Exchange::Hose),
+            "HNX" => Ok(Exchange::Hnx),
+            "UPCOM" => Ok(Exchange::Upcom),
+            other => Err(format!("Unknown exchange: {}", other)),
+        }
+    }
+}
+
+/// Kind of listed security.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SecurityType {
+    Stock,
+    Etf,
+    CoveredWarrant,
+    Bond,
+    Fund,
+    Futures,
+    Other,
+}
+
+impl SecurityType {
+    /// Parses listing type codes ("STOCK", "ETF", "CW", "BOND", "FUND", "FU").
+    pub fn from_code(code: &str) -> SecurityType {
+        match code.trim().to_uppercase().as_str() {
+            "STOCK" | "STO" => SecurityType::Stock,
+            "ETF" | "EF" => SecurityType::Etf,
+            "CW" | "COVERED_WARRANT" => SecurityType::CoveredWarrant,
+            "BOND" | "CORPORATE_BOND" | "BON" => SecurityType::Bond,
+            "FUND" | "UNIT_TRUST" => SecurityType::Fund,
+            "FU" | "FUTURES" | "FUTURE" => SecurityType::Futures,
+            _ => SecurityType::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityType::Stock => "STOCK",
+            SecurityType::Etf => "ETF",
+            SecurityType::CoveredWarrant => "CW",
+            SecurityType::Bond => "BOND",
+            SecurityType::Fund => "FUND",
+            SecurityType::Futures => "FU",
+            SecurityType::Other => "OTHER",
+        }
+    }
+}
+
+/// Exchange trading status of a listed symbol, ordered from least to most
+/// restrictive so the strictest of several signals can be picked with `max`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum TradingStatus {
+    Normal,
+    /// "Diện cảnh báo": trades normally but flagged by the exchange.
+    Warning,
+    /// "Diện kiểm soát": under control, may be limited to afternoon sessions.
+    Control,
+    /// Trading restricted to specific sessions ("hạn chế giao dịch").
+    Restricted,
+    /// Halted or suspended ("tạm ngừng", "đình chỉ").
+    Halted,
+    Unknown(String),
+}
+
+impl TradingStatus {
+    /// Classifies a provider status code or notice title. Matches both the
+    /// English codes used by the price boards and Vietnamese notice wording.
+    pub fn classify(text: &str) -> Option<TradingStatus> {
+        // Needles are unaccented so titles typed without diacritics also match
+        let normalized = crate::text::normalize(text);
+        let matches_any = |needles: &[&str]| needles.iter().any(|needle| normalized.contains(needle));
+
+        if matches_any(&["halt", "suspend", "tam ngung", "dinh chi", "ngung giao dich"]) {
+            Some(TradingStatus::Halted)
+        } else if matches_any(&["restrict", "han che"]) {
+            Some(TradingStatus::Restricted)
+        } else if matches_any(&["control", "kiem soat"]) {
+            Some(TradingStatus::Control)
+        } else if matches_any(&["warning", "canh bao"]) {
+            Some(TradingStatus::Warning)
+        } else if matches_any(&["normal", "available", "binh thuong"]) || normalized == "n" {
+            Some(TradingStatus::Normal)
+        } else {
+            None
+        }
+    }
+
+    /// True when order flow is limited in any way (control or stricter).
+    pub fn is_restricted(&self) -> bool {
+        matches!(self, TradingStatus::Control | TradingStatus::Restricted | TradingStatus::Halted)
+    }
+}
+
+/// The current response models, frozen under a version name so consumers can
+/// pin to them while migrating.
+pub mod v1 {
+    pub use super::{IndexTick, Ohlcv, Quote, TickData};
+}
+
+/// Next-generation response models. Symbols and intervals are always
+/// present, and quote price levels are grouped. Convert from v1 with the
+/// `from_v1`/`From` helpers and back with `From`.
+pub mod v2 {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Serialize};
+
+    use super::{v1, Interval};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Ohlcv {
+        pub symbol: String,
+        pub interval: Interval,
+        pub time: DateTime<Utc>,
+        pub open: f64,
+        pub high: f64,
+        pub low: f64,
+        pub close: f64,
+        pub volume: u64,
+    }
+
+    impl Ohlcv {
+        /// v1 bars carry neither interval nor, sometimes, symbol; both are
+        /// supplied here and `symbol` is used only when the bar has none.
+        pub fn from_v1(bar: v1::Ohlcv, symbol: &str, interval: Interval) -> Self {
+            Ohlcv {
+                symbol: bar.symbol.unwrap_or_else(|| symbol.to_uppercase()),
+                interval,
+                time: bar.time,
+                open: bar.open,
+                high: bar.high,
+                low: bar.low,
+                close: bar.close,
+                volume: bar.volume,
+            }
+        }
+    }
+
+    impl From<Ohlcv> for v1::Ohlcv {
+        fn from(bar: Ohlcv) -> Self {
+            v1::Ohlcv {
+                time: bar.time,
+                open: bar.open,
+                high: bar.high,
+                low: bar.low,
+                close: bar.close,
+                volume: bar.volume,
+                symbol: Some(bar.symbol),
+                breakdown: None,
+                futures: None,
+            }
+        }
+    }
+
+    /// Daily limits around the reference price.
+    #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+    pub struct PriceBand {
+        pub reference: Option<f64>,
+        pub ceiling: Option<f64>,
+        pub floor: Option<f64>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+    pub struct SessionRange {
+        pub open: Option<f64>,
+        pub high: Option<f64>,
+        pub low: Option<f64>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+    pub struct BestPrices {
+        pub bid: Option<f64>,
+        pub ask: Option<f64>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct Quote {
+        pub symbol: String,
+        pub time: DateTime<Utc>,
+        pub price: f64,
+        pub band: PriceBand,
+        pub session: SessionRange,
+        pub book: BestPrices,
+        pub volume: u64,
+        pub value: Option<f64>,
+    }
+
+    impl From<v1::Quote> for Quote {
+        fn from(quote: v1::Quote) -> Self {
+            Quote {
+                symbol: quote.symbol,
+                time: quote.time,
+                price: quote.price,
+                band: PriceBand { reference: quote.reference_price, ceiling: quote.ceiling_price, floor: quote.floor_price },
+                session: SessionRange { open: quote.open, high: quote.high, low: quote.low },
+                book: BestPrices { bid: quote.best_bid, ask: quote.best_ask },
+                volume: quote.volume,
+                value: quote.value,
+            }
+        }
+    }
+
+    impl From<Quote> for v1::Quote {
+        fn from(quote: Quote) -> Self {
+            v1::Quote {
+                symbol: quote.symbol,
+                time: quote.time,
+                price: quote.price,
+                reference_price: quote.band.reference,
+                ceiling_price: quote.band.ceiling,
+                floor_price: quote.band.floor,
+                open: quote.session.open,
+                high: quote.session.high,
+                low: quote.session.low,
+                volume: quote.volume,
+                value: quote.value,
+                best_bid: quote.book.bid,
+                best_ask: quote.book.ask,
+            }
+        }
+    }
+
+    /// Upgrades a v1 series in one go.
+    pub fn migrate_bars(bars: Vec<v1::Ohlcv>, symbol: &str, interval: Interval) -> Vec<Ohlcv> {
+        bars.into_iter().map(|bar| Ohlcv::from_v1(bar, symbol, interval)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_range_validation() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let late_evening = Utc.with_ymd_and_hms(2024, 3, 4, 18, 0, 0).unwrap();
+        let range = DateRange::new(date, late_evening).unwrap();
+        assert_eq!(range.end_str(), "2024-03-05");
+        assert!(DateRange::new("2024-03-05", "2024-03-01").is_err());
+        assert!(DateRange::parse("01/03/2024", None).unwrap_err().contains("YYYY-MM-DD"));
+        assert_eq!(DateRange::through(date, Some(date)).unwrap().start_str(), "2024-03-01");
+        assert!(DateRange::through(range.end, Some(date)).is_err());
+    }
+
+    #[test]
+    fn test_interval_round_trip() {
+        for interval in Interval::ALL {
+            assert_eq!(interval.as_str().parse::<Interval>(), Ok(interval));
+        }
+        assert_eq!("1M".parse::<Interval>(), Ok(Interval::MN1));
+        assert!("4H".parse::<Interval>().is_err());
+    }
+
+    #[test]
+    fn test_trading_status_classify() {
+        assert_eq!(TradingStatus::classify("NORMAL"), Some(TradingStatus::Normal));
+        assert_eq!(TradingStatus::classify("Cổ phiếu bị đưa vào diện cảnh báo"), Some(TradingStatus::Warning));
+        assert_eq!(TradingStatus::classify("Tạm ngừng giao dịch cổ phiếu ABC"), Some(TradingStatus::Halted));
+        assert_eq!(TradingStatus::classify("ĐHCĐ thường niên"), None);
+        assert_eq!(TradingStatus::classify("Co phieu bi dua vao dien kiem soat"), Some(TradingStatus::Control));
+        assert!(TradingStatus::Control.is_restricted());
+        assert!(TradingStatus::Halted > TradingStatus::Warning);
+    }
+
+    #[test]
+    fn test_v1_v2_round_trip() {
+        let quote = Quote {
+            symbol: "FPT".to_string(),
+            time: Utc::now(),
+            price: 120_000.0,
+            reference_price: Some(118_000.0),
+            ceiling_price: Some(126_200.0),
+            floor_price: Some(109_800.0),
+            open: Some(118_500.0),
+            high: Some(121_000.0),
+            low: Some(118_000.0),
+            volume: 1_000_000,
+            value: None,
+            best_bid: Some(119_900.0),
+            best_ask: Some(120_000.0),
+        };
+        let upgraded = v2::Quote::from(quote.clone());
+        assert_eq!(upgraded.band.ceiling, Some(126_200.0));
+        assert_eq!(v1::Quote::from(upgraded), quote);
+
+        let bar = Ohlcv { time: quote.time, open: 1.0, high: 2.0, low: 0.5, close: 1.5, volume: 10, symbol: None, breakdown: None, futures: None };
+        let bars = v2::migrate_bars(vec![bar], "fpt", Interval::D1);
+        assert_eq!(bars[0].symbol, "FPT");
+        assert_eq!(v1::Ohlcv::from(bars[0].clone()).symbol.as_deref(), Some("FPT"));
+    }
+
+    #[test]
+    fn test_futures_symbols_and_basis() {
+        assert!(is_futures_symbol("VN30F2406"));
+        assert!(is_futures_symbol("41I1F7000"));
+        assert!(!is_futures_symbol("FPT"));
+        assert_eq!(Index::from_symbol("HNXIndex"), Some(Index::HnxIndex));
+        assert_eq!(Index::from_symbol("upcom").map(|i| i.vci_code()), Some("HNXUpcomIndex"));
+        assert_eq!(Index::from_symbol("FPT"), None);
+
+        let bar = Ohlcv { time: Utc::now(), open: 1300.0, high: 1310.0, low: 1295.0, close: 1305.5, volume: 10, symbol: None, breakdown: None, futures: None };
+        assert_eq!(bar.with_basis(1300.0).futures.and_then(|futures| futures.basis), Some(5.5));
+    }
+}
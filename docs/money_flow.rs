@@ -0,0 +1,249 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::models::{Ohlcv, TickData, TradeSide};
+use crate::ta::Indicator;
+
+/// Matched volume of one bar split by aggressor side, from the providers'
+/// buy-up/sell-down tick tagging.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActiveFlow {
+    pub time: DateTime<Utc>,
+    pub buy_volume: u64,
+    pub sell_volume: u64,
+    /// Auction prints and ticks without a side.
+    pub unattributed_volume: u64,
+}
+
+impl ActiveFlow {
+    fn empty(time: DateTime<Utc>) -> Self {
+        ActiveFlow { time, buy_volume: 0, sell_volume: 0, unattributed_volume: 0 }
+    }
+
+    /// Buy-up minus sell-down volume.
+    pub fn delta(&self) -> i64 {
+        self.buy_volume as i64 - self.sell_volume as i64
+    }
+
+    /// Share of attributed volume that was buy-initiated, `0.0..=1.0`.
+    pub fn buy_ratio(&self) -> Option<f64> {
+        let attributed = self.buy_volume + self.sell_volume;
+        (attributed > 0).then(|| self.buy_volume as f64 / attributed as f64)
+    }
+
+    fn is_attributed(&self) -> bool {
+        self.buy_volume + self.sell_volume > 0
+    }
+}
+
+/// Buckets `ticks` into `bars` (sorted by time): a tick belongs to the last
+/// bar starting at or before it. One flow per bar; bars without ticks get
+/// an empty flow, and ticks before the first bar are dropped.
+pub fn active_flow(bars: &[Ohlcv], ticks: &[TickData]) -> Vec<ActiveFlow> {
+    let mut flows: Vec<ActiveFlow> = bars.iter().map(|bar| ActiveFlow::empty(bar.time)).collect();
+    for tick in ticks {
+        let index = bars.partition_point(|bar| bar.time <= tick.time);
+        let Some(flow) = index.checked_sub(1).and_then(|index| flows.get_mut(index)) else {
+            continue;
+        };
+        match tick.side {
+            TradeSide::Buy => flow.buy_volume += tick.volume,
+            TradeSide::Sell => flow.sell_volume += tick.volume,
+            TradeSide::Unknown => flow.unattributed_volume += tick.volume,
+        }
+    }
+    flows
+}
+
+fn typical_price(bar: &Ohlcv) -> f64 {
+    (bar.high + bar.low + bar.close) / 3.0
+}
+
+/// Close location value, `-1.0..=1.0`; zero for a bar with no range.
+fn close_location(bar: &Ohlcv) -> f64 {
+    let range = bar.high - bar.low;
+    if range > 0.0 { ((bar.close - bar.low) - (bar.high - bar.close)) / range } else { 0.0 }
+}
+
+/// Money flow index over `period` bars. Classic MFI assigns a bar's whole
+/// money flow to the side its typical price moved; [`active_mfi`] splits it
+/// by aggressor instead.
+#[derive(Debug, Clone)]
+pub struct Mfi {
+    period: usize,
+    previous_typical: Option<f64>,
+    window: VecDeque<(f64, f64)>,
+}
+
+impl Mfi {
+    pub fn new(period: usize) -> Self {
+        Mfi { period: period.max(1), previous_typical: None, window: VecDeque::new() }
+    }
+
+    fn push(&mut self, positive: f64, negative: f64) -> Option<f64> {
+        self.window.push_back((positive, negative));
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+        let (positive, negative) = self.window.iter().fold((0.0, 0.0), |(p, n), (bp, bn)| (p + bp, n + bn));
+        Some(if positive + negative == 0.0 { 50.0 } else { 100.0 * positive / (positive + negative) })
+    }
+}
+
+impl Default for Mfi {
+    fn default() -> Self {
+        Mfi::new(14)
+    }
+}
+
+impl Indicator for Mfi {
+    type Output = f64;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<f64> {
+        let typical = typical_price(bar);
+        let previous = self.previous_typical.replace(typical)?;
+        let flow = typical * bar.volume as f64;
+        match typical.partial_cmp(&previous) {
+            Some(std::cmp::Ordering::Greater) => self.push(flow, 0.0),
+            Some(std::cmp::Ordering::Less) => self.push(0.0, flow),
+            _ => self.push(0.0, 0.0),
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Mfi::new(self.period);
+    }
+}
+
+/// Chaikin accumulation/distribution line: cumulative close location × volume.
+#[derive(Debug, Clone, Default)]
+pub struct AdLine {
+    value: f64,
+}
+
+impl Indicator for AdLine {
+    type Output = f64;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<f64> {
+        self.value += close_location(bar) * bar.volume as f64;
+        Some(self.value)
+    }
+
+    fn reset(&mut self) {
+        self.value = 0.0;
+    }
+}
+
+/// MFI with each bar's money flow split into buy-up and sell-down value
+/// (typical price × side volume). Bars whose flow has no attributed volume,
+/// e.g. days before tick history starts, fall back to the classic rule so
+/// the series stays continuous. `flows` is aligned with `bars`, as returned
+/// by [`active_flow`].
+pub fn active_mfi(bars: &[Ohlcv], flows: &[ActiveFlow], period: usize) -> Vec<Option<f64>> {
+    let mut mfi = Mfi::new(period);
+    bars.iter()
+        .enumerate()
+        .map(|(i, bar)| match flows.get(i).filter(|flow| flow.is_attributed()) {
+            Some(flow) => {
+                let typical = typical_price(bar);
+                mfi.previous_typical = Some(typical);
+                mfi.push(typical * flow.buy_volume as f64, typical * flow.sell_volume as f64)
+            }
+            None => mfi.update(bar),
+        })
+        .collect()
+}
+
+/// How each bar contributes to an accumulation/distribution line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdVariant {
+    /// Close location × volume (Chaikin).
+    Classic,
+    /// Buy-up minus sell-down volume (cumulative volume delta).
+    ActiveVolume,
+    /// Volume delta × typical price, in VND.
+    ActiveValue,
+}
+
+/// Cumulative A/D line. The active variants use the bar's aggressor split
+/// and fall back to [`AdVariant::Classic`] weighting (in the same unit) for
+/// bars without attributed volume.
+pub fn ad_line(bars: &[Ohlcv], flows: &[ActiveFlow], variant: AdVariant) -> Vec<f64> {
+    let mut total = 0.0;
+    bars.iter()
+        .enumerate()
+        .map(|(i, bar)| {
+            let flow = flows.get(i).filter(|flow| flow.is_attributed());
+            let volume = match (variant, flow) {
+                (AdVariant::Classic, _) | (_, None) => close_location(bar) * bar.volume as f64,
+                (_, Some(flow)) => flow.delta() as f64,
+            };
+            total += match variant {
+                AdVariant::ActiveValue => volume * typical_price(bar),
+                _ => volume,
+            };
+            total
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn bar(day: i64, high: f64, low: f64, close: f64, volume: u64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 5, 1, 2, 0, 0).unwrap() + Duration::days(day),
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+            symbol: None,
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    fn tick(bar: &Ohlcv, minutes: i64, volume: u64, side: TradeSide) -> TickData {
+        TickData { time: bar.time + Duration::minutes(minutes), price: bar.close, volume, side, id: None }
+    }
+
+    #[test]
+    fn test_active_flow_and_ad_variants() {
+        let bars = vec![bar(0, 11.0, 9.0, 10.5, 1000), bar(1, 11.0, 9.0, 9.5, 1000), bar(2, 12.0, 10.0, 11.0, 600)];
+        let ticks = vec![
+            tick(&bars[1], 5, 300, TradeSide::Buy),
+            tick(&bars[1], 30, 500, TradeSide::Sell),
+            tick(&bars[1], 60, 200, TradeSide::Unknown),
+            tick(&bars[2], 10, 600, TradeSide::Buy),
+            tick(&bars[0], -10, 999, TradeSide::Buy),
+        ];
+        let flows = active_flow(&bars, &ticks);
+        assert_eq!(flows[0], ActiveFlow::empty(bars[0].time));
+        assert_eq!((flows[1].buy_volume, flows[1].sell_volume, flows[1].unattributed_volume), (300, 500, 200));
+        assert_eq!(flows[1].buy_ratio(), Some(0.375));
+
+        assert_eq!(ad_line(&bars, &flows, AdVariant::Classic), vec![500.0, 0.0, 0.0]);
+        assert_eq!(ad_line(&bars, &flows, AdVariant::ActiveVolume), vec![500.0, 300.0, 900.0]);
+        let value = ad_line(&bars, &flows, AdVariant::ActiveValue);
+        assert!((value[2] - (500.0 * 30.5 / 3.0 - 200.0 * 29.5 / 3.0 + 600.0 * 11.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_mfi_classic_and_active() {
+        let bars: Vec<Ohlcv> = (0..4).map(|i| bar(i, 11.0 + i as f64, 9.0 + i as f64, 10.0 + i as f64, 100)).collect();
+        let classic = crate::ta::compute(&mut Mfi::new(2), &bars);
+        assert_eq!(classic, vec![None, None, Some(100.0), Some(100.0)]);
+
+        let flows: Vec<ActiveFlow> = bars.iter().map(|b| ActiveFlow { buy_volume: 30, sell_volume: 70, ..ActiveFlow::empty(b.time) }).collect();
+        let active = active_mfi(&bars, &flows, 2);
+        assert_eq!(active[0], None);
+        assert!((active[3].unwrap() - 30.0).abs() < 1e-9);
+    }
+}
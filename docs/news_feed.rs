@@ -0,0 +1,257 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use futures::stream::{BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+use crate::models::vietnam_offset;
+use crate::shutdown::{self, ShutdownToken};
+use crate::tcbs::{NewsItem, TcbsClient};
+use crate::text;
+use crate::vci::{CorporateEvent, VciClient};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum NewsSource {
+    /// TCBS activity news.
+    Tcbs,
+    /// VCI `OrganizationEvents` announcements.
+    VciEvents,
+}
+
+impl NewsSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NewsSource::Tcbs => "tcbs",
+            NewsSource::VciEvents => "vci_events",
+        }
+    }
+}
+
+/// One item as reported by one source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourcedNews {
+    pub source: NewsSource,
+    pub symbol: String,
+    pub title: String,
+    pub published: Option<DateTime<Utc>>,
+    /// Original publisher, when the source names one.
+    pub publisher: Option<String>,
+}
+
+/// Parses `YYYY-MM-DD[ T]HH:MM:SS`, RFC 3339 or a bare date; naive times
+/// are exchange time.
+pub fn parse_publish_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%d/%m/%Y %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value.get(..19)?, format).ok())
+        .or_else(|| {
+            let day = value.get(..10)?;
+            NaiveDate::parse_from_str(day, "%Y-%m-%d").or_else(|_| NaiveDate::parse_from_str(day, "%d/%m/%Y")).ok()?.and_hms_opt(0, 0, 0)
+        })?;
+    vietnam_offset().from_local_datetime(&naive).single().map(|time| time.with_timezone(&Utc))
+}
+
+impl From<&NewsItem> for SourcedNews {
+    fn from(item: &NewsItem) -> Self {
+        SourcedNews {
+            source: NewsSource::Tcbs,
+            symbol: item.symbol.to_uppercase(),
+            title: item.title.clone(),
+            published: item.publish_date.as_deref().and_then(parse_publish_time),
+            publisher: item.source.clone(),
+        }
+    }
+}
+
+impl From<&CorporateEvent> for SourcedNews {
+    fn from(event: &CorporateEvent) -> Self {
+        SourcedNews {
+            source: NewsSource::VciEvents,
+            symbol: event.symbol.to_uppercase(),
+            title: event.title.clone(),
+            published: event.public_date.as_deref().and_then(parse_publish_time),
+            publisher: None,
+        }
+    }
+}
+
+/// An item merged across sources. `title` and `published` come from the
+/// first source that reported it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergedNews {
+    pub symbol: String,
+    pub title: String,
+    pub published: Option<DateTime<Utc>>,
+    pub sources: Vec<SourcedNews>,
+}
+
+impl MergedNews {
+    pub fn source_names(&self) -> BTreeSet<NewsSource> {
+        self.sources.iter().map(|s| s.source).collect()
+    }
+}
+
+/// Two items are the same story when they share a symbol, their titles'
+/// word sets overlap by at least `min_similarity` (Jaccard, unaccented) and
+/// their timestamps lie within `max_time_gap`. Items without a timestamp
+/// only merge on identical normalized titles.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DedupConfig {
+    pub min_similarity: f64,
+    pub max_time_gap: Duration,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig { min_similarity: 0.6, max_time_gap: Duration::days(2) }
+    }
+}
+
+fn words(title: &str) -> BTreeSet<String> {
+    text::normalize(title)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// Incremental deduplicator keeping every story seen so far.
+#[derive(Debug, Default)]
+pub struct NewsMerger {
+    config: DedupConfig,
+    merged: Vec<MergedNews>,
+}
+
+impl NewsMerger {
+    pub fn new(config: DedupConfig) -> Self {
+        NewsMerger { config, merged: Vec::new() }
+    }
+
+    fn same_story(&self, story: &MergedNews, item: &SourcedNews) -> bool {
+        if story.symbol != item.symbol {
+            return false;
+        }
+        match (story.published, item.published) {
+            (Some(a), Some(b)) => (a - b).abs() <= self.config.max_time_gap && title_similarity(&story.title, &item.title) >= self.config.min_similarity,
+            _ => text::normalize(&story.title) == text::normalize(&item.title),
+        }
+    }
+
+    /// Adds `item`, returning the new story if it was not seen before. A
+    /// repeat from another source is attributed to the existing story; an
+    /// exact repeat from the same source is dropped.
+    pub fn push(&mut self, item: SourcedNews) -> Option<&MergedNews> {
+        if let Some(index) = self.merged.iter().position(|story| self.same_story(story, &item)) {
+            let story = &mut self.merged[index];
+            if !story.sources.iter().any(|s| s.source == item.source && s.title == item.title) {
+                story.sources.push(item);
+            }
+            return None;
+        }
+        self.merged.push(MergedNews { symbol: item.symbol.clone(), title: item.title.clone(), published: item.published, sources: vec![item] });
+        self.merged.last()
+    }
+
+    /// All stories, newest first; undated ones last.
+    pub fn stories(&self) -> Vec<MergedNews> {
+        let mut stories = self.merged.clone();
+        stories.sort_by_key(|story| std::cmp::Reverse(story.published));
+        stories
+    }
+}
+
+pub fn merge_news(items: impl IntoIterator<Item = SourcedNews>, config: DedupConfig) -> Vec<MergedNews> {
+    let mut merger = NewsMerger::new(config);
+    for item in items {
+        merger.push(item);
+    }
+    merger.stories()
+}
+
+/// New stories for `symbols` from TCBS news and VCI announcements, polled
+/// every `poll_interval`. Each story is emitted once, when first seen; the
+/// poller stops once the stream is dropped or `shutdown` is signalled.
+pub fn news_stream(
+    tcbs: Arc<TcbsClient>,
+    vci: Arc<VciClient>,
+    symbols: &[String],
+    poll_interval: std::time::Duration,
+    config: DedupConfig,
+    mut shutdown: Option<ShutdownToken>,
+) -> BoxStream<'static, MergedNews> {
+    let symbols: Vec<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+    let (tx, rx) = mpsc::channel(64);
+    tokio::spawn(async move {
+        let mut merger = NewsMerger::new(config);
+        loop {
+            for symbol in &symbols {
+                let (news, events) = tokio::join!(tcbs.news_page(symbol, None, 20), vci.events(symbol));
+                let mut items: Vec<SourcedNews> = Vec::new();
+                match news {
+                    Ok(page) => items.extend(page.items.iter().map(SourcedNews::from)),
+                    Err(e) => tracing::warn!("TCBS news poll failed for {}: {:?}", symbol, e),
+                }
+                match events {
+                    Ok(events) => items.extend(events.iter().map(SourcedNews::from)),
+                    Err(e) => tracing::warn!("VCI events poll failed for {}: {:?}", symbol, e),
+                }
+                // Oldest first so the stream reads chronologically
+                items.sort_by_key(|item| item.published);
+                for item in items {
+                    if let Some(story) = merger.push(item) {
+                        if tx.send(story.clone()).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(poll_interval) => {}
+                _ = tx.closed() => return,
+                _ = shutdown::signalled(&mut shutdown) => return,
+            }
+        }
+    });
+    futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|story| (story, rx)) }).boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(source: NewsSource, title: &str, published: &str) -> SourcedNews {
+        SourcedNews { source, symbol: "VNM".to_string(), title: title.to_string(), published: parse_publish_time(published), publisher: None }
+    }
+
+    #[test]
+    fn test_merge_across_sources() {
+        let stories = merge_news(
+            [
+                item(NewsSource::Tcbs, "VNM: Nghị quyết HĐQT về việc chi trả cổ tức đợt 2/2024", "2024-06-10 08:00:00"),
+                item(NewsSource::VciEvents, "VNM - Nghị quyết HĐQT về việc chi trả cổ tức đợt 2 năm 2024", "2024-06-10"),
+                // Same wording a year later is a different story
+                item(NewsSource::Tcbs, "VNM: Nghị quyết HĐQT về việc chi trả cổ tức đợt 2/2024", "2025-06-10 08:00:00"),
+                item(NewsSource::Tcbs, "VNM: Báo cáo tài chính quý 2", "2024-07-20T09:30:00+07:00"),
+            ],
+            DedupConfig::default(),
+        );
+        assert_eq!(stories.len(), 3);
+        assert_eq!(stories[2].source_names(), BTreeSet::from([NewsSource::Tcbs, NewsSource::VciEvents]));
+        assert_eq!(stories[1].title, "VNM: Báo cáo tài chính quý 2");
+        assert_eq!(parse_publish_time("2024-06-10 08:00:00"), Some(Utc.with_ymd_and_hms(2024, 6, 10, 1, 0, 0).unwrap()));
+    }
+}
@@ -0,0 +1,89 @@
+use futures::stream::{BoxStream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// One page of a list endpoint. Pass `next_cursor` back to the endpoint to
+/// get the following page; `None` means this was the last one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    /// Total item count, when the provider reports it.
+    pub total: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Page of an offset-paged endpoint where the cursor is the page index.
+    /// A short page, or reaching `total`, ends the sequence.
+    pub fn from_page_index(items: Vec<T>, page: u32, page_size: u32, total: Option<u64>) -> Self {
+        let seen = (page as u64 + 1) * page_size as u64;
+        let more = items.len() as u32 >= page_size && page_size > 0 && total.is_none_or(|total| seen < total);
+        Page {
+            items,
+            next_cursor: more.then(|| (page + 1).to_string()),
+            total,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            total: self.total,
+        }
+    }
+}
+
+/// Page index encoded in `cursor`; no cursor is the first page.
+pub fn page_index(cursor: Option<&str>) -> u32 {
+    cursor.and_then(|cursor| cursor.parse().ok()).unwrap_or(0)
+}
+
+/// Walks every page by calling `fetch` with each cursor in turn, starting
+/// from `None`. The stream ends after the last page or the first error.
+pub fn pages<'a, T, E, F, Fut>(fetch: F) -> BoxStream<'a, Result<Page<T>, E>>
+where
+    T: Send + 'a,
+    E: Send + 'a,
+    F: FnMut(Option<String>) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<Page<T>, E>> + Send + 'a,
+{
+    // State: fetcher plus the cursor to fetch next, or None once finished
+    futures::stream::unfold((fetch, Some(None::<String>)), |(mut fetch, cursor)| async move {
+        let cursor = cursor?;
+        match fetch(cursor).await {
+            Ok(page) => {
+                let next = page.next_cursor.clone().map(Some);
+                Some((Ok(page), (fetch, next)))
+            }
+            Err(e) => Some((Err(e), (fetch, None))),
+        }
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pages_follow_cursors() {
+        let data: Vec<u32> = (0..7).collect();
+        let fetched: Vec<Page<u32>> = pages(|cursor: Option<String>| {
+            let data = data.clone();
+            async move {
+                let page = page_index(cursor.as_deref());
+                let items = data.iter().skip(page as usize * 3).take(3).copied().collect();
+                Ok::<_, ()>(Page::from_page_index(items, page, 3, None))
+            }
+        })
+        .map(Result::unwrap)
+        .collect()
+        .await;
+
+        assert_eq!(fetched.len(), 3);
+        assert_eq!(fetched[2].items, vec![6]);
+        assert_eq!(fetched[2].next_cursor, None);
+        assert_eq!(Page::from_page_index(vec![1, 2, 3], 1, 3, Some(6)).next_cursor, None);
+    }
+}
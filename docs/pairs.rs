@@ -0,0 +1,171 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::calendar;
+use crate::models::Ohlcv;
+use crate::provider::{ProviderError, StockDataProvider};
+
+/// Engle-Granger 5% critical value for the ADF statistic on residuals of a
+/// two-variable regression with constant (MacKinnon, large sample).
+pub const EG_CRITICAL_5PCT: f64 = -3.34;
+
+/// Closes of both symbols on trading days where both traded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlignedPair {
+    pub dates: Vec<NaiveDate>,
+    pub a: Vec<f64>,
+    pub b: Vec<f64>,
+}
+
+impl AlignedPair {
+    pub fn from_bars(a: &[Ohlcv], b: &[Ohlcv], from: NaiveDate, to: NaiveDate) -> Self {
+        let closes = |bars: &[Ohlcv]| -> HashMap<NaiveDate, f64> {
+            bars.iter().filter(|bar| bar.close > 0.0).map(|bar| (bar.time.date_naive(), bar.close)).collect()
+        };
+        let (closes_a, closes_b) = (closes(a), closes(b));
+        let mut pair = AlignedPair { dates: Vec::new(), a: Vec::new(), b: Vec::new() };
+        for day in calendar::trading_days_between(from, to) {
+            if let (Some(&a), Some(&b)) = (closes_a.get(&day), closes_b.get(&day)) {
+                pair.dates.push(day);
+                pair.a.push(a);
+                pair.b.push(b);
+            }
+        }
+        pair
+    }
+
+    /// Log-price spread `ln(a) - hedge_ratio * ln(b)`.
+    pub fn spread(&self, hedge_ratio: f64) -> Vec<f64> {
+        self.a.iter().zip(&self.b).map(|(a, b)| a.ln() - hedge_ratio * b.ln()).collect()
+    }
+
+    /// Price ratio `a / b`.
+    pub fn ratio(&self) -> Vec<f64> {
+        self.a.iter().zip(&self.b).map(|(a, b)| a / b).collect()
+    }
+
+    pub fn cointegration(&self) -> Option<Cointegration> {
+        let log_a: Vec<f64> = self.a.iter().map(|p| p.ln()).collect();
+        let log_b: Vec<f64> = self.b.iter().map(|p| p.ln()).collect();
+        cointegration(&log_a, &log_b)
+    }
+}
+
+/// Aligned daily closes of `a` and `b` over `[from, to]`.
+pub async fn fetch_pair(provider: &dyn StockDataProvider, a: &str, b: &str, from: NaiveDate, to: NaiveDate) -> Result<AlignedPair, ProviderError> {
+    let bars_a = provider.get_history(a, from, Some(to), "1D").await?;
+    let bars_b = provider.get_history(b, from, Some(to), "1D").await?;
+    Ok(AlignedPair::from_bars(&bars_a, &bars_b, from, to))
+}
+
+/// Z-score of each value against the preceding `window` values, including
+/// itself. The first `window - 1` entries are `None`.
+pub fn rolling_zscore(values: &[f64], window: usize) -> Vec<Option<f64>> {
+    (0..values.len())
+        .map(|i| {
+            if window < 2 || i + 1 < window {
+                return None;
+            }
+            let slice = &values[i + 1 - window..=i];
+            let mean = slice.iter().sum::<f64>() / window as f64;
+            let sd = (slice.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (window - 1) as f64).sqrt();
+            (sd > 0.0).then(|| (values[i] - mean) / sd)
+        })
+        .collect()
+}
+
+/// Ordinary least squares `y = alpha + beta * x`, as `(alpha, beta)`.
+fn ols(x: &[f64], y: &[f64]) -> Option<(f64, f64)> {
+    let n = x.len().min(y.len());
+    if n < 2 {
+        return None;
+    }
+    let (mean_x, mean_y) = (x[..n].iter().sum::<f64>() / n as f64, y[..n].iter().sum::<f64>() / n as f64);
+    let sxy: f64 = x[..n].iter().zip(&y[..n]).map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let sxx: f64 = x[..n].iter().map(|x| (x - mean_x).powi(2)).sum();
+    (sxx > 0.0).then(|| (mean_y - sxy / sxx * mean_x, sxy / sxx))
+}
+
+/// Dickey-Fuller t-statistic for a unit root (no lags, with constant):
+/// regresses `Δe_t` on `e_{t-1}`.
+pub fn adf_statistic(series: &[f64]) -> Option<f64> {
+    if series.len() < 10 {
+        return None;
+    }
+    let lagged = &series[..series.len() - 1];
+    let diffs: Vec<f64> = series.windows(2).map(|w| w[1] - w[0]).collect();
+    let (alpha, gamma) = ols(lagged, &diffs)?;
+    let n = diffs.len() as f64;
+    let residual_ss: f64 = lagged.iter().zip(&diffs).map(|(x, d)| (d - alpha - gamma * x).powi(2)).sum();
+    let mean = lagged.iter().sum::<f64>() / n;
+    let sxx: f64 = lagged.iter().map(|x| (x - mean).powi(2)).sum();
+    let se = (residual_ss / (n - 2.0) / sxx).sqrt();
+    (se > 0.0).then(|| gamma / se)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Cointegration {
+    pub hedge_ratio: f64,
+    pub intercept: f64,
+    pub adf_statistic: f64,
+    /// Half-life of spread mean reversion in days, if it reverts at all.
+    pub half_life: Option<f64>,
+}
+
+impl Cointegration {
+    pub fn is_cointegrated(&self) -> bool {
+        self.adf_statistic < EG_CRITICAL_5PCT
+    }
+}
+
+/// Engle-Granger two-step test: regress `a` on `b`, then test the residual
+/// for a unit root. Pass log prices for a hedge ratio in return terms.
+pub fn cointegration(a: &[f64], b: &[f64]) -> Option<Cointegration> {
+    let (intercept, hedge_ratio) = ols(b, a)?;
+    let residuals: Vec<f64> = a.iter().zip(b).map(|(a, b)| a - intercept - hedge_ratio * b).collect();
+    let adf = adf_statistic(&residuals)?;
+    let diffs: Vec<f64> = residuals.windows(2).map(|w| w[1] - w[0]).collect();
+    let half_life = ols(&residuals[..residuals.len() - 1], &diffs)
+        .map(|(_, gamma)| gamma)
+        .filter(|&gamma| gamma < 0.0)
+        .map(|gamma| -std::f64::consts::LN_2 / (1.0 + gamma).ln());
+    Some(Cointegration { hedge_ratio, intercept, adf_statistic: adf, half_life })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_rolling_zscore() {
+        let z = rolling_zscore(&[1.0, 2.0, 3.0, 10.0], 3);
+        assert_eq!(z[..2], [None, None]);
+        assert!((z[2].unwrap() - 1.0).abs() < 1e-9);
+        assert!(z[3].unwrap() > 1.0);
+    }
+
+    #[test]
+    fn test_cointegration_detects_mean_reverting_spread() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let (mut b, mut walk, mut noise) = (Vec::new(), 3.0, 0.0);
+        let mut a = Vec::new();
+        let mut independent = Vec::new();
+        let mut other = 3.0;
+        for _ in 0..400 {
+            walk += rng.gen_range(-0.02..0.02);
+            other += rng.gen_range(-0.02..0.02);
+            noise = 0.5 * noise + rng.gen_range(-0.01..0.01);
+            b.push(walk);
+            a.push(0.2 + 1.5 * walk + noise);
+            independent.push(other);
+        }
+
+        let result = cointegration(&a, &b).unwrap();
+        assert!((result.hedge_ratio - 1.5).abs() < 0.05);
+        assert!(result.is_cointegrated());
+        assert!(result.half_life.unwrap() < 5.0);
+        assert!(!cointegration(&independent, &b).unwrap().is_cointegrated());
+    }
+}
@@ -0,0 +1,266 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::events::Event;
+use crate::models::Ohlcv;
+use crate::ta::Indicator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Pattern {
+    BullishEngulfing,
+    BearishEngulfing,
+    Doji,
+    /// Small body near the high with a long lower shadow, after a decline.
+    Hammer,
+    ThreeWhiteSoldiers,
+}
+
+impl Pattern {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Pattern::BullishEngulfing => "bullish_engulfing",
+            Pattern::BearishEngulfing => "bearish_engulfing",
+            Pattern::Doji => "doji",
+            Pattern::Hammer => "hammer",
+            Pattern::ThreeWhiteSoldiers => "three_white_soldiers",
+        }
+    }
+
+    /// Number of bars the pattern spans, ending at the detection bar.
+    pub fn bars(&self) -> usize {
+        match self {
+            Pattern::Doji | Pattern::Hammer => 1,
+            Pattern::BullishEngulfing | Pattern::BearishEngulfing => 2,
+            Pattern::ThreeWhiteSoldiers => 3,
+        }
+    }
+}
+
+/// Detection thresholds, as fractions of a bar's high-low range or body.
+/// Loosening them finds more, weaker patterns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternConfig {
+    /// Doji: body at most this fraction of the range.
+    pub doji_body: f64,
+    /// Hammer: lower shadow at least this multiple of the body.
+    pub hammer_shadow: f64,
+    /// Hammer: upper shadow at most this fraction of the range.
+    pub hammer_upper_shadow: f64,
+    /// Engulfing: current body at least this multiple of the previous one.
+    pub engulfing_body: f64,
+    /// Three white soldiers: each upper shadow at most this fraction of its body.
+    pub soldier_upper_shadow: f64,
+    /// Closes the hammer's prior decline is measured over; 0 skips the check.
+    pub trend_lookback: usize,
+}
+
+impl Default for PatternConfig {
+    fn default() -> Self {
+        PatternConfig {
+            doji_body: 0.1,
+            hammer_shadow: 2.0,
+            hammer_upper_shadow: 0.1,
+            engulfing_body: 1.0,
+            soldier_upper_shadow: 0.3,
+            trend_lookback: 5,
+        }
+    }
+}
+
+/// A pattern completed on `time`'s bar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternEvent {
+    pub symbol: Option<String>,
+    pub time: DateTime<Utc>,
+    pub pattern: Pattern,
+}
+
+impl PatternEvent {
+    pub fn to_event(&self) -> Event {
+        let symbol = self.symbol.clone().unwrap_or_default();
+        Event::AlertFired {
+            name: format!("pattern:{}", self.pattern.as_str()),
+            message: format!("{} {} on {}", symbol, self.pattern.as_str(), self.time.format("%Y-%m-%d %H:%M")),
+            symbol,
+            time: self.time,
+        }
+    }
+}
+
+fn body(bar: &Ohlcv) -> f64 {
+    (bar.close - bar.open).abs()
+}
+
+fn range(bar: &Ohlcv) -> f64 {
+    bar.high - bar.low
+}
+
+fn bullish(bar: &Ohlcv) -> bool {
+    bar.close > bar.open
+}
+
+fn bearish(bar: &Ohlcv) -> bool {
+    bar.close < bar.open
+}
+
+/// Patterns completed on the last bar of `window` (oldest first), which
+/// holds at most three bars plus the hammer's trend lookback.
+fn patterns_at(window: &[Ohlcv], config: &PatternConfig) -> Vec<Pattern> {
+    let Some(bar) = window.last() else { return Vec::new() };
+    let mut found = Vec::new();
+    let bar_range = range(bar);
+
+    if bar_range > 0.0 {
+        let upper = bar.high - bar.open.max(bar.close);
+        let lower = bar.open.min(bar.close) - bar.low;
+        if body(bar) <= config.doji_body * bar_range {
+            found.push(Pattern::Doji);
+        } else if lower >= config.hammer_shadow * body(bar) && upper <= config.hammer_upper_shadow * bar_range {
+            let prior = &window[..window.len() - 1];
+            let declining = config.trend_lookback == 0
+                || (prior.len() >= config.trend_lookback && prior[prior.len() - config.trend_lookback].close > bar.open.max(bar.close));
+            if declining {
+                found.push(Pattern::Hammer);
+            }
+        }
+    }
+
+    if let [.., previous, current] = window {
+        let engulfs = current.open.min(current.close) <= previous.open.min(previous.close)
+            && current.open.max(current.close) >= previous.open.max(previous.close)
+            && body(current) > 0.0
+            && body(current) >= config.engulfing_body * body(previous);
+        if engulfs && bearish(previous) && bullish(current) {
+            found.push(Pattern::BullishEngulfing);
+        } else if engulfs && bullish(previous) && bearish(current) {
+            found.push(Pattern::BearishEngulfing);
+        }
+    }
+
+    if let [.., first, second, third] = window {
+        let soldiers = [first, second, third];
+        let strong = soldiers.iter().all(|bar| bullish(bar) && bar.high - bar.close <= config.soldier_upper_shadow * body(bar));
+        let climbing = soldiers.windows(2).all(|pair| {
+            let (previous, current) = (pair[0], pair[1]);
+            current.close > previous.close && current.open > previous.open && current.open <= previous.close
+        });
+        if strong && climbing {
+            found.push(Pattern::ThreeWhiteSoldiers);
+        }
+    }
+    found
+}
+
+/// Incremental detector over closed bars; runs on a live feed through
+/// [`crate::ta::LiveIndicator`] like any other indicator.
+#[derive(Debug, Clone)]
+pub struct PatternDetector {
+    config: PatternConfig,
+    window: VecDeque<Ohlcv>,
+}
+
+impl PatternDetector {
+    pub fn new(config: PatternConfig) -> Self {
+        PatternDetector { config, window: VecDeque::new() }
+    }
+
+    fn capacity(&self) -> usize {
+        3.max(self.config.trend_lookback + 1)
+    }
+}
+
+impl Indicator for PatternDetector {
+    /// Patterns completed on the bar; `None` when there are none.
+    type Output = Vec<Pattern>;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<Vec<Pattern>> {
+        self.window.push_back(bar.clone());
+        if self.window.len() > self.capacity() {
+            self.window.pop_front();
+        }
+        let found = patterns_at(self.window.make_contiguous(), &self.config);
+        (!found.is_empty()).then_some(found)
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+}
+
+/// Every pattern in `bars` (oldest first), in bar order.
+pub fn detect(bars: &[Ohlcv], config: &PatternConfig) -> Vec<PatternEvent> {
+    let capacity = 3.max(config.trend_lookback + 1);
+    (0..bars.len())
+        .flat_map(|i| {
+            let window = &bars[(i + 1).saturating_sub(capacity)..=i];
+            patterns_at(window, config).into_iter().map(move |pattern| PatternEvent {
+                symbol: bars[i].symbol.clone(),
+                time: bars[i].time,
+                pattern,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ta;
+    use chrono::{Datelike, TimeZone};
+
+    fn bar(day: u32, open: f64, high: f64, low: f64, close: f64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 5, day, 0, 0, 0).unwrap(),
+            open,
+            high,
+            low,
+            close,
+            volume: 100,
+            symbol: Some("HPG".to_string()),
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_detects_each_pattern() {
+        let bars = vec![
+            bar(1, 30.0, 30.5, 29.0, 29.2),
+            bar(2, 29.2, 29.4, 28.0, 28.2),
+            bar(3, 28.2, 28.4, 27.0, 27.2),
+            bar(4, 27.2, 27.3, 26.0, 26.2),
+            bar(5, 26.2, 26.3, 25.0, 25.2),
+            // Hammer after five lower closes.
+            bar(6, 24.5, 25.05, 23.0, 25.0),
+            // Bullish engulfing of a small red bar.
+            bar(7, 25.2, 25.3, 24.8, 24.9),
+            bar(8, 24.8, 25.6, 24.7, 25.5),
+            // Three white soldiers, ending on day 10.
+            bar(9, 25.4, 26.3, 25.3, 26.2),
+            bar(10, 26.0, 27.1, 25.9, 27.0),
+            bar(11, 27.05, 27.15, 26.9, 27.06),
+        ];
+        let events = detect(&bars, &PatternConfig::default());
+        let found: Vec<(u32, Pattern)> = events.iter().map(|e| (e.time.day(), e.pattern)).collect();
+        assert!(found.contains(&(6, Pattern::Hammer)));
+        assert!(found.contains(&(8, Pattern::BullishEngulfing)));
+        assert!(found.contains(&(10, Pattern::ThreeWhiteSoldiers)));
+        assert!(found.contains(&(11, Pattern::Doji)));
+        assert!(!found.iter().any(|(_, pattern)| *pattern == Pattern::BearishEngulfing));
+        assert_eq!(events[0].to_event().symbol(), Some("HPG"));
+    }
+
+    #[test]
+    fn test_detector_matches_batch_and_sensitivity() {
+        let bars = vec![bar(1, 10.0, 10.6, 9.9, 10.5), bar(2, 10.6, 10.7, 9.8, 9.9), bar(3, 10.0, 10.5, 9.5, 10.02)];
+        let config = PatternConfig { trend_lookback: 0, ..PatternConfig::default() };
+        let batch: Vec<Vec<Pattern>> = detect(&bars, &config).into_iter().map(|e| vec![e.pattern]).collect();
+        let incremental: Vec<Vec<Pattern>> = ta::compute(&mut PatternDetector::new(config), &bars).into_iter().flatten().collect();
+        assert_eq!(batch, incremental);
+        assert!(incremental.contains(&vec![Pattern::BearishEngulfing]));
+
+        let strict = PatternConfig { doji_body: 0.01, ..PatternConfig::default() };
+        assert!(detect(&bars, &strict).iter().all(|e| e.pattern != Pattern::Doji));
+    }
+}
@@ -0,0 +1,604 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use crate::text;
+use crate::watchlist::split_line;
+
+#[derive(Debug)]
+pub enum PortfolioError {
+    Io(std::io::Error),
+    Zip(zip::result::ZipError),
+    InvalidStatement(String),
+    Oversold { symbol: String, date: NaiveDate },
+}
+
+impl From<std::io::Error> for PortfolioError {
+    fn from(error: std::io::Error) -> Self {
+        PortfolioError::Io(error)
+    }
+}
+
+impl From<zip::result::ZipError> for PortfolioError {
+    fn from(error: zip::result::ZipError) -> Self {
+        PortfolioError::Zip(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    /// Parses "Mua"/"Bán"/"Buy"/"Sell" and the one-letter codes of the
+    /// brokers' M/B columns, where "M" is Mua (buy) and "B" is Bán (sell).
+    pub fn parse(raw: &str) -> Option<Self> {
+        match text::normalize(raw).as_str() {
+            s if s.starts_with("mua") || s == "buy" || s == "m" => Some(Side::Buy),
+            s if s.starts_with("ban") || s == "sell" || s == "b" || s == "s" => Some(Side::Sell),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    pub date: NaiveDate,
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: u64,
+    pub price: f64,
+    pub fee: f64,
+    pub tax: f64,
+}
+
+impl Trade {
+    pub fn gross_value(&self) -> f64 {
+        self.quantity as f64 * self.price
+    }
+}
+
+/// Header names (normalized) for each statement column.
+struct StatementColumns {
+    date: &'static [&'static str],
+    symbol: &'static [&'static str],
+    side: &'static [&'static str],
+    quantity: &'static [&'static str],
+    price: &'static [&'static str],
+    fee: &'static [&'static str],
+    tax: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Broker {
+    Tcbs,
+    Ssi,
+    Vps,
+}
+
+impl Broker {
+    fn columns(self) -> StatementColumns {
+        match self {
+            Broker::Tcbs => StatementColumns {
+                date: &["ngay giao dich", "ngay gd", "trade date"],
+                symbol: &["ma cp", "ma ck", "symbol"],
+                side: &["loai lenh", "lenh", "side"],
+                quantity: &["khoi luong khop", "kl khop", "matched volume"],
+                price: &["gia khop", "gia khop tb", "matched price"],
+                fee: &["phi giao dich", "phi"],
+                tax: &["thue", "thue tncn"],
+            },
+            Broker::Ssi => StatementColumns {
+                date: &["ngay gd", "ngay giao dich", "date"],
+                symbol: &["ma ck", "ma chung khoan", "stock"],
+                side: &["mua/ban", "loai gd", "giao dich"],
+                quantity: &["kl khop", "so luong", "khoi luong"],
+                price: &["gia khop", "gia"],
+                fee: &["phi", "phi gd"],
+                tax: &["thue", "thue ban"],
+            },
+            Broker::Vps => StatementColumns {
+                date: &["ngay", "ngay khop", "ngay giao dich"],
+                symbol: &["ma", "ma ck", "ma cp"],
+                side: &["m/b", "loai", "lenh"],
+                quantity: &["kl", "kl khop", "khoi luong khop"],
+                price: &["gia", "gia khop"],
+                fee: &["phi", "phi gd"],
+                tax: &["thue", "thue tncn"],
+            },
+        }
+    }
+
+    /// VPS exports use the Vietnamese locale: `.` groups thousands and `,`
+    /// is the decimal mark. TCBS and SSI use the opposite.
+    fn decimal_comma(self) -> bool {
+        matches!(self, Broker::Vps)
+    }
+}
+
+/// Trade history built from one or more broker statements.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Portfolio {
+    pub trades: Vec<Trade>,
+}
+
+impl Portfolio {
+    pub fn from_trades(trades: Vec<Trade>) -> Self {
+        let mut portfolio = Portfolio::default();
+        portfolio.extend(trades);
+        portfolio
+    }
+
+    /// Adds trades keeping the history in date order; same-day trades keep
+    /// their statement order.
+    pub fn extend(&mut self, trades: impl IntoIterator<Item = Trade>) {
+        self.trades.extend(trades);
+        self.trades.sort_by_key(|trade| trade.date);
+    }
+
+    /// Imports a `.csv` or `.xlsx` statement and appends its trades.
+    pub fn import_statement(&mut self, broker: Broker, path: impl AsRef<Path>) -> Result<usize, PortfolioError> {
+        let path = path.as_ref();
+        let trades = match path.extension().and_then(|ext| ext.to_str()).map(str::to_lowercase).as_deref() {
+            Some("xlsx") => parse_statement_xlsx(broker, &fs::read(path)?)?,
+            _ => parse_statement_csv(broker, &fs::read_to_string(path)?)?,
+        };
+        let count = trades.len();
+        self.extend(trades);
+        Ok(count)
+    }
+
+    pub fn trades_for<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a Trade> + 'a {
+        self.trades.iter().filter(move |trade| trade.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    /// Net share count per symbol; symbols fully sold out are omitted.
+    pub fn positions(&self) -> BTreeMap<String, i64> {
+        let mut positions = BTreeMap::new();
+        for trade in &self.trades {
+            let qty = trade.quantity as i64;
+            *positions.entry(trade.symbol.clone()).or_insert(0) += if trade.side == Side::Buy { qty } else { -qty };
+        }
+        positions.retain(|_, qty| *qty != 0);
+        positions
+    }
+}
+
+/// Personal income tax withheld on the gross value of every sale.
+pub const SELL_TAX_RATE: f64 = 0.001;
+
+/// Costs applied to trades whose statement row leaves fee or tax blank.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeSchedule {
+    pub commission_rate: f64,
+    pub sell_tax_rate: f64,
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        FeeSchedule { commission_rate: 0.0015, sell_tax_rate: SELL_TAX_RATE }
+    }
+}
+
+impl FeeSchedule {
+    /// (fee, tax) for `trade`: the statement figures when present, otherwise
+    /// modeled from the schedule. Buys are never taxed.
+    pub fn costs(&self, trade: &Trade) -> (f64, f64) {
+        let fee = if trade.fee > 0.0 { trade.fee } else { trade.gross_value() * self.commission_rate };
+        let tax = match trade.side {
+            Side::Buy => 0.0,
+            Side::Sell if trade.tax > 0.0 => trade.tax,
+            Side::Sell => trade.gross_value() * self.sell_tax_rate,
+        };
+        (fee, tax)
+    }
+}
+
+/// Open shares from one buy; the buy fee is folded into the cost.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxLot {
+    pub symbol: String,
+    pub acquired: NaiveDate,
+    pub quantity: u64,
+    pub cost_per_share: f64,
+}
+
+/// Part of a sale matched against one lot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealizedGain {
+    pub symbol: String,
+    pub acquired: NaiveDate,
+    pub sold: NaiveDate,
+    pub quantity: u64,
+    /// Sale value net of the matched share of fee and tax.
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    pub fee: f64,
+    pub tax: f64,
+}
+
+impl RealizedGain {
+    pub fn pnl(&self) -> f64 {
+        self.proceeds - self.cost_basis
+    }
+
+    pub fn holding_days(&self) -> i64 {
+        (self.sold - self.acquired).num_days()
+    }
+}
+
+/// FIFO matching of a trade history into open lots and realized gains.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LotBook {
+    pub open: BTreeMap<String, VecDeque<TaxLot>>,
+    pub realized: Vec<RealizedGain>,
+}
+
+impl LotBook {
+    pub fn build<'a>(trades: impl IntoIterator<Item = &'a Trade>, schedule: &FeeSchedule) -> Result<Self, PortfolioError> {
+        let mut book = LotBook::default();
+        for trade in trades {
+            let (fee, tax) = schedule.costs(trade);
+            let lots = book.open.entry(trade.symbol.clone()).or_default();
+            if trade.side == Side::Buy {
+                lots.push_back(TaxLot {
+                    symbol: trade.symbol.clone(),
+                    acquired: trade.date,
+                    quantity: trade.quantity,
+                    cost_per_share: (trade.gross_value() + fee) / trade.quantity as f64,
+                });
+                continue;
+            }
+
+            let mut remaining = trade.quantity;
+            while remaining > 0 {
+                let Some(lot) = lots.front_mut() else {
+                    return Err(PortfolioError::Oversold { symbol: trade.symbol.clone(), date: trade.date });
+                };
+                let quantity = remaining.min(lot.quantity);
+                let share = quantity as f64 / trade.quantity as f64;
+                book.realized.push(RealizedGain {
+                    symbol: trade.symbol.clone(),
+                    acquired: lot.acquired,
+                    sold: trade.date,
+                    quantity,
+                    proceeds: quantity as f64 * trade.price - (fee + tax) * share,
+                    cost_basis: quantity as f64 * lot.cost_per_share,
+                    fee: fee * share,
+                    tax: tax * share,
+                });
+                lot.quantity -= quantity;
+                remaining -= quantity;
+                if lot.quantity == 0 {
+                    lots.pop_front();
+                }
+            }
+        }
+        book.open.retain(|_, lots| !lots.is_empty());
+        Ok(book)
+    }
+
+    /// Market value minus cost of open lots; symbols without a price are skipped.
+    pub fn unrealized(&self, prices: &HashMap<String, f64>) -> BTreeMap<String, f64> {
+        self.open
+            .iter()
+            .filter_map(|(symbol, lots)| {
+                let price = prices.get(symbol)?;
+                Some((symbol.clone(), lots.iter().map(|lot| lot.quantity as f64 * (price - lot.cost_per_share)).sum()))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Period {
+    /// Report bucket for `date`: "2024-03", "2024-Q1" or "2024".
+    pub fn label(&self, date: NaiveDate) -> String {
+        match self {
+            Period::Month => date.format("%Y-%m").to_string(),
+            Period::Quarter => format!("{}-Q{}", date.year(), date.month0() / 3 + 1),
+            Period::Year => date.year().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeriodPnl {
+    pub realized: f64,
+    pub fees: f64,
+    pub taxes: f64,
+    /// Open-position P&L at the end of the period; only reported for the
+    /// latest period since it needs current prices.
+    pub unrealized: Option<f64>,
+}
+
+impl Portfolio {
+    pub fn lots(&self, schedule: &FeeSchedule) -> Result<LotBook, PortfolioError> {
+        LotBook::build(&self.trades, schedule)
+    }
+
+    /// Realized P&L, fees and taxes bucketed by `period`, with unrealized
+    /// P&L at `prices` attached to the latest bucket. Buy fees count in the
+    /// period they were paid, though they also sit in the lots' cost basis.
+    pub fn pnl_report(&self, period: Period, schedule: &FeeSchedule, prices: &HashMap<String, f64>) -> Result<BTreeMap<String, PeriodPnl>, PortfolioError> {
+        let book = self.lots(schedule)?;
+        let mut report: BTreeMap<String, PeriodPnl> = BTreeMap::new();
+        for trade in &self.trades {
+            let (fee, tax) = schedule.costs(trade);
+            let entry = report.entry(period.label(trade.date)).or_default();
+            entry.fees += fee;
+            entry.taxes += tax;
+        }
+        for gain in &book.realized {
+            report.entry(period.label(gain.sold)).or_default().realized += gain.pnl();
+        }
+        if let Some(latest) = report.values_mut().last() {
+            latest.unrealized = Some(book.unrealized(prices).values().sum());
+        }
+        Ok(report)
+    }
+}
+
+fn parse_number(raw: &str, decimal_comma: bool) -> Option<f64> {
+    let cleaned: String = raw.chars().filter(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-')).collect();
+    let cleaned = if decimal_comma { cleaned.replace('.', "").replace(',', ".") } else { cleaned.replace(',', "") };
+    cleaned.parse().ok()
+}
+
+/// Accepts `dd/mm/yyyy`, `dd-mm-yyyy`, ISO dates (optionally with a time)
+/// and Excel serial day numbers.
+fn parse_date(raw: &str) -> Option<NaiveDate> {
+    let raw = raw.split_whitespace().next()?;
+    let raw = raw.split('T').next()?;
+    ["%d/%m/%Y", "%d-%m-%Y", "%Y-%m-%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(raw, format).ok())
+        .or_else(|| {
+            let serial: f64 = raw.parse().ok()?;
+            NaiveDate::from_ymd_opt(1899, 12, 30)?.checked_add_signed(Duration::days(serial as i64))
+        })
+}
+
+/// Builds trades from a sheet of cells. Statements start with account
+/// details, so the header is the first row naming the date, symbol, side,
+/// quantity and price columns; rows that don't parse (subtotals, footers,
+/// unmatched orders) are skipped.
+fn trades_from_rows(broker: Broker, rows: &[Vec<String>]) -> Result<Vec<Trade>, PortfolioError> {
+    let spec = broker.columns();
+    let find = |headers: &[String], names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+
+    let (header_index, cols) = rows
+        .iter()
+        .enumerate()
+        .find_map(|(i, row)| {
+            let headers: Vec<String> = row.iter().map(|cell| text::normalize(cell)).collect();
+            let required = (
+                find(&headers, spec.date)?,
+                find(&headers, spec.symbol)?,
+                find(&headers, spec.side)?,
+                find(&headers, spec.quantity)?,
+                find(&headers, spec.price)?,
+            );
+            Some((i, (required, find(&headers, spec.fee), find(&headers, spec.tax))))
+        })
+        .ok_or_else(|| PortfolioError::InvalidStatement(format!("no {:?} statement header found", broker)))?;
+    let ((date, symbol, side, quantity, price), fee, tax) = cols;
+
+    let decimal_comma = broker.decimal_comma();
+    let number = |row: &Vec<String>, col: Option<usize>| {
+        col.and_then(|c| row.get(c)).and_then(|raw| parse_number(raw, decimal_comma)).unwrap_or(0.0)
+    };
+
+    let trades = rows[header_index + 1..]
+        .iter()
+        .filter_map(|row| {
+            let symbol = row.get(symbol)?.trim().to_uppercase();
+            if !(3..=10).contains(&symbol.len()) || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return None;
+            }
+            let quantity = number(row, Some(quantity));
+            if quantity <= 0.0 {
+                return None;
+            }
+            Some(Trade {
+                date: parse_date(row.get(date)?)?,
+                symbol,
+                side: Side::parse(row.get(side)?)?,
+                quantity: quantity as u64,
+                price: number(row, Some(price)),
+                fee: number(row, fee),
+                tax: number(row, tax),
+            })
+        })
+        .collect();
+    Ok(trades)
+}
+
+pub fn parse_statement_csv(broker: Broker, csv: &str) -> Result<Vec<Trade>, PortfolioError> {
+    let lines: Vec<&str> = csv.trim_start_matches('\u{feff}').lines().filter(|line| !line.trim().is_empty()).collect();
+    let delimiter = [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|&d| lines.iter().map(|line| line.matches(d).count()).max().unwrap_or(0))
+        .unwrap_or(',');
+    let rows: Vec<Vec<String>> = lines.iter().map(|line| split_line(line, delimiter)).collect();
+    trades_from_rows(broker, &rows)
+}
+
+fn unescape_xml(raw: &str) -> String {
+    raw.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Zero-based column index from a cell reference such as `AB12`.
+fn column_index(reference: &str) -> usize {
+    reference
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .fold(0, |acc, c| acc * 26 + (c.to_ascii_uppercase() as usize - 'A' as usize + 1))
+        .saturating_sub(1)
+}
+
+/// Reads the first worksheet of an `.xlsx` workbook into rows of cell text.
+/// Only what statements use is supported: shared and inline strings and
+/// plain values; formulas are read from their cached results.
+pub fn read_xlsx_rows(bytes: &[u8]) -> Result<Vec<Vec<String>>, PortfolioError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let mut read_entry = |name: &str| -> Result<Option<String>, PortfolioError> {
+        let mut entry = match archive.by_name(name) {
+            Ok(entry) => entry,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let mut xml = String::new();
+        entry.read_to_string(&mut xml)?;
+        Ok(Some(xml))
+    };
+
+    let text_re = Regex::new(r"(?s)<t[^>]*>(.*?)</t>").unwrap();
+    let shared: Vec<String> = read_entry("xl/sharedStrings.xml")?
+        .map(|xml| {
+            Regex::new(r"(?s)<si>(.*?)</si>")
+                .unwrap()
+                .captures_iter(&xml)
+                .map(|si| text_re.captures_iter(&si[1]).map(|t| unescape_xml(&t[1])).collect())
+                .collect()
+        })
+        .unwrap_or_default();
+    let sheet = read_entry("xl/worksheets/sheet1.xml")?
+        .ok_or_else(|| PortfolioError::InvalidStatement("workbook has no first worksheet".to_string()))?;
+
+    let row_re = Regex::new(r"(?s)<row[^>]*>(.*?)</row>").unwrap();
+    let cell_re = Regex::new(r#"(?s)<c\s([^>]*?)(?:/>|>(.*?)</c>)"#).unwrap();
+    let ref_re = Regex::new(r#"\br="([A-Z]+)\d*""#).unwrap();
+    let type_re = Regex::new(r#"\bt="(\w+)""#).unwrap();
+    let value_re = Regex::new(r"(?s)<v>(.*?)</v>").unwrap();
+
+    let rows = row_re
+        .captures_iter(&sheet)
+        .map(|row| {
+            let mut cells = Vec::new();
+            for cell in cell_re.captures_iter(&row[1]) {
+                let attrs = &cell[1];
+                let body = cell.get(2).map(|m| m.as_str()).unwrap_or("");
+                let index = ref_re.captures(attrs).map(|r| column_index(&r[1])).unwrap_or(cells.len());
+                let value = value_re.captures(body).map(|v| unescape_xml(&v[1])).unwrap_or_default();
+                let value = match type_re.captures(attrs).as_ref().map(|t| &t[1]) {
+                    Some("s") => value.parse::<usize>().ok().and_then(|i| shared.get(i).cloned()).unwrap_or_default(),
+                    Some("inlineStr") => text_re.captures_iter(body).map(|t| unescape_xml(&t[1])).collect(),
+                    _ => value,
+                };
+                if cells.len() <= index {
+                    cells.resize(index + 1, String::new());
+                }
+                cells[index] = value;
+            }
+            cells
+        })
+        .collect();
+    Ok(rows)
+}
+
+pub fn parse_statement_xlsx(broker: Broker, bytes: &[u8]) -> Result<Vec<Trade>, PortfolioError> {
+    trades_from_rows(broker, &read_xlsx_rows(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_parse_csv_statements() {
+        let tcbs = "Tài khoản: 105C123456\n\nNgày giao dịch,Mã CP,Loại lệnh,Khối lượng khớp,Giá khớp,Phí giao dịch,Thuế\n\
+                    05/03/2024,FPT,Mua,\"1,000\",\"105,500\",\"15,825\",0\n\
+                    12/03/2024,FPT,Bán,400,\"110,000\",\"6,600\",\"44,000\"\n\
+                    ,Tổng,,,,,\n";
+        let trades = parse_statement_csv(Broker::Tcbs, tcbs).unwrap();
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].price, 105_500.0);
+        assert_eq!(trades[1].side, Side::Sell);
+        assert_eq!(trades[1].tax, 44_000.0);
+
+        let vps = "Ngày;Mã;M/B;KL;Giá;Phí;Thuế\n2024-03-01;VCB;M;200;90.500,5;27.150;0\n";
+        let trades = parse_statement_csv(Broker::Vps, vps).unwrap();
+        assert_eq!(trades[0].price, 90_500.5);
+        assert_eq!([Side::parse("B"), Side::parse("M"), Side::parse("Bán")], [Some(Side::Sell), Some(Side::Buy), Some(Side::Sell)]);
+
+        let mut portfolio = Portfolio::from_trades(parse_statement_csv(Broker::Tcbs, tcbs).unwrap());
+        portfolio.extend(trades);
+        assert_eq!(portfolio.trades[0].symbol, "VCB");
+        assert_eq!(portfolio.positions().get("FPT"), Some(&600));
+    }
+
+    #[test]
+    fn test_parse_xlsx_statement() {
+        let shared = r#"<sst><si><t>Ngày GD</t></si><si><t>Mã CK</t></si><si><t>Mua/Bán</t></si><si><t>KL khớp</t></si><si><t>Giá khớp</t></si><si><t>HPG</t></si><si><r><t>B</t></r><r><t>án</t></r></si></sst>"#;
+        let sheet = r#"<worksheet><sheetData>
+            <row r="1"><c r="A1" t="inlineStr"><is><t>SSI &amp; statement</t></is></c></row>
+            <row r="2"><c r="A2" t="s"><v>0</v></c><c r="B2" t="s"><v>1</v></c><c r="C2" t="s"><v>2</v></c><c r="D2" t="s"><v>3</v></c><c r="E2" t="s"><v>4</v></c></row>
+            <row r="3"><c r="A3"><v>45352</v></c><c r="B3" t="s"><v>5</v></c><c r="C3" t="s"><v>6</v></c><c r="D3"><v>500</v></c><c r="E3"><v>28350</v></c></row>
+        </sheetData></worksheet>"#;
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut buffer);
+        for (name, xml) in [("xl/sharedStrings.xml", shared), ("xl/worksheets/sheet1.xml", sheet)] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let trades = parse_statement_xlsx(Broker::Ssi, buffer.get_ref()).unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].date, NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        assert_eq!((trades[0].side, trades[0].quantity, trades[0].price), (Side::Sell, 500, 28_350.0));
+    }
+
+    #[test]
+    fn test_fifo_lots_and_period_report() {
+        let trade = |date: (i32, u32, u32), side, quantity, price| Trade {
+            date: NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap(),
+            symbol: "FPT".to_string(),
+            side,
+            quantity,
+            price,
+            fee: 0.0,
+            tax: 0.0,
+        };
+        let portfolio = Portfolio::from_trades(vec![
+            trade((2024, 1, 5), Side::Buy, 100, 100.0),
+            trade((2024, 2, 5), Side::Buy, 100, 120.0),
+            trade((2024, 4, 10), Side::Sell, 150, 130.0),
+        ]);
+        let schedule = FeeSchedule { commission_rate: 0.0, sell_tax_rate: SELL_TAX_RATE };
+        let book = portfolio.lots(&schedule).unwrap();
+        assert_eq!(book.realized.len(), 2);
+        assert_eq!(book.realized[0].cost_basis, 10_000.0);
+        assert_eq!(book.open["FPT"][0].quantity, 50);
+
+        let prices = HashMap::from([("FPT".to_string(), 140.0)]);
+        let report = portfolio.pnl_report(Period::Quarter, &schedule, &prices).unwrap();
+        let q2 = &report["2024-Q2"];
+        assert!((q2.taxes - 19.5).abs() < 1e-9);
+        assert!((q2.realized - (19_500.0 - 19.5 - 16_000.0)).abs() < 1e-9);
+        assert_eq!(q2.unrealized, Some(1_000.0));
+        assert_eq!(report["2024-Q1"].unrealized, None);
+
+        let oversold = Portfolio::from_trades(vec![trade((2024, 1, 5), Side::Sell, 1, 1.0)]);
+        assert!(matches!(oversold.lots(&schedule), Err(PortfolioError::Oversold { .. })));
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Largest tolerated difference between local and provider clocks. Beyond
+/// it, date-windowed requests and Vietnam-time session logic go wrong.
+pub const MAX_CLOCK_SKEW_SECS: i64 = 30;
+
+#[derive(Debug)]
+pub enum PreflightError {
+    /// Reports of the providers that failed.
+    Failed(Vec<PreflightReport>),
+}
+
+impl std::fmt::Display for PreflightError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let PreflightError::Failed(reports) = self;
+        let messages: Vec<String> = reports.iter().map(PreflightReport::message).collect();
+        f.write_str(&messages.join("\n"))
+    }
+}
+
+impl std::error::Error for PreflightError {}
+
+/// Something preflight found that would make a batch job fail.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PreflightIssue {
+    /// DNS, TLS, proxy or timeout failure.
+    Unreachable(String),
+    /// 401: the configured token was rejected or is missing.
+    Unauthorized,
+    /// 403/429: the IP or user agent is blocked or rate limited.
+    Blocked(u16),
+    /// Other non-success status from the probe endpoint.
+    UnexpectedStatus(u16),
+    /// Local clock minus provider clock, in seconds.
+    ClockSkew(i64),
+}
+
+impl PreflightIssue {
+    /// What to do about it, for error messages.
+    pub fn advice(&self) -> String {
+        match self {
+            PreflightIssue::Unreachable(reason) => format!("cannot connect ({}); check network, proxy and DNS settings", reason),
+            PreflightIssue::Unauthorized => "authentication failed; check the profile's token".to_string(),
+            PreflightIssue::Blocked(status) => format!("HTTP {}: blocked or rate limited; lower the rate limit or switch proxy", status),
+            PreflightIssue::UnexpectedStatus(status) => format!("HTTP {} from probe endpoint; the provider API may have changed", status),
+            PreflightIssue::ClockSkew(secs) => format!("local clock is off by {}s; sync the system clock (NTP)", secs),
+        }
+    }
+}
+
+/// Result of probing one provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub provider: String,
+    pub latency: Option<Duration>,
+    /// Local minus provider time, from the response `Date` header.
+    pub clock_skew_secs: Option<i64>,
+    pub issues: Vec<PreflightIssue>,
+}
+
+impl PreflightReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Whether the probe got a success status, clock skew aside.
+    pub fn responded(&self) -> bool {
+        self.latency.is_some() && self.issues.iter().all(|issue| matches!(issue, PreflightIssue::ClockSkew(_)))
+    }
+
+    /// Every issue's advice joined into one line.
+    pub fn message(&self) -> String {
+        if self.is_ok() {
+            return format!("{} preflight ok", self.provider);
+        }
+        let advice: Vec<String> = self.issues.iter().map(PreflightIssue::advice).collect();
+        format!("{} preflight failed: {}", self.provider, advice.join("; "))
+    }
+}
+
+/// Sends `request` once, without retries, and inspects status, latency and
+/// the `Date` header.
+pub async fn probe(provider: &str, request: reqwest::RequestBuilder) -> PreflightReport {
+    let started = tokio::time::Instant::now();
+    let mut report = PreflightReport {
+        provider: provider.to_string(),
+        latency: None,
+        clock_skew_secs: None,
+        issues: Vec::new(),
+    };
+
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            report.issues.push(PreflightIssue::Unreachable(e.to_string()));
+            return report;
+        }
+    };
+    report.latency = Some(started.elapsed());
+
+    let status = response.status().as_u16();
+    match status {
+        200..=299 => {}
+        401 => report.issues.push(PreflightIssue::Unauthorized),
+        403 | 429 => report.issues.push(PreflightIssue::Blocked(status)),
+        _ => report.issues.push(PreflightIssue::UnexpectedStatus(status)),
+    }
+
+    let server_time = response.headers()
+        .get(reqwest::header::DATE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_http_date);
+    if let Some(server_time) = server_time {
+        let skew = clock_skew_secs(Utc::now(), server_time);
+        report.clock_skew_secs = Some(skew);
+        if skew.abs() > MAX_CLOCK_SKEW_SECS {
+            report.issues.push(PreflightIssue::ClockSkew(skew));
+        }
+    }
+    report
+}
+
+/// Parses an HTTP `Date` header ("Mon, 03 Jun 2024 02:15:00 GMT").
+pub fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(&value.replace("GMT", "+0000")).ok().map(|time| time.with_timezone(&Utc))
+}
+
+/// Local minus server time in whole seconds. HTTP dates have one-second
+/// resolution, so up to a second of this is rounding.
+pub fn clock_skew_secs(local: DateTime<Utc>, server: DateTime<Utc>) -> i64 {
+    (local - server).num_seconds()
+}
+
+/// Probes both providers concurrently and fails fast with actionable errors.
+pub async fn preflight_all(vci: &crate::VciClient, tcbs: &crate::TcbsClient) -> Result<Vec<PreflightReport>, PreflightError> {
+    let (vci, tcbs) = tokio::join!(vci.preflight(), tcbs.preflight());
+    let reports = vec![vci, tcbs];
+    if reports.iter().all(PreflightReport::is_ok) {
+        Ok(reports)
+    } else {
+        Err(PreflightError::Failed(reports.into_iter().filter(|report| !report.is_ok()).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_http_date_and_skew() {
+        let server = parse_http_date("Mon, 03 Jun 2024 02:15:00 GMT").unwrap();
+        assert_eq!(server, Utc.with_ymd_and_hms(2024, 6, 3, 2, 15, 0).unwrap());
+        assert_eq!(clock_skew_secs(server + chrono::Duration::seconds(95), server), 95);
+
+        let report = PreflightReport {
+            provider: "vci".to_string(),
+            latency: None,
+            clock_skew_secs: Some(95),
+            issues: vec![PreflightIssue::ClockSkew(95), PreflightIssue::Unauthorized],
+        };
+        let message = PreflightError::Failed(vec![report]).to_string();
+        assert!(message.starts_with("vci preflight failed"));
+        assert!(message.contains("NTP") && message.contains("token"));
+    }
+}
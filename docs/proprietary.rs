@@ -0,0 +1,146 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::models::Exchange;
+
+/// What a proprietary-trading series covers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProprietaryScope {
+    Symbol(String),
+    /// All listed symbols on one exchange.
+    Market(Exchange),
+}
+
+impl ProprietaryScope {
+    pub fn label(&self) -> String {
+        match self {
+            ProprietaryScope::Symbol(symbol) => symbol.to_uppercase(),
+            ProprietaryScope::Market(exchange) => exchange.as_str().to_string(),
+        }
+    }
+}
+
+/// One day of broker proprietary-desk ("tự doanh") trading. Values are in VND.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProprietaryTradingDay {
+    /// Symbol, or exchange name for market-wide rows.
+    pub scope: String,
+    pub date: NaiveDate,
+    pub buy_volume: u64,
+    pub buy_value: f64,
+    pub sell_volume: u64,
+    pub sell_value: f64,
+}
+
+impl ProprietaryTradingDay {
+    pub fn net_volume(&self) -> i64 {
+        self.buy_volume as i64 - self.sell_volume as i64
+    }
+
+    pub fn net_value(&self) -> f64 {
+        self.buy_value - self.sell_value
+    }
+}
+
+/// Parses a daily row in the VCI (`totalBuyTradeVolume`) or TCBS
+/// (`propBuyVolume`) spelling. When VCI splits out put-through deals
+/// (`totalDealBuyTradeVolume`), they are added to the matched figures.
+pub(crate) fn parse_proprietary_day(scope: &str, row: &Value) -> Option<ProprietaryTradingDay> {
+    let number = |keys: &[&str]| -> f64 {
+        keys.iter()
+            .filter_map(|key| {
+                let value = row.get(*key)?;
+                value.as_f64().or_else(|| value.as_str()?.replace(',', "").parse().ok())
+            })
+            .sum()
+    };
+    let has = |keys: &[&str]| keys.iter().any(|key| row.get(*key).is_some_and(|v| !v.is_null()));
+    let date_text = ["tradingDate", "dateReport", "date"].iter().find_map(|key| row.get(*key)?.as_str())?;
+    let date_part = date_text.split('T').next()?;
+    let date = NaiveDate::parse_from_str(date_part, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(date_part, "%d/%m/%Y"))
+        .ok()?;
+
+    let buy_volume = ["totalBuyTradeVolume", "totalDealBuyTradeVolume", "propBuyVolume"];
+    let sell_volume = ["totalSellTradeVolume", "totalDealSellTradeVolume", "propSellVolume"];
+    if !has(&buy_volume) && !has(&sell_volume) {
+        return None;
+    }
+    Some(ProprietaryTradingDay {
+        scope: scope.to_uppercase(),
+        date,
+        buy_volume: number(&buy_volume) as u64,
+        buy_value: number(&["totalBuyTradeValue", "totalDealBuyTradeValue", "propBuyValue"]),
+        sell_volume: number(&sell_volume) as u64,
+        sell_value: number(&["totalSellTradeValue", "totalDealSellTradeValue", "propSellValue"]),
+    })
+}
+
+/// Keeps rows within `[start, end]`, oldest first, one per date.
+pub(crate) fn clip(mut days: Vec<ProprietaryTradingDay>, start: NaiveDate, end: NaiveDate) -> Vec<ProprietaryTradingDay> {
+    days.retain(|day| day.date >= start && day.date <= end);
+    days.sort_by_key(|day| day.date);
+    days.dedup_by_key(|day| day.date);
+    days
+}
+
+/// Sums per-symbol days into one row per date under `scope`, e.g. to build
+/// a watchlist-wide series.
+pub fn aggregate(days: &[ProprietaryTradingDay], scope: &str) -> Vec<ProprietaryTradingDay> {
+    let mut totals: BTreeMap<NaiveDate, ProprietaryTradingDay> = BTreeMap::new();
+    for day in days {
+        let total = totals.entry(day.date).or_insert_with(|| ProprietaryTradingDay {
+            scope: scope.to_string(),
+            date: day.date,
+            buy_volume: 0,
+            buy_value: 0.0,
+            sell_volume: 0,
+            sell_value: 0.0,
+        });
+        total.buy_volume += day.buy_volume;
+        total.buy_value += day.buy_value;
+        total.sell_volume += day.sell_volume;
+        total.sell_value += day.sell_value;
+    }
+    totals.into_values().collect()
+}
+
+/// Symbols ranked by net proprietary value over `days`, biggest net buy first.
+pub fn top_net_buyers(days: &[ProprietaryTradingDay], limit: usize) -> Vec<(String, f64)> {
+    let mut net: BTreeMap<&str, f64> = BTreeMap::new();
+    for day in days {
+        *net.entry(day.scope.as_str()).or_default() += day.net_value();
+    }
+    let mut ranked: Vec<(String, f64)> = net.into_iter().map(|(symbol, value)| (symbol.to_string(), value)).collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(limit);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_aggregate() {
+        let rows = [
+            serde_json::json!({"tradingDate": "2024-06-04T00:00:00", "totalBuyTradeVolume": 1000, "totalDealBuyTradeVolume": 500,
+                "totalBuyTradeValue": 2.0e9, "totalSellTradeVolume": 200, "totalSellTradeValue": 4.0e8}),
+            serde_json::json!({"dateReport": "03/06/2024", "propBuyVolume": "1,000", "propBuyValue": 1.0e9, "propSellVolume": 3000, "propSellValue": 3.0e9}),
+            serde_json::json!({"tradingDate": "2024-06-05"}),
+        ];
+        let days = clip(rows.iter().filter_map(|row| parse_proprietary_day("hpg", row)).collect(), NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), NaiveDate::from_ymd_opt(2024, 6, 30).unwrap());
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].net_volume(), -2000);
+        assert_eq!(days[1].buy_volume, 1500);
+        assert_eq!(days[1].scope, "HPG");
+
+        let mut both = days.clone();
+        both.push(ProprietaryTradingDay { scope: "FPT".to_string(), ..days[1].clone() });
+        let total = aggregate(&both, "WATCHLIST");
+        assert_eq!(total[1].buy_volume, 3000);
+        assert_eq!(top_net_buyers(&both, 1), vec![("FPT".to_string(), 1.6e9)]);
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::failover::Provider;
+
+/// Attribution and terms an organization has to carry for one provider's data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataLicense {
+    /// Who the data is credited to, e.g. "Vietcap Securities".
+    pub attribution: String,
+    pub terms_url: Option<String>,
+    /// Free-form usage restriction, e.g. "Internal research only".
+    pub notice: Option<String>,
+}
+
+impl DataLicense {
+    pub fn new(attribution: &str) -> Self {
+        DataLicense { attribution: attribution.to_string(), terms_url: None, notice: None }
+    }
+
+    pub fn with_terms_url(mut self, url: &str) -> Self {
+        self.terms_url = Some(url.to_string());
+        self
+    }
+
+    pub fn with_notice(mut self, notice: &str) -> Self {
+        self.notice = Some(notice.to_string());
+        self
+    }
+}
+
+/// Per-provider licensing, defaulting to a plain attribution of each
+/// provider. Terms and notices are left to the operator, since they depend
+/// on the agreement the organization holds.
+#[derive(Debug, Clone)]
+pub struct Licensing {
+    licenses: HashMap<Provider, DataLicense>,
+}
+
+impl Default for Licensing {
+    fn default() -> Self {
+        Licensing {
+            licenses: HashMap::from([
+                (Provider::Vci, DataLicense::new("Vietcap Securities (VCI)")),
+                (Provider::Tcbs, DataLicense::new("Techcom Securities (TCBS)")),
+                (Provider::Entrade, DataLicense::new("DNSE Entrade")),
+                (Provider::Fireant, DataLicense::new("FireAnt")),
+            ]),
+        }
+    }
+}
+
+impl Licensing {
+    pub fn with(mut self, provider: Provider, license: DataLicense) -> Self {
+        self.licenses.insert(provider, license);
+        self
+    }
+
+    pub fn license(&self, provider: Provider) -> Option<&DataLicense> {
+        self.licenses.get(&provider)
+    }
+
+    /// Provenance record for data fetched from `provider` at `fetched_at`.
+    pub fn provenance(&self, provider: Provider, fetched_at: DateTime<Utc>) -> Provenance {
+        let license = self.licenses.get(&provider).cloned().unwrap_or_else(|| DataLicense::new(provider.as_str()));
+        Provenance { provider, fetched_at, license }
+    }
+
+    pub fn provenance_of(&self, source: &DataSource) -> Provenance {
+        self.provenance(source.provider, source.fetched_at)
+    }
+}
+
+/// Which provider a dataset was fetched from, and when.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataSource {
+    pub provider: Provider,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl DataSource {
+    /// Data fetched from `provider` just now.
+    pub fn now(provider: Provider) -> Self {
+        DataSource { provider, fetched_at: Utc::now() }
+    }
+}
+
+/// Where a dataset came from and under which terms, attached to exported
+/// files and served responses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub provider: Provider,
+    pub fetched_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub license: DataLicense,
+}
+
+impl Provenance {
+    /// `X-Data-*` response headers carrying this record; absent terms are
+    /// left out. Header values are ASCII-only, so the attribution is stripped
+    /// of diacritics.
+    pub fn headers(&self) -> Vec<(&'static str, String)> {
+        let ascii = |text: &str| crate::text::remove_diacritics(text).chars().filter(|ch| ch.is_ascii() && !ch.is_ascii_control()).collect::<String>();
+        let mut headers = vec![
+            ("x-data-provider", self.provider.as_str().to_string()),
+            ("x-data-attribution", ascii(&self.license.attribution)),
+            ("x-data-fetched-at", self.fetched_at.to_rfc3339()),
+        ];
+        headers.extend(self.license.terms_url.as_deref().map(|url| ("x-data-terms", ascii(url))));
+        headers.extend(self.license.notice.as_deref().map(|notice| ("x-data-notice", ascii(notice))));
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_provenance_headers_and_json() {
+        let licensing = Licensing::default().with(Provider::Tcbs, DataLicense::new("TCBS – Chứng khoán Kỹ Thương").with_notice("Internal use only"));
+        let fetched_at = Utc.with_ymd_and_hms(2024, 6, 3, 8, 0, 0).unwrap();
+        let provenance = licensing.provenance(Provider::Tcbs, fetched_at);
+
+        let headers = provenance.headers();
+        assert_eq!(headers[1], ("x-data-attribution", "TCBS  Chung khoan Ky Thuong".to_string()));
+        assert_eq!(headers.last().unwrap(), &("x-data-notice", "Internal use only".to_string()));
+        assert!(!headers.iter().any(|(name, _)| *name == "x-data-terms"));
+
+        let json = serde_json::to_value(&provenance).unwrap();
+        assert_eq!((json["provider"].as_str(), json["attribution"].as_str()), (Some("Tcbs"), Some("TCBS – Chứng khoán Kỹ Thương")));
+        assert_eq!(licensing.provenance(Provider::Vci, fetched_at).license.attribution, "Vietcap Securities (VCI)");
+    }
+}
@@ -0,0 +1,312 @@
+use chrono::NaiveDate;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+
+use crate::entrade::{EntradeClient, EntradeError};
+use crate::failover::{count_back_days, Provider};
+use crate::foreign_room::ForeignTradingDay;
+use crate::models::{Index, Interval, Language, Ohlcv};
+use crate::tcbs::{self, TcbsClient, TcbsError};
+use crate::vci::{self, CompanySection, VciClient, VciError};
+
+#[derive(Debug)]
+pub enum ProviderError {
+    Vci(VciError),
+    Tcbs(TcbsError),
+    Entrade(EntradeError),
+    /// The provider has no client behind the common trait.
+    Unsupported(Provider),
+}
+
+impl From<VciError> for ProviderError {
+    fn from(error: VciError) -> Self {
+        ProviderError::Vci(error)
+    }
+}
+
+impl From<TcbsError> for ProviderError {
+    fn from(error: TcbsError) -> Self {
+        ProviderError::Tcbs(error)
+    }
+}
+
+impl From<EntradeError> for ProviderError {
+    fn from(error: EntradeError) -> Self {
+        ProviderError::Entrade(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holder {
+    pub name: String,
+    pub percentage: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Officer {
+    pub name: String,
+    pub position: String,
+    pub percentage: Option<f64>,
+}
+
+/// Company details common to both providers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompanySummary {
+    pub symbol: String,
+    pub company_name: Option<String>,
+    pub exchange: Option<String>,
+    pub industry: Option<String>,
+    pub established_year: Option<u32>,
+    pub employees: Option<u32>,
+    pub outstanding_shares: Option<u64>,
+    pub market_cap: Option<f64>,
+    pub current_price: Option<f64>,
+    pub website: Option<String>,
+    pub profile: Option<String>,
+    pub shareholders: Vec<Holder>,
+    pub officers: Vec<Officer>,
+}
+
+impl From<vci::CompanyInfo> for CompanySummary {
+    fn from(info: vci::CompanyInfo) -> Self {
+        CompanySummary {
+            symbol: info.symbol,
+            company_name: info.company_name,
+            exchange: info.exchange,
+            industry: info.industry,
+            established_year: info.established_year,
+            employees: info.employees,
+            outstanding_shares: info.outstanding_shares,
+            market_cap: info.market_cap,
+            current_price: info.current_price,
+            website: info.website,
+            profile: info.company_profile,
+            shareholders: info.shareholders.into_iter().map(|s| Holder { name: s.name, percentage: s.percentage }).collect(),
+            officers: info.officers.into_iter().map(|o| Officer { name: o.name, position: o.position, percentage: o.percentage }).collect(),
+        }
+    }
+}
+
+impl From<tcbs::CompanyInfo> for CompanySummary {
+    fn from(info: tcbs::CompanyInfo) -> Self {
+        let overview = info.overview;
+        CompanySummary {
+            symbol: info.symbol,
+            company_name: overview.as_ref().and_then(|o| o.short_name.clone()),
+            exchange: overview.as_ref().and_then(|o| o.exchange.clone()),
+            industry: overview.as_ref().and_then(|o| o.industry.clone()),
+            established_year: overview.as_ref().and_then(|o| o.established_year),
+            employees: overview.as_ref().and_then(|o| o.no_employees),
+            // TCBS reports outstanding shares in millions
+            outstanding_shares: overview.as_ref().and_then(|o| o.outstanding_share).map(|m| (m * 1_000_000.0).round() as u64),
+            market_cap: info.market_cap,
+            current_price: info.current_price,
+            website: overview.and_then(|o| o.website),
+            profile: info.profile.and_then(|p| p.company_profile),
+            shareholders: info.shareholders.into_iter().map(|s| Holder { name: s.share_holder, percentage: s.share_own_percent }).collect(),
+            officers: info
+                .officers
+                .into_iter()
+                .map(|o| Officer { name: o.officer_name, position: o.officer_position, percentage: o.officer_own_percent })
+                .collect(),
+        }
+    }
+}
+
+/// Provider-agnostic market data access, object safe so backends can be
+/// swapped at runtime behind `Box<dyn StockDataProvider>`.
+pub trait StockDataProvider: Send + Sync {
+    fn provider(&self) -> Provider;
+
+    fn get_history<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Ohlcv>, ProviderError>>;
+
+    fn company_info<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<CompanySummary, ProviderError>>;
+
+    fn current_price<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<Option<f64>, ProviderError>>;
+
+    /// Daily foreign buy/sell flow for `[start, end]`, oldest first.
+    fn get_foreign_trading<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: &'a str,
+        end: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<ForeignTradingDay>, ProviderError>>;
+
+    /// Index history; every client maps the canonical symbol to its own code.
+    fn get_index_history<'a>(
+        &'a self,
+        index: Index,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Ohlcv>, ProviderError>> {
+        self.get_history(index.as_str(), start, end, interval)
+    }
+}
+
+impl StockDataProvider for VciClient {
+    fn provider(&self) -> Provider {
+        Provider::Vci
+    }
+
+    fn get_history<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Ohlcv>, ProviderError>> {
+        async move {
+            let bars = VciClient::get_history(self, symbol, start, end, interval).await?;
+            Ok(bars.into_iter().map(Ohlcv::from).collect())
+        }
+        .boxed()
+    }
+
+    fn company_info<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<CompanySummary, ProviderError>> {
+        async move { Ok(VciClient::company_info(self, symbol).await?.into()) }.boxed()
+    }
+
+    fn current_price<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<Option<f64>, ProviderError>> {
+        async move {
+            let info = self.company_info_with(symbol, Language::Vietnamese, &[CompanySection::Price]).await?;
+            Ok(info.current_price)
+        }
+        .boxed()
+    }
+    fn get_foreign_trading<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: &'a str,
+        end: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<ForeignTradingDay>, ProviderError>> {
+        async move { Ok(VciClient::get_foreign_trading(self, symbol, start, end).await?) }.boxed()
+    }
+}
+
+impl StockDataProvider for TcbsClient {
+    fn provider(&self) -> Provider {
+        Provider::Tcbs
+    }
+
+    fn get_history<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Ohlcv>, ProviderError>> {
+        async move {
+            let bars = TcbsClient::get_history(self, symbol, start, end, interval, count_back_days(start, end)).await?;
+            Ok(bars.into_iter().map(Ohlcv::from).collect())
+        }
+        .boxed()
+    }
+
+    fn company_info<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<CompanySummary, ProviderError>> {
+        async move { Ok(TcbsClient::company_info(self, symbol).await?.into()) }.boxed()
+    }
+
+    fn current_price<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<Option<f64>, ProviderError>> {
+        async move { Ok(self.get_current_price(symbol).await?) }.boxed()
+    }
+    fn get_foreign_trading<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: &'a str,
+        end: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<ForeignTradingDay>, ProviderError>> {
+        async move { Ok(TcbsClient::get_foreign_trading(self, symbol, start, end).await?) }.boxed()
+    }
+}
+
+/// Chart data only: company info and foreign flow are not served by Entrade.
+impl StockDataProvider for EntradeClient {
+    fn provider(&self) -> Provider {
+        Provider::Entrade
+    }
+
+    fn get_history<'a>(
+        &'a self,
+        symbol: &'a str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<Ohlcv>, ProviderError>> {
+        async move { Ok(EntradeClient::get_history(self, symbol, start, end, interval).await?) }.boxed()
+    }
+
+    fn company_info<'a>(&'a self, _symbol: &'a str) -> BoxFuture<'a, Result<CompanySummary, ProviderError>> {
+        async move { Err(EntradeError::Unsupported("company info".to_string()).into()) }.boxed()
+    }
+
+    fn current_price<'a>(&'a self, symbol: &'a str) -> BoxFuture<'a, Result<Option<f64>, ProviderError>> {
+        async move { Ok(self.latest_bar(symbol, Interval::M1).await?.map(|bar| bar.close)) }.boxed()
+    }
+    fn get_foreign_trading<'a>(
+        &'a self,
+        _symbol: &'a str,
+        _start: &'a str,
+        _end: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<Vec<ForeignTradingDay>, ProviderError>> {
+        async move { Err(EntradeError::Unsupported("foreign trading".to_string()).into()) }.boxed()
+    }
+}
+
+/// Builds the client for `provider` behind the common trait.
+pub fn connect(provider: Provider, random_agent: bool, rate_limit_per_minute: u32) -> Result<Box<dyn StockDataProvider>, ProviderError> {
+    Ok(match provider {
+        Provider::Vci => Box::new(VciClient::new(random_agent, rate_limit_per_minute)?),
+        Provider::Tcbs => Box::new(TcbsClient::new(random_agent, rate_limit_per_minute)?),
+        Provider::Entrade => Box::new(EntradeClient::new(rate_limit_per_minute)?),
+        Provider::Fireant => return Err(ProviderError::Unsupported(provider)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_and_tcbs_summary() {
+        let providers: Vec<Box<dyn StockDataProvider>> = [Provider::Vci, Provider::Tcbs, Provider::Entrade]
+            .into_iter()
+            .map(|provider| connect(provider, false, 6).unwrap())
+            .collect();
+        assert_eq!(providers.iter().map(|p| p.provider()).collect::<Vec<_>>(), vec![Provider::Vci, Provider::Tcbs, Provider::Entrade]);
+
+        let info = tcbs::CompanyInfo {
+            symbol: "FPT".to_string(),
+            overview: Some(tcbs::CompanyOverview {
+                ticker: "FPT".to_string(),
+                exchange: Some("HOSE".to_string()),
+                industry: None,
+                company_type: None,
+                no_shareholders: None,
+                foreign_percent: None,
+                outstanding_share: Some(1_270.5),
+                issue_share: None,
+                established_year: Some(1988),
+                no_employees: None,
+                stock_rating: None,
+                short_name: Some("FPT Corp".to_string()),
+                website: None,
+            }),
+            profile: None,
+            shareholders: vec![tcbs::ShareholderInfo { share_holder: "SCIC".to_string(), share_own_percent: 5.8 }],
+            officers: Vec::new(),
+            market_cap: None,
+            current_price: Some(120_000.0),
+        };
+        let summary = CompanySummary::from(info);
+        assert_eq!(summary.outstanding_shares, Some(1_270_500_000));
+        assert_eq!(summary.shareholders[0].name, "SCIC");
+    }
+}
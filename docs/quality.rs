@@ -0,0 +1,81 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::calendar;
+use crate::failover::Provider;
+use crate::models::{vietnam_offset, Ohlcv};
+
+/// Exchange-time trading date of a daily bar.
+fn trading_date(bar: &Ohlcv) -> NaiveDate {
+    bar.time.with_timezone(&vietnam_offset()).date_naive()
+}
+
+/// Trading days in `[from, to]` with no daily bar in `bars`.
+pub fn missing_trading_days(bars: &[Ohlcv], from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    let present: BTreeSet<NaiveDate> = bars.iter().map(trading_date).collect();
+    calendar::trading_days_between(from, to)
+        .into_iter()
+        .filter(|date| !present.contains(date))
+        .collect()
+}
+
+/// A daily bar tagged with the provider it came from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourcedBar {
+    pub bar: Ohlcv,
+    pub origin: Provider,
+    /// True when the bar filled a gap in the primary series.
+    pub filled: bool,
+}
+
+/// Merges `alternate` bars into `primary` for exactly the `missing` days,
+/// sorted by time. Alternate bars on other days are ignored.
+pub fn fill_gaps(primary: Vec<Ohlcv>, primary_origin: Provider, alternate: &[Ohlcv], alternate_origin: Provider, missing: &[NaiveDate]) -> Vec<SourcedBar> {
+    let missing: BTreeSet<NaiveDate> = missing.iter().copied().collect();
+    let mut merged: Vec<SourcedBar> = primary.into_iter()
+        .map(|bar| SourcedBar { bar, origin: primary_origin, filled: false })
+        .collect();
+    merged.extend(
+        alternate.iter()
+            .filter(|bar| missing.contains(&trading_date(bar)))
+            .map(|bar| SourcedBar { bar: bar.clone(), origin: alternate_origin, filled: true }),
+    );
+    merged.sort_by_key(|sourced| sourced.bar.time);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn bar(day: u32, close: f64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 6, day, 0, 0, 0).unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 100,
+            symbol: Some("FPT".to_string()),
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_missing_days_filled_from_alternate() {
+        let from = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 7).unwrap();
+        let primary = vec![bar(3, 1.0), bar(4, 1.0), bar(7, 1.0)];
+        let missing = missing_trading_days(&primary, from, to);
+        assert_eq!(missing, vec![NaiveDate::from_ymd_opt(2024, 6, 5).unwrap(), NaiveDate::from_ymd_opt(2024, 6, 6).unwrap()]);
+
+        let alternate = vec![bar(4, 2.0), bar(5, 2.0), bar(6, 2.0)];
+        let merged = fill_gaps(primary, Provider::Vci, &alternate, Provider::Tcbs, &missing);
+        assert_eq!(merged.len(), 5);
+        assert_eq!(merged.iter().filter(|sourced| sourced.filled && sourced.origin == Provider::Tcbs).count(), 2);
+        assert_eq!(merged[1].bar.close, 1.0);
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Ohlcv;
+
+/// Where a range figure came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RangeSource {
+    /// Provider-computed one-year high/low.
+    Provider,
+    /// Computed from daily bars in the local store.
+    LocalHistory,
+}
+
+/// 52-week trading range of a symbol and where the last price sits in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeStats {
+    pub symbol: String,
+    pub price: f64,
+    pub high_52w: f64,
+    pub low_52w: f64,
+    pub source: RangeSource,
+}
+
+impl RangeStats {
+    /// Percent below the 52-week high (0 at the high, negative below it).
+    pub fn pct_from_high(&self) -> Option<f64> {
+        (self.high_52w > 0.0).then(|| (self.price - self.high_52w) / self.high_52w * 100.0)
+    }
+
+    /// Percent above the 52-week low.
+    pub fn pct_above_low(&self) -> Option<f64> {
+        (self.low_52w > 0.0).then(|| (self.price - self.low_52w) / self.low_52w * 100.0)
+    }
+
+    /// Range from daily bars in the 52 weeks up to `as_of`, priced at the
+    /// latest close.
+    pub fn from_bars(symbol: &str, bars: &[Ohlcv], as_of: DateTime<Utc>) -> Option<RangeStats> {
+        let since = as_of - Duration::weeks(52);
+        let window: Vec<&Ohlcv> = bars.iter().filter(|bar| bar.time > since && bar.time <= as_of).collect();
+        let last = window.iter().max_by_key(|bar| bar.time)?;
+        Some(RangeStats {
+            symbol: symbol.to_uppercase(),
+            price: last.close,
+            high_52w: window.iter().map(|bar| bar.high).fold(f64::MIN, f64::max),
+            low_52w: window.iter().map(|bar| bar.low).fold(f64::MAX, f64::min),
+            source: RangeSource::LocalHistory,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_range_from_bars_ignores_old_history() {
+        let bar = |year: i32, month: u32, high: f64, low: f64, close: f64| Ohlcv {
+            time: Utc.with_ymd_and_hms(year, month, 3, 0, 0, 0).unwrap(),
+            open: close,
+            high,
+            low,
+            close,
+            volume: 100,
+            symbol: None,
+            breakdown: None,
+            futures: None,
+        };
+        let bars = vec![bar(2023, 1, 200.0, 10.0, 100.0), bar(2024, 2, 150.0, 90.0, 120.0), bar(2024, 5, 130.0, 80.0, 100.0)];
+        let as_of = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+
+        let stats = RangeStats::from_bars("fpt", &bars, as_of).unwrap();
+        assert_eq!((stats.high_52w, stats.low_52w, stats.price), (150.0, 80.0, 100.0));
+        assert!((stats.pct_from_high().unwrap() + 33.333).abs() < 0.01);
+        assert!((stats.pct_above_low().unwrap() - 25.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::Ohlcv;
+use crate::screener::{bars_as_of, Metric};
+use crate::valuation::PerShareFundamentals;
+
+/// Cross-sectional z-scores are clamped to this many standard deviations so
+/// one outlier cannot dominate the composite.
+const MAX_Z: f64 = 3.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Factor {
+    pub name: String,
+    pub metric: Metric,
+    pub weight: f64,
+    /// Whether a larger metric value scores better (momentum) or worse (P/E).
+    pub higher_is_better: bool,
+}
+
+impl Factor {
+    pub fn new(name: &str, metric: Metric, weight: f64, higher_is_better: bool) -> Self {
+        Factor { name: name.to_string(), metric, weight, higher_is_better }
+    }
+
+    /// Cheap on earnings and book: low P/E and P/B.
+    pub fn value(weight: f64) -> Vec<Factor> {
+        vec![Factor::new("value_pe", Metric::Pe, weight / 2.0, false), Factor::new("value_pb", Metric::Pb, weight / 2.0, false)]
+    }
+
+    /// Return over the last 120 sessions, about six months.
+    pub fn momentum(weight: f64) -> Vec<Factor> {
+        vec![Factor::new("momentum", Metric::Return { days: 120 }, weight, true)]
+    }
+
+    /// Return on equity from the latest public quarter.
+    pub fn quality(weight: f64) -> Vec<Factor> {
+        vec![Factor::new("quality", Metric::Roe, weight, true)]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedSymbol {
+    pub symbol: String,
+    /// 1-based.
+    pub rank: usize,
+    /// Weighted mean of the factor z-scores available for the symbol.
+    pub score: f64,
+    /// Each factor's share of `score`, keyed by factor name; they sum to it.
+    pub contributions: BTreeMap<String, f64>,
+    /// Raw metric values, keyed by factor name.
+    pub values: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ranking {
+    pub as_of: NaiveDate,
+    pub entries: Vec<RankedSymbol>,
+}
+
+/// Composite ranking over weighted factors. Each factor is z-scored across
+/// the universe; symbols missing a factor are scored on the rest, and need
+/// at least `min_coverage` of the total weight to be ranked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ranker {
+    pub factors: Vec<Factor>,
+    pub min_coverage: f64,
+}
+
+impl Default for Ranker {
+    fn default() -> Self {
+        Ranker { factors: Vec::new(), min_coverage: 0.5 }
+    }
+}
+
+impl Ranker {
+    pub fn new(factors: Vec<Factor>) -> Self {
+        Ranker { factors, ..Self::default() }
+    }
+
+    /// Ranks the `series` symbols as of `as_of`, ignoring later bars and
+    /// fundamentals not yet public.
+    pub fn rank(
+        &self,
+        as_of: NaiveDate,
+        series: &BTreeMap<String, Vec<Ohlcv>>,
+        fundamentals: &HashMap<String, Vec<PerShareFundamentals>>,
+    ) -> Ranking {
+        let values: BTreeMap<&str, Vec<Option<f64>>> = series
+            .iter()
+            .filter_map(|(symbol, bars)| {
+                let bars = bars_as_of(bars, as_of)?;
+                let known = fundamentals.get(symbol).map(Vec::as_slice).unwrap_or_default();
+                Some((symbol.as_str(), self.factors.iter().map(|f| f.metric.compute(bars, known)).collect()))
+            })
+            .collect();
+
+        let stats: Vec<Option<(f64, f64)>> = (0..self.factors.len())
+            .map(|i| {
+                let column: Vec<f64> = values.values().filter_map(|v| v[i]).collect();
+                if column.len() < 2 {
+                    return None;
+                }
+                let mean = column.iter().sum::<f64>() / column.len() as f64;
+                let sd = (column.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (column.len() - 1) as f64).sqrt();
+                (sd > 0.0).then_some((mean, sd))
+            })
+            .collect();
+
+        let total_weight: f64 = self.factors.iter().map(|f| f.weight.abs()).sum();
+        let mut entries: Vec<RankedSymbol> = values
+            .into_iter()
+            .filter_map(|(symbol, metric_values)| {
+                let mut weighted = Vec::new();
+                let mut raw = BTreeMap::new();
+                for ((factor, value), stat) in self.factors.iter().zip(&metric_values).zip(&stats) {
+                    let Some(value) = *value else { continue };
+                    raw.insert(factor.name.clone(), value);
+                    let Some((mean, sd)) = *stat else { continue };
+                    let z = ((value - mean) / sd).clamp(-MAX_Z, MAX_Z);
+                    weighted.push((factor, if factor.higher_is_better { z } else { -z }));
+                }
+                let covered: f64 = weighted.iter().map(|(f, _)| f.weight.abs()).sum();
+                if covered == 0.0 || covered < self.min_coverage * total_weight {
+                    return None;
+                }
+                let contributions: BTreeMap<String, f64> = weighted.iter().map(|(f, z)| (f.name.clone(), f.weight * z / covered)).collect();
+                let score = contributions.values().sum();
+                Some(RankedSymbol { symbol: symbol.to_string(), rank: 0, score, contributions, values: raw })
+            })
+            .collect();
+
+        entries.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.symbol.cmp(&b.symbol)));
+        for (i, entry) in entries.iter_mut().enumerate() {
+            entry.rank = i + 1;
+        }
+        Ranking { as_of, entries }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn bars(start: f64, end: f64) -> Vec<Ohlcv> {
+        (0..=120)
+            .map(|i| {
+                let close = start + (end - start) * i as f64 / 120.0;
+                Ohlcv {
+                    time: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::days(i),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 0,
+                    symbol: None,
+                    breakdown: None,
+                    futures: None,
+                }
+            })
+            .collect()
+    }
+
+    fn fundamentals(eps: f64, bvps: f64) -> Vec<PerShareFundamentals> {
+        vec![PerShareFundamentals {
+            period: "2023-Q3".to_string(),
+            period_end: NaiveDate::from_ymd_opt(2023, 9, 30).unwrap(),
+            available_from: NaiveDate::from_ymd_opt(2023, 11, 14).unwrap(),
+            eps: Some(eps),
+            book_value_per_share: Some(bvps),
+        }]
+    }
+
+    #[test]
+    fn test_composite_rank_and_attribution() {
+        let series = BTreeMap::from([
+            ("AAA".to_string(), bars(10.0, 20.0)),
+            ("BBB".to_string(), bars(10.0, 11.0)),
+            ("CCC".to_string(), bars(10.0, 8.0)),
+        ]);
+        let fundamentals = HashMap::from([
+            ("AAA".to_string(), fundamentals(1.0, 10.0)),
+            ("BBB".to_string(), fundamentals(2.0, 10.0)),
+            ("CCC".to_string(), fundamentals(0.5, 10.0)),
+        ]);
+        let ranker = Ranker::new([Factor::momentum(0.5), Factor::quality(0.5)].concat());
+        let ranking = ranker.rank(NaiveDate::from_ymd_opt(2024, 4, 30).unwrap(), &series, &fundamentals);
+
+        let order: Vec<&str> = ranking.entries.iter().map(|e| e.symbol.as_str()).collect();
+        assert_eq!(order, vec!["AAA", "BBB", "CCC"]);
+        let top = &ranking.entries[0];
+        assert!((top.contributions.values().sum::<f64>() - top.score).abs() < 1e-12);
+        assert!(top.contributions["momentum"] > top.contributions["quality"]);
+        assert_eq!(top.values["quality"], 0.1);
+    }
+}
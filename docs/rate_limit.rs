@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Sliding one-minute request budget. Shared behind an `Arc`, it lets several
+/// concurrent tasks (or several clients) draw from the same budget.
+pub struct RateLimiter {
+    per_minute: u32,
+    timestamps: Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        RateLimiter {
+            per_minute: per_minute.max(1),
+            timestamps: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn per_minute(&self) -> u32 {
+        self.per_minute
+    }
+
+    /// Waits until a request slot is free in the current window, then claims it.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut timestamps = self.timestamps.lock().unwrap();
+                let now = Instant::now();
+
+                // Remove timestamps older than 1 minute
+                while timestamps.front().is_some_and(|&t| now.duration_since(t) >= WINDOW) {
+                    timestamps.pop_front();
+                }
+
+                if timestamps.len() < self.per_minute as usize {
+                    timestamps.push_back(now);
+                    return;
+                }
+
+                let oldest = *timestamps.front().unwrap();
+                WINDOW.saturating_sub(now.duration_since(oldest)) + Duration::from_millis(100)
+            };
+            sleep(wait).await;
+        }
+    }
+
+    /// Requests claimed within the last minute.
+    pub fn in_flight(&self) -> usize {
+        let now = Instant::now();
+        self.timestamps.lock().unwrap()
+            .iter()
+            .filter(|&&t| now.duration_since(t) < WINDOW)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_acquire_within_budget_does_not_wait() {
+        let limiter = RateLimiter::new(3);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+        assert_eq!(limiter.in_flight(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_waits_when_exhausted() {
+        let limiter = RateLimiter::new(1);
+        limiter.acquire().await;
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::market_rules;
+use crate::models::{Exchange, Quote};
+use crate::portfolio::{FeeSchedule, Side};
+
+/// What the planner needs to price one symbol.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketPrice {
+    pub price: f64,
+    pub exchange: Exchange,
+    /// Session reference price; limits are derived from it when set,
+    /// otherwise from `price`.
+    pub reference_price: Option<f64>,
+}
+
+impl MarketPrice {
+    pub fn from_quote(quote: &Quote, exchange: Exchange) -> Self {
+        MarketPrice { price: quote.price, exchange, reference_price: quote.reference_price }
+    }
+
+    fn limits(&self) -> (f64, f64) {
+        market_rules::price_limits(self.reference_price.unwrap_or(self.price), self.exchange)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebalanceConfig {
+    /// Cash available in addition to current holdings.
+    pub cash: f64,
+    /// How far past the last price limit orders are placed, as a fraction
+    /// (buys above, sells below), clamped to the day's price band.
+    pub limit_offset: f64,
+    /// Trades smaller than this (VND) are not worth the fees and are skipped.
+    pub min_trade_value: f64,
+    pub fees: FeeSchedule,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        RebalanceConfig { cash: 0.0, limit_offset: 0.005, min_trade_value: 1_000_000.0, fees: FeeSchedule::default() }
+    }
+}
+
+/// Limit order in board lots, within the session's price band.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RebalanceOrder {
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: u64,
+    pub limit_price: f64,
+}
+
+impl RebalanceOrder {
+    pub fn value(&self) -> f64 {
+        self.quantity as f64 * self.limit_price
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RebalancePlan {
+    /// Sells first, so their proceeds fund the buys.
+    pub orders: Vec<RebalanceOrder>,
+    /// Cash left after every order fills at its limit, net of fees and tax.
+    pub cash_after: f64,
+    /// Symbols that couldn't be traded toward target, with the reason.
+    pub skipped: Vec<(String, String)>,
+}
+
+fn limit_price(market: &MarketPrice, side: Side, offset: f64) -> f64 {
+    let (floor, ceiling) = market.limits();
+    match side {
+        Side::Buy => market_rules::round_to_tick(market.price * (1.0 + offset), market.exchange).min(ceiling),
+        Side::Sell => market_rules::round_to_tick(market.price * (1.0 - offset), market.exchange).max(floor),
+    }
+}
+
+/// Splits `quantity` into orders no larger than the exchange's per-order cap.
+fn split_order(symbol: &str, side: Side, quantity: u64, limit_price: f64, exchange: Exchange) -> Vec<RebalanceOrder> {
+    let cap = market_rules::max_order_quantity(exchange).map(|cap| market_rules::round_to_lot(cap, exchange)).unwrap_or(quantity);
+    let mut orders = Vec::new();
+    let mut remaining = quantity;
+    while remaining > 0 {
+        let quantity = remaining.min(cap.max(1));
+        orders.push(RebalanceOrder { symbol: symbol.to_string(), side, quantity, limit_price });
+        remaining -= quantity;
+    }
+    orders
+}
+
+/// Orders moving `positions` toward `targets` (weights of total value,
+/// holdings plus cash; symbols absent from `targets` are sold). Quantities
+/// are whole board lots, so odd-lot remainders stay in place. Buys are
+/// filled largest shortfall first and trimmed to the cash available.
+pub fn plan(
+    targets: &HashMap<String, f64>,
+    positions: &BTreeMap<String, i64>,
+    prices: &HashMap<String, MarketPrice>,
+    config: &RebalanceConfig,
+) -> RebalancePlan {
+    let mut result = RebalancePlan::default();
+    let symbols: BTreeSet<&String> = targets.keys().chain(positions.keys()).collect();
+    let held = |symbol: &str| positions.get(symbol).copied().unwrap_or(0).max(0) as u64;
+
+    let mut total = config.cash;
+    for symbol in &symbols {
+        match prices.get(*symbol) {
+            Some(market) => total += held(symbol) as f64 * market.price,
+            None if held(symbol) > 0 || targets.get(*symbol).is_some_and(|w| *w > 0.0) => {
+                result.skipped.push(((*symbol).clone(), "no price".to_string()));
+            }
+            None => {}
+        }
+    }
+
+    let mut cash = config.cash;
+    let mut buys = Vec::new();
+    for symbol in symbols {
+        let Some(market) = prices.get(symbol) else { continue };
+        let target = targets.get(symbol).copied().unwrap_or(0.0).max(0.0) * total;
+        let delta = target - held(symbol) as f64 * market.price;
+        let side = if delta < 0.0 { Side::Sell } else { Side::Buy };
+        let mut quantity = market_rules::round_to_lot((delta.abs() / market.price) as u64, market.exchange);
+        if side == Side::Sell {
+            quantity = quantity.min(market_rules::round_to_lot(held(symbol), market.exchange));
+        }
+        if quantity == 0 || quantity as f64 * market.price < config.min_trade_value {
+            continue;
+        }
+
+        let limit = limit_price(market, side, config.limit_offset);
+        match side {
+            Side::Sell => {
+                let orders = split_order(symbol, side, quantity, limit, market.exchange);
+                for order in &orders {
+                    let gross = order.value();
+                    cash += gross - gross * (config.fees.commission_rate + config.fees.sell_tax_rate);
+                }
+                result.orders.extend(orders);
+            }
+            Side::Buy => buys.push((delta, symbol, market, quantity, limit)),
+        }
+    }
+
+    buys.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (_, symbol, market, wanted, limit) in buys {
+        let cost_per_share = limit * (1.0 + config.fees.commission_rate);
+        let affordable = market_rules::round_to_lot((cash / cost_per_share).max(0.0) as u64, market.exchange);
+        let quantity = wanted.min(affordable);
+        if quantity == 0 || quantity as f64 * market.price < config.min_trade_value {
+            result.skipped.push((symbol.clone(), "insufficient cash".to_string()));
+            continue;
+        }
+        if quantity < wanted {
+            result.skipped.push((symbol.clone(), format!("buy trimmed from {} to {} shares", wanted, quantity)));
+        }
+        cash -= quantity as f64 * cost_per_share;
+        result.orders.extend(split_order(symbol, Side::Buy, quantity, limit, market.exchange));
+    }
+
+    result.cash_after = cash;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hose(price: f64) -> MarketPrice {
+        MarketPrice { price, exchange: Exchange::Hose, reference_price: Some(price) }
+    }
+
+    #[test]
+    fn test_plan_respects_lots_and_bands() {
+        let targets = HashMap::from([("FPT".to_string(), 0.5), ("VCB".to_string(), 0.5)]);
+        let positions = BTreeMap::from([("FPT".to_string(), 1_050), ("HPG".to_string(), 250)]);
+        let prices = HashMap::from([
+            ("FPT".to_string(), hose(100_000.0)),
+            ("VCB".to_string(), hose(90_000.0)),
+            ("HPG".to_string(), hose(25_000.0)),
+        ]);
+        let config = RebalanceConfig { cash: 20_000_000.0, ..RebalanceConfig::default() };
+        let plan = plan(&targets, &positions, &prices, &config);
+
+        // Total = 105M + 6.25M + 20M = 131.25M; FPT target 65.625M -> sell 300 (lots of 100)
+        let fpt = plan.orders.iter().find(|o| o.symbol == "FPT").unwrap();
+        assert_eq!((fpt.side, fpt.quantity, fpt.limit_price), (Side::Sell, 300, 99_500.0));
+        let hpg = plan.orders.iter().find(|o| o.symbol == "HPG").unwrap();
+        assert_eq!((hpg.side, hpg.quantity), (Side::Sell, 200));
+        let vcb = plan.orders.iter().find(|o| o.symbol == "VCB").unwrap();
+        assert_eq!(vcb.side, Side::Buy);
+        assert_eq!(vcb.quantity % 100, 0);
+        assert!(vcb.limit_price <= market_rules::price_limits(90_000.0, Exchange::Hose).1);
+        assert!(plan.cash_after >= 0.0);
+        assert_eq!(plan.orders.last().unwrap().side, Side::Buy);
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use std::collections::BTreeMap;
+
+use crate::models::{vietnam_offset, Interval, Ohlcv, TickData};
+use crate::session::SessionFilter;
+
+/// Exchange-time midnight of `date`, as stamped on daily bars.
+fn exchange_midnight(date: NaiveDate) -> DateTime<Utc> {
+    vietnam_offset().from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap()).unwrap().with_timezone(&Utc)
+}
+
+/// Exchange-time midnight of the Monday starting the week that contains
+/// `time`.
+pub fn week_start(time: DateTime<Utc>) -> DateTime<Utc> {
+    let date = time.with_timezone(&vietnam_offset()).date_naive();
+    exchange_midnight(date - Duration::days(date.weekday().num_days_from_monday() as i64))
+}
+
+/// Exchange-time midnight of the first day of the month that contains
+/// `time`.
+pub fn month_start(time: DateTime<Utc>) -> DateTime<Utc> {
+    let date = time.with_timezone(&vietnam_offset()).date_naive();
+    exchange_midnight(date - Duration::days(date.day0() as i64))
+}
+
+/// Returns the bucket start function for a coarse interval ("1W" or "1M").
+pub fn period_start_fn(interval: &str) -> Option<fn(DateTime<Utc>) -> DateTime<Utc>> {
+    match interval {
+        "1W" => Some(week_start),
+        "1M" => Some(month_start),
+        _ => None,
+    }
+}
+
+/// Aggregates bars into buckets keyed by `period_start`. Input order does not
+/// matter; output is sorted by bucket start.
+pub fn aggregate(bars: &[Ohlcv], period_start: fn(DateTime<Utc>) -> DateTime<Utc>) -> Vec<Ohlcv> {
+    let mut sorted: Vec<&Ohlcv> = bars.iter().collect();
+    sorted.sort_by_key(|bar| bar.time);
+
+    let mut buckets: BTreeMap<DateTime<Utc>, Ohlcv> = BTreeMap::new();
+    for bar in sorted {
+        let start = period_start(bar.time);
+        buckets.entry(start)
+            .and_modify(|bucket| {
+                bucket.high = bucket.high.max(bar.high);
+                bucket.low = bucket.low.min(bar.low);
+                bucket.close = bar.close; // Last close
+                bucket.volume += bar.volume;
+                // A day without a split leaves the bucket's split unknown
+                bucket.breakdown = bucket.breakdown.zip(bar.breakdown).map(|(a, b)| a.combine(&b));
+                // Open interest and settlement are levels: keep the last bar's
+                if bar.futures.is_some() {
+                    bucket.futures = bar.futures;
+                }
+            })
+            .or_insert(Ohlcv {
+                time: start,
+                ..bar.clone()
+            });
+    }
+
+    buckets.into_values().collect()
+}
+
+/// Resamples daily bars to `interval`. Intervals without a coarser bucket are
+/// returned unchanged.
+pub fn resample(bars: &[Ohlcv], interval: &str) -> Vec<Ohlcv> {
+    match period_start_fn(interval) {
+        Some(period_start) => aggregate(bars, period_start),
+        None => bars.to_vec(),
+    }
+}
+
+/// Aggregates intraday bars (e.g. 1m) into `interval` buckets aligned to the
+/// hour. With a session filter, bars outside trading hours are dropped first,
+/// so the lunch break and post-close never produce bars. Returns the input
+/// unchanged for intervals without a fixed length.
+pub fn resample_intraday(bars: &[Ohlcv], interval: Interval, session: Option<&SessionFilter>) -> Vec<Ohlcv> {
+    let kept: Vec<Ohlcv> = bars.iter()
+        .filter(|bar| session.is_none_or(|filter| filter.accepts(bar.time)))
+        .cloned()
+        .collect();
+
+    let Some(secs) = interval.duration_secs() else {
+        return kept;
+    };
+
+    // Buckets are keyed per symbol so mixed-symbol input stays separated
+    let mut sorted = kept;
+    sorted.sort_by_key(|bar| bar.time);
+    let mut buckets: BTreeMap<(Option<String>, DateTime<Utc>), Ohlcv> = BTreeMap::new();
+    for bar in sorted {
+        let ts = bar.time.timestamp();
+        let start = DateTime::from_timestamp(ts - ts.rem_euclid(secs), 0).unwrap_or(bar.time);
+        buckets.entry((bar.symbol.clone(), start))
+            .and_modify(|bucket| {
+                bucket.high = bucket.high.max(bar.high);
+                bucket.low = bucket.low.min(bar.low);
+                bucket.close = bar.close;
+                bucket.volume += bar.volume;
+            })
+            .or_insert(Ohlcv {
+                time: start,
+                ..bar
+            });
+    }
+
+    let mut result: Vec<Ohlcv> = buckets.into_values().collect();
+    result.sort_by(|a, b| (a.time, &a.symbol).cmp(&(b.time, &b.symbol)));
+    result
+}
+
+/// Builds `interval` bars from matched trades, in any order, with the same
+/// bucketing and session rules as [`resample_intraday`].
+pub fn bars_from_ticks(symbol: &str, ticks: &[TickData], interval: Interval, session: Option<&SessionFilter>) -> Vec<Ohlcv> {
+    let symbol = Some(symbol.to_uppercase());
+    let prints: Vec<Ohlcv> = ticks.iter()
+        .map(|tick| Ohlcv {
+            time: tick.time,
+            open: tick.price,
+            high: tick.price,
+            low: tick.price,
+            close: tick.price,
+            volume: tick.volume,
+            symbol: symbol.clone(),
+            breakdown: None,
+            futures: None,
+        })
+        .collect();
+    resample_intraday(&prints, interval, session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::VolumeBreakdown;
+
+    fn bar(day: u32, open: f64, close: f64, volume: u64) -> Ohlcv {
+        Ohlcv {
+            // Exchange midnight, 17:00 UTC the day before, as VCI stamps them
+            time: vietnam_offset().with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap().with_timezone(&Utc),
+            open,
+            high: open.max(close) + 1.0,
+            low: open.min(close) - 1.0,
+            close,
+            volume,
+            symbol: Some("FPT".to_string()),
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_weekly_aggregation() {
+        // 2024-01-01 is a Monday
+        let bars = vec![bar(3, 12.0, 13.0, 200), bar(1, 10.0, 11.0, 100), bar(8, 20.0, 21.0, 50)];
+        let weekly = resample(&bars, "1W");
+        assert_eq!(weekly.len(), 2);
+        assert_eq!(weekly[0].open, 10.0);
+        assert_eq!(weekly[0].close, 13.0);
+        assert_eq!(weekly[0].volume, 300);
+        assert_eq!(weekly[0].high, 14.0);
+        assert_eq!(weekly[1].time, Utc.with_ymd_and_hms(2024, 1, 7, 17, 0, 0).unwrap());
+        assert_eq!(resample(&bars, "1M").len(), 1);
+    }
+
+    #[test]
+    fn test_weekly_breakdown_sums_put_through() {
+        let bars = vec![
+            bar(1, 10.0, 11.0, 100).with_breakdown(VolumeBreakdown { matched_volume: 80, matched_value: Some(880.0), put_through_volume: 20, put_through_value: Some(220.0) }),
+            bar(2, 11.0, 12.0, 200).with_breakdown(VolumeBreakdown { matched_volume: 150, matched_value: Some(1800.0), put_through_volume: 50, put_through_value: Some(600.0) }),
+        ];
+        let breakdown = resample(&bars, "1W")[0].breakdown.unwrap();
+        assert_eq!(breakdown.matched_volume, 230);
+        assert_eq!(breakdown.put_through_volume, 70);
+        assert_eq!(breakdown.matched_value, Some(2680.0));
+
+        let partial = vec![bars[0].clone(), bar(3, 12.0, 13.0, 50)];
+        assert_eq!(resample(&partial, "1W")[0].breakdown, None);
+    }
+
+    #[test]
+    fn test_resample_intraday_skips_lunch() {
+        use crate::models::Exchange;
+        // 11:20-11:29 and 13:00-13:04 exchange time, plus a stray lunch-break bar
+        let minute = |hour: u32, min: u32| Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 1, 2, hour - 7, min, 0).unwrap(),
+            ..bar(2, 10.0, 10.0, 1)
+        };
+        let mut bars: Vec<Ohlcv> = (20..30).map(|m| minute(11, m)).collect();
+        bars.push(minute(12, 10));
+        bars.extend((0..5).map(|m| minute(13, m)));
+
+        let filter = SessionFilter::new(Exchange::Hose);
+        let half_hour = resample_intraday(&bars, Interval::M30, Some(&filter));
+        assert_eq!(half_hour.len(), 2);
+        assert_eq!(half_hour[0].volume, 10);
+        assert_eq!(half_hour[1].time, Utc.with_ymd_and_hms(2024, 1, 2, 6, 0, 0).unwrap());
+        assert_eq!(resample_intraday(&bars, Interval::M30, None).len(), 3);
+    }
+
+    #[test]
+    fn test_month_start() {
+        let time = Utc.with_ymd_and_hms(2024, 2, 29, 15, 30, 0).unwrap();
+        assert_eq!(month_start(time), Utc.with_ymd_and_hms(2024, 1, 31, 17, 0, 0).unwrap());
+        // The 1 March bar, stamped 29 Feb 17:00 UTC, opens March
+        let first = Utc.with_ymd_and_hms(2024, 2, 29, 17, 0, 0).unwrap();
+        assert_eq!(month_start(first), first);
+    }
+}
@@ -0,0 +1,267 @@
+use chrono::{DateTime, Utc};
+use reqwest::{RequestBuilder, StatusCode};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::chaos::{Chaos, ChaosFault};
+use crate::circuit::CircuitBreaker;
+use crate::rate_limit::RateLimiter;
+use crate::stats::ClientStats;
+
+/// Why an attempt failed, as seen by a [`RetryPolicy`] predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryReason {
+    /// Non-success HTTP status.
+    Status(u16),
+    /// Connection, timeout or other transport failure.
+    Transport,
+    /// Success status with a body that did not parse.
+    InvalidBody,
+}
+
+/// The clients' historical rule: retry throttling (403/429), server errors,
+/// unexpected statuses and transport failures; give up on other 4xx.
+pub fn default_retry_on(reason: RetryReason) -> bool {
+    match reason {
+        RetryReason::Status(403 | 429) => true,
+        RetryReason::Status(status) => !(400..500).contains(&status),
+        RetryReason::Transport | RetryReason::InvalidBody => true,
+    }
+}
+
+/// Attempts, backoff and retry rules for a client's requests. Retry `n`
+/// (counting from 1) waits `base_delay * 2^(n-1)` plus up to `jitter`,
+/// capped at `max_delay`; a `Retry-After` header replaces the computed delay.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: Duration,
+    retry_on: Arc<dyn Fn(RetryReason) -> bool + Send + Sync>,
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            jitter: Duration::from_secs(1),
+            retry_on: Arc::new(default_retry_on),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A single attempt, no retries.
+    pub fn none() -> Self {
+        RetryPolicy { max_attempts: 1, ..Self::default() }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_backoff(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn with_retry_on(mut self, retry_on: impl Fn(RetryReason) -> bool + Send + Sync + 'static) -> Self {
+        self.retry_on = Arc::new(retry_on);
+        self
+    }
+
+    pub fn should_retry(&self, reason: RetryReason) -> bool {
+        (self.retry_on)(reason)
+    }
+
+    /// Wait before retry number `retry` (1-based).
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+        let backoff = self.base_delay.saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)));
+        let jitter = self.jitter.mul_f64(rand::random::<f64>());
+        (backoff + jitter).min(self.max_delay)
+    }
+}
+
+/// Parses a `Retry-After` value: delay seconds or an HTTP date.
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// `Retry-After` of a response, if present and valid.
+pub(crate) fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, Utc::now())
+}
+
+/// Why a request gave up; each client converts it into its own error.
+#[derive(Debug)]
+pub(crate) enum RequestError {
+    /// The endpoint's circuit breaker is open; retry after the given delay.
+    CircuitOpen { endpoint: String, retry_in: Duration },
+    /// Last non-success status. Statuses the policy does not retry are
+    /// returned after the first attempt.
+    Status(StatusCode),
+    /// Transport failure or undecodable body on the last attempt.
+    Http(reqwest::Error),
+}
+
+/// The pieces of a client every JSON request goes through: rate limit,
+/// retry policy, latency stats and, where the client has them, a circuit
+/// breaker and fault injection.
+pub(crate) struct Requester<'a> {
+    pub policy: &'a RetryPolicy,
+    pub rate_limiter: &'a RateLimiter,
+    pub stats: &'a ClientStats,
+    pub circuit_breaker: Option<&'a CircuitBreaker>,
+    pub chaos: Option<&'a Chaos>,
+}
+
+impl Requester<'_> {
+    /// Sends the request `build` makes, retrying per the policy, and decodes
+    /// the JSON body. Every attempt is recorded under `stats_key`; the
+    /// breaker for `endpoint` is consulted once and charged at most one
+    /// failure, after the last attempt.
+    pub async fn send_json(&self, endpoint: &str, stats_key: &str, build: impl Fn() -> RequestBuilder) -> Result<Value, RequestError> {
+        if let Some(breaker) = self.circuit_breaker {
+            breaker.try_acquire(endpoint).map_err(|retry_in| RequestError::CircuitOpen { endpoint: endpoint.to_string(), retry_in })?;
+        }
+
+        let max_attempts = self.policy.max_attempts.max(1);
+        let mut server_delay = None;
+        let mut attempt = 0;
+        loop {
+            self.rate_limiter.acquire().await;
+            if attempt > 0 {
+                let delay = self.policy.delay(attempt, server_delay.take());
+                tracing::info!("Retry backoff for {}: attempt {}/{}, waiting {:.1}s", endpoint, attempt + 1, max_attempts, delay.as_secs_f64());
+                tokio::time::sleep(delay).await;
+            }
+
+            let started = tokio::time::Instant::now();
+            let fault = match self.chaos {
+                Some(chaos) => chaos.inject().await,
+                None => None,
+            };
+            let (reason, error) = match fault {
+                Some(ChaosFault::ServerError(status)) => {
+                    tracing::debug!("Injected HTTP {} for {}", status, endpoint);
+                    self.stats.record(stats_key, started.elapsed(), false);
+                    let code = StatusCode::from_u16(status).unwrap_or(StatusCode::SERVICE_UNAVAILABLE);
+                    (RetryReason::Status(status), RequestError::Status(code))
+                }
+                Some(ChaosFault::Malformed(payload)) => return Ok(payload),
+                None => match build().send().await {
+                    Ok(resp) if resp.status().is_success() => match resp.json::<Value>().await {
+                        Ok(data) => {
+                            self.stats.record(stats_key, started.elapsed(), true);
+                            if let Some(breaker) = self.circuit_breaker {
+                                breaker.record_success(endpoint);
+                            }
+                            return Ok(data);
+                        }
+                        Err(e) => {
+                            self.stats.record(stats_key, started.elapsed(), false);
+                            (RetryReason::InvalidBody, RequestError::Http(e))
+                        }
+                    },
+                    Ok(resp) => {
+                        self.stats.record(stats_key, started.elapsed(), false);
+                        server_delay = retry_after(&resp);
+                        (RetryReason::Status(resp.status().as_u16()), RequestError::Status(resp.status()))
+                    }
+                    Err(e) => {
+                        self.stats.record(stats_key, started.elapsed(), false);
+                        (RetryReason::Transport, RequestError::Http(e))
+                    }
+                },
+            };
+
+            attempt += 1;
+            if attempt >= max_attempts || !self.policy.should_retry(reason) {
+                // Client errors such as 404 say nothing about the endpoint's health
+                if let Some(breaker) = self.circuit_breaker.filter(|_| default_retry_on(reason)) {
+                    breaker.record_failure(endpoint);
+                }
+                return Err(error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::default().with_jitter(Duration::ZERO).with_backoff(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(policy.delay(1, None), Duration::from_millis(100));
+        assert_eq!(policy.delay(3, None), Duration::from_millis(400));
+        assert_eq!(policy.delay(10, None), Duration::from_secs(1));
+        assert_eq!(policy.delay(1, Some(Duration::from_millis(700))), Duration::from_millis(700));
+
+        assert!(policy.should_retry(RetryReason::Status(429)));
+        assert!(policy.should_retry(RetryReason::Status(503)));
+        assert!(!policy.should_retry(RetryReason::Status(404)));
+        let strict = policy.with_retry_on(|reason| reason == RetryReason::Status(503));
+        assert!(!strict.should_retry(RetryReason::Transport));
+
+        let now = Utc.with_ymd_and_hms(2015, 10, 21, 7, 27, 0).unwrap();
+        assert_eq!(parse_retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT", now), Some(Duration::from_secs(60)));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_send_json_charges_breaker_once() {
+        let policy = RetryPolicy::default().with_max_attempts(3).with_jitter(Duration::ZERO).with_backoff(Duration::ZERO, Duration::ZERO);
+        let rate_limiter = RateLimiter::new(600);
+        let stats = ClientStats::new();
+        let breaker = CircuitBreaker::default();
+        let chaos = Chaos::new(crate::chaos::ChaosConfig { server_error_probability: 1.0, seed: Some(7), ..Default::default() });
+        let requester = Requester { policy: &policy, rate_limiter: &rate_limiter, stats: &stats, circuit_breaker: Some(&breaker), chaos: Some(&chaos) };
+        let client = reqwest::Client::new();
+
+        let result = requester.send_json("host/path", "host/path", || client.get("http://127.0.0.1:9/")).await;
+        assert!(matches!(result, Err(RequestError::Status(StatusCode::SERVICE_UNAVAILABLE))));
+        assert_eq!(stats.snapshot()["host/path"].count, 3);
+        assert_eq!(breaker.consecutive_failures("host/path"), 1);
+
+        let strict = policy.clone().with_retry_on(|_| false);
+        let requester = Requester { policy: &strict, ..requester };
+        let result = requester.send_json("host/other", "host/other", || client.get("http://127.0.0.1:9/")).await;
+        assert!(matches!(result, Err(RequestError::Status(_))));
+        assert_eq!(stats.snapshot()["host/other"].count, 1);
+    }
+}
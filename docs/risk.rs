@@ -0,0 +1,217 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::metadata::SymbolMetadata;
+use crate::models::Ohlcv;
+use crate::portfolio::Portfolio;
+
+/// Bucket for holdings whose classification is unknown.
+const UNCLASSIFIED: &str = "Unclassified";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holding {
+    pub symbol: String,
+    pub quantity: u64,
+    pub price: f64,
+    pub value: f64,
+    /// Share of total portfolio market value.
+    pub weight: f64,
+}
+
+/// Marks long positions to `prices`. Symbols without a price are left out.
+pub fn holdings(positions: &BTreeMap<String, i64>, prices: &HashMap<String, f64>) -> Vec<Holding> {
+    let mut holdings: Vec<Holding> = positions
+        .iter()
+        .filter(|(_, &qty)| qty > 0)
+        .filter_map(|(symbol, &qty)| {
+            let price = *prices.get(symbol)?;
+            Some(Holding { symbol: symbol.clone(), quantity: qty as u64, price, value: qty as f64 * price, weight: 0.0 })
+        })
+        .collect();
+    let total: f64 = holdings.iter().map(|h| h.value).sum();
+    for holding in &mut holdings {
+        holding.weight = if total > 0.0 { holding.value / total } else { 0.0 };
+    }
+    holdings
+}
+
+/// Summed weights per group returned by `key`.
+pub fn exposure_by(holdings: &[Holding], key: impl Fn(&Holding) -> Option<String>) -> BTreeMap<String, f64> {
+    let mut exposure = BTreeMap::new();
+    for holding in holdings {
+        let group = key(holding).unwrap_or_else(|| UNCLASSIFIED.to_string());
+        *exposure.entry(group).or_insert(0.0) += holding.weight;
+    }
+    exposure
+}
+
+pub fn sector_exposure(holdings: &[Holding], metadata: &HashMap<String, SymbolMetadata>) -> BTreeMap<String, f64> {
+    exposure_by(holdings, |h| metadata.get(&h.symbol)?.industry.clone())
+}
+
+pub fn exchange_exposure(holdings: &[Holding], metadata: &HashMap<String, SymbolMetadata>) -> BTreeMap<String, f64> {
+    exposure_by(holdings, |h| Some(metadata.get(&h.symbol)?.exchange?.as_str().to_string()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Concentration {
+    /// Herfindahl index of weights: 1.0 for a single holding.
+    pub hhi: f64,
+    /// Number of equally weighted holdings with the same HHI.
+    pub effective_holdings: f64,
+    pub top_weight: f64,
+    pub top5_weight: f64,
+}
+
+pub fn concentration(holdings: &[Holding]) -> Option<Concentration> {
+    let mut weights: Vec<f64> = holdings.iter().map(|h| h.weight).filter(|w| *w > 0.0).collect();
+    if weights.is_empty() {
+        return None;
+    }
+    weights.sort_by(|a, b| b.total_cmp(a));
+    let hhi: f64 = weights.iter().map(|w| w * w).sum();
+    Some(Concentration {
+        hhi,
+        effective_holdings: 1.0 / hhi,
+        top_weight: weights[0],
+        top5_weight: weights.iter().take(5).sum(),
+    })
+}
+
+/// Close-to-close simple returns keyed by the later bar's date.
+pub fn daily_returns(bars: &[Ohlcv]) -> BTreeMap<NaiveDate, f64> {
+    bars.windows(2)
+        .filter(|pair| pair[0].close > 0.0)
+        .map(|pair| (pair[1].time.date_naive(), pair[1].close / pair[0].close - 1.0))
+        .collect()
+}
+
+/// Regression beta of `asset` on `index` over the dates both have.
+pub fn beta(asset: &BTreeMap<NaiveDate, f64>, index: &BTreeMap<NaiveDate, f64>) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = asset.iter().filter_map(|(date, a)| Some((*a, *index.get(date)?))).collect();
+    if pairs.len() < 2 {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let (mean_a, mean_i) = (pairs.iter().map(|p| p.0).sum::<f64>() / n, pairs.iter().map(|p| p.1).sum::<f64>() / n);
+    let covariance: f64 = pairs.iter().map(|(a, i)| (a - mean_a) * (i - mean_i)).sum();
+    let variance: f64 = pairs.iter().map(|(_, i)| (i - mean_i).powi(2)).sum();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+/// One-day historical VaR in VND at `confidence` (e.g. 0.95): the loss the
+/// current holdings would have exceeded on only `1 - confidence` of past
+/// days. Only dates where every holding has a return are used.
+pub fn historical_var(holdings: &[Holding], history: &HashMap<String, Vec<Ohlcv>>, confidence: f64) -> Option<f64> {
+    let returns: Vec<(f64, BTreeMap<NaiveDate, f64>)> = holdings
+        .iter()
+        .map(|h| Some((h.value, daily_returns(history.get(&h.symbol)?))))
+        .collect::<Option<_>>()?;
+    let (_, first) = returns.first()?;
+    let mut pnl: Vec<f64> = first
+        .keys()
+        .filter_map(|date| returns.iter().map(|(value, r)| Some(value * r.get(date)?)).sum::<Option<f64>>())
+        .collect();
+    if pnl.is_empty() {
+        return None;
+    }
+    pnl.sort_by(|a, b| a.total_cmp(b));
+    let index = (((1.0 - confidence) * pnl.len() as f64).floor() as usize).min(pnl.len() - 1);
+    Some((-pnl[index]).max(0.0))
+}
+
+/// Market value expressed in VNINDEX-equivalent VND: each holding's value
+/// times its beta. Holdings with no usable beta count at beta 1.
+pub fn beta_weighted_exposure(holdings: &[Holding], history: &HashMap<String, Vec<Ohlcv>>, index_bars: &[Ohlcv]) -> f64 {
+    let index = daily_returns(index_bars);
+    holdings
+        .iter()
+        .map(|h| h.value * history.get(&h.symbol).and_then(|bars| beta(&daily_returns(bars), &index)).unwrap_or(1.0))
+        .sum()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskReport {
+    pub market_value: f64,
+    pub holdings: Vec<Holding>,
+    pub var_95: Option<f64>,
+    pub var_99: Option<f64>,
+    pub by_sector: BTreeMap<String, f64>,
+    pub by_exchange: BTreeMap<String, f64>,
+    pub concentration: Option<Concentration>,
+    pub beta_weighted_exposure: f64,
+}
+
+impl RiskReport {
+    /// `history` holds daily bars per symbol and `index_bars` daily VNINDEX
+    /// bars over the same lookback.
+    pub fn build(
+        portfolio: &Portfolio,
+        prices: &HashMap<String, f64>,
+        metadata: &HashMap<String, SymbolMetadata>,
+        history: &HashMap<String, Vec<Ohlcv>>,
+        index_bars: &[Ohlcv],
+    ) -> Self {
+        let holdings = holdings(&portfolio.positions(), prices);
+        RiskReport {
+            market_value: holdings.iter().map(|h| h.value).sum(),
+            var_95: historical_var(&holdings, history, 0.95),
+            var_99: historical_var(&holdings, history, 0.99),
+            by_sector: sector_exposure(&holdings, metadata),
+            by_exchange: exchange_exposure(&holdings, metadata),
+            concentration: concentration(&holdings),
+            beta_weighted_exposure: beta_weighted_exposure(&holdings, history, index_bars),
+            holdings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn series(closes: &[f64]) -> Vec<Ohlcv> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| Ohlcv {
+                time: Utc.with_ymd_and_hms(2024, 3, 1 + i as u32, 0, 0, 0).unwrap(),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 0,
+                symbol: None,
+                breakdown: None,
+                futures: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_risk_metrics() {
+        let positions = BTreeMap::from([("FPT".to_string(), 300), ("VCB".to_string(), 100), ("HPG".to_string(), 0)]);
+        let prices = HashMap::from([("FPT".to_string(), 100.0), ("VCB".to_string(), 100.0)]);
+        let holdings = holdings(&positions, &prices);
+        assert_eq!(holdings.len(), 2);
+        let c = concentration(&holdings).unwrap();
+        assert!((c.hhi - 0.625).abs() < 1e-9);
+        assert_eq!(c.top_weight, 0.75);
+
+        let index = series(&[100.0, 101.0, 99.0, 102.0, 100.0]);
+        let fpt = series(&[100.0, 102.0, 98.0, 104.0, 100.0]);
+        let vcb = series(&[100.0, 100.0, 100.0, 100.0, 100.0]);
+        let beta_fpt = beta(&daily_returns(&fpt), &daily_returns(&index)).unwrap();
+        assert!(beta_fpt > 1.9 && beta_fpt < 2.1);
+
+        let history = HashMap::from([("FPT".to_string(), fpt), ("VCB".to_string(), vcb)]);
+        let var = historical_var(&holdings, &history, 0.95).unwrap();
+        // Worst day: FPT 102 -> 98 on a 30,000 VND position
+        assert!((var - 30_000.0 * (1.0 - 98.0 / 102.0)).abs() < 1e-6);
+        let exposure = beta_weighted_exposure(&holdings, &history, &index);
+        assert!((exposure - 30_000.0 * beta_fpt).abs() < 1e-6);
+        assert_eq!(sector_exposure(&holdings, &HashMap::new())[UNCLASSIFIED], 1.0);
+    }
+}
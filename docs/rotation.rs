@@ -0,0 +1,220 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::{vietnam_offset, Index, Ohlcv};
+use crate::provider::{ProviderError, StockDataProvider};
+
+/// Smoothing windows, in bars. With daily bars the defaults approximate the
+/// usual 10-week RS-Ratio and 1-week momentum lookback.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RotationConfig {
+    /// SMA window the relative strength is normalized against.
+    pub ratio_period: usize,
+    /// Bars back RS-Ratio is compared with for RS-Momentum.
+    pub momentum_period: usize,
+}
+
+impl Default for RotationConfig {
+    fn default() -> Self {
+        RotationConfig { ratio_period: 50, momentum_period: 5 }
+    }
+}
+
+/// RRG quadrant, clockwise from top right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Quadrant {
+    Leading,
+    Weakening,
+    Lagging,
+    Improving,
+}
+
+impl Quadrant {
+    pub fn classify(rs_ratio: f64, rs_momentum: f64) -> Self {
+        match (rs_ratio >= 100.0, rs_momentum >= 100.0) {
+            (true, true) => Quadrant::Leading,
+            (true, false) => Quadrant::Weakening,
+            (false, false) => Quadrant::Lagging,
+            (false, true) => Quadrant::Improving,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RotationPoint {
+    pub date: NaiveDate,
+    /// Relative strength versus its own recent average; above 100 means
+    /// outperforming the benchmark by more than usual.
+    pub rs_ratio: f64,
+    /// Rate of change of RS-Ratio; above 100 means relative strength is rising.
+    pub rs_momentum: f64,
+    pub quadrant: Quadrant,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RotationSeries {
+    pub symbol: String,
+    pub points: Vec<RotationPoint>,
+}
+
+impl RotationSeries {
+    pub fn latest(&self) -> Option<&RotationPoint> {
+        self.points.last()
+    }
+
+    /// The last `n` points, as drawn for an RRG tail.
+    pub fn tail(&self, n: usize) -> &[RotationPoint] {
+        &self.points[self.points.len().saturating_sub(n)..]
+    }
+}
+
+/// RS-Ratio and RS-Momentum of `bars` against `benchmark`, on the dates both
+/// have a close. RS is `100 * close / benchmark`; RS-Ratio is RS over its
+/// `ratio_period` SMA and RS-Momentum is RS-Ratio over its value
+/// `momentum_period` bars earlier, both scaled to 100.
+pub fn rotation(symbol: &str, bars: &[Ohlcv], benchmark: &[Ohlcv], config: &RotationConfig) -> RotationSeries {
+    let benchmark: HashMap<NaiveDate, f64> = benchmark.iter().map(|bar| (bar.time.with_timezone(&vietnam_offset()).date_naive(), bar.close)).collect();
+    let rs: Vec<(NaiveDate, f64)> = bars.iter()
+        .filter_map(|bar| {
+            let date = bar.time.with_timezone(&vietnam_offset()).date_naive();
+            let base = benchmark.get(&date).filter(|close| **close > 0.0)?;
+            Some((date, 100.0 * bar.close / base))
+        })
+        .collect();
+
+    let period = config.ratio_period.max(1);
+    let ratios: Vec<(NaiveDate, f64)> = rs.windows(period)
+        .filter_map(|window| {
+            let mean = window.iter().map(|(_, value)| value).sum::<f64>() / period as f64;
+            let (date, value) = window[period - 1];
+            (mean > 0.0).then(|| (date, 100.0 * value / mean))
+        })
+        .collect();
+
+    let lag = config.momentum_period.max(1);
+    let points = ratios.iter()
+        .skip(lag)
+        .zip(&ratios)
+        .map(|(&(date, rs_ratio), &(_, earlier))| {
+            let rs_momentum = 100.0 * rs_ratio / earlier;
+            RotationPoint { date, rs_ratio, rs_momentum, quadrant: Quadrant::classify(rs_ratio, rs_momentum) }
+        })
+        .collect();
+    RotationSeries { symbol: symbol.to_uppercase(), points }
+}
+
+/// Rotation for every series against one benchmark, keyed like the input.
+pub fn rotation_all(series: &BTreeMap<String, Vec<Ohlcv>>, benchmark: &[Ohlcv], config: &RotationConfig) -> Vec<RotationSeries> {
+    series.iter().map(|(symbol, bars)| rotation(symbol, bars, benchmark, config)).collect()
+}
+
+/// Equal-weight basket of `members` rebased to 100, as a close-only daily
+/// series on the dates every member traded. Use it to place a sector on
+/// the chart next to single symbols.
+pub fn basket(name: &str, members: &[&[Ohlcv]]) -> Vec<Ohlcv> {
+    let closes: Vec<BTreeMap<NaiveDate, (chrono::DateTime<chrono::Utc>, f64)>> = members.iter()
+        .map(|bars| bars.iter().map(|bar| (bar.time.with_timezone(&vietnam_offset()).date_naive(), (bar.time, bar.close))).collect())
+        .collect();
+    let Some((first, rest)) = closes.split_first() else { return Vec::new() };
+    let dates: Vec<NaiveDate> = first.keys().filter(|date| rest.iter().all(|member| member.contains_key(date))).copied().collect();
+    let Some(base_date) = dates.first() else { return Vec::new() };
+    let bases: Vec<f64> = closes.iter().map(|member| member[base_date].1).collect();
+    if bases.iter().any(|base| *base <= 0.0) {
+        return Vec::new();
+    }
+
+    dates.iter()
+        .map(|date| {
+            let level = closes.iter().zip(&bases).map(|(member, base)| member[date].1 / base).sum::<f64>() * 100.0 / bases.len() as f64;
+            Ohlcv {
+                time: first[date].0,
+                open: level,
+                high: level,
+                low: level,
+                close: level,
+                volume: 0,
+                symbol: Some(name.to_uppercase()),
+                breakdown: None,
+                futures: None,
+            }
+        })
+        .collect()
+}
+
+/// Fetches daily bars for `symbols` and VNINDEX over `[start, end]` and
+/// returns their rotation. Leave enough history before the dates of
+/// interest for the ratio and momentum windows to fill.
+pub async fn rotation_vs_vnindex(
+    provider: &dyn StockDataProvider,
+    symbols: &[&str],
+    start: NaiveDate,
+    end: Option<NaiveDate>,
+    config: &RotationConfig,
+) -> Result<Vec<RotationSeries>, ProviderError> {
+    let benchmark = provider.get_index_history(Index::VnIndex, start, end, "1D").await?;
+    let mut series = BTreeMap::new();
+    for symbol in symbols {
+        series.insert(symbol.to_uppercase(), provider.get_history(symbol, start, end, "1D").await?);
+    }
+    Ok(rotation_all(&series, &benchmark, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn series(closes: impl Iterator<Item = f64>) -> Vec<Ohlcv> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes.enumerate()
+            .map(|(i, close)| Ohlcv {
+                time: start + Duration::days(i as i64),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 0,
+                symbol: None,
+                breakdown: None,
+                futures: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rotation_quadrants() {
+        let benchmark = series((0..40).map(|_| 1000.0));
+        // Outperforms at an accelerating pace, then falls 5% a day.
+        let peak = 10.0 * (0.002 * 29.0 * 29.0_f64).exp();
+        let strong = series((0..40).map(|i| if i < 30 { 10.0 * (0.002 * (i * i) as f64).exp() } else { peak * 0.95_f64.powi(i - 29) }));
+        let config = RotationConfig { ratio_period: 10, momentum_period: 3 };
+        let result = rotation("hpg", &strong, &benchmark, &config);
+        assert_eq!(result.symbol, "HPG");
+        assert_eq!(result.points.len(), 40 - 9 - 3);
+        assert_eq!(result.points[10].quadrant, Quadrant::Leading);
+        assert_eq!(result.points[19].quadrant, Quadrant::Weakening);
+        assert_eq!(result.latest().map(|p| p.quadrant), Some(Quadrant::Lagging));
+        assert_eq!(result.tail(4).len(), 4);
+
+        let flat = rotation("vnm", &series((0..40).map(|_| 50.0)), &benchmark, &config);
+        assert!(flat.points.iter().all(|p| (p.rs_ratio - 100.0).abs() < 1e-9 && (p.rs_momentum - 100.0).abs() < 1e-9));
+
+        // Bars stamped at exchange midnight (17:00 UTC the day before) pair
+        // with benchmark bars stamped during the same exchange day
+        let midnight: Vec<Ohlcv> = series((0..40).map(|_| 50.0)).into_iter().map(|bar| Ohlcv { time: bar.time - Duration::hours(7), ..bar }).collect();
+        let intraday: Vec<Ohlcv> = benchmark.iter().cloned().map(|bar| Ohlcv { time: bar.time + Duration::hours(3), ..bar }).collect();
+        let aligned = rotation("vnm", &midnight, &intraday, &config);
+        assert_eq!(aligned.points.len(), 40 - 9 - 3);
+        assert_eq!(aligned.points[0].date, NaiveDate::from_ymd_opt(2024, 1, 13).unwrap());
+    }
+
+    #[test]
+    fn test_basket_rebases_members() {
+        let a = series([10.0, 11.0, 12.0].into_iter());
+        let b = series([100.0, 100.0, 90.0].into_iter());
+        let index = basket("steel", &[&a, &b]);
+        assert_eq!(index.iter().map(|bar| bar.close).collect::<Vec<_>>(), vec![100.0, 105.0, 105.0]);
+        assert_eq!(index[0].symbol.as_deref(), Some("STEEL"));
+    }
+}
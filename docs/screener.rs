@@ -0,0 +1,361 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::events::Event;
+use crate::models::{vietnam_offset, Ohlcv};
+use crate::store::{LocalStore, StoreError};
+use crate::universe::{Universe, UniverseFilter};
+use crate::valuation::PerShareFundamentals;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Metric {
+    Close,
+    /// Close / trailing EPS of the latest quarter public on the date.
+    Pe,
+    /// Close / book value per share of the latest quarter public on the date.
+    Pb,
+    /// Close-to-close return over `days` sessions.
+    Return { days: usize },
+    AverageVolume { days: usize },
+    /// Mean of close x volume over `days` sessions.
+    AverageTradedValue { days: usize },
+    /// Close relative to the highest high of `days` sessions, minus one.
+    FromHigh { days: usize },
+    /// Trailing EPS / book value per share of the latest public quarter.
+    Roe,
+}
+
+impl Metric {
+    pub fn label(&self) -> String {
+        match self {
+            Metric::Close => "close".to_string(),
+            Metric::Pe => "pe".to_string(),
+            Metric::Pb => "pb".to_string(),
+            Metric::Return { days } => format!("return_{}d", days),
+            Metric::AverageVolume { days } => format!("avg_volume_{}d", days),
+            Metric::AverageTradedValue { days } => format!("avg_value_{}d", days),
+            Metric::FromHigh { days } => format!("from_high_{}d", days),
+            Metric::Roe => "roe".to_string(),
+        }
+    }
+
+    /// Value on the last bar of `bars` (oldest first), using only figures
+    /// public by that bar's date.
+    pub fn compute(&self, bars: &[Ohlcv], fundamentals: &[PerShareFundamentals]) -> Option<f64> {
+        let last = bars.last()?;
+        let window = |days: usize| (days > 0 && bars.len() >= days).then(|| &bars[bars.len() - days..]);
+        let date = last.time.with_timezone(&vietnam_offset()).date_naive();
+        let latest = fundamentals.iter().filter(|f| f.available_from <= date).max_by_key(|f| f.available_from);
+        let ratio = |denominator: fn(&PerShareFundamentals) -> Option<f64>| {
+            denominator(latest?).filter(|d| *d > 0.0).map(|d| last.close / d)
+        };
+        match *self {
+            Metric::Close => Some(last.close),
+            Metric::Pe => ratio(|f| f.eps),
+            Metric::Pb => ratio(|f| f.book_value_per_share),
+            Metric::Return { days } => {
+                let base = bars.len().checked_sub(days + 1).map(|i| bars[i].close)?;
+                (base > 0.0).then(|| last.close / base - 1.0)
+            }
+            Metric::AverageVolume { days } => window(days).map(|w| w.iter().map(|b| b.volume as f64).sum::<f64>() / days as f64),
+            Metric::AverageTradedValue { days } => window(days).map(|w| w.iter().map(|b| b.close * b.volume as f64).sum::<f64>() / days as f64),
+            Metric::FromHigh { days } => {
+                let high = window(days)?.iter().map(|b| b.high).fold(f64::MIN, f64::max);
+                (high > 0.0).then(|| last.close / high - 1.0)
+            }
+            Metric::Roe => {
+                let latest = latest?;
+                latest.book_value_per_share.filter(|b| *b > 0.0).and_then(|b| Some(latest.eps? / b))
+            }
+        }
+    }
+}
+
+/// Bars up to and including `as_of`, or `None` when the symbol has not
+/// traded within ten days before it (not yet listed, suspended).
+pub(crate) fn bars_as_of(bars: &[Ohlcv], as_of: NaiveDate) -> Option<&[Ohlcv]> {
+    let cut = bars.partition_point(|bar| bar.time.with_timezone(&vietnam_offset()).date_naive() <= as_of);
+    let last = bars[..cut].last()?.time.with_timezone(&vietnam_offset()).date_naive();
+    ((as_of - last).num_days() <= 10).then_some(&bars[..cut])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+    Above(f64),
+    Below(f64),
+    Between(f64, f64),
+}
+
+impl Comparison {
+    pub fn matches(&self, value: f64) -> bool {
+        match *self {
+            Comparison::Above(threshold) => value > threshold,
+            Comparison::Below(threshold) => value < threshold,
+            Comparison::Between(low, high) => (low..=high).contains(&value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Filter {
+    pub metric: Metric,
+    pub comparison: Comparison,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenMatch {
+    pub symbol: String,
+    /// Every filter and sort metric, keyed by [`Metric::label`].
+    pub values: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenResult {
+    pub as_of: NaiveDate,
+    pub matches: Vec<ScreenMatch>,
+}
+
+impl ScreenResult {
+    pub fn symbols(&self) -> Vec<&str> {
+        self.matches.iter().map(|m| m.symbol.as_str()).collect()
+    }
+
+    /// Symbols that entered or left the screen since `previous`.
+    pub fn diff(&self, previous: &ScreenResult) -> ScreenDiff {
+        let before: BTreeSet<&str> = previous.symbols().into_iter().collect();
+        let now: BTreeSet<&str> = self.symbols().into_iter().collect();
+        ScreenDiff {
+            from: previous.as_of,
+            to: self.as_of,
+            entered: self.matches.iter().filter(|m| !before.contains(m.symbol.as_str())).cloned().collect(),
+            exited: previous.matches.iter().filter(|m| !now.contains(m.symbol.as_str())).cloned().collect(),
+            retained: self.matches.iter().filter(|m| before.contains(m.symbol.as_str())).map(|m| m.symbol.clone()).collect(),
+        }
+    }
+}
+
+/// Change between two screen runs. `entered` carries the current values,
+/// `exited` the values from the previous run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenDiff {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub entered: Vec<ScreenMatch>,
+    pub exited: Vec<ScreenMatch>,
+    pub retained: Vec<String>,
+}
+
+impl ScreenDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entered.is_empty() && self.exited.is_empty()
+    }
+
+    /// One [`Event::AlertFired`] per entry or exit, named after the screen,
+    /// ready to publish on an [`crate::events::EventBus`].
+    pub fn to_events(&self, screen: &str) -> Vec<Event> {
+        let time = Utc::now();
+        let alert = |m: &ScreenMatch, verb: &str| Event::AlertFired {
+            name: screen.to_string(),
+            symbol: m.symbol.clone(),
+            message: format!("{} {} screen '{}' on {}", m.symbol, verb, screen, self.to),
+            time,
+        };
+        self.entered.iter().map(|m| alert(m, "entered")).chain(self.exited.iter().map(|m| alert(m, "exited"))).collect()
+    }
+}
+
+/// Client-side screener: symbols pass when every filter holds. A symbol
+/// missing a metric (too little history, no fundamentals yet) fails the
+/// filter on it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Screener {
+    pub filters: Vec<Filter>,
+    /// Sort metric, descending when the flag is set.
+    pub sort_by: Option<(Metric, bool)>,
+    /// Maximum matches kept after sorting; `0` keeps all.
+    pub limit: usize,
+}
+
+impl Screener {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn filter(mut self, metric: Metric, comparison: Comparison) -> Self {
+        self.filters.push(Filter { metric, comparison });
+        self
+    }
+
+    pub fn sort_by(mut self, metric: Metric, descending: bool) -> Self {
+        self.sort_by = Some((metric, descending));
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Evaluates one symbol on the last bar of `bars`.
+    pub fn evaluate(&self, symbol: &str, bars: &[Ohlcv], fundamentals: &[PerShareFundamentals]) -> Option<ScreenMatch> {
+        let mut values = BTreeMap::new();
+        for filter in &self.filters {
+            let value = filter.metric.compute(bars, fundamentals)?;
+            if !filter.comparison.matches(value) {
+                return None;
+            }
+            values.insert(filter.metric.label(), value);
+        }
+        if let Some((metric, _)) = self.sort_by {
+            if let Some(value) = metric.compute(bars, fundamentals) {
+                values.insert(metric.label(), value);
+            }
+        }
+        Some(ScreenMatch { symbol: symbol.to_uppercase(), values })
+    }
+
+    /// Screens in-memory series, cutting each at `as_of` so later bars are
+    /// never seen.
+    pub fn run_on(
+        &self,
+        as_of: NaiveDate,
+        series: &BTreeMap<String, Vec<Ohlcv>>,
+        fundamentals: &HashMap<String, Vec<PerShareFundamentals>>,
+    ) -> ScreenResult {
+        let mut matches: Vec<ScreenMatch> = series
+            .iter()
+            .filter_map(|(symbol, bars)| {
+                let known = fundamentals.get(symbol).map(Vec::as_slice).unwrap_or_default();
+                self.evaluate(symbol, bars_as_of(bars, as_of)?, known)
+            })
+            .collect();
+
+        if let Some((metric, descending)) = self.sort_by {
+            let label = metric.label();
+            let key = |m: &ScreenMatch| m.values.get(&label).copied().filter(|value| !value.is_nan());
+            // Symbols missing the metric go last in either direction
+            matches.sort_by(|a, b| match (key(a), key(b)) {
+                (Some(a), Some(b)) if descending => b.total_cmp(&a),
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            });
+        }
+        if self.limit > 0 {
+            matches.truncate(self.limit);
+        }
+        ScreenResult { as_of, matches }
+    }
+
+    /// Point-in-time screen on `as_of` from stored daily bars. Bars are taken
+    /// from the store's journal as known at that day's close when one exists,
+    /// so later restatements don't leak in; the symbol list comes from
+    /// `universe` as of the date when given, else from `symbols`.
+    pub fn run_as_of(
+        &self,
+        as_of: NaiveDate,
+        store: &LocalStore,
+        symbols: &[String],
+        universe: Option<(&Universe, &UniverseFilter)>,
+        fundamentals: &HashMap<String, Vec<PerShareFundamentals>>,
+    ) -> Result<ScreenResult, StoreError> {
+        let symbols = match universe {
+            Some((universe, filter)) => universe.universe_as_of(as_of, filter),
+            None => symbols.iter().map(|s| s.to_uppercase()).collect(),
+        };
+        let known_at: DateTime<Utc> = vietnam_offset()
+            .from_local_datetime(&as_of.and_hms_opt(23, 59, 59).unwrap())
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let mut series = BTreeMap::new();
+        for symbol in symbols {
+            let bars = if store.journal_path(&symbol).exists() { store.as_of(&symbol, known_at)? } else { store.read(&symbol, "1D")? };
+            series.insert(symbol, bars);
+        }
+        Ok(self.run_on(as_of, &series, fundamentals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn bars(closes: &[f64]) -> Vec<Ohlcv> {
+        let start = Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| Ohlcv {
+                time: start + Duration::days(i as i64),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 1_000,
+                symbol: None,
+                breakdown: None,
+                futures: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_run_as_of_ignores_later_data() {
+        let series = BTreeMap::from([
+            ("HPG".to_string(), bars(&[20.0, 21.0, 22.0, 30.0])),
+            ("HSG".to_string(), bars(&[20.0, 19.0, 18.0, 17.0])),
+        ]);
+        let fundamentals = HashMap::from([(
+            "HPG".to_string(),
+            vec![PerShareFundamentals {
+                period: "2024-Q1".to_string(),
+                period_end: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                available_from: NaiveDate::from_ymd_opt(2024, 5, 15).unwrap(),
+                eps: Some(2.0),
+                book_value_per_share: None,
+            }],
+        )]);
+        let screener = Screener::new().filter(Metric::Return { days: 2 }, Comparison::Above(0.0)).sort_by(Metric::Pe, true);
+
+        // On the 5th HPG is up 10% over two sessions; the 30.0 close is in the future
+        let result = screener.run_on(NaiveDate::from_ymd_opt(2024, 6, 5).unwrap(), &series, &fundamentals);
+        assert_eq!(result.symbols(), vec!["HPG"]);
+        assert_eq!(result.matches[0].values["pe"], 11.0);
+        assert!((result.matches[0].values["return_2d"] - 0.1).abs() < 1e-9);
+
+        // Before any bar exists nothing matches
+        assert!(screener.run_on(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(), &series, &fundamentals).matches.is_empty());
+
+        // On the 4th neither symbol had two sessions of history yet
+        let previous = screener.run_on(NaiveDate::from_ymd_opt(2024, 6, 4).unwrap(), &series, &fundamentals);
+        let diff = result.diff(&previous);
+        assert_eq!(diff.entered[0].symbol, "HPG");
+        assert!(diff.exited.is_empty() && diff.retained.is_empty());
+        let events = diff.to_events("momentum");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].symbol(), Some("HPG"));
+        assert!(previous.diff(&result).exited.iter().any(|m| m.symbol == "HPG"));
+    }
+
+    #[test]
+    fn test_missing_sort_metric_sorts_last() {
+        let series = BTreeMap::from([("AAA".to_string(), bars(&[20.0])), ("HPG".to_string(), bars(&[22.0]))]);
+        let fundamentals = HashMap::from([(
+            "HPG".to_string(),
+            vec![PerShareFundamentals {
+                period: "2024-Q1".to_string(),
+                period_end: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                available_from: NaiveDate::from_ymd_opt(2024, 5, 15).unwrap(),
+                eps: Some(2.0),
+                book_value_per_share: None,
+            }],
+        )]);
+        let as_of = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        for descending in [true, false] {
+            let result = Screener::new().sort_by(Metric::Pe, descending).run_on(as_of, &series, &fundamentals);
+            assert_eq!(result.symbols(), vec!["HPG", "AAA"]);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::cache::{CacheKind, DiskCache};
+use crate::provider::CompanySummary;
+use crate::tcbs::{self, NewsItem};
+use crate::text;
+use crate::vci;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DocumentKind {
+    Company,
+    News,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    pub symbol: String,
+    pub kind: DocumentKind,
+    /// Company name or headline.
+    pub title: String,
+    pub body: String,
+    pub exchange: Option<String>,
+    pub industry: Option<String>,
+    pub date: Option<String>,
+}
+
+impl Document {
+    pub fn from_company(summary: &CompanySummary) -> Self {
+        let body = [&summary.industry, &summary.profile]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .chain(summary.officers.iter().map(|o| o.name.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Document {
+            symbol: summary.symbol.to_uppercase(),
+            kind: DocumentKind::Company,
+            title: summary.company_name.clone().unwrap_or_else(|| summary.symbol.to_uppercase()),
+            body,
+            exchange: summary.exchange.clone(),
+            industry: summary.industry.clone(),
+            date: None,
+        }
+    }
+
+    pub fn from_news(item: &NewsItem) -> Self {
+        Document {
+            symbol: item.symbol.to_uppercase(),
+            kind: DocumentKind::News,
+            title: item.title.clone(),
+            body: String::new(),
+            exchange: None,
+            industry: None,
+            date: item.publish_date.clone(),
+        }
+    }
+}
+
+/// Restricts a search; empty fields match everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchFilters {
+    pub kind: Option<DocumentKind>,
+    pub symbols: Vec<String>,
+    /// Matched against the company's exchange, also for its news.
+    pub exchange: Option<String>,
+    /// Diacritics-insensitive substring of the company's industry.
+    pub industry: Option<String>,
+    /// Maximum number of hits; `0` means no limit.
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub document: Document,
+    pub score: f64,
+}
+
+fn tokens(text: &str) -> Vec<String> {
+    text::normalize(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// In-memory inverted index over company profiles and news headlines. Terms
+/// are unaccented and lowercase, so "thép" and "thep" find the same
+/// documents. The crate has no SQLite backend to host an FTS5 table, so this
+/// index, built from the disk cache, serves local search instead.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    documents: Vec<Document>,
+    /// Term -> (document, term frequency); titles count double.
+    postings: BTreeMap<String, Vec<(usize, u32)>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    pub fn add(&mut self, document: Document) {
+        let id = self.documents.len();
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for token in tokens(&document.title) {
+            *counts.entry(token).or_default() += 2;
+        }
+        for token in tokens(&document.body).into_iter().chain(tokens(&document.symbol)) {
+            *counts.entry(token).or_default() += 1;
+        }
+        for (term, count) in counts {
+            self.postings.entry(term).or_default().push((id, count));
+        }
+        self.documents.push(document);
+    }
+
+    /// Indexes every company profile and news page in `cache`. A symbol
+    /// cached from both providers is indexed once, preferring VCI.
+    pub fn from_cache(cache: &DiskCache) -> Self {
+        let mut companies: BTreeMap<String, CompanySummary> = BTreeMap::new();
+        for (key, info) in cache.entries::<serde_json::Value>(CacheKind::CompanyInfo) {
+            let summary: Option<CompanySummary> = if key.starts_with("vci_") {
+                serde_json::from_value::<vci::CompanyInfo>(info).ok().map(Into::into)
+            } else {
+                serde_json::from_value::<tcbs::CompanyInfo>(info).ok().map(Into::into)
+            };
+            if let Some(summary) = summary {
+                let symbol = summary.symbol.to_uppercase();
+                if key.starts_with("vci_") || !companies.contains_key(&symbol) {
+                    companies.insert(symbol, summary);
+                }
+            }
+        }
+
+        let mut index = SearchIndex::new();
+        for summary in companies.values() {
+            index.add(Document::from_company(summary));
+        }
+        // The same headline is cached once per page size it was fetched with
+        let mut seen_news = HashSet::new();
+        for (_, news) in cache.entries::<Vec<NewsItem>>(CacheKind::News) {
+            for item in &news {
+                let document = Document::from_news(item);
+                if seen_news.insert((document.symbol.clone(), document.title.clone(), document.date.clone())) {
+                    index.add(document);
+                }
+            }
+        }
+        index
+    }
+
+    /// Documents containing every query term, best tf-idf score first. The
+    /// last term also matches as a prefix, for search-as-you-type.
+    pub fn search(&self, query: &str, filters: &SearchFilters) -> Vec<SearchHit> {
+        let terms = tokens(query);
+        let Some((last, rest)) = terms.split_last() else {
+            return Vec::new();
+        };
+        let n = self.documents.len() as f64;
+        let idf = |postings: usize| (1.0 + n / postings as f64).ln();
+
+        let mut scores: HashMap<usize, (usize, f64)> = HashMap::new();
+        for term in rest {
+            for (doc, tf) in self.postings.get(term).into_iter().flatten() {
+                let entry = scores.entry(*doc).or_default();
+                entry.0 += 1;
+                entry.1 += *tf as f64 * idf(self.postings[term].len());
+            }
+        }
+        let mut prefix: HashMap<usize, f64> = HashMap::new();
+        for (term, postings) in self.postings.range(last.clone()..).take_while(|(term, _)| term.starts_with(last.as_str())) {
+            for (doc, tf) in postings {
+                let score = prefix.entry(*doc).or_default();
+                *score = score.max(*tf as f64 * idf(postings.len()) * if term == last { 1.0 } else { 0.5 });
+            }
+        }
+        for (doc, score) in prefix {
+            let entry = scores.entry(doc).or_default();
+            entry.0 += 1;
+            entry.1 += score;
+        }
+
+        // Exchange/industry filters apply to news through the company document
+        let company_of = |symbol: &str| self.documents.iter().find(|d| d.kind == DocumentKind::Company && d.symbol == symbol);
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .filter(|(_, (matched, _))| *matched == terms.len())
+            .map(|(doc, (_, score))| (&self.documents[doc], score))
+            .filter(|(doc, _)| filters.kind.is_none_or(|kind| doc.kind == kind))
+            .filter(|(doc, _)| filters.symbols.is_empty() || filters.symbols.iter().any(|s| s.eq_ignore_ascii_case(&doc.symbol)))
+            .filter(|(doc, _)| {
+                let company = if doc.kind == DocumentKind::Company { Some(*doc) } else { company_of(&doc.symbol) };
+                let exchange_ok = filters.exchange.as_ref().is_none_or(|wanted| {
+                    company.and_then(|c| c.exchange.as_deref()).is_some_and(|e| e.eq_ignore_ascii_case(wanted))
+                });
+                let industry_ok = filters.industry.as_ref().is_none_or(|wanted| {
+                    company.and_then(|c| c.industry.as_deref()).is_some_and(|i| text::contains_normalized(i, wanted))
+                });
+                exchange_ok && industry_ok
+            })
+            .map(|(doc, score)| SearchHit { document: doc.clone(), score })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.document.symbol.cmp(&b.document.symbol)));
+        if filters.limit > 0 {
+            hits.truncate(filters.limit);
+        }
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn company(symbol: &str, name: &str, exchange: &str, industry: &str, profile: &str) -> Document {
+        Document {
+            symbol: symbol.to_string(),
+            kind: DocumentKind::Company,
+            title: name.to_string(),
+            body: profile.to_string(),
+            exchange: Some(exchange.to_string()),
+            industry: Some(industry.to_string()),
+            date: None,
+        }
+    }
+
+    #[test]
+    fn test_search_with_filters() {
+        let mut index = SearchIndex::new();
+        index.add(company("HPG", "Tập đoàn Hòa Phát", "HOSE", "Thép", "Sản xuất thép xây dựng, ống thép"));
+        index.add(company("HSG", "Tập đoàn Hoa Sen", "HOSE", "Thép", "Tôn mạ, ống thép"));
+        index.add(company("TVN", "Tổng công ty Thép Việt Nam", "UPCOM", "Thép", "Thép"));
+        index.add(company("FPT", "FPT", "HOSE", "Công nghệ", "Phần mềm"));
+        index.add(Document::from_news(&NewsItem { symbol: "HPG".into(), title: "Hòa Phát tăng sản lượng thép".into(), source: None, publish_date: None }));
+
+        let hits = index.search("thép", &SearchFilters::default());
+        assert_eq!(hits.len(), 4);
+
+        let filters = SearchFilters { exchange: Some("hose".into()), kind: Some(DocumentKind::Company), ..Default::default() };
+        let symbols: Vec<_> = index.search("thep", &filters).into_iter().map(|h| h.document.symbol).collect();
+        assert_eq!(symbols, vec!["HPG", "HSG"]);
+
+        // Every term is required; the last one may be a prefix
+        let hits = index.search("hoa pha", &SearchFilters::default());
+        assert!(hits.iter().all(|h| h.document.symbol == "HPG"));
+        assert_eq!(hits.len(), 2);
+        assert!(index.search("phần mềm thép", &SearchFilters::default()).is_empty());
+    }
+
+    #[test]
+    fn test_from_cache_dedupes_news_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::open(dir.path()).unwrap();
+        let item = |title: &str| NewsItem { symbol: "HPG".into(), title: title.into(), source: None, publish_date: Some("2024-05-02".into()) };
+        let small = vec![item("Hòa Phát tăng sản lượng thép")];
+        let large = vec![item("Hòa Phát tăng sản lượng thép"), item("Hòa Phát chia cổ tức")];
+        cache.put(CacheKind::News, &crate::cache::news_key("tcbs", "HPG", 1), &small).unwrap();
+        cache.put(CacheKind::News, &crate::cache::news_key("tcbs", "HPG", 20), &large).unwrap();
+
+        let index = SearchIndex::from_cache(&cache);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.search("thép", &SearchFilters::default()).len(), 1);
+    }
+}
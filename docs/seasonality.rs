@@ -0,0 +1,165 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::calendar;
+use crate::models::{vietnam_offset, Ohlcv};
+
+pub use crate::calendar::tet_date;
+
+/// Summary of a group of returns.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SeasonStat {
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    /// Share of returns above zero.
+    pub hit_rate: f64,
+}
+
+impl SeasonStat {
+    pub fn from_returns(returns: &[f64]) -> Option<Self> {
+        if returns.is_empty() {
+            return None;
+        }
+        let mut sorted = returns.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) { (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0 } else { sorted[n / 2] };
+        Some(SeasonStat {
+            count: n,
+            mean: sorted.iter().sum::<f64>() / n as f64,
+            median,
+            hit_rate: sorted.iter().filter(|r| **r > 0.0).count() as f64 / n as f64,
+        })
+    }
+}
+
+fn closes(bars: &[Ohlcv]) -> Vec<(NaiveDate, f64)> {
+    let mut closes: Vec<(NaiveDate, f64)> = bars
+        .iter()
+        .filter(|bar| bar.close > 0.0)
+        .map(|bar| (bar.time.with_timezone(&vietnam_offset()).date_naive(), bar.close))
+        .collect();
+    closes.sort_by_key(|(date, _)| *date);
+    closes
+}
+
+/// Month-over-month returns grouped by calendar month (1 = January),
+/// from month-end closes.
+pub fn monthly(bars: &[Ohlcv]) -> BTreeMap<u32, SeasonStat> {
+    let mut month_end: BTreeMap<(i32, u32), f64> = BTreeMap::new();
+    for (date, close) in closes(bars) {
+        month_end.insert((date.year(), date.month()), close);
+    }
+    let mut groups: BTreeMap<u32, Vec<f64>> = BTreeMap::new();
+    let ends: Vec<_> = month_end.into_iter().collect();
+    for pair in ends.windows(2) {
+        let ((y0, m0), previous) = pair[0];
+        let ((y1, m1), close) = pair[1];
+        // Skip gaps of more than one month (suspensions, missing data)
+        if (y1 * 12 + m1 as i32) - (y0 * 12 + m0 as i32) == 1 {
+            groups.entry(m1).or_default().push(close / previous - 1.0);
+        }
+    }
+    groups.into_iter().filter_map(|(month, returns)| Some((month, SeasonStat::from_returns(&returns)?))).collect()
+}
+
+/// Close-to-close daily returns grouped by weekday (1 = Monday).
+pub fn weekday(bars: &[Ohlcv]) -> BTreeMap<u32, SeasonStat> {
+    let mut groups: BTreeMap<u32, Vec<f64>> = BTreeMap::new();
+    for pair in closes(bars).windows(2) {
+        groups.entry(pair[1].0.weekday().number_from_monday()).or_default().push(pair[1].1 / pair[0].1 - 1.0);
+    }
+    groups.into_iter().filter_map(|(day, returns)| Some((day, SeasonStat::from_returns(&returns)?))).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TetYear {
+    pub year: i32,
+    pub last_session: NaiveDate,
+    /// Return over the `sessions` sessions up to the last close before Tet.
+    pub pre: Option<f64>,
+    /// Return from the last close before Tet over the first `sessions`
+    /// sessions after the market reopens.
+    pub post: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TetEffect {
+    pub sessions: usize,
+    pub years: Vec<TetYear>,
+    pub pre: Option<SeasonStat>,
+    pub post: Option<SeasonStat>,
+}
+
+/// Pre- and post-holiday returns around each Tet covered by `bars`.
+pub fn tet_effect(bars: &[Ohlcv], sessions: usize) -> TetEffect {
+    let closes = closes(bars);
+    let sessions = sessions.max(1);
+    let years: Vec<TetYear> = calendar::TET_DATES
+        .iter()
+        .filter_map(|&(year, m, d)| {
+            let tet = NaiveDate::from_ymd_opt(year, m, d)?;
+            let last = closes.iter().rposition(|(date, _)| *date < tet)?;
+            // Require the data to actually reach the holiday
+            if (tet - closes[last].0).num_days() > 10 {
+                return None;
+            }
+            let close = closes[last].1;
+            let pre = last.checked_sub(sessions).map(|start| close / closes[start].1 - 1.0);
+            let post = closes.get(last + sessions).map(|(_, after)| after / close - 1.0);
+            Some(TetYear { year, last_session: closes[last].0, pre, post })
+        })
+        .collect();
+
+    let pre: Vec<f64> = years.iter().filter_map(|y| y.pre).collect();
+    let post: Vec<f64> = years.iter().filter_map(|y| y.post).collect();
+    TetEffect { sessions, pre: SeasonStat::from_returns(&pre), post: SeasonStat::from_returns(&post), years }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Seasonality {
+    pub monthly: BTreeMap<u32, SeasonStat>,
+    pub weekday: BTreeMap<u32, SeasonStat>,
+    pub tet: TetEffect,
+}
+
+/// All seasonality statistics over a symbol's full daily history, with
+/// the Tet windows spanning five sessions.
+pub fn analyze(bars: &[Ohlcv]) -> Seasonality {
+    Seasonality { monthly: monthly(bars), weekday: weekday(bars), tet: tet_effect(bars, 5) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_seasonality_and_tet() {
+        // Price rises 1% a session, except a 2% drop after Tet 2024
+        let days = calendar::trading_days_between(NaiveDate::from_ymd_opt(2023, 12, 1).unwrap(), NaiveDate::from_ymd_opt(2024, 3, 29).unwrap());
+        let mut price = 100.0;
+        let bars: Vec<Ohlcv> = days
+            .iter()
+            .map(|date| {
+                price *= if *date == NaiveDate::from_ymd_opt(2024, 2, 15).unwrap() { 0.98 } else { 1.01 };
+                let time = chrono::Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+                Ohlcv { time, open: price, high: price, low: price, close: price, volume: 0, symbol: None, breakdown: None, futures: None }
+            })
+            .collect();
+
+        let tet = tet_effect(&bars, 1);
+        assert_eq!(tet.years.len(), 1);
+        assert_eq!(tet.years[0].last_session, NaiveDate::from_ymd_opt(2024, 2, 7).unwrap());
+        assert!((tet.years[0].pre.unwrap() - 0.01).abs() < 1e-9);
+        assert!((tet.years[0].post.unwrap() + 0.02).abs() < 1e-9);
+
+        let by_month = monthly(&bars);
+        assert_eq!(by_month.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(weekday(&bars)[&1].hit_rate, 1.0);
+        assert_eq!(SeasonStat::from_returns(&[0.1, -0.1, 0.3, 0.0]).unwrap().median, 0.05);
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tcbs::{FinancialStatement, TcbsClient};
+use crate::vci::{VciClient, VciError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatementKind {
+    BalanceSheet,
+    IncomeStatement,
+    CashFlow,
+}
+
+impl StatementKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatementKind::BalanceSheet => "balance_sheet",
+            StatementKind::IncomeStatement => "income_statement",
+            StatementKind::CashFlow => "cash_flow",
+        }
+    }
+}
+
+/// One line item of one statement for one company and year.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatementRow {
+    pub symbol: String,
+    pub statement: StatementKind,
+    pub period: String,
+    pub item: String,
+    pub value: f64,
+}
+
+/// Long-format annual statements for a sector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectorStatements {
+    pub industry: String,
+    pub symbols: Vec<String>,
+    pub rows: Vec<StatementRow>,
+    /// Symbols for which no statement could be fetched.
+    pub failed: Vec<String>,
+}
+
+impl SectorStatements {
+    /// Values of `item` across the sector for `period`, as (symbol, value).
+    pub fn cross_section(&self, statement: StatementKind, item: &str, period: &str) -> Vec<(&str, f64)> {
+        self.rows.iter()
+            .filter(|row| row.statement == statement && row.item == item && row.period == period)
+            .map(|row| (row.symbol.as_str(), row.value))
+            .collect()
+    }
+}
+
+/// Flattens statements into long-format rows, sorted by period then item.
+pub fn to_rows(symbol: &str, statement: StatementKind, statements: &[FinancialStatement]) -> Vec<StatementRow> {
+    let mut rows: Vec<StatementRow> = statements.iter()
+        .flat_map(|s| {
+            s.data.iter().map(move |(item, &value)| StatementRow {
+                symbol: symbol.to_uppercase(),
+                statement,
+                period: s.period.clone(),
+                item: item.clone(),
+                value,
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| (&a.period, &a.item).cmp(&(&b.period, &b.item)));
+    rows
+}
+
+/// Annual balance sheet, income statement and cash flow for every company
+/// whose ICB classification (any level) matches `industry`. Symbols are
+/// resolved through VCI and statements fetched from TCBS concurrently; the
+/// TCBS client's rate limiter paces the fan-out.
+pub async fn sector_statements(vci: &VciClient, tcbs: &TcbsClient, industry: &str) -> Result<SectorStatements, VciError> {
+    let symbols: Vec<String> = vci.industry_listings().await?
+        .into_iter()
+        .filter(|listing| listing.in_industry(industry))
+        .map(|listing| listing.symbol)
+        .collect();
+    if symbols.is_empty() {
+        return Err(VciError::NoData);
+    }
+
+    let fetches = symbols.iter().map(|symbol| async move {
+        let (balance, income, cash) = tokio::join!(
+            tcbs.balance_sheets(symbol, "year"),
+            tcbs.income_statements(symbol, "year"),
+            tcbs.cash_flows(symbol, "year"),
+        );
+        let mut rows = Vec::new();
+        for (kind, result) in [
+            (StatementKind::BalanceSheet, balance),
+            (StatementKind::IncomeStatement, income),
+            (StatementKind::CashFlow, cash),
+        ] {
+            match result {
+                Ok(statements) => rows.extend(to_rows(symbol, kind, &statements)),
+                Err(e) => tracing::warn!("TCBS {} fetch failed for {}: {:?}", kind.as_str(), symbol, e),
+            }
+        }
+        (symbol.clone(), rows)
+    });
+
+    let mut sector = SectorStatements {
+        industry: industry.to_string(),
+        symbols: symbols.clone(),
+        rows: Vec::new(),
+        failed: Vec::new(),
+    };
+    for (symbol, rows) in futures::future::join_all(fetches).await {
+        if rows.is_empty() {
+            sector.failed.push(symbol);
+        }
+        sector.rows.extend(rows);
+    }
+    Ok(sector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_rows_long_format() {
+        let statements = vec![FinancialStatement {
+            period: "2023".to_string(),
+            data: [("revenue".to_string(), 100.0), ("post_tax_profit".to_string(), 8.0)].into(),
+        }];
+        let rows = to_rows("hpg", StatementKind::IncomeStatement, &statements);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].item, "post_tax_profit");
+
+        let sector = SectorStatements {
+            industry: "Thép".to_string(),
+            symbols: vec!["HPG".to_string()],
+            rows,
+            failed: Vec::new(),
+        };
+        assert_eq!(sector.cross_section(StatementKind::IncomeStatement, "revenue", "2023"), vec![("HPG", 100.0)]);
+    }
+}
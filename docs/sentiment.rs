@@ -0,0 +1,201 @@
+use chrono::NaiveDate;
+use futures::stream::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::{vietnam_offset, Ohlcv};
+use crate::tcbs::{NewsItem, TcbsClient, TcbsError};
+use crate::text;
+
+/// Unaccented, lowercase phrases with their weight. Longer phrases win over
+/// the words they contain, so "giam lo" (narrowed loss) is not read as "lo".
+const LEXICON: &[(&str, f64)] = &[
+    ("tang truong", 1.0),
+    ("tang manh", 1.0),
+    ("tang von", 0.5),
+    ("tang", 0.5),
+    ("ky luc", 1.0),
+    ("vuot ke hoach", 1.0),
+    ("hoan thanh ke hoach", 1.0),
+    ("lai ky luc", 1.5),
+    ("lai rong", 0.5),
+    ("bao lai", 1.0),
+    ("loi nhuan tang", 1.5),
+    ("co tuc", 0.5),
+    ("mua rong", 0.5),
+    ("trung thau", 1.0),
+    ("khoi sac", 1.0),
+    ("but pha", 1.0),
+    ("giam lo", 0.5),
+    ("mo rong", 0.5),
+    ("nang hang", 1.0),
+    ("giam", -0.5),
+    ("giam manh", -1.0),
+    ("sut giam", -1.0),
+    ("lai giam", -1.0),
+    ("loi nhuan giam", -1.5),
+    ("lo", -1.0),
+    ("thua lo", -1.5),
+    ("bao lo", -1.5),
+    ("lo luy ke", -1.5),
+    ("ban rong", -0.5),
+    ("no xau", -1.0),
+    ("vi pham", -1.0),
+    ("xu phat", -1.0),
+    ("bi phat", -1.0),
+    ("canh bao", -1.0),
+    ("kiem soat", -1.0),
+    ("dinh chi", -1.5),
+    ("huy niem yet", -2.0),
+    ("khoi to", -2.0),
+    ("bi bat", -2.0),
+    ("ha bac", -1.0),
+];
+
+/// Words that flip the polarity of the phrase right after them.
+const NEGATIONS: &[&str] = &["khong", "chua", "chang"];
+
+/// Lexicon score of one headline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeadlineScore {
+    pub positive: f64,
+    pub negative: f64,
+}
+
+impl HeadlineScore {
+    /// `(positive - negative) / (positive + negative)` in `[-1, 1]`; zero
+    /// when no lexicon term matched.
+    pub fn score(&self) -> f64 {
+        let total = self.positive + self.negative;
+        if total > 0.0 { (self.positive - self.negative) / total } else { 0.0 }
+    }
+}
+
+pub fn score_headline(headline: &str) -> HeadlineScore {
+    let normalized = text::normalize(headline);
+    let words: Vec<&str> = normalized
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut score = HeadlineScore { positive: 0.0, negative: 0.0 };
+    let mut negate = false;
+    let mut i = 0;
+    while i < words.len() {
+        if NEGATIONS.contains(&words[i]) {
+            negate = true;
+            i += 1;
+            continue;
+        }
+        let longest = LEXICON
+            .iter()
+            .filter_map(|&(phrase, weight)| {
+                let len = phrase.split(' ').count();
+                (words.get(i..i + len)? == phrase.split(' ').collect::<Vec<_>>()).then_some((len, weight))
+            })
+            .max_by_key(|(len, _)| *len);
+        match longest {
+            Some((len, weight)) => {
+                let weight = if negate { -weight } else { weight };
+                if weight > 0.0 { score.positive += weight } else { score.negative -= weight }
+                i += len;
+            }
+            None => i += 1,
+        }
+        negate = false;
+    }
+    score
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailySentiment {
+    pub date: NaiveDate,
+    pub headlines: usize,
+    /// Mean headline score for the day.
+    pub score: f64,
+}
+
+/// Publish dates come as `YYYY-MM-DD...` or `DD/MM/YYYY...`.
+fn publish_day(date: &str) -> Option<NaiveDate> {
+    let day = date.trim().get(..10)?;
+    NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(day, "%d/%m/%Y"))
+        .ok()
+}
+
+/// Per-symbol daily sentiment series, oldest first. Items without a usable
+/// publish date are dropped.
+pub fn daily_sentiment(news: &[NewsItem]) -> BTreeMap<String, Vec<DailySentiment>> {
+    let mut days: BTreeMap<String, BTreeMap<NaiveDate, Vec<f64>>> = BTreeMap::new();
+    for item in news {
+        let Some(date) = item.publish_date.as_deref().and_then(publish_day) else {
+            continue;
+        };
+        days.entry(item.symbol.to_uppercase()).or_default().entry(date).or_default().push(score_headline(&item.title).score());
+    }
+    days.into_iter()
+        .map(|(symbol, by_day)| {
+            let series = by_day
+                .into_iter()
+                .map(|(date, scores)| DailySentiment { date, headlines: scores.len(), score: scores.iter().sum::<f64>() / scores.len() as f64 })
+                .collect();
+            (symbol, series)
+        })
+        .collect()
+}
+
+/// Sentiment for each bar's trading date. News published on a non-trading
+/// day counts towards the next bar.
+pub fn align_with_bars(series: &[DailySentiment], bars: &[Ohlcv]) -> Vec<Option<f64>> {
+    let mut previous: Option<NaiveDate> = None;
+    bars.iter()
+        .map(|bar| {
+            let date = bar.time.with_timezone(&vietnam_offset()).date_naive();
+            let window: Vec<&DailySentiment> = series
+                .iter()
+                .filter(|day| day.date <= date && previous.is_none_or(|p| day.date > p))
+                .collect();
+            previous = Some(date);
+            let headlines: usize = window.iter().map(|day| day.headlines).sum();
+            (headlines > 0).then(|| window.iter().map(|day| day.score * day.headlines as f64).sum::<f64>() / headlines as f64)
+        })
+        .collect()
+}
+
+/// Daily sentiment of the latest `max_pages` pages of `symbol`'s news.
+pub async fn symbol_sentiment(client: &TcbsClient, symbol: &str, max_pages: usize) -> Result<Vec<DailySentiment>, TcbsError> {
+    let mut news = Vec::new();
+    let mut pages = client.news_pages(symbol, 50).take(max_pages);
+    while let Some(page) = pages.next().await {
+        news.extend(page?.items);
+    }
+    Ok(daily_sentiment(&news).remove(&symbol.to_uppercase()).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn news(title: &str, date: &str) -> NewsItem {
+        NewsItem { symbol: "HPG".to_string(), title: title.to_string(), source: None, publish_date: Some(date.to_string()) }
+    }
+
+    #[test]
+    fn test_headline_scoring() {
+        assert!(score_headline("Hòa Phát báo lãi kỷ lục, vượt kế hoạch năm").score() > 0.9);
+        assert!(score_headline("HPG thua lỗ quý thứ hai liên tiếp").score() < -0.9);
+        // "giảm lỗ" is a narrowed loss, negation flips "tăng"
+        assert_eq!(score_headline("Doanh nghiệp giảm lỗ").score(), 1.0);
+        assert_eq!(score_headline("Lợi nhuận không tăng").score(), -1.0);
+        assert_eq!(score_headline("Đại hội cổ đông thường niên").score(), 0.0);
+
+        let series = daily_sentiment(&[
+            news("Báo lãi kỷ lục", "2024-06-08 09:00:00"),
+            news("Bị xử phạt vi phạm công bố thông tin", "2024-06-08 15:00:00"),
+            news("Khối ngoại mua ròng", "10/06/2024"),
+        ]);
+        let hpg = &series["HPG"];
+        assert_eq!(hpg.len(), 2);
+        assert_eq!((hpg[0].headlines, hpg[0].score), (2, 0.0));
+        assert_eq!(hpg[1].date, NaiveDate::from_ymd_opt(2024, 6, 10).unwrap());
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::auction::AuctionSession;
+use crate::models::{vietnam_offset, Exchange};
+
+/// Part of the trading day a timestamp falls in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionPhase {
+    Closed,
+    Auction(AuctionSession),
+    Continuous,
+    /// 11:30-13:00 midday break.
+    LunchBreak,
+    /// HOSE put-through only window after the closing auction.
+    PutThrough,
+}
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+/// Phase of `exchange`'s trading day at `time`. Auction match prints are
+/// stamped at the window's match time, so the minute after each window still
+/// counts as the auction.
+pub fn phase_at(time: DateTime<Utc>, exchange: Exchange) -> SessionPhase {
+    let local = time.with_timezone(&vietnam_offset()).time();
+    let in_auction = |session: AuctionSession| {
+        let (start, match_time) = session.window();
+        session.applies_to(exchange) && local >= start && local < match_time + chrono::Duration::minutes(1)
+    };
+
+    if in_auction(AuctionSession::Ato) {
+        SessionPhase::Auction(AuctionSession::Ato)
+    } else if in_auction(AuctionSession::Atc) {
+        SessionPhase::Auction(AuctionSession::Atc)
+    } else if local >= hm(11, 30) && local < hm(13, 0) {
+        SessionPhase::LunchBreak
+    } else if (local >= hm(9, 0) && local < hm(14, 30)) || (exchange == Exchange::Upcom && local >= hm(14, 30) && local < hm(15, 0)) {
+        SessionPhase::Continuous
+    } else if exchange != Exchange::Upcom && local >= hm(14, 45) && local < hm(15, 0) {
+        SessionPhase::PutThrough
+    } else {
+        SessionPhase::Closed
+    }
+}
+
+/// Decides which samples belong in intraday bars for an exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionFilter {
+    pub exchange: Exchange,
+    /// Keep ATO/ATC prints; when false only continuous matching is kept.
+    pub include_auctions: bool,
+}
+
+impl SessionFilter {
+    pub fn new(exchange: Exchange) -> Self {
+        SessionFilter {
+            exchange,
+            include_auctions: true,
+        }
+    }
+
+    pub fn accepts(&self, time: DateTime<Utc>) -> bool {
+        match phase_at(time, self.exchange) {
+            SessionPhase::Continuous => true,
+            SessionPhase::Auction(_) => self.include_auctions,
+            SessionPhase::Closed | SessionPhase::LunchBreak | SessionPhase::PutThrough => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        // Exchange time is UTC+7
+        Utc.with_ymd_and_hms(2024, 6, 3, hour - 7, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_phase_at() {
+        assert_eq!(phase_at(at(9, 5), Exchange::Hose), SessionPhase::Auction(AuctionSession::Ato));
+        assert_eq!(phase_at(at(9, 5), Exchange::Hnx), SessionPhase::Continuous);
+        assert_eq!(phase_at(at(9, 15), Exchange::Hose), SessionPhase::Auction(AuctionSession::Ato));
+        assert_eq!(phase_at(at(9, 16), Exchange::Hose), SessionPhase::Continuous);
+        assert_eq!(phase_at(at(12, 0), Exchange::Hose), SessionPhase::LunchBreak);
+        assert_eq!(phase_at(at(14, 45), Exchange::Hose), SessionPhase::Auction(AuctionSession::Atc));
+        assert_eq!(phase_at(at(14, 50), Exchange::Hose), SessionPhase::PutThrough);
+        assert_eq!(phase_at(at(14, 50), Exchange::Upcom), SessionPhase::Continuous);
+
+        let continuous_only = SessionFilter { include_auctions: false, ..SessionFilter::new(Exchange::Hose) };
+        assert!(!continuous_only.accepts(at(14, 40)));
+        assert!(SessionFilter::new(Exchange::Hose).accepts(at(14, 40)));
+        assert!(!SessionFilter::new(Exchange::Hose).accepts(at(11, 45)));
+    }
+}
@@ -0,0 +1,216 @@
+use futures::future::BoxFuture;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Cloneable signal that background loops can await to stop cooperatively.
+#[derive(Debug, Clone)]
+pub struct ShutdownToken {
+    receiver: watch::Receiver<bool>,
+}
+
+impl ShutdownToken {
+    pub fn is_shutdown(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    /// Resolves once shutdown has been requested.
+    pub async fn cancelled(&mut self) {
+        while !*self.receiver.borrow_and_update() {
+            if self.receiver.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Resolves once `token` is signalled; never when there is no token. For
+/// loops whose shutdown token is optional.
+pub(crate) async fn signalled(token: &mut Option<ShutdownToken>) {
+    match token {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Outcome of `Shutdown::shutdown`, by component name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShutdownReport {
+    /// Tasks that finished on their own before the deadline.
+    pub stopped: Vec<String>,
+    /// Tasks still running at the deadline.
+    pub aborted: Vec<String>,
+    /// Hooks (flushes, checkpoints) that completed.
+    pub flushed: Vec<String>,
+    /// Hooks cut off by the deadline.
+    pub timed_out: Vec<String>,
+}
+
+impl ShutdownReport {
+    pub fn is_clean(&self) -> bool {
+        self.aborted.is_empty() && self.timed_out.is_empty()
+    }
+}
+
+type Hook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+/// Coordinates stopping background tasks, then runs flush/persist hooks,
+/// all within one deadline.
+pub struct Shutdown {
+    sender: watch::Sender<bool>,
+    tasks: Mutex<Vec<(String, JoinHandle<()>)>>,
+    hooks: Mutex<Vec<(String, Hook)>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Shutdown {
+            sender,
+            tasks: Mutex::new(Vec::new()),
+            hooks: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn token(&self) -> ShutdownToken {
+        ShutdownToken { receiver: self.sender.subscribe() }
+    }
+
+    /// Tracks an already spawned task (e.g. from `spawn_refresh_loop` given
+    /// one of this shutdown's tokens). It is given until the deadline to
+    /// finish and aborted after that.
+    pub fn register(&self, name: &str, handle: JoinHandle<()>) {
+        self.tasks.lock().unwrap().push((name.to_string(), handle));
+    }
+
+    /// Spawns `task` so that it is dropped at its next await point once
+    /// shutdown is requested.
+    pub fn spawn<F>(&self, name: &str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let mut token = self.token();
+        let handle = tokio::spawn(async move {
+            tokio::select! {
+                _ = task => {}
+                _ = token.cancelled() => {}
+            }
+        });
+        self.register(name, handle);
+    }
+
+    /// Runs after all tasks have stopped, in registration order. Use it to
+    /// flush sinks, persist checkpoints and close connections.
+    pub fn on_shutdown<F, Fut>(&self, name: &str, hook: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.hooks.lock().unwrap().push((name.to_string(), Box::new(move || Box::pin(hook()))));
+    }
+
+    /// Signals every token, waits for tasks, then runs the hooks. Tasks get
+    /// at most half of `deadline` so hooks are never starved by a stuck task;
+    /// anything still running after that is aborted, and hooks still running
+    /// at the deadline are skipped.
+    pub async fn shutdown(&self, deadline: Duration) -> ShutdownReport {
+        let started = Instant::now();
+        let until = started + deadline;
+        let _ = self.sender.send(true);
+        let mut report = ShutdownReport::default();
+
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        for (name, mut handle) in tasks {
+            match tokio::time::timeout_at(started + deadline / 2, &mut handle).await {
+                Ok(_) => report.stopped.push(name),
+                Err(_) => {
+                    handle.abort();
+                    tracing::warn!("Shutdown deadline hit, aborted {}", name);
+                    report.aborted.push(name);
+                }
+            }
+        }
+
+        let hooks = std::mem::take(&mut *self.hooks.lock().unwrap());
+        for (name, hook) in hooks {
+            match tokio::time::timeout_at(until, hook()).await {
+                Ok(()) => report.flushed.push(name),
+                Err(_) => {
+                    tracing::warn!("Shutdown deadline hit during {}", name);
+                    report.timed_out.push(name);
+                }
+            }
+        }
+        report
+    }
+
+    /// Waits for SIGTERM or Ctrl-C, then shuts down within `deadline`.
+    pub async fn shutdown_on_signal(&self, deadline: Duration) -> ShutdownReport {
+        wait_for_signal().await;
+        tracing::info!("Shutdown signal received");
+        self.shutdown(deadline).await
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        let mut term = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(term) => term,
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+                return;
+            }
+        };
+        tokio::select! {
+            _ = term.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_stops_tasks_then_flushes() {
+        let shutdown = Shutdown::new();
+        shutdown.spawn("poller", async {
+            loop {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
+        shutdown.register("stuck", tokio::spawn(async {
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+        }));
+        let flushed = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&flushed);
+        shutdown.on_shutdown("sink", move || async move { flag.store(true, Ordering::SeqCst) });
+
+        let token = shutdown.token();
+        let report = shutdown.shutdown(Duration::from_secs(5)).await;
+
+        assert!(token.is_shutdown());
+        assert_eq!(report.stopped, vec!["poller".to_string()]);
+        assert_eq!(report.aborted, vec!["stuck".to_string()]);
+        assert_eq!(report.flushed, vec!["sink".to_string()]);
+        assert!(flushed.load(Ordering::SeqCst));
+        assert!(!report.is_clean());
+    }
+}
@@ -0,0 +1,239 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::calendar::MarketCalendar;
+use crate::models::{vietnam_offset, Exchange, Quote};
+use crate::session::{self, SessionPhase};
+use crate::shutdown::{self, ShutdownToken};
+use crate::vci::VciClient;
+
+/// Best bid/ask observed at one sampling instant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpreadSample {
+    pub symbol: String,
+    pub time: DateTime<Utc>,
+    pub bid: f64,
+    pub ask: f64,
+}
+
+impl SpreadSample {
+    /// Samples only quotes with a two-sided, uncrossed book.
+    pub fn from_quote(quote: &Quote) -> Option<SpreadSample> {
+        let (bid, ask) = (quote.best_bid?, quote.best_ask?);
+        (bid > 0.0 && ask >= bid).then(|| SpreadSample { symbol: quote.symbol.to_uppercase(), time: quote.time, bid, ask })
+    }
+
+    pub fn spread(&self) -> f64 {
+        self.ask - self.bid
+    }
+
+    /// Spread relative to the mid price, in basis points.
+    pub fn spread_bps(&self) -> f64 {
+        self.spread() / ((self.ask + self.bid) / 2.0) * 10_000.0
+    }
+}
+
+/// Spread statistics for one symbol on one exchange-time day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailySpread {
+    pub date: NaiveDate,
+    pub samples: usize,
+    pub mean_spread: f64,
+    pub mean_spread_bps: f64,
+    pub median_spread_bps: f64,
+    pub min_spread: f64,
+    pub max_spread: f64,
+}
+
+/// Days of samples a [`SpreadTracker`] keeps by default.
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+fn exchange_date(time: DateTime<Utc>) -> NaiveDate {
+    time.with_timezone(&vietnam_offset()).date_naive()
+}
+
+/// Collects spread samples per symbol and summarizes them by day. Only the
+/// last `retention_days` calendar days are kept, so a long-running sampler
+/// stays bounded.
+pub struct SpreadTracker {
+    samples: HashMap<String, Vec<SpreadSample>>,
+    retention_days: i64,
+}
+
+impl Default for SpreadTracker {
+    fn default() -> Self {
+        SpreadTracker { samples: HashMap::new(), retention_days: DEFAULT_RETENTION_DAYS }
+    }
+}
+
+impl SpreadTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_retention_days(mut self, days: i64) -> Self {
+        self.retention_days = days.max(1);
+        self
+    }
+
+    /// Records the quote's book; returns false when it is one-sided or crossed.
+    pub fn record(&mut self, quote: &Quote) -> bool {
+        let Some(sample) = SpreadSample::from_quote(quote) else {
+            return false;
+        };
+        let oldest = exchange_date(sample.time) - chrono::Duration::days(self.retention_days - 1);
+        let samples = self.samples.entry(sample.symbol.clone()).or_default();
+        samples.push(sample);
+        // Samples arrive in time order, so expired ones sit at the front
+        if let Some(keep_from) = samples.iter().position(|sample| exchange_date(sample.time) >= oldest) {
+            samples.drain(..keep_from);
+        }
+        true
+    }
+
+    pub fn samples(&self, symbol: &str) -> &[SpreadSample] {
+        self.samples.get(&symbol.to_uppercase()).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn daily(&self, symbol: &str) -> Vec<DailySpread> {
+        let mut days: BTreeMap<NaiveDate, Vec<&SpreadSample>> = BTreeMap::new();
+        for sample in self.samples(symbol) {
+            days.entry(exchange_date(sample.time)).or_default().push(sample);
+        }
+
+        days.into_iter()
+            .map(|(date, samples)| {
+                let count = samples.len() as f64;
+                let mut bps: Vec<f64> = samples.iter().map(|sample| sample.spread_bps()).collect();
+                bps.sort_by(|a, b| a.total_cmp(b));
+                let median = if bps.len().is_multiple_of(2) {
+                    (bps[bps.len() / 2 - 1] + bps[bps.len() / 2]) / 2.0
+                } else {
+                    bps[bps.len() / 2]
+                };
+                DailySpread {
+                    date,
+                    samples: samples.len(),
+                    mean_spread: samples.iter().map(|sample| sample.spread()).sum::<f64>() / count,
+                    mean_spread_bps: bps.iter().sum::<f64>() / count,
+                    median_spread_bps: median,
+                    min_spread: samples.iter().map(|sample| sample.spread()).fold(f64::MAX, f64::min),
+                    max_spread: samples.iter().map(|sample| sample.spread()).fold(f64::MIN, f64::max),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Symbols whose exchange is in continuous trading at `now`. Auction,
+/// break and holiday books are not representative of trading spreads.
+pub fn sampling_symbols(symbols: &[(String, Exchange)], now: DateTime<Utc>, calendar: &MarketCalendar) -> Vec<String> {
+    if !calendar.is_trading_day(exchange_date(now)) {
+        return Vec::new();
+    }
+    symbols.iter()
+        .filter(|(_, exchange)| session::phase_at(now, *exchange) == SessionPhase::Continuous)
+        .map(|(symbol, _)| symbol.clone())
+        .collect()
+}
+
+/// Polls the price board every `cadence` for the symbols of `symbols`
+/// (ticker and listing exchange) that are in continuous trading, and records
+/// each book into `tracker`. Stops once `shutdown` is signalled, or when the
+/// handle is aborted.
+pub fn spawn_sampler(
+    client: Arc<VciClient>,
+    symbols: Vec<(String, Exchange)>,
+    cadence: Duration,
+    tracker: Arc<Mutex<SpreadTracker>>,
+    mut shutdown: Option<ShutdownToken>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let calendar = MarketCalendar::default();
+        let mut ticker = tokio::time::interval(cadence);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown::signalled(&mut shutdown) => return,
+            }
+            let active = sampling_symbols(&symbols, Utc::now(), &calendar);
+            if active.is_empty() {
+                continue;
+            }
+            match client.quotes(&active).await {
+                Ok(quotes) => {
+                    let mut tracker = tracker.lock().unwrap();
+                    for quote in &quotes {
+                        tracker.record(quote);
+                    }
+                }
+                Err(e) => tracing::warn!("Spread sampling failed: {:?}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn quote(hour: u32, bid: Option<f64>, ask: Option<f64>) -> Quote {
+        Quote {
+            symbol: "FPT".to_string(),
+            time: Utc.with_ymd_and_hms(2024, 6, 3, hour, 0, 0).unwrap(),
+            price: 100_000.0,
+            reference_price: None,
+            ceiling_price: None,
+            floor_price: None,
+            open: None,
+            high: None,
+            low: None,
+            volume: 0,
+            value: None,
+            best_bid: bid,
+            best_ask: ask,
+        }
+    }
+
+    #[test]
+    fn test_daily_spread_stats() {
+        let mut tracker = SpreadTracker::new();
+        assert!(tracker.record(&quote(2, Some(99_900.0), Some(100_100.0))));
+        assert!(tracker.record(&quote(3, Some(99_950.0), Some(100_050.0))));
+        assert!(!tracker.record(&quote(4, Some(99_900.0), None)));
+        assert!(!tracker.record(&quote(5, Some(100_200.0), Some(100_100.0))));
+
+        let daily = tracker.daily("fpt");
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].samples, 2);
+        assert!((daily[0].mean_spread - 150.0).abs() < 1e-9);
+        assert!((daily[0].mean_spread_bps - 15.0).abs() < 1e-9);
+        assert_eq!(daily[0].max_spread, 200.0);
+
+        let mut windowed = SpreadTracker::new().with_retention_days(2);
+        let mut later = quote(3, Some(99_900.0), Some(100_100.0));
+        windowed.record(&quote(3, Some(99_900.0), Some(100_100.0)));
+        later.time += chrono::Duration::days(2);
+        windowed.record(&later);
+        assert_eq!(windowed.samples("FPT").len(), 1);
+    }
+
+    #[test]
+    fn test_sampling_follows_calendar_and_exchange() {
+        let symbols = vec![("FPT".to_string(), Exchange::Hose), ("BSR".to_string(), Exchange::Upcom)];
+        let calendar = MarketCalendar::default();
+        // 14:50 exchange time: HOSE is in put-through, UPCOM still matches
+        let monday = Utc.with_ymd_and_hms(2024, 6, 3, 7, 50, 0).unwrap();
+        assert_eq!(sampling_symbols(&symbols, monday, &calendar), vec!["BSR".to_string()]);
+        let morning = Utc.with_ymd_and_hms(2024, 6, 3, 3, 0, 0).unwrap();
+        assert_eq!(sampling_symbols(&symbols, morning, &calendar).len(), 2);
+        // Reunification Day
+        let holiday = Utc.with_ymd_and_hms(2024, 4, 30, 3, 0, 0).unwrap();
+        assert!(sampling_symbols(&symbols, holiday, &calendar).is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::tcbs::{FinancialInfo, FinancialStatement};
+
+/// First key of `aliases` present in the provider's map. Aliases cover the
+/// snake_case TCBS keys, the alternates older TCBS responses use and the
+/// keys produced by the Fireant line mapping.
+fn pick(data: &HashMap<String, f64>, aliases: &[&str]) -> Option<f64> {
+    aliases.iter().find_map(|key| data.get(*key).copied())
+}
+
+/// Sum of the lines present, `None` when none is.
+fn sum(data: &HashMap<String, f64>, keys: &[&str]) -> Option<f64> {
+    keys.iter().filter_map(|key| data.get(*key)).copied().reduce(|a, b| a + b)
+}
+
+/// (year, quarter) of labels like "2024-Q1", "2024-1" or "2024"; years
+/// sort as quarter 0.
+fn period_key(period: &str) -> (i64, i64) {
+    let (year, quarter) = period.split_once('-').unwrap_or((period, ""));
+    (year.trim().parse().unwrap_or(0), quarter.trim().trim_start_matches(['Q', 'q']).parse().unwrap_or(0))
+}
+
+/// Newest period first.
+fn newest_first<T>(mut statements: Vec<T>, period: impl Fn(&T) -> &str) -> Vec<T> {
+    statements.sort_by_key(|statement| std::cmp::Reverse(period_key(period(statement))));
+    statements
+}
+
+/// Income statement in provider units (TCBS and Fireant report billions of VND).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IncomeStatement {
+    pub period: String,
+    pub revenue: Option<f64>,
+    pub cost_of_goods_sold: Option<f64>,
+    pub gross_profit: Option<f64>,
+    pub operating_expenses: Option<f64>,
+    pub operating_profit: Option<f64>,
+    pub interest_expense: Option<f64>,
+    pub pre_tax_profit: Option<f64>,
+    pub net_income: Option<f64>,
+    /// Net income attributable to shareholders of the parent.
+    pub net_income_to_parent: Option<f64>,
+    pub ebitda: Option<f64>,
+    /// The provider's map the fields were read from.
+    pub raw: HashMap<String, f64>,
+}
+
+impl IncomeStatement {
+    pub fn from_statement(statement: &FinancialStatement) -> Self {
+        let data = &statement.data;
+        IncomeStatement {
+            period: statement.period.clone(),
+            revenue: pick(data, &["revenue", "net_sale", "net_revenue"]),
+            cost_of_goods_sold: pick(data, &["cost_of_good_sold", "cost_of_goods_sold"]),
+            gross_profit: pick(data, &["gross_profit"]),
+            // FireAnt reports selling and admin expenses as separate lines
+            operating_expenses: pick(data, &["operation_expense", "operating_expense"])
+                .or_else(|| sum(data, &["chi_phi_ban_hang", "chi_phi_quan_ly_doanh_nghiep"])),
+            operating_profit: pick(data, &["operation_profit", "operating_profit", "profit_from_business_activities"]),
+            interest_expense: pick(data, &["interest_expense"]),
+            pre_tax_profit: pick(data, &["pre_tax_profit", "profit_before_tax"]),
+            net_income: pick(data, &["post_tax_profit", "profit_after_tax", "net_income"]),
+            net_income_to_parent: pick(data, &["share_holder_income", "shareholder_income"]),
+            ebitda: pick(data, &["ebitda"]),
+            raw: data.clone(),
+        }
+    }
+}
+
+/// Balance sheet in provider units.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSheet {
+    pub period: String,
+    pub cash: Option<f64>,
+    pub short_term_investments: Option<f64>,
+    pub receivables: Option<f64>,
+    pub inventory: Option<f64>,
+    pub current_assets: Option<f64>,
+    pub fixed_assets: Option<f64>,
+    pub non_current_assets: Option<f64>,
+    pub total_assets: Option<f64>,
+    pub short_term_debt: Option<f64>,
+    pub long_term_debt: Option<f64>,
+    pub total_liabilities: Option<f64>,
+    pub equity: Option<f64>,
+    pub charter_capital: Option<f64>,
+    pub retained_earnings: Option<f64>,
+    pub minority_interest: Option<f64>,
+    pub raw: HashMap<String, f64>,
+}
+
+impl BalanceSheet {
+    pub fn from_statement(statement: &FinancialStatement) -> Self {
+        let data = &statement.data;
+        BalanceSheet {
+            period: statement.period.clone(),
+            cash: pick(data, &["cash"]),
+            short_term_investments: pick(data, &["short_invest", "short_term_investment"]),
+            receivables: pick(data, &["short_receivable", "receivable"]),
+            inventory: pick(data, &["inventory"]),
+            current_assets: pick(data, &["short_asset", "current_asset"]),
+            fixed_assets: pick(data, &["fixed_asset"]),
+            non_current_assets: pick(data, &["long_asset", "non_current_asset"]),
+            total_assets: pick(data, &["asset", "total_asset"]),
+            short_term_debt: pick(data, &["short_debt"]),
+            long_term_debt: pick(data, &["long_debt"]),
+            total_liabilities: pick(data, &["debt", "total_liability"]),
+            equity: pick(data, &["equity", "total_equity"]),
+            charter_capital: pick(data, &["capital"]),
+            retained_earnings: pick(data, &["un_distributed_income", "undistributed_income"]),
+            minority_interest: pick(data, &["minor_share_holder_profit"]),
+            raw: data.clone(),
+        }
+    }
+
+    /// Interest-bearing debt over equity.
+    pub fn debt_to_equity(&self) -> Option<f64> {
+        let debt = self.short_term_debt.unwrap_or(0.0) + self.long_term_debt.unwrap_or(0.0);
+        let known = self.short_term_debt.is_some() || self.long_term_debt.is_some();
+        self.equity.filter(|equity| known && *equity != 0.0).map(|equity| debt / equity)
+    }
+}
+
+/// Cash flow statement in provider units.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CashFlow {
+    pub period: String,
+    pub operating: Option<f64>,
+    pub investing: Option<f64>,
+    pub financing: Option<f64>,
+    /// Purchases of fixed assets, as reported (usually negative).
+    pub capital_expenditure: Option<f64>,
+    /// Reported free cash flow, else operating plus capital expenditure.
+    pub free_cash_flow: Option<f64>,
+    pub raw: HashMap<String, f64>,
+}
+
+impl CashFlow {
+    pub fn from_statement(statement: &FinancialStatement) -> Self {
+        let data = &statement.data;
+        let operating = pick(data, &["from_sale", "net_cash_flow_from_operating_activities"]);
+        let capital_expenditure = pick(data, &["invest_cost"]);
+        CashFlow {
+            period: statement.period.clone(),
+            operating,
+            investing: pick(data, &["from_invest", "net_cash_flow_from_investing_activities"]),
+            financing: pick(data, &["from_financial", "net_cash_flow_from_financing_activities"]),
+            capital_expenditure,
+            free_cash_flow: pick(data, &["free_cash_flow"]).or_else(|| Some(operating? + capital_expenditure?)),
+            raw: data.clone(),
+        }
+    }
+}
+
+/// All three statements of one symbol, each sorted newest period first
+/// whatever order the provider returned them in.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Financials {
+    pub symbol: String,
+    pub period: String,
+    pub income_statements: Vec<IncomeStatement>,
+    pub balance_sheets: Vec<BalanceSheet>,
+    pub cash_flows: Vec<CashFlow>,
+}
+
+impl Financials {
+    /// Normalizes a TCBS (or Fireant-backed) [`FinancialInfo`]; missing
+    /// statements become empty lists.
+    pub fn from_info(info: &FinancialInfo) -> Self {
+        Financials {
+            symbol: info.symbol.clone(),
+            period: info.period.clone(),
+            income_statements: newest_first(info.income_statement.iter().flatten().map(IncomeStatement::from_statement).collect(), |s| &s.period),
+            balance_sheets: newest_first(info.balance_sheet.iter().flatten().map(BalanceSheet::from_statement).collect(), |s| &s.period),
+            cash_flows: newest_first(info.cash_flow.iter().flatten().map(CashFlow::from_statement).collect(), |s| &s.period),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement(entries: &[(&str, f64)]) -> FinancialStatement {
+        FinancialStatement {
+            period: "2023".to_string(),
+            data: entries.iter().map(|(key, value)| (key.to_string(), *value)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_normalizes_tcbs_keys() {
+        let income = IncomeStatement::from_statement(&statement(&[("revenue", 52_618.0), ("post_tax_profit", 9_203.0), ("share_holder_income", 7_788.0)]));
+        assert_eq!((income.revenue, income.net_income, income.net_income_to_parent, income.ebitda), (Some(52_618.0), Some(9_203.0), Some(7_788.0), None));
+        assert_eq!(income.raw.len(), 3);
+
+        let balance = BalanceSheet::from_statement(&statement(&[("asset", 100.0), ("equity", 40.0), ("short_debt", 10.0), ("long_debt", 10.0)]));
+        assert_eq!((balance.total_assets, balance.debt_to_equity()), (Some(100.0), Some(0.5)));
+
+        let cash = CashFlow::from_statement(&statement(&[("from_sale", 30.0), ("invest_cost", -12.0)]));
+        assert_eq!(cash.free_cash_flow, Some(18.0));
+        assert_eq!(CashFlow::from_statement(&statement(&[("from_sale", 30.0)])).free_cash_flow, None);
+        assert_eq!(BalanceSheet::from_statement(&statement(&[("payable", 100.0)])).total_liabilities, None);
+    }
+
+    #[test]
+    fn test_normalizes_fireant_lines_newest_first() {
+        let line = |name: &str, q1: f64, q2: f64| serde_json::json!({"name": name, "values": [
+            {"year": 2024, "quarter": 1, "value": q1},
+            {"year": 2024, "quarter": 2, "value": q2}
+        ]});
+        let report = |lines: Vec<serde_json::Value>| crate::fireant::parse_full_report(&serde_json::Value::Array(lines)).unwrap();
+        let info = FinancialInfo {
+            symbol: "HPG".to_string(),
+            period: "quarter".to_string(),
+            income_statement: Some(report(vec![
+                line("1. Doanh thu thuần về bán hàng và cung cấp dịch vụ", 100.0, 120.0),
+                line("Giá vốn hàng bán", -80.0, -90.0),
+                line("Chi phí bán hàng", -5.0, -6.0),
+                line("Chi phí quản lý doanh nghiệp", -3.0, -4.0),
+            ])),
+            balance_sheet: Some(report(vec![
+                line("I. Tiền và các khoản tương đương tiền", 10.0, 12.0),
+                line("Hàng tồn kho", 30.0, 35.0),
+                line("Vay và nợ thuê tài chính ngắn hạn", 20.0, 25.0),
+                line("NỢ PHẢI TRẢ", 60.0, 70.0),
+                line("VỐN CHỦ SỞ HỮU", 50.0, 50.0),
+            ])),
+            cash_flow: Some(report(vec![
+                line("Lưu chuyển tiền thuần từ hoạt động kinh doanh", 15.0, 18.0),
+                line("Tiền chi để mua sắm, xây dựng TSCĐ và các tài sản dài hạn khác", -4.0, -5.0),
+            ])),
+            ratios: None,
+        };
+        let financials = Financials::from_info(&info);
+
+        let income = &financials.income_statements[0];
+        assert_eq!((income.period.as_str(), income.revenue, income.cost_of_goods_sold, income.operating_expenses), ("2024-Q2", Some(120.0), Some(-90.0), Some(-10.0)));
+        let balance = &financials.balance_sheets[0];
+        assert_eq!((balance.cash, balance.inventory, balance.total_liabilities, balance.debt_to_equity()), (Some(12.0), Some(35.0), Some(70.0), Some(0.5)));
+        let cash = &financials.cash_flows[1];
+        assert_eq!((cash.period.as_str(), cash.capital_expenditure, cash.free_cash_flow), ("2024-Q1", Some(-4.0), Some(11.0)));
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::failover::Provider;
+use crate::models::vietnam_offset;
+
+/// Upper bucket bounds in milliseconds; a final overflow bucket catches the rest.
+pub const BUCKET_BOUNDS_MS: [u64; 9] = [25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Fixed-bucket latency distribution of one endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// One count per bound in `BUCKET_BOUNDS_MS`, plus the overflow bucket.
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub failures: u64,
+    pub total_ms: f64,
+    pub min_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: Duration, success: bool) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; BUCKET_BOUNDS_MS.len() + 1];
+        }
+        let ms = latency.as_secs_f64() * 1000.0;
+        let bucket = BUCKET_BOUNDS_MS.iter()
+            .position(|&bound| ms <= bound as f64)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        if !success {
+            self.failures += 1;
+        }
+        self.total_ms += ms;
+        self.min_ms = Some(self.min_ms.map_or(ms, |min| min.min(ms)));
+        self.max_ms = Some(self.max_ms.map_or(ms, |max| max.max(ms)));
+    }
+
+    pub fn mean_ms(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.total_ms / self.count as f64)
+    }
+
+    /// Upper bound of the bucket holding quantile `q` (0.0..=1.0). Requests in
+    /// the overflow bucket report the observed maximum.
+    pub fn quantile_ms(&self, q: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let target = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, &count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                return match BUCKET_BOUNDS_MS.get(bucket) {
+                    Some(&bound) => Some(bound as f64),
+                    None => self.max_ms,
+                };
+            }
+        }
+        self.max_ms
+    }
+
+    pub fn failure_rate(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.failures as f64 / self.count as f64 }
+    }
+}
+
+/// Days of request counts kept for usage reports.
+pub const USAGE_RETENTION_DAYS: i64 = 31;
+
+/// Requests sent to a provider on one exchange-local day.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    pub requests: u64,
+    pub failures: u64,
+    /// Requests per endpoint key, to compare what each feature costs.
+    pub endpoints: BTreeMap<String, u64>,
+}
+
+/// Request counts of one client, oldest day first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub provider: Provider,
+    pub days: Vec<DailyUsage>,
+}
+
+impl UsageReport {
+    pub fn day(&self, date: NaiveDate) -> Option<&DailyUsage> {
+        self.days.iter().find(|day| day.date == date)
+    }
+
+    /// Usage of the current exchange-local day.
+    pub fn today(&self) -> Option<&DailyUsage> {
+        self.day(Utc::now().with_timezone(&vietnam_offset()).date_naive())
+    }
+
+    pub fn total_requests(&self) -> u64 {
+        self.days.iter().map(|day| day.requests).sum()
+    }
+
+    /// Days on which more than `daily_quota` requests were sent.
+    pub fn days_over(&self, daily_quota: u64) -> Vec<NaiveDate> {
+        self.days.iter().filter(|day| day.requests > daily_quota).map(|day| day.date).collect()
+    }
+}
+
+/// Per-endpoint latency histograms and daily request counts recorded by a
+/// client. Every HTTP attempt counts, including retries.
+#[derive(Debug, Default)]
+pub struct ClientStats {
+    endpoints: Mutex<HashMap<String, LatencyHistogram>>,
+    daily: Mutex<BTreeMap<NaiveDate, DailyUsage>>,
+}
+
+impl ClientStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, endpoint: &str, latency: Duration, success: bool) {
+        self.endpoints.lock().unwrap()
+            .entry(endpoint.to_string())
+            .or_default()
+            .record(latency, success);
+        self.count_on(Utc::now().with_timezone(&vietnam_offset()).date_naive(), endpoint, success);
+    }
+
+    fn count_on(&self, date: NaiveDate, endpoint: &str, success: bool) {
+        let mut daily = self.daily.lock().unwrap();
+        let day = daily.entry(date).or_insert_with(|| DailyUsage { date, ..Default::default() });
+        day.requests += 1;
+        if !success {
+            day.failures += 1;
+        }
+        *day.endpoints.entry(endpoint.to_string()).or_default() += 1;
+        let oldest = date - chrono::Duration::days(USAGE_RETENTION_DAYS - 1);
+        daily.retain(|&day, _| day >= oldest);
+    }
+
+    /// Daily request counts of the last [`USAGE_RETENTION_DAYS`] days.
+    pub fn usage(&self, provider: Provider) -> UsageReport {
+        UsageReport { provider, days: self.daily.lock().unwrap().values().cloned().collect() }
+    }
+
+    /// Clears the daily request counts. [`Self::reset`] leaves them alone so
+    /// quota tracking survives a latency reset.
+    pub fn reset_usage(&self) {
+        self.daily.lock().unwrap().clear();
+    }
+
+    /// Copy of the histograms, keyed by endpoint.
+    pub fn snapshot(&self) -> BTreeMap<String, LatencyHistogram> {
+        self.endpoints.lock().unwrap()
+            .iter()
+            .map(|(endpoint, histogram)| (endpoint.clone(), histogram.clone()))
+            .collect()
+    }
+
+    /// Clears the latency histograms.
+    pub fn reset(&self) {
+        self.endpoints.lock().unwrap().clear();
+    }
+}
+
+/// Endpoint key for `url`: the path without host or query string, with ticker segments replaced by `{symbol}` so per-symbol URLs
+/// share one histogram.
+pub fn endpoint_key(url: &str) -> String {
+    let path = url.split_once("://").map_or(url, |(_, rest)| rest.split_once('/').map_or("", |(_, path)| path));
+    let path = path.split('?').next().unwrap_or(path);
+    path.trim_matches('/')
+        .split('/')
+        .map(|segment| {
+            let is_ticker = (3..=10).contains(&segment.len())
+                && segment.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && segment.chars().any(|c| c.is_ascii_uppercase());
+            if is_ticker { "{symbol}" } else { segment }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Root fields of a GraphQL query, comma-separated, e.g.
+/// "CompanyListingInfo,TickerPriceInfo". GraphQL endpoints serve every
+/// feature from one URL, so stats key them by operation instead.
+pub fn graphql_operation(query: &str) -> String {
+    let mut fields: Vec<String> = Vec::new();
+    let (mut depth, mut parens) = (0usize, 0usize);
+    let mut token = String::new();
+    for ch in query.chars() {
+        if parens > 0 {
+            match ch {
+                '(' => parens += 1,
+                ')' => parens -= 1,
+                _ => {}
+            }
+            continue;
+        }
+        if depth == 1 && (ch.is_alphanumeric() || ch == '_') {
+            token.push(ch);
+            continue;
+        }
+        if depth == 1 && !token.is_empty() && ch != ':' {
+            fields.push(std::mem::take(&mut token));
+        }
+        token.clear();
+        match ch {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            '(' => parens += 1,
+            _ => {}
+        }
+    }
+    fields.dedup();
+    fields.join(",")
+}
+
+/// Stats key of a GraphQL request to `url`: the endpoint key plus the
+/// query's root fields.
+pub fn graphql_key(url: &str, query: &str) -> String {
+    format!("{}#{}", endpoint_key(url), graphql_operation(query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_quantiles() {
+        let mut histogram = LatencyHistogram::default();
+        for ms in [10, 40, 80, 90, 300] {
+            histogram.record(Duration::from_millis(ms), true);
+        }
+        histogram.record(Duration::from_millis(20_000), false);
+
+        assert_eq!(histogram.count, 6);
+        assert_eq!(histogram.quantile_ms(0.5), Some(100.0));
+        assert_eq!(histogram.quantile_ms(1.0), Some(20_000.0));
+        assert!((histogram.failure_rate() - 1.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_endpoint_key_groups_symbols() {
+        assert_eq!(endpoint_key("https://apipubaws.tcbs.com.vn/tcanalysis/v1/ticker/FPT/overview"), "tcanalysis/v1/ticker/{symbol}/overview");
+        assert_eq!(endpoint_key("https://apipubaws.tcbs.com.vn/stock-insight/v1/stock/bars?ticker=FPT"), "stock-insight/v1/stock/bars");
+        assert_eq!(endpoint_key("https://trading.vietcap.com.vn/data-mt/graphql"), "data-mt/graphql");
+
+        let query = "query Query($ticker: String!) {\n  CompanyListingInfo(ticker: $ticker) { issueShare __typename }\n  prices: TickerPriceInfo(ticker: $ticker) { financialRatio { pe } }\n}";
+        assert_eq!(graphql_key("https://trading.vietcap.com.vn/data-mt/graphql", query), "data-mt/graphql#CompanyListingInfo,TickerPriceInfo");
+        assert_eq!(graphql_operation("{ CompaniesListingInfo { ticker } }"), "CompaniesListingInfo");
+    }
+
+    #[test]
+    fn test_daily_usage_report() {
+        let stats = ClientStats::new();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+        stats.count_on(day(3), "data-mt/graphql", true);
+        stats.count_on(day(3), "api/chart/OHLCChart/gap", false);
+        stats.count_on(day(3), "data-mt/graphql", true);
+        stats.count_on(day(4), "data-mt/graphql", true);
+
+        let report = stats.usage(Provider::Vci);
+        assert_eq!(report.total_requests(), 4);
+        let first = report.day(day(3)).unwrap();
+        assert_eq!((first.requests, first.failures, first.endpoints["data-mt/graphql"]), (3, 1, 2));
+        assert_eq!(report.days_over(2), vec![day(3)]);
+
+        stats.count_on(day(3) + chrono::Duration::days(USAGE_RETENTION_DAYS), "data-mt/graphql", true);
+        assert_eq!(stats.usage(Provider::Vci).days.first().map(|usage| usage.date), Some(day(4)));
+
+        stats.reset();
+        assert_eq!(stats.usage(Provider::Vci).days.len(), 2);
+        stats.reset_usage();
+        assert!(stats.usage(Provider::Vci).days.is_empty());
+    }
+}
@@ -0,0 +1,388 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Timelike, Utc};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::models::Ohlcv;
+use crate::provenance::DataSource;
+use crate::resample;
+
+/// Coarse intervals kept up to date from daily bars on every write.
+pub const MATERIALIZED_INTERVALS: [&str; 2] = ["1W", "1M"];
+
+const CSV_HEADER: &str = "ticker,time,open,high,low,close,volume";
+
+const JOURNAL_HEADER: &str = "recorded_at,ticker,time,open,high,low,close,volume";
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(std::io::Error),
+    InvalidRecord(String),
+}
+
+impl From<std::io::Error> for StoreError {
+    fn from(error: std::io::Error) -> Self {
+        StoreError::Io(error)
+    }
+}
+
+/// CSV-backed local store laid out as `<root>/<interval>/<TICKER>.csv`, using
+/// the same `ticker,time,open,high,low,close,volume` columns as `market_data/`.
+///
+/// Daily bars written through [`LocalStore::upsert_daily`] also refresh the
+/// weekly and monthly views, so reading `1W`/`1M` never re-aggregates.
+///
+/// With [`LocalStore::with_journal`], every new or restated daily bar is also
+/// appended to `<root>/journal/<TICKER>.csv` with the time it was recorded,
+/// which [`LocalStore::as_of`] replays to show the series as it was known at
+/// a past instant.
+pub struct LocalStore {
+    root: PathBuf,
+    journaled: bool,
+}
+
+impl LocalStore {
+    pub fn open(root: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+        Ok(LocalStore { root, journaled: false })
+    }
+
+    /// Enables the append-only journal for daily upserts. History written
+    /// before enabling it cannot be reconstructed.
+    pub fn with_journal(mut self) -> Self {
+        self.journaled = true;
+        self
+    }
+
+    pub fn journal_path(&self, symbol: &str) -> PathBuf {
+        self.root.join("journal").join(format!("{}.csv", symbol.to_uppercase()))
+    }
+
+    pub fn source_path(&self, symbol: &str) -> PathBuf {
+        self.root.join("sources").join(format!("{}.json", symbol.to_uppercase()))
+    }
+
+    /// Records where the latest daily bars of `symbol` were fetched from, so
+    /// data served from the store keeps its provenance.
+    pub fn record_source(&self, symbol: &str, source: &DataSource) -> Result<(), StoreError> {
+        let path = self.source_path(symbol);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string(source).map_err(std::io::Error::other)?)?;
+        Ok(())
+    }
+
+    /// The last recorded source of `symbol`, `None` when none was recorded.
+    pub fn source(&self, symbol: &str) -> Option<DataSource> {
+        serde_json::from_str(&fs::read_to_string(self.source_path(symbol)).ok()?).ok()
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn path_for(&self, symbol: &str, interval: &str) -> PathBuf {
+        self.root.join(interval).join(format!("{}.csv", symbol.to_uppercase()))
+    }
+
+    /// Reads all stored bars for `symbol` at `interval`, oldest first. A
+    /// missing file yields an empty series.
+    pub fn read(&self, symbol: &str, interval: &str) -> Result<Vec<Ohlcv>, StoreError> {
+        let path = self.path_for(symbol, interval);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let reader = BufReader::new(fs::File::open(&path)?);
+        let mut bars = Vec::new();
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line_no == 0 || line.trim().is_empty() {
+                continue;
+            }
+            bars.push(parse_record(&line).ok_or_else(|| {
+                StoreError::InvalidRecord(format!("{}:{}: {}", path.display(), line_no + 1, line))
+            })?);
+        }
+
+        bars.sort_by_key(|bar| bar.time);
+        Ok(bars)
+    }
+
+    /// Replaces the stored series for `symbol` at `interval`.
+    pub fn write(&self, symbol: &str, interval: &str, bars: &[Ohlcv]) -> Result<(), StoreError> {
+        let path = self.path_for(symbol, interval);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let symbol = symbol.to_uppercase();
+        let mut writer = BufWriter::new(fs::File::create(&path)?);
+        writeln!(writer, "{}", CSV_HEADER)?;
+        for bar in bars {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                symbol,
+                format_time(bar.time),
+                bar.open,
+                bar.high,
+                bar.low,
+                bar.close,
+                bar.volume
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Merges daily bars into the `1D` series (new bars win on equal
+    /// timestamps) and refreshes the materialized weekly/monthly views from
+    /// the earliest affected period onward.
+    pub fn upsert_daily(&self, symbol: &str, bars: &[Ohlcv]) -> Result<(), StoreError> {
+        self.upsert_daily_at(symbol, bars, Utc::now())
+    }
+
+    fn upsert_daily_at(&self, symbol: &str, bars: &[Ohlcv], recorded_at: DateTime<Utc>) -> Result<(), StoreError> {
+        let Some(earliest) = bars.iter().map(|bar| bar.time).min() else {
+            return Ok(());
+        };
+
+        let mut merged: BTreeMap<DateTime<Utc>, Ohlcv> = self.read(symbol, "1D")?
+            .into_iter()
+            .map(|bar| (bar.time, bar))
+            .collect();
+        if self.journaled {
+            let changed: Vec<&Ohlcv> = bars.iter().filter(|bar| merged.get(&bar.time).is_none_or(|old| !same_values(old, bar))).collect();
+            self.append_journal(symbol, &changed, recorded_at)?;
+        }
+        for bar in bars {
+            merged.insert(bar.time, bar.clone());
+        }
+        let daily: Vec<Ohlcv> = merged.into_values().collect();
+        self.write(symbol, "1D", &daily)?;
+
+        for interval in MATERIALIZED_INTERVALS {
+            self.refresh_view(symbol, interval, &daily, Some(earliest))?;
+        }
+        Ok(())
+    }
+
+    fn append_journal(&self, symbol: &str, bars: &[&Ohlcv], recorded_at: DateTime<Utc>) -> Result<(), StoreError> {
+        if bars.is_empty() {
+            return Ok(());
+        }
+        let path = self.journal_path(symbol);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let is_new = !path.exists();
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        if is_new {
+            writeln!(file, "{}", JOURNAL_HEADER)?;
+        }
+        for bar in bars {
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{},{}",
+                recorded_at.to_rfc3339(),
+                symbol.to_uppercase(),
+                format_time(bar.time),
+                bar.open,
+                bar.high,
+                bar.low,
+                bar.close,
+                bar.volume
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Daily series for `symbol` as it was known at `timestamp`: for each
+    /// date, the latest version recorded at or before it. Bars recorded later
+    /// (new days or restatements) are not visible.
+    pub fn as_of(&self, symbol: &str, timestamp: DateTime<Utc>) -> Result<Vec<Ohlcv>, StoreError> {
+        let path = self.journal_path(symbol);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let reader = BufReader::new(fs::File::open(&path)?);
+        let mut versions: BTreeMap<DateTime<Utc>, Ohlcv> = BTreeMap::new();
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line_no == 0 || line.trim().is_empty() {
+                continue;
+            }
+            let entry = line.split_once(',').and_then(|(recorded_at, record)| {
+                Some((DateTime::parse_from_rfc3339(recorded_at).ok()?.with_timezone(&Utc), parse_record(record)?))
+            });
+            let (recorded_at, bar) = entry.ok_or_else(|| {
+                StoreError::InvalidRecord(format!("{}:{}: {}", path.display(), line_no + 1, line))
+            })?;
+            // Journal lines are in recording order, so later lines win
+            if recorded_at <= timestamp {
+                versions.insert(bar.time, bar);
+            }
+        }
+        Ok(versions.into_values().collect())
+    }
+
+    /// Rebuilds every materialized view for `symbol` from its full daily series.
+    pub fn rebuild_views(&self, symbol: &str) -> Result<(), StoreError> {
+        let daily = self.read(symbol, "1D")?;
+        for interval in MATERIALIZED_INTERVALS {
+            self.refresh_view(symbol, interval, &daily, None)?;
+        }
+        Ok(())
+    }
+
+    fn refresh_view(
+        &self,
+        symbol: &str,
+        interval: &str,
+        daily: &[Ohlcv],
+        changed_from: Option<DateTime<Utc>>,
+    ) -> Result<(), StoreError> {
+        let Some(period_start) = resample::period_start_fn(interval) else {
+            return Ok(());
+        };
+
+        let view = match changed_from {
+            Some(changed) => {
+                // Periods before the first touched one are unaffected
+                let cutoff = period_start(changed);
+                let mut view: Vec<Ohlcv> = self.read(symbol, interval)?
+                    .into_iter()
+                    .filter(|bar| bar.time < cutoff)
+                    .collect();
+                let touched: Vec<Ohlcv> = daily.iter()
+                    .filter(|bar| bar.time >= cutoff)
+                    .cloned()
+                    .collect();
+                view.extend(resample::aggregate(&touched, period_start));
+                view
+            }
+            None => resample::aggregate(daily, period_start),
+        };
+
+        self.write(symbol, interval, &view)
+    }
+}
+
+fn same_values(a: &Ohlcv, b: &Ohlcv) -> bool {
+    (a.open, a.high, a.low, a.close, a.volume) == (b.open, b.high, b.low, b.close, b.volume)
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    if time.hour() == 0 && time.minute() == 0 && time.second() == 0 {
+        time.format("%Y-%m-%d").to_string()
+    } else {
+        time.format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        return Some(datetime.and_utc());
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| datetime.and_utc())
+}
+
+fn parse_record(line: &str) -> Option<Ohlcv> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() < 7 {
+        return None;
+    }
+
+    Some(Ohlcv {
+        time: parse_time(fields[1])?,
+        open: fields[2].parse().ok()?,
+        high: fields[3].parse().ok()?,
+        low: fields[4].parse().ok()?,
+        close: fields[5].parse().ok()?,
+        volume: fields[6].parse::<f64>().ok()? as u64,
+        symbol: Some(fields[0].to_string()),
+        breakdown: None,
+        futures: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn bar(month: u32, day: u32, close: f64, volume: u64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, month, day, 0, 0, 0).unwrap(),
+            open: close - 1.0,
+            high: close + 1.0,
+            low: close - 2.0,
+            close,
+            volume,
+            symbol: Some("FPT".to_string()),
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_upsert_daily_maintains_views() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalStore::open(dir.path()).unwrap();
+
+        store.upsert_daily("fpt", &[bar(1, 29, 100.0, 10), bar(1, 30, 101.0, 20)]).unwrap();
+        store.upsert_daily("FPT", &[bar(2, 1, 105.0, 30), bar(1, 30, 102.0, 25)]).unwrap();
+
+        let daily = store.read("FPT", "1D").unwrap();
+        assert_eq!(daily.len(), 3);
+        assert_eq!(daily[1].close, 102.0);
+
+        let weekly = store.read("FPT", "1W").unwrap();
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly[0].volume, 65);
+        assert_eq!(weekly[0].close, 105.0);
+
+        let monthly = store.read("FPT", "1M").unwrap();
+        assert_eq!(monthly.len(), 2);
+        assert_eq!(monthly[0].volume, 35);
+        assert_eq!(monthly[1].volume, 30);
+    }
+
+    #[test]
+    fn test_as_of_replays_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalStore::open(dir.path()).unwrap().with_journal();
+        let t1 = Utc.with_ymd_and_hms(2024, 1, 30, 9, 0, 0).unwrap();
+        let t2 = Utc.with_ymd_and_hms(2024, 2, 2, 9, 0, 0).unwrap();
+
+        store.upsert_daily_at("FPT", &[bar(1, 29, 100.0, 10), bar(1, 30, 101.0, 20)], t1).unwrap();
+        // Restates the 30th and adds a day; the unchanged 29th is not journaled again
+        store.upsert_daily_at("FPT", &[bar(1, 29, 100.0, 10), bar(1, 30, 99.0, 20), bar(2, 1, 97.0, 5)], t2).unwrap();
+
+        let before = store.as_of("FPT", t1).unwrap();
+        assert_eq!(before.len(), 2);
+        assert_eq!(before[1].close, 101.0);
+        assert_eq!(store.as_of("FPT", t2).unwrap(), store.read("FPT", "1D").unwrap());
+        assert!(store.as_of("FPT", t1 - chrono::Duration::hours(1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_missing_series_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalStore::open(dir.path()).unwrap();
+        assert!(store.read("VCB", "1D").unwrap().is_empty());
+        assert!(store.source("VCB").is_none());
+
+        let source = DataSource { provider: crate::failover::Provider::Tcbs, fetched_at: Utc.with_ymd_and_hms(2024, 6, 3, 8, 0, 0).unwrap() };
+        store.record_source("vcb", &source).unwrap();
+        assert_eq!(store.source("VCB"), Some(source));
+    }
+}
@@ -0,0 +1,728 @@
+use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, Stream, StreamExt};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::Instant;
+
+use crate::auction::{AuctionImbalance, ImbalanceTracker};
+use crate::models::{IndexTick, Interval, Ohlcv, Quote};
+use crate::session::SessionFilter;
+use crate::shutdown::{self, ShutdownToken};
+use crate::vci::VciClient;
+
+/// Polling cadence, backoff and buffering for long-poll subscriptions.
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    pub poll_interval: Duration,
+    /// Events buffered before the poller waits on a slow consumer.
+    pub channel_capacity: usize,
+    /// Suppress events identical to the previous one for the same symbol.
+    pub deduplicate: bool,
+    /// Longest wait between retries after consecutive failed polls.
+    pub max_backoff: Duration,
+    /// Warn when no poll has succeeded with changed data for this long.
+    pub heartbeat_timeout: Duration,
+    /// Ends the stream once signalled, for daemon shutdown.
+    pub shutdown: Option<ShutdownToken>,
+}
+
+impl Default for StreamConfig {
+    fn default() -> Self {
+        StreamConfig {
+            poll_interval: Duration::from_secs(3),
+            channel_capacity: 1024,
+            deduplicate: false,
+            max_backoff: Duration::from_secs(60),
+            heartbeat_timeout: Duration::from_secs(120),
+            shutdown: None,
+        }
+    }
+}
+
+/// Spawns a poller feeding a bounded channel from `fetch` and returns the
+/// receiving end as a stream. After a failed poll the next attempt waits
+/// twice as long as the last, up to `max_backoff`; the first success
+/// restores the normal cadence. The feed is considered alive while polls
+/// keep returning items `is_new` accepts, and a stale feed is logged once
+/// per `heartbeat_timeout` of silence. With `deduplicate` set, rejected
+/// items are dropped. The poller stops once the stream is dropped or the
+/// config's shutdown token is signalled.
+fn poll_feed<T, F, Fut, E, N>(config: &StreamConfig, label: &'static str, mut fetch: F, mut is_new: N) -> BoxStream<'static, T>
+where
+    T: Send + 'static,
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<Vec<T>, E>> + Send,
+    E: std::fmt::Debug + Send,
+    N: FnMut(&T) -> bool + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(config.channel_capacity.max(1));
+    let StreamConfig { poll_interval, deduplicate, max_backoff, heartbeat_timeout, mut shutdown, .. } = config.clone();
+    tokio::spawn(async move {
+        let mut failures = 0u32;
+        let mut last_alive = Instant::now();
+        loop {
+            match fetch().await {
+                Ok(items) => {
+                    if failures > 0 {
+                        tracing::info!("{} feed recovered after {} failed polls", label, failures);
+                    }
+                    failures = 0;
+                    for item in items {
+                        if is_new(&item) {
+                            last_alive = Instant::now();
+                        } else if deduplicate {
+                            continue;
+                        }
+                        if tx.send(item).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(e) => {
+                    failures += 1;
+                    tracing::warn!("{} poll failed ({} in a row): {:?}", label, failures, e);
+                }
+            }
+
+            if last_alive.elapsed() >= heartbeat_timeout {
+                tracing::warn!("{} feed stale: no changed data for {:?}", label, last_alive.elapsed());
+                last_alive = Instant::now();
+            }
+
+            let delay = match failures {
+                0 => poll_interval,
+                n => (poll_interval * 2u32.saturating_pow(n.min(16))).min(max_backoff.max(poll_interval)),
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = tx.closed() => return,
+                _ = shutdown::signalled(&mut shutdown) => return,
+            }
+        }
+    });
+
+    futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|item| (item, rx)) }).boxed()
+}
+
+/// Index levels for `indices`, long-polled from the VCI market-index board,
+/// with retry backoff and stale-feed detection. A tick counts as changed
+/// when its value or volume moved.
+pub fn subscribe_index_ticks(client: Arc<VciClient>, indices: &[String], config: StreamConfig) -> BoxStream<'static, IndexTick> {
+    let indices: Vec<String> = indices.iter().map(|s| s.to_uppercase()).collect();
+    let mut last_seen: HashMap<String, IndexTick> = HashMap::new();
+    poll_feed(
+        &config,
+        "Index tick",
+        move || {
+            let client = Arc::clone(&client);
+            let indices = indices.clone();
+            async move { client.index_ticks(&indices).await }
+        },
+        move |tick| {
+            let previous = last_seen.insert(tick.symbol.clone(), tick.clone());
+            previous.is_none_or(|previous| previous.value != tick.value || previous.volume != tick.volume)
+        },
+    )
+}
+
+/// Auction imbalance readings for `symbols`, one per symbol per poll while
+/// an ATO/ATC auction runs, long-polled from the VCI price board with retry
+/// backoff and stale-feed detection. Start it before the auction window so
+/// drift is measured from the last continuous price.
+pub fn subscribe_auction_imbalance(client: Arc<VciClient>, symbols: &[String], config: StreamConfig) -> BoxStream<'static, AuctionImbalance> {
+    let symbols: Vec<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+    let snapshots = poll_feed(
+        &config,
+        "Auction snapshot",
+        move || {
+            let client = Arc::clone(&client);
+            let symbols = symbols.clone();
+            async move { client.auction_snapshots(&symbols).await }
+        },
+        |_| true,
+    );
+    let mut tracker = ImbalanceTracker::default();
+    snapshots.filter_map(move |snapshot| futures::future::ready(tracker.update(&snapshot))).boxed()
+}
+
+/// Drives a quote feed from `fetch`, treating quotes that repeat the
+/// previous price and volume as unchanged.
+fn quote_feed<F, Fut, E>(config: &StreamConfig, fetch: F) -> BoxStream<'static, Quote>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<Vec<Quote>, E>> + Send,
+    E: std::fmt::Debug + Send,
+{
+    let mut dedup = QuoteDeduplicator::default();
+    poll_feed(config, "Quote", fetch, move |quote| dedup.is_new(quote))
+}
+
+/// Quotes for `symbols`, long-polled from the VCI price board, with retry
+/// backoff and stale-feed detection. The poller stops once the stream is
+/// dropped.
+pub fn subscribe_quotes(client: Arc<VciClient>, symbols: &[String], config: StreamConfig) -> BoxStream<'static, Quote> {
+    let symbols: Vec<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+    quote_feed(&config, move || {
+        let client = Arc::clone(&client);
+        let symbols = symbols.clone();
+        async move { client.quotes(&symbols).await }
+    })
+}
+
+struct OpenBar {
+    bar: Ohlcv,
+    last_volume: Option<u64>,
+}
+
+/// Builds fixed-length intraday bars from level samples, per symbol.
+/// Volume is the increase in accumulated session volume within the bar.
+pub struct BarBuilder {
+    duration_secs: i64,
+    open: HashMap<String, OpenBar>,
+    session: Option<SessionFilter>,
+}
+
+impl BarBuilder {
+    /// Returns `None` for intervals without a fixed length (daily and above).
+    pub fn new(interval: Interval) -> Option<Self> {
+        Some(BarBuilder {
+            duration_secs: interval.duration_secs()?,
+            open: HashMap::new(),
+            session: None,
+        })
+    }
+
+    /// Ignores samples outside trading hours (and auctions, if excluded),
+    /// so polling through the lunch break produces no bars.
+    pub fn with_session(mut self, session: SessionFilter) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    fn bucket_start(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        let secs = time.timestamp();
+        DateTime::from_timestamp(secs - secs.rem_euclid(self.duration_secs), 0).unwrap_or(time)
+    }
+
+    /// Applies a sample; returns the previous bar when this one starts a new bucket.
+    pub fn update(&mut self, symbol: &str, time: DateTime<Utc>, price: f64, accumulated_volume: Option<u64>) -> Option<Ohlcv> {
+        if self.session.is_some_and(|session| !session.accepts(time)) {
+            return None;
+        }
+        let start = self.bucket_start(time);
+        let symbol = symbol.to_uppercase();
+
+        if let Some(open) = self.open.get_mut(&symbol) {
+            if open.bar.time == start {
+                open.bar.high = open.bar.high.max(price);
+                open.bar.low = open.bar.low.min(price);
+                open.bar.close = price;
+                if let (Some(now), Some(last)) = (accumulated_volume, open.last_volume) {
+                    open.bar.volume += now.saturating_sub(last);
+                }
+                open.last_volume = accumulated_volume.or(open.last_volume);
+                return None;
+            }
+            if start < open.bar.time {
+                return None; // Late sample for a closed bucket
+            }
+        }
+
+        let last_volume = self.open.get(&symbol).and_then(|open| open.last_volume);
+        let volume = match (accumulated_volume, last_volume) {
+            (Some(now), Some(last)) => now.saturating_sub(last),
+            _ => 0,
+        };
+        let next = OpenBar {
+            bar: Ohlcv {
+                time: start,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume,
+                symbol: Some(symbol.clone()),
+                breakdown: None,
+                futures: None,
+            },
+            last_volume: accumulated_volume.or(last_volume),
+        };
+        self.open.insert(symbol, next).map(|previous| previous.bar)
+    }
+
+    /// Removes and returns all open bars.
+    pub fn flush(&mut self) -> Vec<Ohlcv> {
+        let mut bars: Vec<Ohlcv> = self.open.drain().map(|(_, open)| open.bar).collect();
+        bars.sort_by(|a, b| (a.time, &a.symbol).cmp(&(b.time, &b.symbol)));
+        bars
+    }
+}
+
+/// Turns an index tick stream into completed intraday bars. Each bar is
+/// emitted when the first tick of the next bucket arrives; open bars are
+/// flushed when the tick stream ends. Pass a session filter to skip
+/// samples taken while the market is closed.
+pub fn index_bars<S>(ticks: S, interval: Interval, session: Option<SessionFilter>) -> Option<BoxStream<'static, Ohlcv>>
+where
+    S: Stream<Item = IndexTick> + Send + 'static,
+{
+    let mut builder = BarBuilder::new(interval)?;
+    if let Some(session) = session {
+        builder = builder.with_session(session);
+    }
+    let state = (ticks.boxed().fuse(), builder, Vec::<Ohlcv>::new());
+    let bars = futures::stream::unfold(state, |(mut ticks, mut builder, mut pending)| async move {
+        loop {
+            if let Some(bar) = pending.pop() {
+                return Some((bar, (ticks, builder, pending)));
+            }
+            match ticks.next().await {
+                Some(tick) => {
+                    if let Some(bar) = builder.update(&tick.symbol, tick.time, tick.value, tick.volume) {
+                        return Some((bar, (ticks, builder, pending)));
+                    }
+                }
+                None => {
+                    pending = builder.flush();
+                    pending.reverse();
+                    if pending.is_empty() {
+                        return None;
+                    }
+                }
+            }
+        }
+    });
+    Some(bars.boxed())
+}
+
+/// Fan-out point for one quote feed shared by several consumers.
+#[derive(Clone)]
+pub struct QuoteBroadcast {
+    sender: broadcast::Sender<Quote>,
+}
+
+impl QuoteBroadcast {
+    /// `capacity` quotes are retained for slow subscribers before they start
+    /// skipping ahead.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        QuoteBroadcast { sender }
+    }
+
+    /// Publishes to current subscribers; returns how many received it.
+    pub fn publish(&self, quote: Quote) -> usize {
+        self.sender.send(quote).unwrap_or(0)
+    }
+
+    /// Forwards every quote of `quotes` into the broadcast until it ends.
+    pub fn spawn_feed<S>(&self, quotes: S) -> tokio::task::JoinHandle<()>
+    where
+        S: Stream<Item = Quote> + Send + 'static,
+    {
+        let broadcast = self.clone();
+        tokio::spawn(async move {
+            let mut quotes = Box::pin(quotes);
+            while let Some(quote) = quotes.next().await {
+                broadcast.publish(quote);
+            }
+        })
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Every quote, in order. A subscriber that falls too far behind skips
+    /// the quotes it missed.
+    pub fn subscribe(&self) -> BoxStream<'static, Quote> {
+        let receiver = self.sender.subscribe();
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(quote) => return Some((quote, receiver)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("Quote subscriber lagged, skipped {} quotes", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// At most one quote per symbol every `min_interval`. Quotes arriving
+    /// sooner are conflated: only the latest is delivered once the interval
+    /// has passed.
+    pub fn subscribe_throttled(&self, min_interval: Duration) -> BoxStream<'static, Quote> {
+        let mut receiver = self.sender.subscribe();
+        let (tx, rx) = mpsc::channel(1024);
+        tokio::spawn(async move {
+            let mut last_sent: HashMap<String, Instant> = HashMap::new();
+            let mut pending: HashMap<String, Quote> = HashMap::new();
+            let mut ticker = tokio::time::interval(min_interval.max(Duration::from_millis(10)));
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                let mut due = Vec::new();
+                tokio::select! {
+                    received = receiver.recv() => match received {
+                        Ok(quote) => {
+                            let now = Instant::now();
+                            let ready = last_sent.get(&quote.symbol).is_none_or(|&sent| now - sent >= min_interval);
+                            if ready {
+                                pending.remove(&quote.symbol);
+                                last_sent.insert(quote.symbol.clone(), now);
+                                due.push(quote);
+                            } else {
+                                pending.insert(quote.symbol.clone(), quote);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => {
+                            for quote in pending.into_values() {
+                                let _ = tx.send(quote).await;
+                            }
+                            return;
+                        }
+                    },
+                    _ = ticker.tick() => {
+                        let now = Instant::now();
+                        let ready: Vec<String> = pending.keys()
+                            .filter(|symbol| last_sent.get(*symbol).is_none_or(|&sent| now - sent >= min_interval))
+                            .cloned()
+                            .collect();
+                        for symbol in ready {
+                            if let Some(quote) = pending.remove(&symbol) {
+                                last_sent.insert(symbol, now);
+                                due.push(quote);
+                            }
+                        }
+                    }
+                }
+                for quote in due {
+                    if tx.send(quote).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|quote| (quote, rx)) }).boxed()
+    }
+}
+
+/// What counts as a change when deduplicating quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DedupMode {
+    /// Last price and accumulated volume.
+    #[default]
+    PriceVolume,
+    /// Price, volume and best bid/ask, for consumers that render the book top.
+    WithBook,
+}
+
+/// Drops quotes that repeat the previous quote for the same symbol.
+#[derive(Debug, Clone, Default)]
+pub struct QuoteDeduplicator {
+    pub mode: DedupMode,
+    last: HashMap<String, Quote>,
+}
+
+impl QuoteDeduplicator {
+    pub fn new(mode: DedupMode) -> Self {
+        QuoteDeduplicator {
+            mode,
+            last: HashMap::new(),
+        }
+    }
+
+    /// True when `quote` differs from the last one passed for its symbol.
+    pub fn is_new(&mut self, quote: &Quote) -> bool {
+        let changed = match self.last.get(&quote.symbol) {
+            None => true,
+            Some(previous) => {
+                previous.price != quote.price
+                    || previous.volume != quote.volume
+                    || (self.mode == DedupMode::WithBook
+                        && (previous.best_bid != quote.best_bid || previous.best_ask != quote.best_ask))
+            }
+        };
+        if changed {
+            self.last.insert(quote.symbol.clone(), quote.clone());
+        }
+        changed
+    }
+
+    pub fn apply<S>(mut self, quotes: S) -> BoxStream<'static, Quote>
+    where
+        S: Stream<Item = Quote> + Send + 'static,
+    {
+        quotes
+            .filter(move |quote| futures::future::ready(self.is_new(quote)))
+            .boxed()
+    }
+}
+
+/// Reason a live quote looks corrupt.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteAnomaly {
+    NonPositivePrice,
+    /// Price outside the day's floor/ceiling band.
+    OutsideBand { floor: f64, ceiling: f64 },
+    /// Accumulated session volume went backwards within the same day.
+    VolumeDecreased { previous: u64, current: u64 },
+    /// Volume added since the previous quote exceeds the configured limit.
+    VolumeSpike { delta: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyAction {
+    /// Pass every quote through with its anomalies attached.
+    Flag,
+    /// Drop anomalous quotes; they never reach the consumer.
+    Drop,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlaggedQuote {
+    pub quote: Quote,
+    pub anomalies: Vec<QuoteAnomaly>,
+}
+
+/// Sanity checks for live quotes, tracking the last accepted quote per symbol.
+#[derive(Debug, Clone)]
+pub struct AnomalyFilter {
+    pub action: AnomalyAction,
+    /// Largest plausible volume increase between two consecutive quotes.
+    pub max_volume_delta: Option<u64>,
+    last: HashMap<String, Quote>,
+}
+
+impl AnomalyFilter {
+    pub fn new(action: AnomalyAction) -> Self {
+        AnomalyFilter {
+            action,
+            max_volume_delta: None,
+            last: HashMap::new(),
+        }
+    }
+
+    pub fn with_max_volume_delta(mut self, delta: u64) -> Self {
+        self.max_volume_delta = Some(delta);
+        self
+    }
+
+    /// Anomalies in `quote`. Only clean quotes become the new baseline for
+    /// volume checks, so one garbage print can't poison the next comparison.
+    pub fn check(&mut self, quote: &Quote) -> Vec<QuoteAnomaly> {
+        let mut anomalies = Vec::new();
+        if quote.price <= 0.0 || !quote.price.is_finite() {
+            anomalies.push(QuoteAnomaly::NonPositivePrice);
+        }
+        if let (Some(floor), Some(ceiling)) = (quote.floor_price, quote.ceiling_price) {
+            if floor > 0.0 && ceiling > 0.0 && (quote.price < floor - 1e-6 || quote.price > ceiling + 1e-6) {
+                anomalies.push(QuoteAnomaly::OutsideBand { floor, ceiling });
+            }
+        }
+
+        if let Some(previous) = self.last.get(&quote.symbol) {
+            if previous.time.date_naive() == quote.time.date_naive() {
+                if quote.volume < previous.volume {
+                    anomalies.push(QuoteAnomaly::VolumeDecreased { previous: previous.volume, current: quote.volume });
+                } else if let Some(max) = self.max_volume_delta {
+                    let delta = quote.volume - previous.volume;
+                    if delta > max {
+                        anomalies.push(QuoteAnomaly::VolumeSpike { delta });
+                    }
+                }
+            }
+        }
+
+        if anomalies.is_empty() {
+            self.last.insert(quote.symbol.clone(), quote.clone());
+        }
+        anomalies
+    }
+
+    /// Applies the filter to a quote stream according to [`AnomalyFilter::action`].
+    pub fn apply<S>(self, quotes: S) -> BoxStream<'static, FlaggedQuote>
+    where
+        S: Stream<Item = Quote> + Send + 'static,
+    {
+        quotes
+            .scan(self, |filter, quote| {
+                let anomalies = filter.check(&quote);
+                let keep = filter.action == AnomalyAction::Flag || anomalies.is_empty();
+                if !keep {
+                    tracing::debug!("Dropping anomalous quote for {}: {:?}", quote.symbol, anomalies);
+                }
+                futures::future::ready(Some(keep.then_some(FlaggedQuote { quote, anomalies })))
+            })
+            .filter_map(futures::future::ready)
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn tick(minute: u32, second: u32, value: f64, volume: u64) -> IndexTick {
+        IndexTick {
+            symbol: "VN30".to_string(),
+            time: Utc.with_ymd_and_hms(2024, 6, 3, 2, minute, second).unwrap(),
+            value,
+            reference_value: None,
+            volume: Some(volume),
+        }
+    }
+
+    fn quote(price: f64, volume: u64) -> Quote {
+        Quote {
+            symbol: "FPT".to_string(),
+            time: Utc.with_ymd_and_hms(2024, 6, 3, 3, 0, 0).unwrap(),
+            price,
+            reference_price: Some(100.0),
+            ceiling_price: Some(107.0),
+            floor_price: Some(93.0),
+            open: None,
+            high: None,
+            low: None,
+            volume,
+            value: None,
+            best_bid: None,
+            best_ask: None,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_quote_feed_backs_off_and_recovers() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&calls);
+        let config = StreamConfig { deduplicate: true, ..StreamConfig::default() };
+        let mut quotes = quote_feed(&config, move || {
+            let mut log = log.lock().unwrap();
+            log.push(Instant::now());
+            let result = match log.len() {
+                1 | 2 => Err("connection reset"),
+                3 | 4 => Ok(vec![quote(101.0, 1000)]),
+                _ => Ok(vec![quote(101.5, 1200)]),
+            };
+            async move { result }
+        });
+
+        assert_eq!(quotes.next().await.unwrap().volume, 1000);
+        // The repeated quote from the fourth poll is deduplicated
+        assert_eq!(quotes.next().await.unwrap().volume, 1200);
+        let calls = calls.lock().unwrap();
+        let gaps: Vec<Duration> = calls.windows(2).map(|w| w[1] - w[0]).collect();
+        assert_eq!(&gaps[..3], &[Duration::from_secs(6), Duration::from_secs(12), Duration::from_secs(3)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_poll_feed_stops_when_dropped() {
+        let polls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&polls);
+        let fetch = move || {
+            let n = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            async move { Ok::<_, String>(if n == 0 { vec![n] } else { Vec::new() }) }
+        };
+        let mut items = poll_feed(&StreamConfig::default(), "Test", fetch, |_| true);
+        assert_eq!(items.next().await, Some(0));
+        drop(items);
+
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        let after_drop = polls.load(std::sync::atomic::Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        assert_eq!(polls.load(std::sync::atomic::Ordering::SeqCst), after_drop);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pollers_stop_on_shutdown() {
+        let shutdown = crate::shutdown::Shutdown::new();
+        let config = StreamConfig { shutdown: Some(shutdown.token()), ..StreamConfig::default() };
+        let mut items = poll_feed(&config, "Test", || async { Ok::<_, String>(vec![1]) }, |_| true);
+        let mut quotes = quote_feed(&config, || async { Ok::<_, String>(vec![quote(101.0, 1000)]) });
+        assert_eq!(items.next().await, Some(1));
+        assert!(quotes.next().await.is_some());
+
+        let client = Arc::new(VciClient::new(false, 6).unwrap());
+        let cache = crate::metadata::MetadataCache::new(client, chrono::Duration::hours(12));
+        shutdown.register("metadata", cache.spawn_refresh_loop(Duration::from_secs(60), Some(shutdown.token())));
+        let report = shutdown.shutdown(Duration::from_secs(10)).await;
+
+        assert_eq!(report.stopped, vec!["metadata".to_string()]);
+        assert!(report.is_clean());
+        // Buffered items drain, then both streams end
+        while items.next().await.is_some() {}
+        while quotes.next().await.is_some() {}
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttled_subscriber_conflates() {
+        let broadcast = QuoteBroadcast::new(64);
+        let mut all = broadcast.subscribe();
+        let mut throttled = broadcast.subscribe_throttled(Duration::from_secs(1));
+        tokio::task::yield_now().await;
+
+        for volume in [1000, 1100, 1200] {
+            broadcast.publish(quote(101.0, volume));
+        }
+        assert_eq!(all.next().await.unwrap().volume, 1000);
+        assert_eq!(throttled.next().await.unwrap().volume, 1000);
+
+        // 1100 is conflated away; 1200 arrives once the second has passed
+        let conflated = throttled.next().await.unwrap();
+        assert_eq!(conflated.volume, 1200);
+        assert_eq!(all.next().await.unwrap().volume, 1100);
+    }
+
+    #[tokio::test]
+    async fn test_quote_deduplication() {
+        let mut with_bid = quote(101.0, 1000);
+        with_bid.best_bid = Some(100.9);
+        let quotes = vec![quote(101.0, 1000), quote(101.0, 1000), with_bid.clone(), quote(101.0, 1100)];
+
+        let by_price: Vec<Quote> = QuoteDeduplicator::default().apply(futures::stream::iter(quotes.clone())).collect().await;
+        assert_eq!(by_price.len(), 2);
+        let with_book: Vec<Quote> = QuoteDeduplicator::new(DedupMode::WithBook).apply(futures::stream::iter(quotes)).collect().await;
+        assert_eq!(with_book.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_anomaly_filter() {
+        let mut filter = AnomalyFilter::new(AnomalyAction::Flag).with_max_volume_delta(1_000_000);
+        assert!(filter.check(&quote(101.0, 1000)).is_empty());
+        assert_eq!(filter.check(&quote(150.0, 1000)), vec![QuoteAnomaly::OutsideBand { floor: 93.0, ceiling: 107.0 }]);
+        assert_eq!(filter.check(&quote(101.0, 500)), vec![QuoteAnomaly::VolumeDecreased { previous: 1000, current: 500 }]);
+        assert_eq!(filter.check(&quote(101.0, 5_000_000)), vec![QuoteAnomaly::VolumeSpike { delta: 4_999_000 }]);
+
+        let quotes = futures::stream::iter(vec![quote(101.0, 1000), quote(0.0, 1000), quote(102.0, 1200)]);
+        let kept: Vec<FlaggedQuote> = AnomalyFilter::new(AnomalyAction::Drop).apply(quotes).collect().await;
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[1].quote.price, 102.0);
+    }
+
+    #[tokio::test]
+    async fn test_index_bars_from_ticks() {
+        let ticks = futures::stream::iter(vec![
+            tick(0, 5, 1300.0, 1000),
+            tick(0, 40, 1302.5, 1500),
+            tick(0, 50, 1299.0, 1800),
+            tick(1, 2, 1301.0, 2000),
+        ]);
+        let bars: Vec<Ohlcv> = index_bars(ticks, Interval::M1, None).unwrap().collect().await;
+
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].open, 1300.0);
+        assert_eq!(bars[0].high, 1302.5);
+        assert_eq!(bars[0].low, 1299.0);
+        assert_eq!(bars[0].close, 1299.0);
+        assert_eq!(bars[0].volume, 800);
+        assert_eq!(bars[1].volume, 200);
+        assert!(index_bars(futures::stream::empty(), Interval::D1, None).is_none());
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::calendar;
+use crate::models::Ohlcv;
+use crate::risk::Holding;
+use crate::store::{LocalStore, StoreError};
+
+/// Historical period replayed against current holdings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShockWindow {
+    pub name: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl ShockWindow {
+    pub fn new(name: &str, start: NaiveDate, end: NaiveDate) -> Self {
+        ShockWindow { name: name.to_string(), start, end }
+    }
+
+    /// VNINDEX peak-to-trough windows of recent market-wide selloffs.
+    pub fn presets() -> Vec<ShockWindow> {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        vec![
+            ShockWindow::new("2018 correction", date(2018, 4, 9), date(2018, 7, 5)),
+            ShockWindow::new("COVID crash", date(2020, 1, 20), date(2020, 3, 24)),
+            ShockWindow::new("2022 correction", date(2022, 4, 4), date(2022, 11, 16)),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StressResult {
+    pub window: ShockWindow,
+    pub start_value: f64,
+    pub end_value: f64,
+    pub max_drawdown: f64,
+    pub worst_day: Option<(NaiveDate, f64)>,
+    /// Window return per holding, after any proxy substitution.
+    pub by_symbol: BTreeMap<String, f64>,
+    /// Holdings with no stored history covering the window start. They
+    /// follow the proxy when one is available, otherwise stay flat.
+    pub missing: Vec<String>,
+}
+
+impl StressResult {
+    pub fn total_return(&self) -> f64 {
+        if self.start_value > 0.0 { self.end_value / self.start_value - 1.0 } else { 0.0 }
+    }
+
+    pub fn pnl(&self) -> f64 {
+        self.end_value - self.start_value
+    }
+}
+
+/// Close on each of `days`, relative to the last close at or before the first
+/// day, carrying forward over suspensions. `None` if nothing precedes it.
+fn relative_path(bars: &[Ohlcv], days: &[NaiveDate]) -> Option<Vec<f64>> {
+    let closes: BTreeMap<NaiveDate, f64> = bars.iter().filter(|b| b.close > 0.0).map(|b| (b.time.date_naive(), b.close)).collect();
+    let first = *days.first()?;
+    let base = closes.range(..=first).next_back()?.1;
+    let mut last = *base;
+    Some(days.iter().map(|day| {
+        if let Some(close) = closes.get(day) {
+            last = *close;
+        }
+        last / base
+    }).collect())
+}
+
+/// Replays `window` against `holdings` using daily bars from `store`.
+/// Holdings without history at the window start follow the `proxy`
+/// symbol's series (typically VNINDEX) when the store has it.
+pub fn stress_test(holdings: &[Holding], store: &LocalStore, window: &ShockWindow, proxy: Option<&str>) -> Result<StressResult, StoreError> {
+    let days = calendar::trading_days_between(window.start, window.end);
+    let proxy_path = match proxy {
+        Some(symbol) => relative_path(&store.read(symbol, "1D")?, &days),
+        None => None,
+    };
+
+    let mut values = vec![0.0; days.len()];
+    let mut by_symbol = BTreeMap::new();
+    let mut missing = Vec::new();
+    for holding in holdings {
+        let path = match relative_path(&store.read(&holding.symbol, "1D")?, &days) {
+            Some(path) => path,
+            None => {
+                missing.push(holding.symbol.clone());
+                proxy_path.clone().unwrap_or_else(|| vec![1.0; days.len()])
+            }
+        };
+        for (value, factor) in values.iter_mut().zip(&path) {
+            *value += holding.value * factor;
+        }
+        by_symbol.insert(holding.symbol.clone(), path.last().map(|f| f - 1.0).unwrap_or(0.0));
+    }
+
+    let start_value: f64 = holdings.iter().map(|h| h.value).sum();
+    let mut peak = start_value;
+    let mut max_drawdown: f64 = 0.0;
+    let mut previous = start_value;
+    let mut worst_day: Option<(NaiveDate, f64)> = None;
+    for (day, &value) in days.iter().zip(&values) {
+        peak = peak.max(value);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max(1.0 - value / peak);
+        }
+        if previous > 0.0 {
+            let change = value / previous - 1.0;
+            if worst_day.is_none_or(|(_, worst)| change < worst) {
+                worst_day = Some((*day, change));
+            }
+        }
+        previous = value;
+    }
+
+    Ok(StressResult {
+        window: window.clone(),
+        start_value,
+        end_value: values.last().copied().unwrap_or(start_value),
+        max_drawdown,
+        worst_day,
+        by_symbol,
+        missing,
+    })
+}
+
+/// [`stress_test`] for each window, in order.
+pub fn stress_all(holdings: &[Holding], store: &LocalStore, windows: &[ShockWindow], proxy: Option<&str>) -> Result<Vec<StressResult>, StoreError> {
+    windows.iter().map(|window| stress_test(holdings, store, window, proxy)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn bars(symbol: &str, closes: &[(u32, f64)]) -> Vec<Ohlcv> {
+        closes
+            .iter()
+            .map(|&(day, close)| Ohlcv {
+                time: Utc.with_ymd_and_hms(2020, 3, day, 0, 0, 0).unwrap(),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 0,
+                symbol: Some(symbol.to_string()),
+                breakdown: None,
+                futures: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stress_replay_with_proxy() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LocalStore::open(dir.path()).unwrap();
+        // Mon 2 .. Fri 6 March 2020; FPT suspended on the 4th
+        store.write("FPT", "1D", &bars("FPT", &[(2, 100.0), (3, 90.0), (5, 80.0), (6, 95.0)])).unwrap();
+        store.write("VNINDEX", "1D", &bars("VNINDEX", &[(2, 1000.0), (3, 950.0), (4, 900.0), (5, 900.0), (6, 1000.0)])).unwrap();
+
+        let holding = |symbol: &str| Holding { symbol: symbol.to_string(), quantity: 100, price: 100.0, value: 10_000.0, weight: 0.5 };
+        let window = ShockWindow::new("test", NaiveDate::from_ymd_opt(2020, 3, 2).unwrap(), NaiveDate::from_ymd_opt(2020, 3, 6).unwrap());
+        let result = stress_test(&[holding("FPT"), holding("NEW")], &store, &window, Some("VNINDEX")).unwrap();
+
+        assert_eq!(result.missing, vec!["NEW"]);
+        assert!((result.by_symbol["FPT"] + 0.05).abs() < 1e-9);
+        assert!((result.end_value - (9_500.0 + 10_000.0)).abs() < 1e-6);
+        // Trough on the 5th: 8,000 + 9,000 against a 20,000 start
+        assert!((result.max_drawdown - 0.15).abs() < 1e-9);
+        assert_eq!(result.worst_day.map(|(day, _)| day), NaiveDate::from_ymd_opt(2020, 3, 3));
+    }
+}
@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::models::SecurityType;
+use crate::text;
+use crate::vci::ListedSymbol;
+
+/// How a listing matched the query, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum MatchKind {
+    Ticker,
+    TickerPrefix,
+    /// A word of the company name starts with the query.
+    NamePrefix,
+    NameContains,
+    TickerContains,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolMatch {
+    pub listing: ListedSymbol,
+    pub kind: MatchKind,
+}
+
+fn name_match(name: &str, query: &str) -> Option<MatchKind> {
+    let name = text::normalize(name);
+    if name.starts_with(query) || name.contains(&format!(" {}", query)) {
+        Some(MatchKind::NamePrefix)
+    } else if name.contains(query) {
+        Some(MatchKind::NameContains)
+    } else {
+        None
+    }
+}
+
+fn match_kind(listing: &ListedSymbol, query: &str) -> Option<MatchKind> {
+    let ticker = listing.symbol.to_lowercase();
+    let by_ticker = if ticker == query {
+        Some(MatchKind::Ticker)
+    } else if ticker.starts_with(query) {
+        Some(MatchKind::TickerPrefix)
+    } else {
+        None
+    };
+    let by_name = [listing.organ_name.as_deref(), listing.en_organ_name.as_deref()]
+        .into_iter()
+        .flatten()
+        .filter_map(|name| name_match(name, query))
+        .min();
+    let by_contains = (query.len() >= 2 && ticker.contains(query)).then_some(MatchKind::TickerContains);
+    [by_ticker, by_name, by_contains].into_iter().flatten().min()
+}
+
+/// Listings matching `query` by ticker or by Vietnamese or English company
+/// name, accent- and case-insensitive, best `limit` first. Within a match
+/// kind, stocks rank ahead of other securities, then shorter tickers.
+pub fn rank_symbols(listings: &[ListedSymbol], query: &str, limit: usize) -> Vec<SymbolMatch> {
+    let query = text::normalize(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches: Vec<SymbolMatch> = listings.iter()
+        .filter_map(|listing| Some(SymbolMatch { kind: match_kind(listing, &query)?, listing: listing.clone() }))
+        .collect();
+    matches.sort_by(|a, b| {
+        let rank = |m: &SymbolMatch| (m.kind, m.listing.kind() != SecurityType::Stock, m.listing.exchange.is_none(), m.listing.symbol.len());
+        rank(a).cmp(&rank(b)).then_with(|| a.listing.symbol.cmp(&b.listing.symbol))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+/// How long a client reuses the full listing for searches by default.
+pub const LISTING_TTL: Duration = Duration::from_secs(3600);
+
+/// The full listing kept in memory for `ttl`, so autocomplete doesn't
+/// download the whole universe per keystroke.
+#[derive(Debug)]
+pub struct ListingCache {
+    ttl: Duration,
+    entry: Mutex<Option<(Instant, Arc<Vec<ListedSymbol>>)>>,
+}
+
+impl Default for ListingCache {
+    fn default() -> Self {
+        ListingCache::new(LISTING_TTL)
+    }
+}
+
+impl ListingCache {
+    pub fn new(ttl: Duration) -> Self {
+        ListingCache { ttl, entry: Mutex::new(None) }
+    }
+
+    /// The stored listing if younger than the TTL.
+    pub fn get(&self) -> Option<Arc<Vec<ListedSymbol>>> {
+        self.entry.lock().unwrap()
+            .as_ref()
+            .filter(|(stored_at, _)| stored_at.elapsed() < self.ttl)
+            .map(|(_, listing)| Arc::clone(listing))
+    }
+
+    pub fn store(&self, listing: Vec<ListedSymbol>) -> Arc<Vec<ListedSymbol>> {
+        let listing = Arc::new(listing);
+        *self.entry.lock().unwrap() = Some((Instant::now(), Arc::clone(&listing)));
+        listing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Exchange;
+
+    fn listed(symbol: &str, kind: &str, name: &str) -> ListedSymbol {
+        ListedSymbol {
+            symbol: symbol.to_string(),
+            exchange: Some(Exchange::Hose),
+            security_type: Some(kind.to_string()),
+            organ_name: Some(name.to_string()),
+            en_organ_name: None,
+            isin: None,
+        }
+    }
+
+    #[test]
+    fn test_rank_symbols() {
+        let listings = vec![
+            listed("CFPT2401", "CW", "Chứng quyền FPT/ACBS/Call"),
+            listed("FPT", "STOCK", "Công ty Cổ phần FPT"),
+            listed("FTS", "STOCK", "Công ty Cổ phần Chứng khoán FPT"),
+            listed("VCB", "STOCK", "Ngân hàng TMCP Ngoại thương Việt Nam"),
+        ];
+        let symbols = |query: &str| rank_symbols(&listings, query, 10).into_iter().map(|m| m.listing.symbol).collect::<Vec<_>>();
+        assert_eq!(symbols("fpt"), vec!["FPT", "FTS", "CFPT2401"]);
+        assert_eq!(symbols("ngoai thuong"), vec!["VCB"]);
+        assert_eq!(rank_symbols(&listings, "Ngân", 10)[0].kind, MatchKind::NamePrefix);
+        assert!(symbols(" ").is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_listing_cache_expires() {
+        let cache = ListingCache::new(Duration::from_secs(60));
+        assert!(cache.get().is_none());
+        cache.store(vec![listed("FPT", "STOCK", "Công ty Cổ phần FPT")]);
+        tokio::time::advance(Duration::from_secs(59)).await;
+        assert_eq!(cache.get().map(|listing| listing.len()), Some(1));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(cache.get().is_none());
+    }
+}
@@ -0,0 +1,574 @@
+use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::models::Ohlcv;
+
+/// A technical indicator computed one bar at a time. `update` is fed closed
+/// bars in time order and returns the value as of that bar, or `None` while
+/// warming up. Implementors only hold the state they need to step forward,
+/// so historical frames and live streams share the same code path.
+pub trait Indicator {
+    type Output: Clone;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<Self::Output>;
+
+    /// Value `update(bar)` would return, without committing `bar`. Used to
+    /// evaluate a still-forming live bar; the default steps a copy, which is
+    /// cheap for indicators without buffers.
+    fn peek(&self, bar: &Ohlcv) -> Option<Self::Output>
+    where
+        Self: Clone,
+    {
+        self.clone().update(bar)
+    }
+
+    /// Drops all state, as if no bar had been seen.
+    fn reset(&mut self);
+}
+
+/// Runs `indicator` over a historical frame, one value per bar.
+pub fn compute<I: Indicator>(indicator: &mut I, bars: &[Ohlcv]) -> Vec<Option<I::Output>> {
+    bars.iter().map(|bar| indicator.update(bar)).collect()
+}
+
+/// Simple moving average of closes.
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        Sma { period: period.max(1), window: VecDeque::with_capacity(period.max(1) + 1), sum: 0.0 }
+    }
+
+    fn push(&mut self, value: f64) -> Option<f64> {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or(0.0);
+        }
+        (self.window.len() == self.period).then(|| self.sum / self.period as f64)
+    }
+
+    fn peek_value(&self, value: f64) -> Option<f64> {
+        let evicted = if self.window.len() == self.period { self.window.front().copied()? } else { 0.0 };
+        (self.window.len() + 1 >= self.period).then(|| (self.sum - evicted + value) / self.period as f64)
+    }
+}
+
+impl Indicator for Sma {
+    type Output = f64;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<f64> {
+        self.push(bar.close)
+    }
+
+    fn peek(&self, bar: &Ohlcv) -> Option<f64> {
+        self.peek_value(bar.close)
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+    }
+}
+
+/// Exponential moving average of closes, seeded with the SMA of the first
+/// `period` bars.
+#[derive(Debug, Clone)]
+pub struct Ema {
+    period: usize,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        Ema { period: period.max(1), seed: Sma::new(period), value: None }
+    }
+
+    fn next(&self, input: f64) -> Option<f64> {
+        let alpha = 2.0 / (self.period as f64 + 1.0);
+        match self.value {
+            Some(previous) => Some(previous + alpha * (input - previous)),
+            None => self.seed.peek_value(input),
+        }
+    }
+
+    fn push(&mut self, input: f64) -> Option<f64> {
+        let next = self.next(input);
+        if self.value.is_none() {
+            self.seed.push(input);
+            if next.is_some() {
+                // The seed window is no longer needed; keeping it empty keeps copies cheap.
+                self.seed.reset();
+            }
+        }
+        self.value = next.or(self.value);
+        next
+    }
+}
+
+impl Indicator for Ema {
+    type Output = f64;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<f64> {
+        self.push(bar.close)
+    }
+
+    fn peek(&self, bar: &Ohlcv) -> Option<f64> {
+        self.next(bar.close)
+    }
+
+    fn reset(&mut self) {
+        self.seed.reset();
+        self.value = None;
+    }
+}
+
+/// Wilder's relative strength index of closes.
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    previous_close: Option<f64>,
+    seen: usize,
+    average_gain: f64,
+    average_loss: f64,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Rsi { period: period.max(1), previous_close: None, seen: 0, average_gain: 0.0, average_loss: 0.0 }
+    }
+}
+
+impl Indicator for Rsi {
+    type Output = f64;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<f64> {
+        let previous = self.previous_close.replace(bar.close)?;
+        let change = bar.close - previous;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let n = self.period as f64;
+        self.seen += 1;
+        if self.seen <= self.period {
+            self.average_gain += gain / n;
+            self.average_loss += loss / n;
+            if self.seen < self.period {
+                return None;
+            }
+        } else {
+            self.average_gain = (self.average_gain * (n - 1.0) + gain) / n;
+            self.average_loss = (self.average_loss * (n - 1.0) + loss) / n;
+        }
+        if self.average_loss == 0.0 {
+            return Some(if self.average_gain == 0.0 { 50.0 } else { 100.0 });
+        }
+        Some(100.0 - 100.0 / (1.0 + self.average_gain / self.average_loss))
+    }
+
+    fn reset(&mut self) {
+        *self = Rsi::new(self.period);
+    }
+}
+
+/// Wilder's average true range.
+#[derive(Debug, Clone)]
+pub struct Atr {
+    period: usize,
+    previous_close: Option<f64>,
+    seen: usize,
+    value: f64,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Atr { period: period.max(1), previous_close: None, seen: 0, value: 0.0 }
+    }
+}
+
+impl Indicator for Atr {
+    type Output = f64;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<f64> {
+        let range = bar.high - bar.low;
+        let true_range = match self.previous_close.replace(bar.close) {
+            Some(close) => range.max((bar.high - close).abs()).max((bar.low - close).abs()),
+            None => range,
+        };
+        let n = self.period as f64;
+        self.seen += 1;
+        if self.seen <= self.period {
+            self.value += true_range / n;
+            return (self.seen == self.period).then_some(self.value);
+        }
+        self.value = (self.value * (n - 1.0) + true_range) / n;
+        Some(self.value)
+    }
+
+    fn reset(&mut self) {
+        *self = Atr::new(self.period);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MacdValue {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// MACD line (fast minus slow EMA of closes) with its signal EMA.
+#[derive(Debug, Clone)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+}
+
+impl Macd {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        Macd { fast: Ema::new(fast), slow: Ema::new(slow), signal: Ema::new(signal) }
+    }
+}
+
+impl Default for Macd {
+    /// The usual 12/26/9 parameters.
+    fn default() -> Self {
+        Macd::new(12, 26, 9)
+    }
+}
+
+impl Indicator for Macd {
+    type Output = MacdValue;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<MacdValue> {
+        let (fast, slow) = (self.fast.push(bar.close), self.slow.push(bar.close));
+        let macd = fast? - slow?;
+        let signal = self.signal.push(macd)?;
+        Some(MacdValue { macd, signal, histogram: macd - signal })
+    }
+
+    fn peek(&self, bar: &Ohlcv) -> Option<MacdValue> {
+        let macd = self.fast.next(bar.close)? - self.slow.next(bar.close)?;
+        let signal = self.signal.next(macd)?;
+        Some(MacdValue { macd, signal, histogram: macd - signal })
+    }
+
+    fn reset(&mut self) {
+        self.fast.reset();
+        self.slow.reset();
+        self.signal.reset();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BollingerBands {
+    pub middle: f64,
+    pub upper: f64,
+    pub lower: f64,
+}
+
+/// SMA of closes with bands `width` population standard deviations away.
+#[derive(Debug, Clone)]
+pub struct Bollinger {
+    width: f64,
+    mean: Sma,
+    sum_squares: f64,
+}
+
+impl Bollinger {
+    pub fn new(period: usize, width: f64) -> Self {
+        Bollinger { width, mean: Sma::new(period), sum_squares: 0.0 }
+    }
+
+    fn bands(&self, mean: f64, sum_squares: f64) -> BollingerBands {
+        let variance = (sum_squares / self.mean.period as f64 - mean * mean).max(0.0);
+        let deviation = self.width * variance.sqrt();
+        BollingerBands { middle: mean, upper: mean + deviation, lower: mean - deviation }
+    }
+}
+
+impl Indicator for Bollinger {
+    type Output = BollingerBands;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<BollingerBands> {
+        let evicted = if self.mean.window.len() == self.mean.period { self.mean.window.front().copied() } else { None };
+        self.sum_squares += bar.close * bar.close - evicted.map_or(0.0, |v| v * v);
+        let mean = self.mean.push(bar.close)?;
+        Some(self.bands(mean, self.sum_squares))
+    }
+
+    fn peek(&self, bar: &Ohlcv) -> Option<BollingerBands> {
+        let evicted = if self.mean.window.len() == self.mean.period { self.mean.window.front().copied() } else { None };
+        let mean = self.mean.peek_value(bar.close)?;
+        Some(self.bands(mean, self.sum_squares + bar.close * bar.close - evicted.map_or(0.0, |v| v * v)))
+    }
+
+    fn reset(&mut self) {
+        self.mean.reset();
+        self.sum_squares = 0.0;
+    }
+}
+
+/// An indicator whose output is passed through `f`, e.g. to pick a single
+/// MACD component for an [`IndicatorBank`].
+#[derive(Debug, Clone)]
+pub struct Map<I, F> {
+    inner: I,
+    f: F,
+}
+
+impl<I, F, T> Indicator for Map<I, F>
+where
+    I: Indicator,
+    F: Fn(I::Output) -> T,
+    T: Clone,
+{
+    type Output = T;
+
+    fn update(&mut self, bar: &Ohlcv) -> Option<T> {
+        self.inner.update(bar).map(&self.f)
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+pub trait IndicatorExt: Indicator + Sized {
+    fn map<F, T>(self, f: F) -> Map<Self, F>
+    where
+        F: Fn(Self::Output) -> T,
+    {
+        Map { inner: self, f }
+    }
+}
+
+impl<I: Indicator> IndicatorExt for I {}
+
+/// Adapts an [`Indicator`] to a live bar feed where the forming bar is
+/// re-sent on every update. A bar is committed only once a bar with a later
+/// time arrives; until then its revisions are evaluated with
+/// [`Indicator::peek`], so each update costs one step rather than a replay.
+#[derive(Debug, Clone)]
+pub struct LiveIndicator<I> {
+    committed: I,
+    forming: Option<Ohlcv>,
+}
+
+impl<I: Indicator + Clone> LiveIndicator<I> {
+    pub fn new(indicator: I) -> Self {
+        LiveIndicator { committed: indicator, forming: None }
+    }
+
+    /// Primes the state with closed historical bars before going live.
+    pub fn warm_up(&mut self, bars: &[Ohlcv]) {
+        for bar in bars {
+            self.update(bar);
+        }
+    }
+
+    /// Value as of `bar`. Bars older than the forming one are ignored.
+    pub fn update(&mut self, bar: &Ohlcv) -> Option<I::Output> {
+        match self.forming.take() {
+            Some(forming) if bar.time < forming.time => {
+                self.forming = Some(forming);
+                return None;
+            }
+            Some(forming) if bar.time > forming.time => {
+                self.committed.update(&forming);
+            }
+            _ => {}
+        }
+        let value = self.committed.peek(bar);
+        self.forming = Some(bar.clone());
+        value
+    }
+
+    pub fn reset(&mut self) {
+        self.committed.reset();
+        self.forming = None;
+    }
+}
+
+/// Object-safe view of a live scalar indicator, for [`IndicatorBank`].
+trait LiveScalar: Send {
+    fn update(&mut self, bar: &Ohlcv) -> Option<f64>;
+}
+
+impl<I> LiveScalar for LiveIndicator<I>
+where
+    I: Indicator<Output = f64> + Clone + Send,
+{
+    fn update(&mut self, bar: &Ohlcv) -> Option<f64> {
+        LiveIndicator::update(self, bar)
+    }
+}
+
+type NamedIndicators = Vec<(String, Box<dyn LiveScalar>)>;
+
+/// Latest indicator values of one symbol after a bar update.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndicatorUpdate {
+    pub symbol: String,
+    pub time: DateTime<Utc>,
+    /// Indicator name -> value; warming-up indicators are absent.
+    pub values: BTreeMap<String, f64>,
+}
+
+/// Named live indicators per symbol, e.g. ten indicators across a
+/// watchlist. Each bar only touches its own symbol's indicators, each in O(1)
+/// for the built-ins.
+#[derive(Default)]
+pub struct IndicatorBank {
+    indicators: HashMap<String, NamedIndicators>,
+}
+
+impl IndicatorBank {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `indicator` as `name` for `symbol`, replacing any indicator
+    /// already registered under that name.
+    pub fn add<I>(&mut self, symbol: &str, name: &str, indicator: I)
+    where
+        I: Indicator<Output = f64> + Clone + Send + 'static,
+    {
+        let entries = self.indicators.entry(symbol.to_uppercase()).or_default();
+        entries.retain(|(existing, _)| existing != name);
+        entries.push((name.to_string(), Box::new(LiveIndicator::new(indicator))));
+    }
+
+    /// Registers the same indicator for every symbol.
+    pub fn add_all<I>(&mut self, symbols: &[String], name: &str, indicator: I)
+    where
+        I: Indicator<Output = f64> + Clone + Send + 'static,
+    {
+        for symbol in symbols {
+            self.add(symbol, name, indicator.clone());
+        }
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.indicators.keys().map(|s| s.as_str())
+    }
+
+    /// Feeds `bar` to `symbol`'s indicators; `None` for unknown symbols.
+    pub fn update(&mut self, symbol: &str, bar: &Ohlcv) -> Option<IndicatorUpdate> {
+        let symbol = symbol.to_uppercase();
+        let entries = self.indicators.get_mut(&symbol)?;
+        let values = entries.iter_mut()
+            .filter_map(|(name, indicator)| Some((name.clone(), indicator.update(bar)?)))
+            .collect();
+        Some(IndicatorUpdate { symbol, time: bar.time, values })
+    }
+
+    /// Runs the bank over a mixed-symbol live feed, routing by `bar.symbol`.
+    pub fn apply<S>(mut self, bars: S) -> BoxStream<'static, IndicatorUpdate>
+    where
+        S: Stream<Item = Ohlcv> + Send + 'static,
+    {
+        bars.filter_map(move |bar| {
+            let update = bar.symbol.clone().and_then(|symbol| self.update(&symbol, &bar));
+            futures::future::ready(update)
+        })
+        .boxed()
+    }
+}
+
+/// Pairs every bar of a live feed with the indicator's value as of it.
+pub fn apply_stream<I, S>(indicator: I, bars: S) -> BoxStream<'static, (Ohlcv, Option<I::Output>)>
+where
+    I: Indicator + Clone + Send + 'static,
+    I::Output: Send,
+    S: Stream<Item = Ohlcv> + Send + 'static,
+{
+    bars.scan(LiveIndicator::new(indicator), |live, bar| {
+        let value = live.update(&bar);
+        futures::future::ready(Some((bar, value)))
+    })
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn bar(day: u32, close: f64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 5, day, 0, 0, 0).unwrap(),
+            open: close,
+            high: close + 1.0,
+            low: close - 1.0,
+            close,
+            volume: 100,
+            symbol: None,
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_builtin_indicators() {
+        let bars: Vec<Ohlcv> = [10.0, 11.0, 12.0, 13.0, 12.0].iter().enumerate().map(|(i, &c)| bar(i as u32 + 1, c)).collect();
+        assert_eq!(compute(&mut Sma::new(3), &bars), vec![None, None, Some(11.0), Some(12.0), Some(12.0 + 1.0 / 3.0)]);
+        let ema = compute(&mut Ema::new(3), &bars);
+        assert_eq!(ema[2], Some(11.0));
+        assert_eq!(ema[3], Some(12.0));
+        let rsi = compute(&mut Rsi::new(3), &bars);
+        assert_eq!(rsi[3], Some(100.0));
+        assert!(rsi[4].unwrap() < 100.0);
+        assert_eq!(compute(&mut Atr::new(2), &bars)[1], Some(2.0));
+    }
+
+    #[test]
+    fn test_peek_matches_update() {
+        let bars: Vec<Ohlcv> = (1..=28).map(|day| bar(day, 10.0 + ((day * 7) % 5) as f64)).collect();
+        fn check<I: Indicator + Clone>(mut indicator: I, bars: &[Ohlcv])
+        where
+            I::Output: PartialEq + std::fmt::Debug,
+        {
+            for bar in bars {
+                let peeked = indicator.peek(bar);
+                assert_eq!(peeked, indicator.update(bar));
+            }
+        }
+        check(Sma::new(5), &bars);
+        check(Ema::new(5), &bars);
+        check(Bollinger::new(5, 2.0), &bars);
+        check(Macd::new(3, 6, 4), &bars);
+        check(Rsi::new(5).map(|v| v.round()), &bars);
+    }
+
+    #[tokio::test]
+    async fn test_live_revisions_match_batch() {
+        let closes = [10.0, 11.0, 12.0, 13.0];
+        let bars: Vec<Ohlcv> = closes.iter().enumerate().map(|(i, &c)| bar(i as u32 + 1, c)).collect();
+        let expected = compute(&mut Ema::new(2), &bars);
+
+        // Each bar is first sent with a provisional close, then its final one.
+        let feed: Vec<Ohlcv> = closes.iter().enumerate().flat_map(|(i, &c)| [bar(i as u32 + 1, c * 2.0), bar(i as u32 + 1, c)]).collect();
+        let values: Vec<_> = apply_stream(Ema::new(2), futures::stream::iter(feed)).map(|(_, v)| v).collect().await;
+        let finals: Vec<_> = values.into_iter().skip(1).step_by(2).collect();
+        assert_eq!(finals, expected);
+    }
+
+    #[test]
+    fn test_indicator_bank_routes_by_symbol() {
+        let mut bank = IndicatorBank::new();
+        bank.add_all(&["HPG".to_string(), "FPT".to_string()], "sma2", Sma::new(2));
+        bank.add("HPG", "macd_hist", Macd::new(2, 3, 2).map(|m| m.histogram));
+        assert!(bank.update("HPG", &bar(1, 10.0)).unwrap().values.is_empty());
+        assert_eq!(bank.update("hpg", &bar(2, 12.0)).unwrap().values["sma2"], 11.0);
+        assert!(bank.update("FPT", &bar(2, 50.0)).unwrap().values.is_empty());
+        assert!(bank.update("VNM", &bar(2, 50.0)).is_none());
+    }
+}
@@ -1,33 +1,100 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::{Client, Error as ReqwestError};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::{Duration, SystemTime};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::time::sleep;
+use futures::stream::{BoxStream, StreamExt};
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 
+use crate::corporate_actions::{self, CorporateAction};
+use crate::foreign_room::{self, ForeignTradingDay};
+use crate::proprietary::{self, ProprietaryTradingDay};
+use crate::compare::{self, ComparisonMatrix};
+use crate::growth::{self, EarningsEstimate, GrowthProfile};
+#[cfg(feature = "cache")]
+use crate::cache::{self, CacheKind, DiskCache};
+use crate::chaos::{Chaos, ChaosConfig};
+use crate::circuit::CircuitBreaker;
+use crate::coalesce::Coalescer;
+use crate::pagination::{self, Page};
+use crate::preflight::{self, PreflightReport};
+use crate::rate_limit::RateLimiter;
+use crate::resample;
+use crate::session::SessionFilter;
+use crate::retry::{RequestError, Requester, RetryPolicy};
+use crate::statements::Financials;
+use crate::failover::Provider;
+use crate::stats::{self, ClientStats, LatencyHistogram, UsageReport};
+use crate::valuation::{self, RatioMetric, RatioPoint};
+use crate::calendar;
+use crate::models::{self, is_futures_symbol, vietnam_offset, DateRange, DepthLevel, Index, Interval, Ohlcv, PriceAdjustment, PriceDepth, TickData, TradeSide, TradingStatus};
+
 #[derive(Debug)]
 pub enum TcbsError {
     Http(ReqwestError),
     Serialization(serde_json::Error),
     InvalidInterval(String),
+    InvalidDateRange(String),
     InvalidResponse(String),
+    InvalidConfig(String),
     RateLimit,
     NoData,
+    /// Failure of a coalesced request started by another caller.
+    Shared(Arc<TcbsError>),
+    /// The endpoint's circuit breaker is open; retry after the given delay.
+    CircuitOpen { endpoint: String, retry_in: Duration },
+    /// Non-success HTTP status, after any retries the policy allows.
+    Status(reqwest::StatusCode),
 }
 
+/// Result handed to every caller of a coalesced request.
+type SharedResult<T> = Result<T, Arc<TcbsError>>;
+
 impl From<ReqwestError> for TcbsError {
     fn from(error: ReqwestError) -> Self {
         TcbsError::Http(error)
     }
 }
 
+impl From<RequestError> for TcbsError {
+    fn from(error: RequestError) -> Self {
+        match error {
+            RequestError::CircuitOpen { endpoint, retry_in } => TcbsError::CircuitOpen { endpoint, retry_in },
+            RequestError::Status(status) => TcbsError::Status(status),
+            RequestError::Http(error) => TcbsError::Http(error),
+        }
+    }
+}
+
 impl From<serde_json::Error> for TcbsError {
     fn from(error: serde_json::Error) -> Self {
         TcbsError::Serialization(error)
     }
 }
 
+/// Largest `countBack` the bars endpoints serve in full.
+const MAX_COUNT_BACK: u32 = 1_000;
+
+/// Extra bars requested beyond the estimate, as slack for sessions the
+/// calendar doesn't know about.
+const COUNT_BACK_BUFFER: u32 = 20;
+
+/// Upper bound on bars per trading day: 270 session minutes plus the
+/// closing auction.
+fn bars_per_day(interval: &str) -> u32 {
+    match interval {
+        "1m" => 271,
+        "5m" => 55,
+        "15m" => 19,
+        "30m" => 10,
+        "1H" => 6,
+        _ => 1,
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OhlcvData {
     pub time: DateTime<Utc>,
@@ -108,20 +175,219 @@ pub struct FinancialInfo {
     pub ratios: Option<Vec<FinancialStatement>>,
 }
 
+/// Disclosed trade by an insider, major shareholder or one of their related
+/// parties, from the TCBS insider-dealing feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedPartyDeal {
+    pub symbol: String,
+    pub announce_date: Option<String>,
+    pub party: Option<String>,
+    /// Relationship to the company or its insiders as disclosed.
+    pub relation: Option<String>,
+    pub side: TradeSide,
+    pub quantity: f64,
+    pub price: Option<f64>,
+    /// Ownership ratio after the deal, when reported.
+    pub ownership_ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedPartySummary {
+    pub symbol: String,
+    pub deals: Vec<RelatedPartyDeal>,
+    pub bought_quantity: f64,
+    pub sold_quantity: f64,
+    /// Sum of quantity × price for deals that report a price.
+    pub bought_value: f64,
+    pub sold_value: f64,
+}
+
+impl RelatedPartySummary {
+    pub fn from_deals(symbol: &str, deals: Vec<RelatedPartyDeal>) -> Self {
+        let mut summary = RelatedPartySummary {
+            symbol: symbol.to_uppercase(),
+            deals: Vec::new(),
+            bought_quantity: 0.0,
+            sold_quantity: 0.0,
+            bought_value: 0.0,
+            sold_value: 0.0,
+        };
+        for deal in &deals {
+            let value = deal.price.map(|price| price * deal.quantity).unwrap_or(0.0);
+            match deal.side {
+                TradeSide::Buy => {
+                    summary.bought_quantity += deal.quantity;
+                    summary.bought_value += value;
+                }
+                TradeSide::Sell => {
+                    summary.sold_quantity += deal.quantity;
+                    summary.sold_value += value;
+                }
+                TradeSide::Unknown => {}
+            }
+        }
+        summary.deals = deals;
+        summary
+    }
+
+    pub fn net_quantity(&self) -> f64 {
+        self.bought_quantity - self.sold_quantity
+    }
+}
+
+/// Exchange notice about a symbol's trading status (warning, control,
+/// suspension), taken from the TCBS activity-news feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeNotice {
+    pub symbol: String,
+    pub title: String,
+    pub source: Option<String>,
+    pub publish_date: Option<String>,
+    pub status: TradingStatus,
+}
+
+/// Item of the TCBS activity-news feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsItem {
+    pub symbol: String,
+    pub title: String,
+    pub source: Option<String>,
+    pub publish_date: Option<String>,
+}
+
 pub struct TcbsClient {
     client: Client,
     base_url: String,
-    rate_limit_per_minute: u32,
-    request_timestamps: Vec<SystemTime>,
+    rate_limiter: Arc<RateLimiter>,
+    stats: Arc<ClientStats>,
+    chaos: Option<Arc<Chaos>>,
+    extra_headers: HeaderMap,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Arc<CircuitBreaker>,
+    inflight_history: Arc<Coalescer<String, SharedResult<Vec<OhlcvData>>>>,
+    inflight_company: Arc<Coalescer<String, SharedResult<CompanyInfo>>>,
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<DiskCache>>,
     user_agents: Vec<String>,
     random_agent: bool,
 }
 
-impl TcbsClient {
-    pub fn new(random_agent: bool, rate_limit_per_minute: u32) -> Result<Self, TcbsError> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()?;
+/// Configures a [`TcbsClient`]: HTTP timeouts, proxy, extra headers, base URL
+/// and request budget. An injected `reqwest::Client` takes precedence over
+/// the timeout and proxy settings.
+pub struct TcbsClientBuilder {
+    random_agent: bool,
+    rate_limit_per_minute: u32,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    timeout: Duration,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    headers: Vec<(String, String)>,
+    http_client: Option<Client>,
+    base_url: Option<String>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl Default for TcbsClientBuilder {
+    fn default() -> Self {
+        TcbsClientBuilder {
+            random_agent: false,
+            rate_limit_per_minute: 6,
+            rate_limiter: None,
+            timeout: Duration::from_secs(30),
+            connect_timeout: None,
+            proxy: None,
+            headers: Vec::new(),
+            http_client: None,
+            base_url: None,
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: None,
+        }
+    }
+}
+
+impl TcbsClientBuilder {
+    pub fn random_agent(mut self, random_agent: bool) -> Self {
+        self.random_agent = random_agent;
+        self
+    }
+
+    pub fn rate_limit(mut self, per_minute: u32) -> Self {
+        self.rate_limit_per_minute = per_minute;
+        self
+    }
+
+    /// Shared request budget; overrides [`Self::rate_limit`].
+    pub fn rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes all traffic through `url`, e.g. `http://proxy.corp:3128`.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Sent with every request, replacing the browser header of the same name.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Points the client at another host, e.g. a mock server in tests.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    pub fn build(self) -> Result<TcbsClient, TcbsError> {
+        let client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder().timeout(self.timeout);
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(proxy) = &self.proxy {
+                    builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+                }
+                builder.build()?
+            }
+        };
+
+        let mut extra_headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| TcbsError::InvalidConfig(format!("Invalid header name '{}'", name)))?;
+            let value = HeaderValue::from_str(value).map_err(|_| TcbsError::InvalidConfig(format!("Invalid value for header '{}'", name)))?;
+            extra_headers.insert(name, value);
+        }
 
         let user_agents = vec![
             "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string(),
@@ -133,38 +399,166 @@ impl TcbsClient {
 
         Ok(TcbsClient {
             client,
-            base_url: "https://apipubaws.tcbs.com.vn".to_string(),
-            rate_limit_per_minute,
-            request_timestamps: Vec::new(),
+            base_url: self.base_url.unwrap_or_else(|| "https://apipubaws.tcbs.com.vn".to_string()),
+            extra_headers,
+            rate_limiter: self.rate_limiter.unwrap_or_else(|| Arc::new(RateLimiter::new(self.rate_limit_per_minute))),
+            stats: Arc::new(ClientStats::new()),
+            chaos: None,
+            retry_policy: self.retry_policy,
+            circuit_breaker: self.circuit_breaker.unwrap_or_default(),
+            inflight_history: Arc::new(Coalescer::new()),
+            inflight_company: Arc::new(Coalescer::new()),
+            #[cfg(feature = "cache")]
+            cache: None,
             user_agents,
-            random_agent,
+            random_agent: self.random_agent,
         })
     }
+}
 
-    fn get_interval_value(&self, interval: &str) -> Result<String, TcbsError> {
-        let interval_map = HashMap::from([
-            ("1m", "1"),
-            ("5m", "5"),
-            ("15m", "15"),
-            ("30m", "30"),
-            ("1H", "60"),
-            ("1D", "D"),
-            ("1W", "W"),
-            ("1M", "M"),
-        ]);
-
-        interval_map.get(interval)
-            .map(|s| s.to_string())
-            .ok_or_else(|| TcbsError::InvalidInterval(interval.to_string()))
-    }
-
-    fn get_index_mapping(&self, symbol: &str) -> String {
-        match symbol {
-            "VNINDEX" => "VNINDEX".to_string(),
-            "HNXINDEX" => "HNXIndex".to_string(),
-            "UPCOMINDEX" => "UPCOM".to_string(),
-            _ => symbol.to_string(),
+impl TcbsClient {
+    pub fn new(random_agent: bool, rate_limit_per_minute: u32) -> Result<Self, TcbsError> {
+        Self::builder().random_agent(random_agent).rate_limit(rate_limit_per_minute).build()
+    }
+
+    pub fn builder() -> TcbsClientBuilder {
+        TcbsClientBuilder::default()
+    }
+
+    /// Shares `limiter` with this client, e.g. to run several clients or
+    /// concurrent tasks under one request budget.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+
+    /// Replaces the underlying HTTP client, e.g. one built with a proxy.
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Replaces the default retry policy (5 attempts, exponential backoff
+    /// from 1s with up to 1s of jitter).
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Shares `breaker` with this client. By default each client has its
+    /// own breaker opening after 5 consecutive failures for 30s.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = breaker;
+        self
+    }
+
+    pub fn circuit_breaker(&self) -> Arc<CircuitBreaker> {
+        Arc::clone(&self.circuit_breaker)
+    }
+
+    pub fn rate_limiter(&self) -> Arc<RateLimiter> {
+        Arc::clone(&self.rate_limiter)
+    }
+
+    /// Injects delays, 5xx responses and malformed payloads into this
+    /// client's requests. Intended for tests of error handling only.
+    pub fn with_chaos(mut self, config: ChaosConfig) -> Self {
+        self.chaos = Some(Arc::new(Chaos::new(config)));
+        self
+    }
+
+    /// Serves history and company info through `cache` in the `*_cached`
+    /// methods, so repeated calls within the TTL skip the API.
+    #[cfg(feature = "cache")]
+    pub fn with_cache(mut self, cache: Arc<DiskCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// [`Self::get_history`] read through the attached cache. `force_refresh`
+    /// skips the lookup but still stores the fresh result.
+    #[cfg(feature = "cache")]
+    pub async fn get_history_cached(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: impl AsRef<str>,
+        count_back: u32,
+        force_refresh: bool,
+    ) -> Result<Vec<OhlcvData>, TcbsError> {
+        let interval = interval.as_ref();
+        let Some(cache) = &self.cache else {
+            return self.get_history(symbol, start, end, interval, count_back).await;
+        };
+        let kind = CacheKind::for_interval(interval);
+        let key = format!("{}_{}", cache::history_key("tcbs", symbol, &models::iso_date(start), end.map(models::iso_date).as_deref(), interval), count_back);
+        if !force_refresh {
+            if let Some(bars) = cache.get(kind, &key) {
+                return Ok(bars);
+            }
         }
+        let bars = self.get_history(symbol, start, end, interval, count_back).await?;
+        if let Err(e) = cache.put(kind, &key, &bars) {
+            tracing::warn!("Cache write failed for {}: {:?}", key, e);
+        }
+        Ok(bars)
+    }
+
+    /// [`Self::company_info`] read through the attached cache.
+    #[cfg(feature = "cache")]
+    pub async fn company_info_cached(&self, symbol: &str, force_refresh: bool) -> Result<CompanyInfo, TcbsError> {
+        let Some(cache) = &self.cache else {
+            return self.company_info(symbol).await;
+        };
+        // TCBS serves company data in Vietnamese only
+        let key = cache::company_key("tcbs", symbol, crate::models::Language::Vietnamese);
+        if !force_refresh {
+            if let Some(info) = cache.get(CacheKind::CompanyInfo, &key) {
+                return Ok(info);
+            }
+        }
+        let info = self.company_info(symbol).await?;
+        if let Err(e) = cache.put(CacheKind::CompanyInfo, &key, &info) {
+            tracing::warn!("Cache write failed for {}: {:?}", key, e);
+        }
+        Ok(info)
+    }
+
+    fn requester(&self) -> Requester<'_> {
+        Requester {
+            policy: &self.retry_policy,
+            rate_limiter: &self.rate_limiter,
+            stats: &self.stats,
+            circuit_breaker: Some(&self.circuit_breaker),
+            chaos: self.chaos.as_deref(),
+        }
+    }
+
+    /// Probes the ticker overview once to check connectivity, auth and
+    /// clock skew before a batch job starts.
+    pub async fn preflight(&self) -> PreflightReport {
+        let url = format!("{}/tcanalysis/v1/ticker/VNM/overview", self.base_url);
+        let started = tokio::time::Instant::now();
+        let report = preflight::probe("tcbs", self.with_browser_headers(self.client.get(&url), self.get_user_agent())).await;
+        self.stats.record(&stats::endpoint_key(&url), started.elapsed(), report.responded());
+        report
+    }
+
+    /// Latency histograms per endpoint for every request attempt so far.
+    pub fn stats(&self) -> std::collections::BTreeMap<String, LatencyHistogram> {
+        self.stats.snapshot()
+    }
+
+    /// Requests sent per day and endpoint, to keep under the provider's
+    /// informal quotas.
+    pub fn usage_report(&self) -> UsageReport {
+        self.stats.usage(Provider::Tcbs)
+    }
+
+    fn get_interval_value(&self, interval: &str) -> Result<String, TcbsError> {
+        let interval: Interval = interval.parse().map_err(TcbsError::InvalidInterval)?;
+        Ok(interval.tcbs_resolution().to_string())
     }
 
     fn get_user_agent(&self) -> String {
@@ -178,93 +572,42 @@ impl TcbsClient {
         }
     }
 
-    async fn enforce_rate_limit(&mut self) {
-        let current_time = SystemTime::now();
-        
-        // Remove timestamps older than 1 minute
-        self.request_timestamps.retain(|&timestamp| {
-            current_time.duration_since(timestamp).unwrap_or(Duration::from_secs(0)) < Duration::from_secs(60)
-        });
-
-        // If we're at the rate limit, wait
-        if self.request_timestamps.len() >= self.rate_limit_per_minute as usize {
-            if let Some(&oldest_request) = self.request_timestamps.first() {
-                let wait_time = Duration::from_secs(60) - current_time.duration_since(oldest_request).unwrap_or(Duration::from_secs(0));
-                if !wait_time.is_zero() {
-                    sleep(wait_time + Duration::from_millis(100)).await;
-                }
-            }
-        }
-
-        self.request_timestamps.push(current_time);
+    fn with_browser_headers(&self, request: reqwest::RequestBuilder, user_agent: String) -> reqwest::RequestBuilder {
+        request
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "en-US,en;q=0.9,vi-VN;q=0.8,vi;q=0.7")
+            .header("Accept-Encoding", "gzip, deflate, br")
+            .header("Connection", "keep-alive")
+            .header("Cache-Control", "no-cache")
+            .header("Pragma", "no-cache")
+            .header("DNT", "1")
+            .header("Sec-Fetch-Dest", "empty")
+            .header("Sec-Fetch-Mode", "cors")
+            .header("Sec-Fetch-Site", "cross-site")
+            .header("sec-ch-ua", "\"Not_A Brand\";v=\"8\", \"Chromium\";v=\"120\", \"Google Chrome\";v=\"120\"")
+            .header("sec-ch-ua-mobile", "?0")
+            .header("sec-ch-ua-platform", "\"Windows\"")
+            .header("User-Agent", user_agent)
+            .header("Referer", "https://www.tcbs.com.vn/")
+            .header("Origin", "https://www.tcbs.com.vn")
+            .headers(self.extra_headers.clone())
     }
 
-    async fn make_request(&mut self, url: &str, params: Option<&[(&str, &str)]>) -> Result<Value, TcbsError> {
-        const MAX_RETRIES: u32 = 5;
-        
-        for attempt in 0..MAX_RETRIES {
-            self.enforce_rate_limit().await;
-
-            if attempt > 0 {
-                let delay = Duration::from_secs_f64(2.0_f64.powi(attempt as i32 - 1) + rand::random::<f64>());
-                let delay = delay.min(Duration::from_secs(60));
-                sleep(delay).await;
+    async fn make_request(&self, url: &str, params: Option<&[(&str, &str)]>) -> Result<Value, TcbsError> {
+        let endpoint = stats::endpoint_key(url);
+        let build = || {
+            let request = self.with_browser_headers(self.client.get(url), self.get_user_agent());
+            match params {
+                Some(query_params) => request.query(query_params),
+                None => request,
             }
-
-            let user_agent = self.get_user_agent();
-            let mut request = self.client
-                .get(url)
-                .header("Accept", "application/json, text/plain, */*")
-                .header("Accept-Language", "en-US,en;q=0.9,vi-VN;q=0.8,vi;q=0.7")
-                .header("Accept-Encoding", "gzip, deflate, br")
-                .header("Connection", "keep-alive")
-                .header("Cache-Control", "no-cache")
-                .header("Pragma", "no-cache")
-                .header("DNT", "1")
-                .header("Sec-Fetch-Dest", "empty")
-                .header("Sec-Fetch-Mode", "cors")
-                .header("Sec-Fetch-Site", "cross-site")
-                .header("sec-ch-ua", "\"Not_A Brand\";v=\"8\", \"Chromium\";v=\"120\", \"Google Chrome\";v=\"120\"")
-                .header("sec-ch-ua-mobile", "?0")
-                .header("sec-ch-ua-platform", "\"Windows\"")
-                .header("User-Agent", user_agent)
-                .header("Referer", "https://www.tcbs.com.vn/")
-                .header("Origin", "https://www.tcbs.com.vn");
-
-            if let Some(query_params) = params {
-                request = request.query(query_params);
-            }
-
-            let response = request.send().await;
-
-            match response {
-                Ok(resp) => {
-                    let status = resp.status();
-                    if status.is_success() {
-                        match resp.json::<Value>().await {
-                            Ok(data) => return Ok(data),
-                            Err(_) => continue,
-                        }
-                    } else if status == 403 || status == 429 || status.is_server_error() {
-                        continue;
-                    } else if status.is_client_error() {
-                        break;
-                    } else {
-                        continue;
-                    }
-                }
-                Err(_) => continue,
-            }
-        }
-
-        Err(TcbsError::InvalidResponse("Max retries exceeded".to_string()))
+        };
+        Ok(self.requester().send_json(&endpoint, &endpoint, build).await?)
     }
 
     fn camel_to_snake(&self, name: &str) -> String {
         let mut result = String::new();
-        let mut chars = name.chars().peekable();
-
-        while let Some(ch) = chars.next() {
+        for ch in name.chars() {
             if ch.is_uppercase() && !result.is_empty() {
                 result.push('_');
             }
@@ -274,35 +617,126 @@ impl TcbsClient {
         result
     }
 
+    /// Bars for `[start, end]` at `interval` (an [`Interval`] or its string
+    /// form), `end` defaulting to today. `count_back` is raised to cover the
+    /// range, and ranges beyond one request's limit are fetched in
+    /// consecutive chunks, deduplicated and returned as one series.
     pub async fn get_history(
-        &mut self,
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: impl AsRef<str>,
+        count_back: u32,
+    ) -> Result<Vec<OhlcvData>, TcbsError> {
+        let interval = interval.as_ref();
+        let (start, end) = (models::iso_date(start), end.map(models::iso_date));
+        let key = format!("{}|{}|{}|{}|{}", symbol.to_uppercase(), start, end.as_deref().unwrap_or(""), interval, count_back);
+        self.inflight_history
+            .run(key, async { self.fetch_history(symbol, &start, end.as_deref(), interval, count_back).await.map_err(Arc::new) })
+            .await
+            .map_err(|e| Arc::try_unwrap(e).unwrap_or_else(TcbsError::Shared))
+    }
+
+    /// [`get_history`](Self::get_history) with a choice of price
+    /// adjustment. Adjusted bars are back-adjusted from the dividend history
+    /// only, as TCBS does not report splits or rights issues; if that lookup
+    /// fails they are returned unadjusted with a warning. Indices are never
+    /// adjusted.
+    pub async fn get_history_with(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: impl AsRef<str>,
+        count_back: u32,
+        adjustment: PriceAdjustment,
+    ) -> Result<Vec<OhlcvData>, TcbsError> {
+        let mut bars = self.get_history(symbol, start, end, interval, count_back).await?;
+        if adjustment == PriceAdjustment::Unadjusted || Index::from_symbol(symbol).is_some() {
+            return Ok(bars);
+        }
+        let actions = match self.get_dividends(symbol).await {
+            Ok(actions) => actions,
+            Err(e) => {
+                tracing::warn!("Corporate actions unavailable for {}, returning unadjusted bars: {:?}", symbol, e);
+                return Ok(bars);
+            }
+        };
+        corporate_actions::back_adjust(&mut bars, &actions);
+        Ok(bars)
+    }
+
+    async fn fetch_history(
+        &self,
         symbol: &str,
         start: &str,
         end: Option<&str>,
         interval: &str,
         count_back: u32,
     ) -> Result<Vec<OhlcvData>, TcbsError> {
-        let interval_value = self.get_interval_value(interval)?;
-        let mapped_symbol = self.get_index_mapping(symbol);
-
-        let start_time = NaiveDate::parse_from_str(start, "%Y-%m-%d")
-            .map_err(|_| TcbsError::InvalidResponse("Invalid start date".to_string()))?;
-        
-        let end_time = match end {
-            Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
-                .map_err(|_| TcbsError::InvalidResponse("Invalid end date".to_string()))?,
-            None => Utc::now().date_naive(),
-        };
+        self.get_interval_value(interval)?;
+        let DateRange { start: start_date, end: end_date } = DateRange::parse(start, end).map_err(TcbsError::InvalidDateRange)?;
+
+        let per_day = bars_per_day(interval);
+        let needed = |from: NaiveDate, to: NaiveDate| calendar::trading_days_between(from, to).len() as u32 * per_day + COUNT_BACK_BUFFER;
+        let chunks = calendar::chunk_range(start_date, end_date, ((MAX_COUNT_BACK - COUNT_BACK_BUFFER) / per_day) as usize);
+        if chunks.len() == 1 {
+            let count_back = count_back.max(needed(start_date, end_date)).min(MAX_COUNT_BACK);
+            return self.history_once(symbol, start, end, interval, count_back).await;
+        }
 
-        if end_time < start_time {
-            return Err(TcbsError::InvalidResponse("End date cannot be earlier than start date".to_string()));
+        tracing::debug!("TCBS splitting {} [{}] {}..{} into {} requests", symbol, interval, start_date, end_date, chunks.len());
+        let mut result = Vec::new();
+        for (from, to) in chunks {
+            let (from_str, to_str) = (from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string());
+            match self.history_once(symbol, &from_str, Some(&to_str), interval, needed(from, to)).await {
+                Ok(bars) => result.extend(bars.into_iter().filter(|bar| bar.time.date_naive() <= to)),
+                Err(TcbsError::NoData) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        if result.is_empty() {
+            return Err(TcbsError::NoData);
         }
+        result.sort_by_key(|bar| bar.time);
+        result.dedup_by_key(|bar| bar.time);
+        Ok(result)
+    }
+
+    /// History of a market index, labelled with its canonical symbol.
+    pub async fn get_index_history(&self, index: Index, start: NaiveDate, end: Option<NaiveDate>, interval: impl AsRef<str>) -> Result<Vec<OhlcvData>, TcbsError> {
+        self.get_history(index.as_str(), start, end, interval, 0).await
+    }
+
+    /// [`TcbsClient::get_history`] over a validated [`DateRange`], sizing
+    /// `count_back` from the range.
+    pub async fn get_history_range(&self, symbol: &str, range: DateRange, interval: impl AsRef<str>) -> Result<Vec<OhlcvData>, TcbsError> {
+        self.get_history(symbol, range.start, Some(range.end), interval, 0).await
+    }
+
+    async fn history_once(
+        &self,
+        symbol: &str,
+        start: &str,
+        end: Option<&str>,
+        interval: &str,
+        count_back: u32,
+    ) -> Result<Vec<OhlcvData>, TcbsError> {
+        let interval_value = self.get_interval_value(interval)?;
+        let index = Index::from_symbol(symbol);
+        let mapped_symbol = index.map_or(symbol, |index| index.tcbs_code()).to_string();
+        let label = index.map_or(symbol, |index| index.as_str());
+
+        let DateRange { start: start_time, end: end_time } = DateRange::parse(start, end).map_err(TcbsError::InvalidDateRange)?;
 
         let end_timestamp = end_time.and_hms_opt(23, 59, 59).unwrap().and_utc().timestamp();
 
         // Determine asset type and endpoint
-        let (asset_type, base_path) = if symbol.contains("F2") {
+        let (asset_type, base_path) = if is_futures_symbol(symbol) {
             ("derivative", "futures-insight")
+        } else if index.is_some() {
+            ("index", "stock-insight")
         } else {
             ("stock", "stock-insight")
         };
@@ -346,7 +780,7 @@ impl TcbsClient {
                     let naive_date = NaiveDate::parse_from_str(date_part, "%Y-%m-%d")
                         .map_err(|_| TcbsError::InvalidResponse("Invalid trading date format".to_string()))?;
 
-                    if naive_date >= start_time.into() {
+                    if naive_date >= start_time {
                         let time = Utc.from_utc_datetime(&naive_date.and_hms_opt(0, 0, 0).unwrap());
 
                         result.push(OhlcvData {
@@ -356,7 +790,7 @@ impl TcbsClient {
                             low: item.get("low").and_then(|v| v.as_f64()).unwrap_or(0.0),
                             close: item.get("close").and_then(|v| v.as_f64()).unwrap_or(0.0),
                             volume: item.get("volume").and_then(|v| v.as_u64()).unwrap_or(0),
-                            symbol: Some(symbol.to_string()),
+                            symbol: Some(label.to_string()),
                         });
                     }
                 }
@@ -365,7 +799,7 @@ impl TcbsClient {
             // VCI-style format with parallel arrays
             let required_keys = ["t", "o", "h", "l", "c", "v"];
             for key in &required_keys {
-                if !data.get(key).is_some() {
+                if data.get(key).is_none() {
                     return Err(TcbsError::InvalidResponse(format!("Missing key: {}", key)));
                 }
             }
@@ -394,71 +828,53 @@ impl TcbsClient {
                         low: lows[i].as_f64().unwrap_or(0.0),
                         close: closes[i].as_f64().unwrap_or(0.0),
                         volume: volumes[i].as_u64().unwrap_or(0),
-                        symbol: Some(symbol.to_string()),
+                        symbol: Some(label.to_string()),
                     });
                 }
             }
         }
 
-        result.sort_by(|a, b| a.time.cmp(&b.time));
+        result.sort_by_key(|a| a.time);
         Ok(result)
     }
 
-    // pub async fn get_batch_history(
-    //     &mut self,
-    //     symbols: &[String],
-    //     start: &str,
-    //     end: Option<&str>,
-    //     interval: &str,
-    //     count_back: u32,
-    // ) -> Result<HashMap<String, Option<Vec<OhlcvData>>>, TcbsError> {
-    //     if symbols.is_empty() {
-    //         return Err(TcbsError::InvalidResponse("Symbols list cannot be empty".to_string()));
-    //     }
-
-    //     println!("Fetching batch data for {} symbols: {}", symbols.len(), symbols.join(", "));
-    //     println!("Date range: {} to {} [{}] (count_back={})", 
-    //              start, end.unwrap_or("now"), interval, count_back);
-
-    //     let mut results = HashMap::new();
-    //     let mut successful_count = 0;
-
-    //     // Process each symbol sequentially with rate limiting (TCBS doesn't support true batch requests)
-    //     for (i, symbol) in symbols.iter().enumerate() {
-    //         println!("Processing {} ({}/{})", symbol, i + 1, symbols.len());
-
-    //         // Add small delay between requests
-    //         if i > 0 {
-    //             sleep(Duration::from_millis(500)).await;
-    //         }
-
-    //         match self.get_history(symbol, start, end, interval, count_back).await {
-    //             Ok(mut data) => {
-    //                 if !data.is_empty() {
-    //                     // Add symbol column for identification
-    //                     for item in &mut data {
-    //                         item.symbol = Some(symbol.clone());
-    //                     }
-    //                     results.insert(symbol.clone(), Some(data));
-    //                     successful_count += 1;
-    //                     println!("✅ {}: {} data points", symbol, results[symbol].as_ref().unwrap().len());
-    //                 } else {
-    //                     results.insert(symbol.clone(), None);
-    //                     println!("❌ {}: No data", symbol);
-    //                 }
-    //             }
-    //             Err(e) => {
-    //                 results.insert(symbol.clone(), None);
-    //                 println!("❌ {}: Error - {:?}", symbol, e);
-    //             }
-    //         }
-    //     }
-
-    //     println!("Successfully fetched data for {}/{} symbols", successful_count, symbols.len());
-    //     Ok(results)
-    // }
-
-    pub async fn overview(&mut self, symbol: &str) -> Result<CompanyOverview, TcbsError> {
+    /// History for many symbols with at most `concurrency` requests in
+    /// flight. TCBS has no multi-symbol endpoint, so each symbol is its own
+    /// request through the shared rate limiter; failures are reported per
+    /// symbol instead of failing the batch.
+    pub async fn get_batch_history(
+        &self,
+        symbols: &[String],
+        start: &str,
+        end: Option<&str>,
+        interval: &str,
+        count_back: u32,
+        concurrency: usize,
+    ) -> Result<HashMap<String, Result<Vec<OhlcvData>, TcbsError>>, TcbsError> {
+        if symbols.is_empty() {
+            return Err(TcbsError::InvalidResponse("Symbols list cannot be empty".to_string()));
+        }
+        self.get_interval_value(interval)?;
+        let range = DateRange::parse(start, end).map_err(TcbsError::InvalidDateRange)?;
+        let end = end.map(|_| range.end);
+
+        let fetches = symbols.iter().map(|symbol| async move {
+            let result = self.get_history(symbol, range.start, end, interval, count_back).await.map(|mut data| {
+                for item in &mut data {
+                    item.symbol = Some(symbol.clone());
+                }
+                data
+            });
+            if let Err(e) = &result {
+                tracing::warn!("TCBS batch fetch failed for {} [{}]: {:?}", symbol, interval, e);
+            }
+            (symbol.clone(), result)
+        });
+
+        Ok(futures::stream::iter(fetches).buffer_unordered(concurrency.max(1)).collect().await)
+    }
+
+    pub async fn overview(&self, symbol: &str) -> Result<CompanyOverview, TcbsError> {
         let url = format!("{}/tcanalysis/v1/ticker/{}/overview", self.base_url, symbol.to_uppercase());
 
 
@@ -470,7 +886,7 @@ impl TcbsClient {
         Ok(overview)
     }
 
-    pub async fn profile(&mut self, symbol: &str) -> Result<CompanyProfile, TcbsError> {
+    pub async fn profile(&self, symbol: &str) -> Result<CompanyProfile, TcbsError> {
         let url = format!("{}/tcanalysis/v1/company/{}/overview", self.base_url, symbol.to_uppercase());
 
 
@@ -497,7 +913,7 @@ impl TcbsClient {
         Ok(profile)
     }
 
-    pub async fn shareholders(&mut self, symbol: &str) -> Result<Vec<ShareholderInfo>, TcbsError> {
+    pub async fn shareholders(&self, symbol: &str) -> Result<Vec<ShareholderInfo>, TcbsError> {
         let url = format!("{}/tcanalysis/v1/company/{}/large-share-holders", self.base_url, symbol.to_uppercase());
 
 
@@ -523,7 +939,7 @@ impl TcbsClient {
         Ok(shareholders)
     }
 
-    pub async fn officers(&mut self, symbol: &str) -> Result<Vec<OfficerInfo>, TcbsError> {
+    pub async fn officers(&self, symbol: &str) -> Result<Vec<OfficerInfo>, TcbsError> {
         let url = format!("{}/tcanalysis/v1/company/{}/key-officers", self.base_url, symbol.to_uppercase());
 
 
@@ -556,7 +972,155 @@ impl TcbsClient {
         Ok(officers)
     }
 
-    pub async fn get_current_price(&mut self, symbol: &str) -> Result<Option<f64>, TcbsError> {
+    /// Insider and related-party deals disclosed for `symbol`, with totals.
+    pub async fn related_party_deals(&self, symbol: &str, page_size: u32) -> Result<RelatedPartySummary, TcbsError> {
+        let page = self.insider_deals_page(symbol, None, page_size).await?;
+        Ok(RelatedPartySummary::from_deals(symbol, page.items))
+    }
+
+    /// One page of insider deals; pass the returned `next_cursor` for more.
+    pub async fn insider_deals_page(&self, symbol: &str, cursor: Option<&str>, page_size: u32) -> Result<Page<RelatedPartyDeal>, TcbsError> {
+        let url = format!("{}/tcanalysis/v1/company/{}/insider-dealing", self.base_url, symbol.to_uppercase());
+        let (items, total) = self.fetch_page(&url, "listInsiderDealing", cursor, page_size).await?;
+        let deals = items.iter().filter_map(|deal| parse_insider_deal(symbol, deal)).collect();
+        Ok(Page::from_page_index(deals, pagination::page_index(cursor), page_size, total))
+    }
+
+    /// Every page of insider deals, oldest request first.
+    pub fn insider_deal_pages<'a>(&'a self, symbol: &'a str, page_size: u32) -> BoxStream<'a, Result<Page<RelatedPartyDeal>, TcbsError>> {
+        pagination::pages(move |cursor: Option<String>| async move { self.insider_deals_page(symbol, cursor.as_deref(), page_size).await })
+    }
+
+    /// One page of daily foreign trading for `symbol`, newest first.
+    pub async fn foreign_trading_page(&self, symbol: &str, cursor: Option<&str>, page_size: u32) -> Result<Page<ForeignTradingDay>, TcbsError> {
+        let url = format!("{}/tcanalysis/v1/ticker/{}/foreign-trading", self.base_url, symbol.to_uppercase());
+        let (items, total) = self.fetch_page(&url, "listForeignTrading", cursor, page_size).await?;
+        let days = items.iter().filter_map(|item| foreign_room::parse_foreign_trading_day(symbol, item)).collect();
+        Ok(Page::from_page_index(days, pagination::page_index(cursor), page_size, total))
+    }
+
+    /// Daily foreign buy/sell volume and value for `[start, end]`, oldest
+    /// first. Pages are walked back until they pass `start`.
+    pub async fn get_foreign_trading(&self, symbol: &str, start: &str, end: Option<&str>) -> Result<Vec<ForeignTradingDay>, TcbsError> {
+        let range = DateRange::parse(start, end).map_err(TcbsError::InvalidDateRange)?;
+        let mut days = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = self.foreign_trading_page(symbol, cursor.as_deref(), 100).await?;
+            let reached_start = page.items.iter().any(|day| day.date < range.start);
+            days.extend(page.items);
+            match page.next_cursor {
+                Some(next) if !reached_start => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok(foreign_room::clip_foreign_trading(days, range.start, range.end))
+    }
+
+    /// Daily proprietary-desk buy/sell for `symbol` over `[start, end]`,
+    /// oldest first. TCBS serves per-symbol series only; for market totals
+    /// use `VciClient::get_proprietary_trading`.
+    pub async fn get_proprietary_trading(&self, symbol: &str, start: &str, end: Option<&str>) -> Result<Vec<ProprietaryTradingDay>, TcbsError> {
+        let range = DateRange::parse(start, end).map_err(TcbsError::InvalidDateRange)?;
+        let url = format!("{}/tcanalysis/v1/ticker/{}/proprietary-trading", self.base_url, symbol.to_uppercase());
+        let mut days = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let (items, total) = self.fetch_page(&url, "listProprietaryTrading", cursor.as_deref(), 100).await?;
+            let parsed: Vec<ProprietaryTradingDay> = items.iter().filter_map(|item| proprietary::parse_proprietary_day(symbol, item)).collect();
+            let reached_start = parsed.iter().any(|day| day.date < range.start);
+            let page = Page::from_page_index(parsed, pagination::page_index(cursor.as_deref()), 100, total);
+            days.extend(page.items);
+            match page.next_cursor {
+                Some(next) if !reached_start => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok(proprietary::clip(days, range.start, range.end))
+    }
+
+    /// Cash and stock dividend history for `symbol`, oldest ex-date first.
+    pub async fn get_dividends(&self, symbol: &str) -> Result<Vec<CorporateAction>, TcbsError> {
+        let url = format!("{}/tcanalysis/v1/company/{}/dividend-payment-histories", self.base_url, symbol.to_uppercase());
+        let (items, _) = self.fetch_page(&url, "listDividendPaymentHis", None, 100).await?;
+        let mut dividends: Vec<CorporateAction> = items.iter().filter_map(|item| corporate_actions::parse_tcbs_dividend(symbol, item)).collect();
+        dividends.sort_by_key(|dividend| dividend.ex_date);
+        Ok(dividends)
+    }
+
+    /// One page of the activity-news feed for `symbol`.
+    pub async fn news_page(&self, symbol: &str, cursor: Option<&str>, page_size: u32) -> Result<Page<NewsItem>, TcbsError> {
+        let url = format!("{}/tcanalysis/v1/ticker/{}/activity-news", self.base_url, symbol.to_uppercase());
+        let (items, total) = self.fetch_page(&url, "listActivityNews", cursor, page_size).await?;
+        let text = |item: &Value, key: &str| item.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let news = items.iter()
+            .filter_map(|item| {
+                Some(NewsItem {
+                    symbol: symbol.to_uppercase(),
+                    title: text(item, "title")?,
+                    source: text(item, "source"),
+                    publish_date: text(item, "publishDate"),
+                })
+            })
+            .collect();
+        Ok(Page::from_page_index(news, pagination::page_index(cursor), page_size, total))
+    }
+
+    pub fn news_pages<'a>(&'a self, symbol: &'a str, page_size: u32) -> BoxStream<'a, Result<Page<NewsItem>, TcbsError>> {
+        pagination::pages(move |cursor: Option<String>| async move { self.news_page(symbol, cursor.as_deref(), page_size).await })
+    }
+
+    /// One page of the latest session's matched trades, newest first.
+    /// `cursor` is the previous page's `next_cursor` (a page index); `None`
+    /// starts from the latest trade. Ticks are dated to the last session
+    /// that opened, so pre-open and weekend calls get the prior session.
+    pub async fn get_intraday(&self, symbol: &str, page_size: u32, cursor: Option<&str>) -> Result<Page<TickData>, TcbsError> {
+        let symbol = symbol.to_uppercase();
+        let url = format!("{}/stock-insight/v1/intraday/{}/his/paging", self.base_url, symbol);
+        let page = pagination::page_index(cursor);
+        let (page_str, size) = (page.to_string(), page_size.to_string());
+        let params = &[("page", page_str.as_str()), ("size", size.as_str()), ("headIndex", "-1")];
+
+        let response_data = self.make_request(&url, Some(params)).await?;
+        let items = response_data.get("data").and_then(|v| v.as_array()).ok_or(TcbsError::NoData)?;
+        let session = calendar::session_date(Utc::now());
+        let ticks = items.iter().filter_map(|item| parse_intraday_tick(item, session)).collect();
+        let total = response_data.get("total").and_then(|v| v.as_u64());
+        Ok(Page::from_page_index(ticks, page, page_size, total))
+    }
+
+    pub fn intraday_pages<'a>(&'a self, symbol: &'a str, page_size: u32) -> BoxStream<'a, Result<Page<TickData>, TcbsError>> {
+        pagination::pages(move |cursor: Option<String>| async move { self.get_intraday(symbol, page_size, cursor.as_deref()).await })
+    }
+
+    /// The latest session as `interval` bars, built from every page of
+    /// matched trades. Pass a session filter to drop auction and put-through
+    /// prints.
+    pub async fn session_bars(&self, symbol: &str, interval: Interval, session: Option<&SessionFilter>) -> Result<Vec<Ohlcv>, TcbsError> {
+        let mut ticks = Vec::new();
+        let mut pages = self.intraday_pages(symbol, 10_000);
+        while let Some(page) = pages.next().await {
+            ticks.extend(page?.items);
+        }
+        Ok(resample::bars_from_ticks(symbol, &ticks, interval, session))
+    }
+
+    /// Items under `list_key` of a page/size endpoint, plus its total if given.
+    async fn fetch_page(&self, url: &str, list_key: &str, cursor: Option<&str>, page_size: u32) -> Result<(Vec<Value>, Option<u64>), TcbsError> {
+        let page = pagination::page_index(cursor).to_string();
+        let size = page_size.to_string();
+        let params = &[("page", page.as_str()), ("size", size.as_str())];
+
+        let response_data = self.make_request(url, Some(params)).await?;
+        let items = response_data.get(list_key)
+            .and_then(|v| v.as_array())
+            .cloned()
+            .ok_or(TcbsError::NoData)?;
+        let total = response_data.get("total").and_then(|v| v.as_u64());
+        Ok((items, total))
+    }
+
+    pub async fn get_current_price(&self, symbol: &str) -> Result<Option<f64>, TcbsError> {
         let url = format!("{}/stock-insight/v1/stock/second-tc-price", self.base_url);
         let symbol_upper = symbol.to_uppercase();
         let params = &[("tickers", symbol_upper.as_str())];
@@ -572,46 +1136,69 @@ impl TcbsClient {
         Ok(current_price)
     }
 
-    async fn make_financial_request(&mut self, url: &str, params: &[(&str, &str)]) -> Result<Value, TcbsError> {
-        // Use direct HTTP request like Python does for financial endpoints
-        self.enforce_rate_limit().await;
-        
-        let user_agent = self.get_user_agent();
-        let request = self.client
-            .get(url)
-            .header("Accept", "application/json, text/plain, */*")
-            .header("Accept-Language", "en-US,en;q=0.9,vi-VN;q=0.8,vi;q=0.7")
-            .header("Accept-Encoding", "gzip, deflate, br")
-            .header("Connection", "keep-alive")
-            .header("Cache-Control", "no-cache")
-            .header("Pragma", "no-cache")
-            .header("DNT", "1")
-            .header("Sec-Fetch-Dest", "empty")
-            .header("Sec-Fetch-Mode", "cors")
-            .header("Sec-Fetch-Site", "cross-site")
-            .header("sec-ch-ua", "\"Not_A Brand\";v=\"8\", \"Chromium\";v=\"120\", \"Google Chrome\";v=\"120\"")
-            .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", "\"Windows\"")
-            .header("User-Agent", user_agent)
-            .header("Referer", "https://www.tcbs.com.vn/")
-            .header("Origin", "https://www.tcbs.com.vn")
-            .timeout(Duration::from_secs(30))
-            .query(params);
-
-        let response = request.send().await?;
-        
-        if response.status().is_success() {
-            let data = response.json::<Value>().await?;
-            Ok(data)
-        } else {
-            Err(TcbsError::Http(reqwest::Error::from(
-                response.error_for_status().unwrap_err()
-            )))
+    /// Top three bid/ask levels from the `second-tc-price` board
+    /// (`b1`/`b1v` .. `o3`/`o3v`). Empty levels are omitted.
+    pub async fn get_price_depth(&self, symbol: &str) -> Result<PriceDepth, TcbsError> {
+        let url = format!("{}/stock-insight/v1/stock/second-tc-price", self.base_url);
+        let symbol_upper = symbol.to_uppercase();
+        let params = &[("tickers", symbol_upper.as_str())];
+
+        let response_data = self.make_request(&url, Some(params)).await?;
+        let data = response_data.get("data")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first())
+            .ok_or(TcbsError::NoData)?;
+        Ok(parse_board_depth(&symbol_upper, data, Utc::now()))
+    }
+
+    /// First page of `symbol`'s news read through the attached cache.
+    #[cfg(feature = "cache")]
+    pub async fn news_cached(&self, symbol: &str, page_size: u32, force_refresh: bool) -> Result<Vec<NewsItem>, TcbsError> {
+        let Some(cache) = &self.cache else {
+            return Ok(self.news_page(symbol, None, page_size).await?.items);
+        };
+        let key = cache::news_key("tcbs", symbol, page_size);
+        if !force_refresh {
+            if let Some(news) = cache.get(CacheKind::News, &key) {
+                return Ok(news);
+            }
         }
+        let news = self.news_page(symbol, None, page_size).await?.items;
+        if let Err(e) = cache.put(CacheKind::News, &key, &news) {
+            tracing::warn!("Cache write failed for {}: {:?}", key, e);
+        }
+        Ok(news)
+    }
+
+    /// Recent activity news for `symbol` that announce a trading-status change.
+    pub async fn exchange_notices(&self, symbol: &str, page_size: u32) -> Result<Vec<ExchangeNotice>, TcbsError> {
+        let page = self.news_page(symbol, None, page_size).await?;
+        let notices = page.items.into_iter()
+            .filter_map(|item| {
+                let status = TradingStatus::classify(&item.title)?;
+                Some(ExchangeNotice {
+                    symbol: item.symbol,
+                    title: item.title,
+                    source: item.source,
+                    publish_date: item.publish_date,
+                    status,
+                })
+            })
+            .filter(|notice| notice.status != TradingStatus::Normal)
+            .collect();
+
+        Ok(notices)
     }
 
-    pub async fn company_info(&mut self, symbol: &str) -> Result<CompanyInfo, TcbsError> {
+    /// Concurrent calls for the same symbol share one set of requests.
+    pub async fn company_info(&self, symbol: &str) -> Result<CompanyInfo, TcbsError> {
+        self.inflight_company
+            .run(symbol.to_uppercase(), async { self.fetch_company_info(symbol).await.map_err(Arc::new) })
+            .await
+            .map_err(|e| Arc::try_unwrap(e).unwrap_or_else(TcbsError::Shared))
+    }
 
+    async fn fetch_company_info(&self, symbol: &str) -> Result<CompanyInfo, TcbsError> {
         let mut company_info = CompanyInfo {
             symbol: symbol.to_uppercase(),
             overview: None,
@@ -680,165 +1267,232 @@ impl TcbsClient {
         Ok(company_info)
     }
 
-    pub async fn financial_info(&mut self, symbol: &str, period: &str) -> Result<FinancialInfo, TcbsError> {
-        let period_value = match period {
-            "quarter" => "1",  // Python uses "1" as string for quarter
-            "year" => "0",     // Python uses "0" as string for year
-            _ => "1",
-        };
+    /// Balance sheet, income statement, cash flow and ratios for `symbol`.
+    /// A section whose request fails is left as `None`.
+    pub async fn financial_info(&self, symbol: &str, period: &str) -> Result<FinancialInfo, TcbsError> {
+        let balance_sheet = self.balance_sheets(symbol, period).await.ok();
+        sleep(Duration::from_millis(500)).await;
+        let income_statement = self.income_statements(symbol, period).await.ok();
+        sleep(Duration::from_millis(500)).await;
+        let cash_flow = self.cash_flows(symbol, period).await.ok();
+        sleep(Duration::from_millis(500)).await;
+        let ratios = self.financial_ratios(symbol, period).await.ok();
 
-        let mut financial_info = FinancialInfo {
+        Ok(FinancialInfo {
             symbol: symbol.to_uppercase(),
             period: period.to_string(),
-            balance_sheet: None,
-            income_statement: None,
-            cash_flow: None,
-            ratios: None,
-        };
+            balance_sheet,
+            income_statement,
+            cash_flow,
+            ratios,
+        })
+    }
+
+    /// [`financial_info`](Self::financial_info) mapped onto the
+    /// provider-independent statement structs.
+    pub async fn financials(&self, symbol: &str, period: &str) -> Result<Financials, TcbsError> {
+        Ok(Financials::from_info(&self.financial_info(symbol, period).await?))
+    }
 
-        // Get balance sheet data - using direct request like Python
-        let bs_url = format!("{}/tcanalysis/v1/finance/{}/balance_sheet", self.base_url, symbol.to_uppercase());
+    /// Financial ratio history (P/E, P/B, ROE, margins, growth) for `symbol`,
+    /// one statement per period with snake_case keys.
+    pub async fn financial_ratios(&self, symbol: &str, period: &str) -> Result<Vec<FinancialStatement>, TcbsError> {
+        self.fetch_statements("financialratio", symbol, period).await
+    }
+
+    /// Balance sheet history for `symbol`, one statement per period.
+    pub async fn balance_sheets(&self, symbol: &str, period: &str) -> Result<Vec<FinancialStatement>, TcbsError> {
+        self.fetch_statements("balance_sheet", symbol, period).await
+    }
+
+    /// Cash flow history for `symbol`, one statement per period.
+    pub async fn cash_flows(&self, symbol: &str, period: &str) -> Result<Vec<FinancialStatement>, TcbsError> {
+        self.fetch_statements("cash_flow", symbol, period).await
+    }
+
+    /// Income statement history for `symbol`, one statement per period.
+    pub async fn income_statements(&self, symbol: &str, period: &str) -> Result<Vec<FinancialStatement>, TcbsError> {
+        self.fetch_statements("income_statement", symbol, period).await
+    }
+
+    async fn fetch_statements(&self, report: &str, symbol: &str, period: &str) -> Result<Vec<FinancialStatement>, TcbsError> {
+        let period_value = if period == "year" { "0" } else { "1" };
+        let url = format!("{}/tcanalysis/v1/finance/{}/{}", self.base_url, symbol.to_uppercase(), report);
         let params = &[("yearly", period_value), ("isAll", "true")];
 
-        match self.make_financial_request(&bs_url, params).await {
-            Ok(data) => {
-                if let Some(bs_array) = data.as_array() {
-                    let mut statements = Vec::new();
-                    for item in bs_array {
-                        let year = item.get("year").and_then(|v| v.as_str()).unwrap_or("");
-                        let quarter = item.get("quarter").and_then(|v| v.as_str()).unwrap_or("");
-                        let period_str = if period == "quarter" && !quarter.is_empty() {
-                            format!("{}-Q{}", year, quarter)
-                        } else {
-                            year.to_string()
-                        };
-
-                        let mut data_map = HashMap::new();
-                        for (key, value) in item.as_object().unwrap() {
-                            if let Some(num_value) = value.as_f64() {
-                                let snake_case_key = self.camel_to_snake(key);
-                                data_map.insert(snake_case_key, num_value);
-                            }
-                        }
-
-                        statements.push(FinancialStatement {
-                            period: period_str,
-                            data: data_map,
-                        });
-                    }
-                    financial_info.balance_sheet = Some(statements);
-                }
-            }
-            Err(_) => financial_info.balance_sheet = None,
-        }
+        let data = self.make_request(&url, Some(params)).await?;
+        let rows = data.as_array().ok_or(TcbsError::NoData)?;
+
+        let statements = rows.iter()
+            .filter_map(|item| {
+                let year = json_label(item.get("year"));
+                let quarter = json_label(item.get("quarter"));
+                let period_str = if period == "quarter" && !quarter.is_empty() {
+                    format!("{}-Q{}", year, quarter)
+                } else {
+                    year
+                };
+
+                let data_map = item.as_object()?
+                    .iter()
+                    .filter(|(key, _)| key.as_str() != "year" && key.as_str() != "quarter")
+                    .filter_map(|(key, value)| Some((self.camel_to_snake(key), value.as_f64()?)))
+                    .collect();
+
+                Some(FinancialStatement {
+                    period: period_str,
+                    data: data_map,
+                })
+            })
+            .collect();
+
+        Ok(statements)
+    }
 
-        sleep(Duration::from_millis(500)).await;
+    /// Forward yearly estimates from the TCBS analysis feed. Not every
+    /// symbol is covered; uncovered symbols return an empty list.
+    pub async fn earnings_estimates(&self, symbol: &str) -> Result<Vec<EarningsEstimate>, TcbsError> {
+        let url = format!("{}/tcanalysis/v1/ticker/{}/financial-forecast", self.base_url, symbol.to_uppercase());
+        let data = self.make_request(&url, None).await?;
 
-        // Get income statement data - using direct request like Python
-        let is_url = format!("{}/tcanalysis/v1/finance/{}/income_statement", self.base_url, symbol.to_uppercase());
-        match self.make_financial_request(&is_url, params).await {
-            Ok(data) => {
-                if let Some(is_array) = data.as_array() {
-                    let mut statements = Vec::new();
-                    for item in is_array {
-                        let year = item.get("year").and_then(|v| v.as_str()).unwrap_or("");
-                        let quarter = item.get("quarter").and_then(|v| v.as_str()).unwrap_or("");
-                        let period_str = if period == "quarter" && !quarter.is_empty() {
-                            format!("{}-Q{}", year, quarter)
-                        } else {
-                            year.to_string()
-                        };
-
-                        let mut data_map = HashMap::new();
-                        for (key, value) in item.as_object().unwrap() {
-                            if let Some(num_value) = value.as_f64() {
-                                let snake_case_key = self.camel_to_snake(key);
-                                data_map.insert(snake_case_key, num_value);
-                            }
-                        }
-
-                        statements.push(FinancialStatement {
-                            period: period_str,
-                            data: data_map,
-                        });
-                    }
-                    financial_info.income_statement = Some(statements);
-                }
-            }
-            Err(_) => financial_info.income_statement = None,
-        }
+        let rows = data.get("listForecast")
+            .or_else(|| data.get("data"))
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let number = |row: &Value, keys: &[&str]| keys.iter().find_map(|key| row.get(*key).and_then(|v| v.as_f64()));
+        let estimates = rows.iter()
+            .filter_map(|row| {
+                let period = json_label(row.get("year"));
+                (!period.is_empty()).then(|| EarningsEstimate {
+                    period,
+                    revenue: number(row, &["revenue", "netRevenue"]),
+                    net_profit: number(row, &["postTaxProfit", "shareHolderIncome", "netProfit"]),
+                    eps: number(row, &["eps", "earningPerShare"]),
+                })
+            })
+            .collect();
+
+        Ok(estimates)
+    }
 
-        sleep(Duration::from_millis(500)).await;
+    /// Daily point-in-time history of one valuation ratio over `[start, end]`,
+    /// from TCBS daily closes and quarterly per-share fundamentals.
+    pub async fn ratio_history(&self, symbol: &str, metric: RatioMetric, start: NaiveDate, end: Option<NaiveDate>) -> Result<Vec<RatioPoint>, TcbsError> {
+        let range = DateRange::through(start, end).map_err(TcbsError::InvalidDateRange)?;
+        let count_back = calendar::trading_days_between(range.start, range.end).len() as u32 + 10;
 
-        // Get cash flow data - using direct request like Python
-        let cf_url = format!("{}/tcanalysis/v1/finance/{}/cash_flow", self.base_url, symbol.to_uppercase());
-        match self.make_financial_request(&cf_url, params).await {
-            Ok(data) => {
-                if let Some(cf_array) = data.as_array() {
-                    let mut statements = Vec::new();
-                    for item in cf_array {
-                        let year = item.get("year").and_then(|v| v.as_str()).unwrap_or("");
-                        let quarter = item.get("quarter").and_then(|v| v.as_str()).unwrap_or("");
-                        
-                        let mut data_map = HashMap::new();
-                        for (key, value) in item.as_object().unwrap() {
-                            if let Some(num_value) = value.as_f64() {
-                                let snake_case_key = self.camel_to_snake(key);
-                                data_map.insert(snake_case_key, num_value);
-                            }
-                        }
-
-                        statements.push(FinancialStatement {
-                            period: format!("{}-{}", year, quarter),
-                            data: data_map,
-                        });
-                    }
-                    financial_info.cash_flow = Some(statements);
-                }
-            }
-            Err(_) => financial_info.cash_flow = None,
-        }
+        let (prices, ratios) = tokio::join!(
+            self.get_history(symbol, start, end, "1D", count_back),
+            self.financial_ratios(symbol, "quarter"),
+        );
+        let prices: Vec<Ohlcv> = prices?.into_iter().map(Ohlcv::from).collect();
+        let fundamentals = valuation::fundamentals_from_ratios(&ratios?);
 
-        sleep(Duration::from_millis(500)).await;
+        Ok(valuation::ratio_series(&prices, &fundamentals, metric))
+    }
 
-        // Get financial ratios - using direct request like Python
-        let ratios_url = format!("{}/tcanalysis/v1/finance/{}/financialratio", self.base_url, symbol.to_uppercase());
-        match self.make_financial_request(&ratios_url, params).await {
-            Ok(data) => {
-                if let Some(ratios_array) = data.as_array() {
-                    let mut statements = Vec::new();
-                    for item in ratios_array {
-                        let year = item.get("year").and_then(|v| v.as_str()).unwrap_or("");
-                        let quarter = item.get("quarter").and_then(|v| v.as_str()).unwrap_or("");
-                        let period_str = if period == "quarter" && !quarter.is_empty() {
-                            format!("{}-Q{}", year, quarter)
-                        } else {
-                            year.to_string()
-                        };
-
-                        let mut data_map = HashMap::new();
-                        for (key, value) in item.as_object().unwrap() {
-                            if let Some(num_value) = value.as_f64() {
-                                let snake_case_key = self.camel_to_snake(key);
-                                data_map.insert(snake_case_key, num_value);
-                            }
-                        }
-
-                        statements.push(FinancialStatement {
-                            period: period_str,
-                            data: data_map,
-                        });
-                    }
-                    financial_info.ratios = Some(statements);
-                }
-            }
-            Err(_) => financial_info.ratios = None,
+    /// Quarterly YoY revenue/earnings growth plus forward estimates. The two
+    /// sources are fetched concurrently; missing estimates are not an error.
+    pub async fn growth_profile(&self, symbol: &str) -> Result<GrowthProfile, TcbsError> {
+        let (statements, estimates) = tokio::join!(
+            self.income_statements(symbol, "quarter"),
+            self.earnings_estimates(symbol),
+        );
+
+        Ok(GrowthProfile {
+            symbol: symbol.to_uppercase(),
+            quarterly: growth::quarterly_growth(&statements?),
+            estimates: estimates.unwrap_or_default(),
+        })
+    }
+
+    /// Latest quarterly valuation, growth and profitability metrics for
+    /// `symbols`, aligned into one matrix. Symbols whose ratios cannot be
+    /// fetched appear with empty columns.
+    pub async fn compare(&self, symbols: &[&str]) -> Result<ComparisonMatrix, TcbsError> {
+        if symbols.is_empty() {
+            return Err(TcbsError::InvalidResponse("Symbols list cannot be empty".to_string()));
         }
 
-        Ok(financial_info)
+        let fetches = symbols.iter().map(|symbol| async move {
+            let ratios = self.financial_ratios(symbol, "quarter").await.unwrap_or_else(|e| {
+                tracing::warn!("TCBS ratios fetch failed for {}: {:?}", symbol, e);
+                Vec::new()
+            });
+            (symbol.to_uppercase(), ratios)
+        });
+
+        let statements = futures::future::join_all(fetches).await;
+        Ok(compare::build_matrix(&statements))
     }
 }
 
+fn parse_insider_deal(symbol: &str, deal: &Value) -> Option<RelatedPartyDeal> {
+    let text = |key: &str| deal.get(key).and_then(|v| v.as_str()).filter(|s| !s.is_empty()).map(str::to_string);
+    // dealingAction: 0 = buy, 1 = sell; newer payloads send text instead
+    let side = match deal.get("dealingAction") {
+        Some(Value::Number(n)) if n.as_i64() == Some(0) => TradeSide::Buy,
+        Some(Value::Number(n)) if n.as_i64() == Some(1) => TradeSide::Sell,
+        Some(Value::String(action)) if action.to_lowercase().starts_with("mua") => TradeSide::Buy,
+        Some(Value::String(action)) if action.to_lowercase().starts_with("bán") => TradeSide::Sell,
+        Some(Value::String(action)) => TradeSide::from_code(action),
+        _ => TradeSide::Unknown,
+    };
+
+    Some(RelatedPartyDeal {
+        symbol: symbol.to_uppercase(),
+        announce_date: text("anDate"),
+        party: text("name").or_else(|| text("dealerName")),
+        relation: text("relationship").or_else(|| text("position")),
+        side,
+        quantity: deal.get("quantity").and_then(|v| v.as_f64())?.abs(),
+        price: deal.get("price").and_then(|v| v.as_f64()).filter(|&p| p > 0.0),
+        ownership_ratio: deal.get("ratio").and_then(|v| v.as_f64()),
+    })
+}
+
+/// Period component as text; TCBS sends `year`/`quarter` as either strings or numbers.
+fn json_label(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn parse_board_depth(symbol: &str, data: &Value, time: DateTime<Utc>) -> PriceDepth {
+    let number = |key: String| data.get(&key).and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()));
+    let levels = |prefix: &str| -> Vec<DepthLevel> {
+        (1..=3)
+            .filter_map(|n| {
+                let price = number(format!("{}{}", prefix, n)).filter(|&p| p > 0.0)?;
+                Some(DepthLevel {
+                    price,
+                    volume: number(format!("{}{}v", prefix, n)).unwrap_or(0.0) as u64,
+                    orders: None,
+                })
+            })
+            .collect()
+    };
+    PriceDepth { symbol: symbol.to_string(), time, bids: levels("b"), asks: levels("o") }
+}
+
+/// Intraday record `{p, v, a, t}` where `t` is `HH:MM:SS` exchange time on
+/// `date` and `a` is `BU`/`SD` (blank for auction prints).
+fn parse_intraday_tick(item: &Value, date: NaiveDate) -> Option<TickData> {
+    let clock = chrono::NaiveTime::parse_from_str(item.get("t")?.as_str()?, "%H:%M:%S").ok()?;
+    let time = date.and_time(clock).and_local_timezone(vietnam_offset()).single()?.with_timezone(&Utc);
+    Some(TickData {
+        time,
+        price: item.get("p")?.as_f64()?,
+        volume: item.get("v")?.as_f64()? as u64,
+        side: TradeSide::from_code(item.get("a").and_then(|v| v.as_str()).unwrap_or("")),
+        id: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -849,6 +1503,24 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_builder_configuration() {
+        let client = TcbsClient::builder()
+            .base_url("http://127.0.0.1:8080")
+            .header("X-Api-Key", "secret")
+            .proxy("http://proxy.example:3128")
+            .timeout(Duration::from_secs(5))
+            .rate_limit(120)
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url, "http://127.0.0.1:8080");
+        assert_eq!(client.extra_headers["x-api-key"], "secret");
+        assert_eq!(client.rate_limiter().per_minute(), 120);
+
+        let invalid = TcbsClient::builder().header("bad header", "x").build();
+        assert!(matches!(invalid, Err(TcbsError::InvalidConfig(_))));
+    }
+
     #[tokio::test]
     async fn test_interval_mapping() {
         let client = TcbsClient::new(false, 6).unwrap();
@@ -857,6 +1529,20 @@ mod tests {
         assert!(client.get_interval_value("invalid").is_err());
     }
 
+    #[test]
+    fn test_related_party_summary() {
+        let deals = [
+            serde_json::json!({"anDate": "15/05/24", "dealingAction": 0, "quantity": 1000.0, "price": 20.5}),
+            serde_json::json!({"anDate": "20/05/24", "dealingAction": "Bán", "quantity": 400.0}),
+        ];
+        let deals = deals.iter().filter_map(|deal| parse_insider_deal("hpg", deal)).collect();
+        let summary = RelatedPartySummary::from_deals("hpg", deals);
+        assert_eq!(summary.deals[1].side, TradeSide::Sell);
+        assert_eq!(summary.net_quantity(), 600.0);
+        assert_eq!(summary.bought_value, 20_500.0);
+        assert_eq!(summary.sold_value, 0.0);
+    }
+
     #[test]
     fn test_camel_to_snake() {
         let client = TcbsClient::new(false, 6).unwrap();
@@ -864,4 +1550,22 @@ mod tests {
         assert_eq!(client.camel_to_snake("PascalCase"), "pascal_case");
         assert_eq!(client.camel_to_snake("simple"), "simple");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_intraday_tick() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let tick = parse_intraday_tick(&serde_json::json!({"p": 28350.0, "v": 1200, "a": "SD", "t": "14:29:58"}), date).unwrap();
+        assert_eq!(tick.time, Utc.with_ymd_and_hms(2024, 6, 3, 7, 29, 58).unwrap());
+        assert_eq!((tick.price, tick.volume, tick.side), (28350.0, 1200, TradeSide::Sell));
+        assert_eq!(parse_intraday_tick(&serde_json::json!({"p": 28350.0, "v": 100, "a": "", "t": "14:45:00"}), date).unwrap().side, TradeSide::Unknown);
+    }
+
+    #[test]
+    fn test_parse_board_depth() {
+        let data = serde_json::json!({"b1": 28300, "b1v": 15000, "b2": 28250, "b2v": 4200, "b3": 0, "o1": 28350, "o1v": 9000});
+        let depth = parse_board_depth("HPG", &data, Utc::now());
+        assert_eq!(depth.bids.len(), 2);
+        assert_eq!(depth.spread(), Some(50.0));
+        assert!((depth.imbalance().unwrap() - 19_200.0 / 28_200.0).abs() < 1e-9);
+    }
+}
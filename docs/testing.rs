@@ -0,0 +1,182 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::calendar;
+use crate::market_rules;
+use crate::models::{vietnam_offset, Exchange, Ohlcv};
+use crate::vci::{CompanyInfo, FinancialRatio, OfficerInfo, ShareholderInfo};
+
+/// Well-known tickers with their listing exchange, used before any
+/// generated codes.
+pub const SAMPLE_TICKERS: [(&str, Exchange); 16] = [
+    ("VNM", Exchange::Hose),
+    ("FPT", Exchange::Hose),
+    ("HPG", Exchange::Hose),
+    ("VCB", Exchange::Hose),
+    ("MWG", Exchange::Hose),
+    ("VIC", Exchange::Hose),
+    ("SSI", Exchange::Hose),
+    ("TCB", Exchange::Hose),
+    ("SHS", Exchange::Hnx),
+    ("PVS", Exchange::Hnx),
+    ("CEO", Exchange::Hnx),
+    ("IDC", Exchange::Hnx),
+    ("ACV", Exchange::Upcom),
+    ("BSR", Exchange::Upcom),
+    ("VGI", Exchange::Upcom),
+    ("QNS", Exchange::Upcom),
+];
+
+const INDUSTRIES: [&str; 6] = ["Ngân hàng", "Bất động sản", "Công nghệ thông tin", "Thép", "Bán lẻ", "Chứng khoán"];
+const SURNAMES: [&str; 6] = ["Nguyễn", "Trần", "Lê", "Phạm", "Hoàng", "Vũ"];
+const GIVEN_NAMES: [&str; 6] = ["Văn An", "Thị Bình", "Minh Châu", "Quốc Dũng", "Thu Hà", "Đức Long"];
+
+/// Seeded generator of synthetic but plausible market data. The same seed
+/// always yields the same data, so fixtures can be regenerated instead of
+/// recorded.
+pub struct FixtureGenerator {
+    rng: StdRng,
+}
+
+impl FixtureGenerator {
+    pub fn new(seed: u64) -> Self {
+        FixtureGenerator { rng: StdRng::seed_from_u64(seed) }
+    }
+
+    /// `count` distinct tickers: the sample list first, then generated
+    /// three-letter codes on random exchanges.
+    pub fn tickers(&mut self, count: usize) -> Vec<(String, Exchange)> {
+        let mut tickers: Vec<(String, Exchange)> = SAMPLE_TICKERS.iter()
+            .take(count)
+            .map(|(symbol, exchange)| (symbol.to_string(), *exchange))
+            .collect();
+        while tickers.len() < count {
+            let symbol: String = (0..3).map(|_| self.rng.gen_range(b'A'..=b'Z') as char).collect();
+            if tickers.iter().any(|(existing, _)| *existing == symbol) {
+                continue;
+            }
+            let exchange = *[Exchange::Hose, Exchange::Hnx, Exchange::Upcom].choose(&mut self.rng).unwrap();
+            tickers.push((symbol, exchange));
+        }
+        tickers
+    }
+
+    /// Daily bars on `days` consecutive trading days from `start`. Prices
+    /// follow a random walk that respects the exchange's daily price band
+    /// and tick grid; volumes are whole board lots.
+    pub fn daily_bars(&mut self, symbol: &str, exchange: Exchange, start: NaiveDate, days: usize, start_price: f64) -> Vec<Ohlcv> {
+        let dates = calendar::trading_days_between(start, start + chrono::Duration::days(days as i64 * 2 + 14));
+        let band = market_rules::price_band_pct(exchange);
+        let lot = market_rules::board_lot(exchange);
+        let mut reference = market_rules::round_to_tick(start_price, exchange);
+        let mut bars = Vec::with_capacity(days);
+
+        for date in dates.into_iter().take(days) {
+            let (floor, ceiling) = market_rules::price_limits(reference, exchange);
+            let sample = |rng: &mut StdRng| {
+                let price = reference * (1.0 + rng.gen_range(-band..band) * 0.5);
+                market_rules::round_to_tick(price.clamp(floor, ceiling), exchange).clamp(floor, ceiling)
+            };
+            let open = sample(&mut self.rng);
+            let close = sample(&mut self.rng);
+            let high = open.max(close).max(sample(&mut self.rng));
+            let low = open.min(close).min(sample(&mut self.rng));
+            let volume = self.rng.gen_range(50..20_000) * lot;
+
+            let time = vietnam_offset()
+                .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+                .unwrap()
+                .with_timezone(&Utc);
+            bars.push(Ohlcv { time, open, high, low, close, volume, symbol: Some(symbol.to_string()), breakdown: None, futures: None });
+            reference = close;
+        }
+        bars
+    }
+
+    pub fn company_info(&mut self, symbol: &str, exchange: Exchange) -> CompanyInfo {
+        let outstanding_shares = self.rng.gen_range(10..5_000) * 1_000_000;
+        let current_price = market_rules::round_to_tick(self.rng.gen_range(5_000.0..150_000.0), exchange);
+        let shareholders = (0..3)
+            .map(|_| ShareholderInfo { name: self.person_name(), percentage: self.rng.gen_range(0.01..0.2) })
+            .collect();
+        let officers = ["Chủ tịch HĐQT", "Tổng Giám đốc"].iter()
+            .map(|position| OfficerInfo {
+                name: self.person_name(),
+                position: position.to_string(),
+                percentage: Some(self.rng.gen_range(0.0..0.05)),
+            })
+            .collect();
+
+        CompanyInfo {
+            symbol: symbol.to_uppercase(),
+            company_name: Some(format!("Công ty Cổ phần {}", symbol.to_uppercase())),
+            exchange: Some(exchange.as_str().to_string()),
+            industry: Some(INDUSTRIES.choose(&mut self.rng).unwrap().to_string()),
+            company_type: Some("CT".to_string()),
+            established_year: Some(self.rng.gen_range(1990..2015)),
+            employees: Some(self.rng.gen_range(100..20_000)),
+            market_cap: Some(current_price * outstanding_shares as f64),
+            current_price: Some(current_price),
+            outstanding_shares: Some(outstanding_shares),
+            company_profile: None,
+            website: Some(format!("https://www.{}.com.vn", symbol.to_lowercase())),
+            shareholders,
+            officers,
+        }
+    }
+
+    /// Ratio set with internally consistent EPS, P/E and ROE.
+    pub fn financial_ratio(&mut self, price: f64) -> FinancialRatio {
+        let eps = self.rng.gen_range(300.0..8_000.0);
+        let roe = self.rng.gen_range(0.03..0.3);
+        let book_value = eps / roe;
+        let revenue = self.rng.gen_range(500.0..100_000.0) * 1e9;
+        FinancialRatio {
+            pe: Some(price / eps),
+            pb: Some(price / book_value),
+            roe: Some(roe),
+            roa: Some(roe * self.rng.gen_range(0.1..0.6)),
+            revenue: Some(revenue),
+            net_profit: Some(revenue * self.rng.gen_range(0.02..0.25)),
+            dividend: Some(self.rng.gen_range(0.0..0.2)),
+            eps: Some(eps),
+        }
+    }
+
+    fn person_name(&mut self) -> String {
+        format!("{} {}", SURNAMES.choose(&mut self.rng).unwrap(), GIVEN_NAMES.choose(&mut self.rng).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bars_are_deterministic_and_within_band() {
+        let start = NaiveDate::from_ymd_opt(2024, 6, 3).unwrap();
+        let a = FixtureGenerator::new(7).daily_bars("FPT", Exchange::Hose, start, 30, 120_000.0);
+        let b = FixtureGenerator::new(7).daily_bars("FPT", Exchange::Hose, start, 30, 120_000.0);
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 30);
+
+        for pair in a.windows(2) {
+            let (floor, ceiling) = market_rules::price_limits(pair[0].close, Exchange::Hose);
+            let bar = &pair[1];
+            assert!(bar.low >= floor && bar.high <= ceiling);
+            assert!(bar.low <= bar.open.min(bar.close) && bar.high >= bar.open.max(bar.close));
+            assert!(market_rules::is_valid_tick(bar.close, Exchange::Hose));
+        }
+    }
+
+    #[test]
+    fn test_tickers_are_unique() {
+        let tickers = FixtureGenerator::new(1).tickers(40);
+        let mut symbols: Vec<&str> = tickers.iter().map(|(symbol, _)| symbol.as_str()).collect();
+        symbols.sort();
+        symbols.dedup();
+        assert_eq!(symbols.len(), 40);
+    }
+}
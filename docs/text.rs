@@ -0,0 +1,59 @@
+/// Base letter for a precomposed Vietnamese character, or `None` when the
+/// character carries no Vietnamese diacritic.
+fn base_letter(ch: char) -> Option<char> {
+    let base = match ch {
+        'à' | 'á' | 'ả' | 'ã' | 'ạ' | 'ă' | 'ằ' | 'ắ' | 'ẳ' | 'ẵ' | 'ặ' | 'â' | 'ầ' | 'ấ' | 'ẩ' | 'ẫ' | 'ậ' => 'a',
+        'À' | 'Á' | 'Ả' | 'Ã' | 'Ạ' | 'Ă' | 'Ằ' | 'Ắ' | 'Ẳ' | 'Ẵ' | 'Ặ' | 'Â' | 'Ầ' | 'Ấ' | 'Ẩ' | 'Ẫ' | 'Ậ' => 'A',
+        'è' | 'é' | 'ẻ' | 'ẽ' | 'ẹ' | 'ê' | 'ề' | 'ế' | 'ể' | 'ễ' | 'ệ' => 'e',
+        'È' | 'É' | 'Ẻ' | 'Ẽ' | 'Ẹ' | 'Ê' | 'Ề' | 'Ế' | 'Ể' | 'Ễ' | 'Ệ' => 'E',
+        'ì' | 'í' | 'ỉ' | 'ĩ' | 'ị' => 'i',
+        'Ì' | 'Í' | 'Ỉ' | 'Ĩ' | 'Ị' => 'I',
+        'ò' | 'ó' | 'ỏ' | 'õ' | 'ọ' | 'ô' | 'ồ' | 'ố' | 'ổ' | 'ỗ' | 'ộ' | 'ơ' | 'ờ' | 'ớ' | 'ở' | 'ỡ' | 'ợ' => 'o',
+        'Ò' | 'Ó' | 'Ỏ' | 'Õ' | 'Ọ' | 'Ô' | 'Ồ' | 'Ố' | 'Ổ' | 'Ỗ' | 'Ộ' | 'Ơ' | 'Ờ' | 'Ớ' | 'Ở' | 'Ỡ' | 'Ợ' => 'O',
+        'ù' | 'ú' | 'ủ' | 'ũ' | 'ụ' | 'ư' | 'ừ' | 'ứ' | 'ử' | 'ữ' | 'ự' => 'u',
+        'Ù' | 'Ú' | 'Ủ' | 'Ũ' | 'Ụ' | 'Ư' | 'Ừ' | 'Ứ' | 'Ử' | 'Ữ' | 'Ự' => 'U',
+        'ỳ' | 'ý' | 'ỷ' | 'ỹ' | 'ỵ' => 'y',
+        'Ỳ' | 'Ý' | 'Ỷ' | 'Ỹ' | 'Ỵ' => 'Y',
+        'đ' => 'd',
+        'Đ' => 'D',
+        _ => return None,
+    };
+    Some(base)
+}
+
+/// Strips Vietnamese diacritics, keeping case: "Đình chỉ" -> "Dinh chi".
+/// Handles both precomposed (NFC) text and decomposed combining marks.
+pub fn remove_diacritics(text: &str) -> String {
+    text.chars()
+        .filter(|ch| !('\u{0300}'..='\u{036F}').contains(ch))
+        .map(|ch| base_letter(ch).unwrap_or(ch))
+        .collect()
+}
+
+/// Search key form: no diacritics, lowercase, whitespace collapsed to single spaces.
+pub fn normalize(text: &str) -> String {
+    remove_diacritics(text)
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Diacritics- and case-insensitive substring match.
+pub fn contains_normalized(haystack: &str, needle: &str) -> bool {
+    normalize(haystack).contains(&normalize(needle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(remove_diacritics("Tổng Công ty Đầu tư"), "Tong Cong ty Dau tu");
+        assert_eq!(normalize("  NGÂN   hàng  "), "ngan hang");
+        // Decomposed "ế" (e + circumflex + acute)
+        assert_eq!(normalize("Ke\u{0302}\u{0301} toa\u{0301}n"), "ke toan");
+        assert!(contains_normalized("Cổ phiếu bị Tạm ngừng giao dịch", "tam ngung"));
+    }
+}
@@ -0,0 +1,398 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::trading_days_between;
+use crate::models::Ohlcv;
+
+/// Resolutions advertised to the charting library, in UDF notation.
+pub const SUPPORTED_RESOLUTIONS: [&str; 8] = ["1", "5", "15", "30", "60", "D", "W", "M"];
+
+/// HOSE/HNX continuous + closing auction window in exchange time.
+pub const SESSION: &str = "0900-1130,1300-1500";
+
+pub const TIMEZONE: &str = "Asia/Ho_Chi_Minh";
+
+/// Maps a UDF resolution string to the crate's interval notation.
+pub fn resolution_to_interval(resolution: &str) -> Option<&'static str> {
+    match resolution {
+        "1" => Some("1m"),
+        "5" => Some("5m"),
+        "15" => Some("15m"),
+        "30" => Some("30m"),
+        "60" | "1H" => Some("1H"),
+        "D" | "1D" => Some("1D"),
+        "W" | "1W" => Some("1W"),
+        "M" | "1M" => Some("1M"),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UdfConfig {
+    pub supported_resolutions: Vec<String>,
+    pub supports_group_request: bool,
+    pub supports_marks: bool,
+    pub supports_search: bool,
+    pub supports_timescale_marks: bool,
+    pub supports_time: bool,
+}
+
+impl Default for UdfConfig {
+    fn default() -> Self {
+        UdfConfig {
+            supported_resolutions: SUPPORTED_RESOLUTIONS.iter().map(|s| s.to_string()).collect(),
+            supports_group_request: false,
+            supports_marks: false,
+            supports_search: false,
+            supports_timescale_marks: false,
+            supports_time: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UdfSymbolInfo {
+    pub name: String,
+    pub ticker: String,
+    pub description: String,
+    #[serde(rename = "type")]
+    pub symbol_type: String,
+    pub session: String,
+    pub exchange: String,
+    pub listed_exchange: String,
+    pub timezone: String,
+    pub minmov: u32,
+    pub pricescale: u32,
+    pub has_intraday: bool,
+    pub has_weekly_and_monthly: bool,
+    pub supported_resolutions: Vec<String>,
+    pub volume_precision: u32,
+    pub data_status: String,
+}
+
+impl UdfSymbolInfo {
+    pub fn new(symbol: &str, exchange: Option<&str>) -> Self {
+        let symbol = symbol.to_uppercase();
+        let exchange = exchange.unwrap_or("HOSE").to_string();
+        UdfSymbolInfo {
+            name: symbol.clone(),
+            ticker: symbol.clone(),
+            description: symbol,
+            symbol_type: "stock".to_string(),
+            session: SESSION.to_string(),
+            exchange: exchange.clone(),
+            listed_exchange: exchange,
+            timezone: TIMEZONE.to_string(),
+            minmov: 1,
+            pricescale: 1,
+            has_intraday: true,
+            has_weekly_and_monthly: true,
+            supported_resolutions: SUPPORTED_RESOLUTIONS.iter().map(|s| s.to_string()).collect(),
+            volume_precision: 0,
+            data_status: "streaming".to_string(),
+        }
+    }
+}
+
+/// `/history` response in UDF's column-array layout.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UdfHistory {
+    pub s: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub t: Vec<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub o: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub h: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub l: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub c: Vec<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub v: Vec<u64>,
+    #[serde(rename = "nextTime", skip_serializing_if = "Option::is_none")]
+    pub next_time: Option<i64>,
+    #[serde(rename = "errmsg", skip_serializing_if = "Option::is_none")]
+    pub err_msg: Option<String>,
+}
+
+impl UdfHistory {
+    pub fn error(message: &str) -> Self {
+        UdfHistory {
+            s: "error".to_string(),
+            err_msg: Some(message.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Session ranges of `[from, to]` a store holding daily bars from `stored.0`
+/// to `stored.1` still needs: the trading days before its first bar, and its
+/// last session onwards up to `session` so a bar stored mid-session is
+/// refetched once final. Dates are exchange dates; ranges without a trading
+/// day are dropped.
+pub fn missing_ranges(stored: Option<(NaiveDate, NaiveDate)>, from: NaiveDate, to: NaiveDate, session: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+    let span = |start: NaiveDate, end: NaiveDate| {
+        let days = trading_days_between(start, end);
+        days.first().copied().zip(days.last().copied())
+    };
+    let Some((first, last)) = stored else {
+        return span(from, to.min(session)).into_iter().collect();
+    };
+    let mut ranges = Vec::new();
+    if from < first {
+        ranges.extend(span(from, to.min(first - Duration::days(1))));
+    }
+    if last <= to.min(session) {
+        ranges.extend(span(last, to.min(session)));
+    }
+    ranges
+}
+
+/// Builds a `/history` response from bars within `[from, to]` (unix seconds).
+/// When nothing falls in range, `nextTime` points at the latest earlier bar so
+/// the chart can jump back instead of paging through empty windows.
+pub fn history_response(bars: &[Ohlcv], from: i64, to: i64) -> UdfHistory {
+    let mut history = UdfHistory {
+        s: "ok".to_string(),
+        ..Default::default()
+    };
+
+    for bar in bars {
+        let t = bar.time.timestamp();
+        if t < from || t > to {
+            continue;
+        }
+        history.t.push(t);
+        history.o.push(bar.open);
+        history.h.push(bar.high);
+        history.l.push(bar.low);
+        history.c.push(bar.close);
+        history.v.push(bar.volume);
+    }
+
+    if history.t.is_empty() {
+        history.s = "no_data".to_string();
+        history.next_time = bars.iter()
+            .map(|bar| bar.time.timestamp())
+            .filter(|&t| t < from)
+            .max();
+    }
+
+    history
+}
+
+#[cfg(feature = "server")]
+pub mod server {
+    use super::*;
+    use axum::extract::{Query, State};
+    use axum::response::AppendHeaders;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use chrono::{DateTime, NaiveDate, Utc};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    use crate::calendar::session_date;
+    use crate::failover::Provider;
+    use crate::models::{vietnam_offset, Language};
+    use crate::provenance::{DataSource, Licensing};
+    use crate::store::LocalStore;
+    use crate::vci::{CompanySection, VciClient};
+
+    /// Shared state behind the UDF routes: the upstream client plus an
+    /// optional local store used as a read-through cache for daily data.
+    pub struct UdfState {
+        client: VciClient,
+        store: Option<LocalStore>,
+        exchanges: Mutex<HashMap<String, String>>,
+        licensing: Licensing,
+    }
+
+    impl UdfState {
+        pub fn new(client: VciClient, store: Option<LocalStore>) -> Self {
+            UdfState {
+                client,
+                store,
+                exchanges: Mutex::new(HashMap::new()),
+                licensing: Licensing::default(),
+            }
+        }
+
+        /// Attribution and terms sent as `X-Data-*` headers on `/history`
+        /// responses carrying bars.
+        pub fn with_licensing(mut self, licensing: Licensing) -> Self {
+            self.licensing = licensing;
+            self
+        }
+
+        async fn exchange(&self, symbol: &str) -> Option<String> {
+            if let Some(exchange) = self.exchanges.lock().await.get(symbol) {
+                return Some(exchange.clone());
+            }
+            let info = self.client.company_info_with(symbol, Language::Vietnamese, &[CompanySection::Price]).await.ok()?;
+            let exchange = info.exchange?;
+            self.exchanges.lock().await.insert(symbol.to_string(), exchange.clone());
+            Some(exchange)
+        }
+
+        async fn fetch(&self, symbol: &str, interval: &str, from: NaiveDate, to: NaiveDate) -> Option<(Vec<Ohlcv>, DataSource)> {
+            match self.client.get_history(symbol, from, Some(to), interval).await {
+                Ok(bars) => Some((bars.into_iter().map(Ohlcv::from).collect(), DataSource::now(Provider::Vci))),
+                Err(e) => {
+                    tracing::warn!("UDF history fetch failed for {} [{}]: {:?}", symbol, interval, e);
+                    None
+                }
+            }
+        }
+
+        /// Serves daily/weekly/monthly bars from the store, fetching only the
+        /// sessions before the first stored bar and from the last one on,
+        /// which is refetched in case it was stored mid-session. Intraday
+        /// always goes upstream. The source is the store's record of its
+        /// latest fetch, or `None` when unknown.
+        async fn load_bars(&self, symbol: &str, interval: &str, from: NaiveDate, to: NaiveDate) -> (Vec<Ohlcv>, Option<DataSource>) {
+            let Some(store) = self.store.as_ref().filter(|_| matches!(interval, "1D" | "1W" | "1M")) else {
+                return match self.fetch(symbol, interval, from, to).await {
+                    Some((bars, source)) => (bars, Some(source)),
+                    None => (Vec::new(), None),
+                };
+            };
+
+            let daily = store.read(symbol, "1D").unwrap_or_default();
+            let exchange_date = |bar: &Ohlcv| bar.time.with_timezone(&vietnam_offset()).date_naive();
+            let stored = daily.first().zip(daily.last()).map(|(first, last)| (exchange_date(first), exchange_date(last)));
+
+            for (missing_from, missing_to) in missing_ranges(stored, from, to, session_date(Utc::now())) {
+                let Some((fetched, source)) = self.fetch(symbol, "1D", missing_from, missing_to).await else {
+                    continue;
+                };
+                let stored = store.upsert_daily(symbol, &fetched).and_then(|_| store.record_source(symbol, &source));
+                if let Err(e) = stored {
+                    tracing::warn!("UDF store update failed for {}: {:?}", symbol, e);
+                }
+            }
+
+            (store.read(symbol, interval).unwrap_or_default(), store.source(symbol))
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SymbolQuery {
+        pub symbol: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct HistoryQuery {
+        pub symbol: String,
+        pub resolution: String,
+        pub from: i64,
+        pub to: i64,
+    }
+
+    async fn config() -> Json<UdfConfig> {
+        Json(UdfConfig::default())
+    }
+
+    async fn time() -> String {
+        Utc::now().timestamp().to_string()
+    }
+
+    async fn symbols(State(state): State<Arc<UdfState>>, Query(query): Query<SymbolQuery>) -> Json<UdfSymbolInfo> {
+        let symbol = query.symbol.to_uppercase();
+        let exchange = state.exchange(&symbol).await;
+        Json(UdfSymbolInfo::new(&symbol, exchange.as_deref()))
+    }
+
+    async fn history(
+        State(state): State<Arc<UdfState>>,
+        Query(query): Query<HistoryQuery>,
+    ) -> (AppendHeaders<Vec<(&'static str, String)>>, Json<UdfHistory>) {
+        let no_headers = || AppendHeaders(Vec::new());
+        let Some(interval) = resolution_to_interval(&query.resolution) else {
+            return (no_headers(), Json(UdfHistory::error(&format!("Unsupported resolution: {}", query.resolution))));
+        };
+        let (Some(from), Some(to)) = (
+            DateTime::<Utc>::from_timestamp(query.from, 0),
+            DateTime::<Utc>::from_timestamp(query.to, 0),
+        ) else {
+            return (no_headers(), Json(UdfHistory::error("Invalid from/to timestamps")));
+        };
+
+        let symbol = query.symbol.to_uppercase();
+        let (bars, source) = state.load_bars(&symbol, interval, from.with_timezone(&vietnam_offset()).date_naive(), to.with_timezone(&vietnam_offset()).date_naive()).await;
+        let history = history_response(&bars, query.from, query.to);
+        let headers = match source {
+            Some(source) if history.s == "ok" => AppendHeaders(state.licensing.provenance_of(&source).headers()),
+            _ => no_headers(),
+        };
+        (headers, Json(history))
+    }
+
+    /// Router exposing `/config`, `/time`, `/symbols` and `/history`.
+    pub fn router(state: Arc<UdfState>) -> Router {
+        Router::new()
+            .route("/config", get(config))
+            .route("/time", get(time))
+            .route("/symbols", get(symbols))
+            .route("/history", get(history))
+            .with_state(state)
+    }
+
+    pub async fn serve(addr: &str, state: UdfState) -> std::io::Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, router(Arc::new(state))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn bar(day: u32) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 5, day, 0, 0, 0).unwrap(),
+            open: 1.0,
+            high: 2.0,
+            low: 0.5,
+            close: 1.5,
+            volume: 10,
+            symbol: None,
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_missing_ranges_cover_both_ends() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        // 2024-05-01 is Labour Day; the 4th/5th and 11th/12th are weekends
+        assert_eq!(missing_ranges(None, day(1), day(9), day(20)), vec![(day(2), day(9))]);
+        assert_eq!(missing_ranges(Some((day(6), day(8))), day(1), day(10), day(20)), vec![(day(2), day(3)), (day(8), day(10))]);
+        assert_eq!(missing_ranges(Some((day(6), day(10))), day(7), day(8), day(20)), vec![]);
+        assert_eq!(missing_ranges(Some((day(6), day(10))), day(7), day(12), day(10)), vec![(day(10), day(10))]);
+        assert_eq!(missing_ranges(Some((day(6), day(8))), day(13), day(14), day(14)), vec![(day(8), day(14))]);
+    }
+
+    #[test]
+    fn test_resolution_mapping() {
+        assert_eq!(resolution_to_interval("D"), Some("1D"));
+        assert_eq!(resolution_to_interval("60"), Some("1H"));
+        assert_eq!(resolution_to_interval("240"), None);
+    }
+
+    #[test]
+    fn test_history_response_no_data_sets_next_time() {
+        let bars = vec![bar(2), bar(3)];
+        let from = Utc.with_ymd_and_hms(2024, 5, 10, 0, 0, 0).unwrap().timestamp();
+        let history = history_response(&bars, from, from + 86400);
+        assert_eq!(history.s, "no_data");
+        assert_eq!(history.next_time, Some(bars[1].time.timestamp()));
+
+        let json = serde_json::to_value(history_response(&bars, 0, i64::MAX)).unwrap();
+        assert_eq!(json["s"], "ok");
+        assert_eq!(json["t"].as_array().unwrap().len(), 2);
+    }
+}
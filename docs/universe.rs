@@ -0,0 +1,134 @@
+use chrono::NaiveDate;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::index_membership::IndexMembershipHistory;
+use crate::models::Exchange;
+use crate::store::StoreError;
+use crate::vci::ListedSymbol;
+
+/// Restricts [`Universe::universe_as_of`]. An empty `exchanges` list means all
+/// exchanges; `index` keeps only that index's constituents on the date.
+#[derive(Debug, Clone, Default)]
+pub struct UniverseFilter {
+    pub exchanges: Vec<Exchange>,
+    pub index: Option<String>,
+    pub stocks_only: bool,
+}
+
+/// Investable universe over time. Listings are tracked as membership of an
+/// exchange, so a move from HNX to HOSE closes one period and opens another,
+/// and a delisting closes the last one.
+pub struct Universe {
+    listings: IndexMembershipHistory,
+    indices: IndexMembershipHistory,
+    /// Security type per symbol as last observed (e.g. "STOCK", "ETF", "CW").
+    security_types: HashMap<String, String>,
+}
+
+impl Universe {
+    pub fn new(listings: IndexMembershipHistory, indices: IndexMembershipHistory) -> Self {
+        Universe {
+            listings,
+            indices,
+            security_types: HashMap::new(),
+        }
+    }
+
+    pub fn listings(&self) -> &IndexMembershipHistory {
+        &self.listings
+    }
+
+    pub fn indices(&self) -> &IndexMembershipHistory {
+        &self.indices
+    }
+
+    pub fn indices_mut(&mut self) -> &mut IndexMembershipHistory {
+        &mut self.indices
+    }
+
+    /// Records a full listing snapshot taken on `date`. An empty snapshot is
+    /// ignored rather than treated as every symbol being delisted.
+    pub fn record_listings(&mut self, date: NaiveDate, symbols: &[ListedSymbol]) -> Result<(), StoreError> {
+        if symbols.is_empty() {
+            return Ok(());
+        }
+
+        let mut by_exchange: HashMap<Exchange, Vec<String>> = HashMap::new();
+        for listed in symbols {
+            if let Some(kind) = &listed.security_type {
+                self.security_types.insert(listed.symbol.to_uppercase(), kind.to_uppercase());
+            }
+            if let Some(exchange) = listed.exchange {
+                by_exchange.entry(exchange).or_default().push(listed.symbol.clone());
+            }
+        }
+
+        for exchange in [Exchange::Hose, Exchange::Hnx, Exchange::Upcom] {
+            let members = by_exchange.remove(&exchange).unwrap_or_default();
+            self.listings.record_constituents(exchange.as_str(), date, &members)?;
+        }
+        Ok(())
+    }
+
+    /// Symbols that were listed on `date` and pass `filter`, sorted. Only
+    /// history recorded up to that date is used, so delisted names appear for
+    /// the dates they traded.
+    pub fn universe_as_of(&self, date: NaiveDate, filter: &UniverseFilter) -> Vec<String> {
+        let exchanges: Vec<Exchange> = if filter.exchanges.is_empty() {
+            vec![Exchange::Hose, Exchange::Hnx, Exchange::Upcom]
+        } else {
+            filter.exchanges.clone()
+        };
+
+        let mut symbols: BTreeSet<String> = exchanges.iter()
+            .flat_map(|exchange| self.listings.members_as_of(exchange.as_str(), date))
+            .collect();
+
+        if let Some(index) = &filter.index {
+            let members: BTreeSet<String> = self.indices.members_as_of(index, date).into_iter().collect();
+            symbols.retain(|symbol| members.contains(symbol));
+        }
+        if filter.stocks_only {
+            symbols.retain(|symbol| self.security_types.get(symbol).is_none_or(|kind| kind == "STOCK"));
+        }
+
+        symbols.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(symbol: &str, exchange: Exchange) -> ListedSymbol {
+        ListedSymbol {
+            symbol: symbol.to_string(),
+            exchange: Some(exchange),
+            security_type: Some("STOCK".to_string()),
+            organ_name: None,
+            en_organ_name: None,
+            isin: None,
+        }
+    }
+
+    #[test]
+    fn test_universe_as_of_includes_delisted() {
+        let date = |month: u32| NaiveDate::from_ymd_opt(2024, month, 1).unwrap();
+        let mut universe = Universe::new(IndexMembershipHistory::new(), IndexMembershipHistory::new());
+
+        universe.record_listings(date(1), &[listed("FPT", Exchange::Hose), listed("ROS", Exchange::Hose), listed("SHS", Exchange::Hnx)]).unwrap();
+        universe.record_listings(date(6), &[listed("FPT", Exchange::Hose), listed("SHS", Exchange::Hose)]).unwrap();
+        universe.indices_mut().record_constituents("VN30", date(1), &["FPT".to_string()]).unwrap();
+
+        let all = UniverseFilter::default();
+        assert_eq!(universe.universe_as_of(date(3), &all), vec!["FPT", "ROS", "SHS"]);
+        assert_eq!(universe.universe_as_of(date(7), &all), vec!["FPT", "SHS"]);
+
+        let hnx = UniverseFilter { exchanges: vec![Exchange::Hnx], ..Default::default() };
+        assert_eq!(universe.universe_as_of(date(3), &hnx), vec!["SHS"]);
+        assert!(universe.universe_as_of(date(7), &hnx).is_empty());
+
+        let vn30 = UniverseFilter { index: Some("vn30".to_string()), ..Default::default() };
+        assert_eq!(universe.universe_as_of(date(3), &vn30), vec!["FPT"]);
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Ohlcv;
+use crate::tcbs::FinancialStatement;
+
+/// Days after quarter end before results are assumed public. Listed companies
+/// have up to 45 days to publish consolidated quarterly reports.
+pub const REPORTING_LAG_DAYS: i64 = 45;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RatioMetric {
+    /// Price / trailing EPS.
+    Pe,
+    /// Price / book value per share.
+    Pb,
+}
+
+/// Per-share fundamentals for one reported quarter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerShareFundamentals {
+    pub period: String,
+    pub period_end: NaiveDate,
+    /// First date the figures are used, to avoid look-ahead bias.
+    pub available_from: NaiveDate,
+    pub eps: Option<f64>,
+    pub book_value_per_share: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RatioPoint {
+    pub date: NaiveDate,
+    pub value: f64,
+}
+
+/// Last day of the quarter named by a "YYYY-Qn" label.
+pub fn quarter_end(label: &str) -> Option<NaiveDate> {
+    let (year, quarter) = label.split_once("-Q")?;
+    let (year, quarter): (i32, u32) = (year.parse().ok()?, quarter.parse().ok()?);
+    if !(1..=4).contains(&quarter) {
+        return None;
+    }
+    let next_start = if quarter == 4 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, quarter * 3 + 1, 1)?
+    };
+    Some(next_start - Duration::days(1))
+}
+
+/// Extracts per-share figures from TCBS quarterly ratio statements
+/// (`earning_per_share` is trailing twelve months).
+pub fn fundamentals_from_ratios(statements: &[FinancialStatement]) -> Vec<PerShareFundamentals> {
+    let mut rows: Vec<PerShareFundamentals> = statements.iter()
+        .filter_map(|statement| {
+            let period_end = quarter_end(&statement.period)?;
+            Some(PerShareFundamentals {
+                period: statement.period.clone(),
+                period_end,
+                available_from: period_end + Duration::days(REPORTING_LAG_DAYS),
+                eps: statement.data.get("earning_per_share").copied(),
+                book_value_per_share: statement.data.get("book_value_per_share").copied(),
+            })
+        })
+        .collect();
+    rows.sort_by_key(|row| row.period_end);
+    rows
+}
+
+/// Daily ratio series from closes and point-in-time fundamentals. Each day
+/// uses the newest quarter already available on that date; days without a
+/// usable (positive) denominator are skipped.
+pub fn ratio_series(prices: &[Ohlcv], fundamentals: &[PerShareFundamentals], metric: RatioMetric) -> Vec<RatioPoint> {
+    let mut sorted: Vec<&PerShareFundamentals> = fundamentals.iter().collect();
+    sorted.sort_by_key(|f| f.available_from);
+
+    prices.iter()
+        .filter_map(|bar| {
+            let date = bar.time.date_naive();
+            let latest = sorted.iter().rev().find(|f| f.available_from <= date)?;
+            let denominator = match metric {
+                RatioMetric::Pe => latest.eps,
+                RatioMetric::Pb => latest.book_value_per_share,
+            }?;
+            (denominator > 0.0).then(|| RatioPoint { date, value: bar.close / denominator })
+        })
+        .collect()
+}
+
+/// Keeps one point per calendar month (the last), for long-horizon charts.
+pub fn monthly(points: &[RatioPoint]) -> Vec<RatioPoint> {
+    let mut result: Vec<RatioPoint> = Vec::new();
+    for point in points {
+        match result.last_mut() {
+            Some(last) if (last.date.year(), last.date.month()) == (point.date.year(), point.date.month()) => *last = *point,
+            _ => result.push(*point),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn close(month: u32, day: u32, close: f64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, month, day, 0, 0, 0).unwrap(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 0,
+            symbol: None,
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_point_in_time_pe() {
+        assert_eq!(quarter_end("2023-Q4"), NaiveDate::from_ymd_opt(2023, 12, 31));
+        let statement = |period: &str, eps: f64| FinancialStatement {
+            period: period.to_string(),
+            data: [("earning_per_share".to_string(), eps)].into(),
+        };
+        let fundamentals = fundamentals_from_ratios(&[statement("2023-Q4", 2000.0), statement("2024-Q1", 2500.0)]);
+
+        // Q1 results become usable on 15 May (31 Mar + 45 days)
+        let prices = vec![close(1, 5, 30000.0), close(3, 1, 40000.0), close(5, 14, 50000.0), close(5, 15, 50000.0)];
+        let series = ratio_series(&prices, &fundamentals, RatioMetric::Pe);
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[0].value, 20.0);
+        assert_eq!(series[1].value, 25.0);
+        assert_eq!(series[2].value, 20.0);
+        assert_eq!(monthly(&series).len(), 2);
+    }
+}
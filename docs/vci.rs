@@ -1,27 +1,77 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::{Client, Error as ReqwestError};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
-use std::time::{Duration as StdDuration, SystemTime};
-use tokio::time::sleep;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use futures::stream::{BoxStream, StreamExt};
 use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc, Weekday, TimeZone, Datelike};
 
+use crate::auction::{self, AuctionData, AuctionSession, AuctionSnapshot};
+use crate::foreign_room::{self, ForeignRoomSnapshot, ForeignTradingDay};
+use crate::proprietary::{self, ProprietaryScope, ProprietaryTradingDay};
+use crate::calendar;
+use crate::corporate_actions::{self, CorporateAction};
+use crate::derivatives::{self, CoveredWarrant};
+use crate::industry::{IcbIndustry, IndustryTree};
+#[cfg(feature = "cache")]
+use crate::cache::{self, CacheKind, DiskCache};
+use crate::chaos::{Chaos, ChaosConfig};
+use crate::circuit::CircuitBreaker;
+use crate::coalesce::Coalescer;
+use crate::liquidity::LiquidityBar;
+use crate::pagination::{self, Page};
+use crate::preflight::{self, PreflightReport};
+use crate::range::{RangeSource, RangeStats};
+use crate::rate_limit::RateLimiter;
+use crate::resample;
+use crate::session::SessionFilter;
+use crate::retry::{RequestError, Requester, RetryPolicy};
+use crate::store::LocalStore;
+use crate::failover::Provider;
+use crate::stats::{self, ClientStats, LatencyHistogram, UsageReport};
+use crate::symbol_search::{self, ListingCache, SymbolMatch};
+use crate::text;
+use crate::models::{self, vietnam_offset, DateRange, DepthLevel, Exchange, Index, IndexTick, Interval, Language, Ohlcv, PriceAdjustment, PriceDepth, Quote, SecurityType, TickData, TradeSide, TradingStatus, VolumeBreakdown};
+
 #[derive(Debug)]
 pub enum VciError {
     Http(ReqwestError),
     Serialization(serde_json::Error),
     InvalidInterval(String),
+    InvalidDateRange(String),
     InvalidResponse(String),
+    InvalidConfig(String),
     RateLimit,
     NoData,
+    /// Failure of a coalesced request started by another caller.
+    Shared(Arc<VciError>),
+    /// The endpoint's circuit breaker is open; retry after the given delay.
+    CircuitOpen { endpoint: String, retry_in: StdDuration },
+    /// Non-success HTTP status, after any retries the policy allows.
+    Status(reqwest::StatusCode),
 }
 
+/// Result handed to every caller of a coalesced request.
+type SharedResult<T> = Result<T, Arc<VciError>>;
+
 impl From<ReqwestError> for VciError {
     fn from(error: ReqwestError) -> Self {
         VciError::Http(error)
     }
 }
 
+impl From<RequestError> for VciError {
+    fn from(error: RequestError) -> Self {
+        match error {
+            RequestError::CircuitOpen { endpoint, retry_in } => VciError::CircuitOpen { endpoint, retry_in },
+            RequestError::Status(status) => VciError::Status(status),
+            RequestError::Http(error) => VciError::Http(error),
+        }
+    }
+}
+
 impl From<serde_json::Error> for VciError {
     fn from(error: serde_json::Error) -> Self {
         VciError::Serialization(error)
@@ -51,6 +101,7 @@ where
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompanyInfo {
     pub symbol: String,
+    pub company_name: Option<String>,
     pub exchange: Option<String>,
     pub industry: Option<String>,
     pub company_type: Option<String>,
@@ -90,21 +141,615 @@ pub struct FinancialRatio {
     pub eps: Option<f64>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolStatus {
+    pub symbol: String,
+    pub exchange: Option<String>,
+    pub status: TradingStatus,
+    pub trading_status_code: Option<String>,
+    pub security_status_code: Option<String>,
+}
+
+/// Row from VCI's full listing (`price/symbols/getAll`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListedSymbol {
+    pub symbol: String,
+    pub exchange: Option<Exchange>,
+    /// "STOCK", "ETF", "CW", "BOND", ...
+    pub security_type: Option<String>,
+    pub organ_name: Option<String>,
+    pub en_organ_name: Option<String>,
+    /// ISIN code, when the listing reports one.
+    pub isin: Option<String>,
+}
+
+impl ListedSymbol {
+    pub fn kind(&self) -> SecurityType {
+        self.security_type.as_deref().map_or(SecurityType::Other, SecurityType::from_code)
+    }
+}
+
+/// ICB industry classification of a listed company.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndustryListing {
+    pub symbol: String,
+    pub organ_name: Option<String>,
+    /// ICB level 2 (sector), 3 (industry) and 4 (sub-industry) names.
+    pub icb_name2: Option<String>,
+    pub icb_name3: Option<String>,
+    pub icb_name4: Option<String>,
+    /// ICB codes for levels 1 to 4.
+    pub icb_codes: [Option<String>; 4],
+}
+
+impl IndustryListing {
+    /// ICB code at `level` (1-4).
+    pub fn icb_code(&self, level: u8) -> Option<&str> {
+        self.icb_codes.get((level as usize).checked_sub(1)?)?.as_deref()
+    }
+
+    /// Whether the company sits under `code` at any level.
+    pub fn in_icb(&self, code: &str) -> bool {
+        self.icb_codes.iter().flatten().any(|own| own == code)
+    }
+
+    /// Diacritics- and case-insensitive match against any ICB level.
+    pub fn in_industry(&self, industry: &str) -> bool {
+        let target = text::normalize(industry);
+        [&self.icb_name2, &self.icb_name3, &self.icb_name4]
+            .into_iter()
+            .flatten()
+            .any(|name| text::normalize(name) == target)
+    }
+}
+
+/// Corporate event from VCI's `OrganizationEvents` (dividends, AGMs,
+/// issuances). Dates are `YYYY-MM-DD` as returned by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorporateEvent {
+    pub symbol: String,
+    pub title: String,
+    pub event_code: Option<String>,
+    pub event_name: Option<String>,
+    pub public_date: Option<String>,
+    pub record_date: Option<String>,
+    pub exright_date: Option<String>,
+    pub issue_date: Option<String>,
+    pub ratio: Option<f64>,
+    pub value: Option<f64>,
+}
+
+impl CorporateEvent {
+    /// The date that matters to a holder: ex-right, else record, else issue.
+    pub fn effective_date(&self) -> Option<NaiveDate> {
+        [&self.exright_date, &self.record_date, &self.issue_date]
+            .into_iter()
+            .find_map(parse_event_date)
+    }
+
+    pub fn is_cash_dividend(&self) -> bool {
+        self.event_code.as_deref().is_some_and(|code| code.eq_ignore_ascii_case("DIV"))
+            || self.title.to_lowercase().contains("cổ tức bằng tiền")
+    }
+
+    /// Converts a cash-dividend event that pays on or after `today`. Events
+    /// without a payment date count as unpaid until their ex-right date passes.
+    pub fn to_pending_dividend(&self, today: NaiveDate) -> Option<CashDividend> {
+        if !self.is_cash_dividend() {
+            return None;
+        }
+
+        let payment_date = parse_event_date(&self.issue_date);
+        let exright_date = parse_event_date(&self.exright_date);
+        let pending = match (payment_date, exright_date) {
+            (Some(paid), _) => paid >= today,
+            (None, Some(exright)) => exright >= today,
+            (None, None) => false,
+        };
+        if !pending {
+            return None;
+        }
+
+        let amount_per_share = self.value
+            .filter(|&v| v > 0.0)
+            .or_else(|| self.ratio.filter(|&r| r > 0.0).map(|r| r * PAR_VALUE_VND))?;
+
+        Some(CashDividend {
+            symbol: self.symbol.clone(),
+            announcement_date: parse_event_date(&self.public_date),
+            record_date: parse_event_date(&self.record_date),
+            exright_date,
+            payment_date,
+            amount_per_share,
+        })
+    }
+}
+
+/// Cash dividend that has been announced but not yet paid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashDividend {
+    pub symbol: String,
+    pub announcement_date: Option<NaiveDate>,
+    pub record_date: Option<NaiveDate>,
+    pub exright_date: Option<NaiveDate>,
+    pub payment_date: Option<NaiveDate>,
+    /// VND per share.
+    pub amount_per_share: f64,
+}
+
+impl CashDividend {
+    /// Cash received for `shares` held on the record date, before tax.
+    pub fn cash_for(&self, shares: u64) -> f64 {
+        self.amount_per_share * shares as f64
+    }
+}
+
+/// How new shares came into existence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CapitalRaiseKind {
+    PrivatePlacement,
+    PublicOffering,
+    RightsIssue,
+    StockDividend,
+    BonusShares,
+    Conversion,
+    EmployeeStock,
+    Other,
+}
+
+impl CapitalRaiseKind {
+    /// Classifies an issuance by its Vietnamese event title.
+    pub fn classify(title: &str) -> CapitalRaiseKind {
+        let lower = title.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| lower.contains(needle));
+
+        if has(&["esop", "người lao động", "lựa chọn"]) {
+            CapitalRaiseKind::EmployeeStock
+        } else if has(&["chuyển đổi"]) {
+            CapitalRaiseKind::Conversion
+        } else if has(&["riêng lẻ"]) {
+            CapitalRaiseKind::PrivatePlacement
+        } else if has(&["đại chúng", "công chúng"]) {
+            CapitalRaiseKind::PublicOffering
+        } else if has(&["quyền mua", "cổ đông hiện hữu"]) {
+            CapitalRaiseKind::RightsIssue
+        } else if has(&["cổ tức bằng cổ phiếu", "trả cổ tức"]) {
+            CapitalRaiseKind::StockDividend
+        } else if has(&["thưởng", "vốn chủ sở hữu"]) {
+            CapitalRaiseKind::BonusShares
+        } else {
+            CapitalRaiseKind::Other
+        }
+    }
+}
+
+/// One change to charter capital. Share counts are estimated from the event
+/// ratio when VCI does not report the issued volume.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapitalChange {
+    pub date: Option<NaiveDate>,
+    pub kind: CapitalRaiseKind,
+    pub title: String,
+    pub ratio: Option<f64>,
+    pub shares_issued: Option<f64>,
+    pub shares_after: Option<f64>,
+    pub charter_capital_after: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapitalHistory {
+    pub symbol: String,
+    pub charter_capital: Option<f64>,
+    pub outstanding_shares: Option<f64>,
+    /// Oldest first.
+    pub changes: Vec<CapitalChange>,
+}
+
+impl CapitalHistory {
+    /// Shares outstanding at the close of `date`, for restating per-share metrics.
+    pub fn shares_on(&self, date: NaiveDate) -> Option<f64> {
+        let later = self.changes.iter().find(|change| change.date.is_some_and(|d| d > date));
+        match later {
+            Some(change) => change.shares_after.zip(change.shares_issued).map(|(after, issued)| after - issued),
+            None => self.outstanding_shares,
+        }
+    }
+}
+
+/// True for events that create new shares. Additional-listing notices repeat
+/// an earlier issuance and are skipped.
+fn is_share_issuance(event: &CorporateEvent) -> bool {
+    let code = event.event_code.as_deref().unwrap_or("").to_uppercase();
+    if code == "AIS" || event.is_cash_dividend() {
+        return false;
+    }
+    code == "ISS" || event.title.to_lowercase().contains("phát hành")
+}
+
+/// Walks issuance events newest to oldest, peeling each one off the current
+/// share count, then returns the changes in chronological order.
+fn reconstruct_capital_changes(events: &[CorporateEvent], current_shares: Option<f64>) -> Vec<CapitalChange> {
+    let mut issuances: Vec<&CorporateEvent> = events.iter().filter(|event| is_share_issuance(event)).collect();
+    issuances.sort_by_key(|event| std::cmp::Reverse(event.effective_date()));
+
+    let mut shares_after = current_shares;
+    let mut changes: Vec<CapitalChange> = issuances.into_iter()
+        .map(|event| {
+            let ratio = event.ratio.filter(|&r| r > 0.0);
+            let shares_issued = event.value
+                .filter(|&v| v > 0.0)
+                .or_else(|| shares_after.zip(ratio).map(|(after, r)| after - after / (1.0 + r)));
+            let change = CapitalChange {
+                date: event.effective_date(),
+                kind: CapitalRaiseKind::classify(&event.title),
+                title: event.title.clone(),
+                ratio,
+                shares_issued,
+                shares_after,
+                charter_capital_after: shares_after.map(|shares| shares * PAR_VALUE_VND),
+            };
+            shares_after = shares_after.zip(shares_issued).map(|(after, issued)| after - issued);
+            change
+        })
+        .collect();
+
+    changes.reverse();
+    changes
+}
+
+/// Par value of Vietnamese listed shares; cash dividend ratios are a percentage of it.
+pub(crate) const PAR_VALUE_VND: f64 = 10_000.0;
+
+/// Largest `countBack` the gap-chart endpoint serves in full; longer
+/// requests come back truncated to the most recent bars.
+const MAX_BARS_PER_REQUEST: u32 = 5_000;
+
+/// Vietcap IQ insight API, which serves the per-company daily history feeds.
+const DEFAULT_IQ_BASE_URL: &str = "https://iq.vietcap.com.vn/api/iq-insight-service/v1/";
+
+/// Bars per trading day, matching the estimate in `calculate_count_back`.
+fn bars_per_day(interval: &str) -> f64 {
+    match interval {
+        "1D" | "1W" | "1M" => 1.0,
+        "1H" => 6.5,
+        _ => 6.5 * 60.0,
+    }
+}
+
+/// Concatenates gap-chart series, keyed by timestamp so candles in the
+/// overlap between chunks appear once (the later chunk wins).
+fn merge_series(items: Vec<Value>) -> Value {
+    let timestamp = |v: &Value| v.as_i64().or_else(|| v.as_str()?.parse().ok());
+    let mut rows = std::collections::BTreeMap::new();
+    for (k, item) in items.iter().enumerate() {
+        for (i, t) in item["t"].as_array().into_iter().flatten().enumerate() {
+            if let Some(t) = timestamp(t) {
+                rows.insert(t, (k, i));
+            }
+        }
+    }
+
+    let mut merged = serde_json::Map::new();
+    for item in &items {
+        for (key, value) in item.as_object().into_iter().flatten() {
+            let series = value.is_array() && value.as_array().map(|a| a.len()) == item["t"].as_array().map(|t| t.len());
+            if !series {
+                merged.insert(key.clone(), value.clone());
+            } else if !merged.contains_key(key) {
+                let column = rows.values().map(|&(k, i)| items[k][key.as_str()].get(i).cloned().unwrap_or(Value::Null)).collect();
+                merged.insert(key.clone(), Value::Array(column));
+            }
+        }
+    }
+    Value::Object(merged)
+}
+
+pub(crate) fn parse_event_date(date: &Option<String>) -> Option<NaiveDate> {
+    let date = date.as_deref()?;
+    NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d").ok()
+}
+
+/// Everything a symbol page needs, gathered concurrently by [`VciClient::snapshot`].
+/// A part that failed to load is `None`/empty rather than failing the whole call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolSnapshot {
+    pub symbol: String,
+    pub quote: Option<Quote>,
+    pub ratios: Option<FinancialRatio>,
+    pub foreign: Option<ForeignRoomSnapshot>,
+    pub upcoming_events: Vec<CorporateEvent>,
+}
+
+/// Top-level parts of the company GraphQL query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompanySection {
+    /// Analyst report files (not parsed into `CompanyInfo`).
+    Reports,
+    /// News with price context (not parsed into `CompanyInfo`).
+    News,
+    /// Name, profile, industry and share count.
+    Listing,
+    /// Exchange, last price, one-year range and key ratios.
+    Price,
+    Shareholders,
+    Officers,
+}
+
+impl CompanySection {
+    pub const ALL: [CompanySection; 6] = [
+        CompanySection::Reports,
+        CompanySection::News,
+        CompanySection::Listing,
+        CompanySection::Price,
+        CompanySection::Shareholders,
+        CompanySection::Officers,
+    ];
+
+    fn fragment(&self) -> &'static str {
+        match self {
+            CompanySection::Reports => r#"    AnalysisReportFiles(ticker: $ticker, langCode: $lang) {
+        date
+        description
+        link
+        name
+        __typename
+    }"#,
+            CompanySection::News => r#"    News(ticker: $ticker, langCode: $lang) {
+        id
+        organCode
+        ticker
+        newsTitle
+        newsSubTitle
+        friendlySubTitle
+        newsImageUrl
+        newsSourceLink
+        createdAt
+        publicDate
+        updatedAt
+        langCode
+        newsId
+        newsShortContent
+        newsFullContent
+        closePrice
+        referencePrice
+        floorPrice
+        ceilingPrice
+        percentPriceChange
+        __typename
+    }"#,
+            CompanySection::Listing => r#"    CompanyListingInfo(ticker: $ticker) {
+        id
+        organName
+        enOrganName
+        issueShare
+        history
+        en_History
+        companyProfile
+        en_CompanyProfile
+        icbName3
+        enIcbName3
+        icbName2
+        enIcbName2
+        icbName4
+        enIcbName4
+        financialRatio {
+            id
+            ticker
+            issueShare
+            charterCapital
+            __typename
+        }
+        __typename
+    }"#,
+            CompanySection::Price => r#"    TickerPriceInfo(ticker: $ticker) {
+        ticker
+        exchange
+        matchPrice
+        priceChange
+        percentPriceChange
+        totalVolume
+        highestPrice1Year
+        lowestPrice1Year
+        financialRatio {
+            pe
+            pb
+            roe
+            roa
+            eps
+            revenue
+            netProfit
+            dividend
+            __typename
+        }
+        __typename
+    }"#,
+            CompanySection::Shareholders => r#"    OrganizationShareHolders(ticker: $ticker) {
+        id
+        ticker
+        ownerFullName
+        en_OwnerFullName
+        percentage
+        updateDate
+        __typename
+    }"#,
+            CompanySection::Officers => r#"    OrganizationManagers(ticker: $ticker) {
+        id
+        ticker
+        fullName
+        positionName
+        en_PositionName
+        percentage
+        __typename
+    }"#,
+        }
+    }
+}
+
+/// Company GraphQL query selecting only `sections`, in canonical order.
+pub fn company_query(sections: &[CompanySection]) -> String {
+    let fragments: Vec<&str> = CompanySection::ALL.iter()
+        .filter(|section| sections.contains(section))
+        .map(CompanySection::fragment)
+        .collect();
+    // Only Reports and News take a language; GraphQL rejects unused variables
+    let variables = if fragments.iter().any(|fragment| fragment.contains("$lang")) { "$ticker: String!, $lang: String!" } else { "$ticker: String!" };
+    format!("query Query({}) {{\n{}\n}}", variables, fragments.join("\n"))
+}
+
 pub struct VciClient {
     client: Client,
     base_url: String,
-    rate_limit_per_minute: u32,
-    request_timestamps: Vec<SystemTime>,
+    iq_base_url: String,
+    rate_limiter: Arc<RateLimiter>,
+    stats: Arc<ClientStats>,
+    chaos: Option<Arc<Chaos>>,
+    extra_headers: HeaderMap,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Arc<CircuitBreaker>,
+    inflight_history: Arc<Coalescer<String, SharedResult<Vec<OhlcvData>>>>,
+    inflight_company: Arc<Coalescer<String, SharedResult<CompanyInfo>>>,
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<DiskCache>>,
     user_agents: Vec<String>,
     random_agent: bool,
     resample_map: HashMap<String, String>,
+    listing_cache: Arc<ListingCache>,
 }
 
-impl VciClient {
-    pub fn new(random_agent: bool, rate_limit_per_minute: u32) -> Result<Self, VciError> {
-        let client = Client::builder()
-            .timeout(StdDuration::from_secs(30))
-            .build()?;
+/// Configures a [`VciClient`]: HTTP timeouts, proxy, extra headers, base URL
+/// and request budget. An injected `reqwest::Client` takes precedence over
+/// the timeout and proxy settings.
+pub struct VciClientBuilder {
+    random_agent: bool,
+    rate_limit_per_minute: u32,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    timeout: StdDuration,
+    connect_timeout: Option<StdDuration>,
+    proxy: Option<String>,
+    headers: Vec<(String, String)>,
+    http_client: Option<Client>,
+    base_url: Option<String>,
+    iq_base_url: Option<String>,
+    retry_policy: RetryPolicy,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    listing_ttl: StdDuration,
+}
+
+impl Default for VciClientBuilder {
+    fn default() -> Self {
+        VciClientBuilder {
+            random_agent: false,
+            rate_limit_per_minute: 6,
+            rate_limiter: None,
+            timeout: StdDuration::from_secs(30),
+            connect_timeout: None,
+            proxy: None,
+            headers: Vec::new(),
+            http_client: None,
+            base_url: None,
+            iq_base_url: None,
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: None,
+            listing_ttl: symbol_search::LISTING_TTL,
+        }
+    }
+}
+
+impl VciClientBuilder {
+    pub fn random_agent(mut self, random_agent: bool) -> Self {
+        self.random_agent = random_agent;
+        self
+    }
+
+    pub fn rate_limit(mut self, per_minute: u32) -> Self {
+        self.rate_limit_per_minute = per_minute;
+        self
+    }
+
+    /// Shared request budget; overrides [`Self::rate_limit`].
+    pub fn rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: StdDuration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: StdDuration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes all traffic through `url`, e.g. `http://proxy.corp:3128`.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Sent with every request, replacing the browser header of the same name.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Points the client at another host, e.g. a mock server in tests. The
+    /// IQ feeds follow under `iq-insight-service/v1/` unless
+    /// [`Self::iq_base_url`] is set.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Host of the IQ insight feeds (foreign, proprietary and price history).
+    pub fn iq_base_url(mut self, iq_base_url: impl Into<String>) -> Self {
+        self.iq_base_url = Some(iq_base_url.into());
+        self
+    }
+
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    pub fn circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// How long [`VciClient::search_symbols`] reuses the listing.
+    pub fn listing_ttl(mut self, ttl: StdDuration) -> Self {
+        self.listing_ttl = ttl;
+        self
+    }
+
+    pub fn build(self) -> Result<VciClient, VciError> {
+        let client = match self.http_client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder().timeout(self.timeout);
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(proxy) = &self.proxy {
+                    builder = builder.proxy(reqwest::Proxy::all(proxy.as_str())?);
+                }
+                builder.build()?
+            }
+        };
+
+        let mut extra_headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| VciError::InvalidConfig(format!("Invalid header name '{}'", name)))?;
+            let value = HeaderValue::from_str(value).map_err(|_| VciError::InvalidConfig(format!("Invalid value for header '{}'", name)))?;
+            extra_headers.insert(name, value);
+        }
 
         let user_agents = vec![
             "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string(),
@@ -115,41 +760,185 @@ impl VciClient {
         ];
 
         let mut resample_map = HashMap::new();
-        resample_map.insert("1W".to_string(), "1W".to_string());
-        resample_map.insert("1M".to_string(), "1M".to_string());
+        for interval in ["5m", "15m", "30m", "1W", "1M"] {
+            resample_map.insert(interval.to_string(), interval.to_string());
+        }
 
+        let iq_base_url = match (self.iq_base_url, &self.base_url) {
+            (Some(iq_base_url), _) => iq_base_url,
+            (None, Some(base_url)) => format!("{}iq-insight-service/v1/", base_url),
+            (None, None) => DEFAULT_IQ_BASE_URL.to_string(),
+        };
         Ok(VciClient {
             client,
-            base_url: "https://trading.vietcap.com.vn/api/".to_string(),
-            rate_limit_per_minute,
-            request_timestamps: Vec::new(),
+            base_url: self.base_url.unwrap_or_else(|| "https://trading.vietcap.com.vn/api/".to_string()),
+            iq_base_url,
+            extra_headers,
+            rate_limiter: self.rate_limiter.unwrap_or_else(|| Arc::new(RateLimiter::new(self.rate_limit_per_minute))),
+            stats: Arc::new(ClientStats::new()),
+            chaos: None,
+            retry_policy: self.retry_policy,
+            circuit_breaker: self.circuit_breaker.unwrap_or_default(),
+            inflight_history: Arc::new(Coalescer::new()),
+            inflight_company: Arc::new(Coalescer::new()),
+            #[cfg(feature = "cache")]
+            cache: None,
             user_agents,
-            random_agent,
+            random_agent: self.random_agent,
             resample_map,
+            listing_cache: Arc::new(ListingCache::new(self.listing_ttl)),
         })
     }
+}
+
+impl VciClient {
+    pub fn new(random_agent: bool, rate_limit_per_minute: u32) -> Result<Self, VciError> {
+        Self::builder().random_agent(random_agent).rate_limit(rate_limit_per_minute).build()
+    }
+
+    pub fn builder() -> VciClientBuilder {
+        VciClientBuilder::default()
+    }
+
+    /// Shares `limiter` with this client, e.g. to run several clients or
+    /// concurrent tasks under one request budget.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+
+    /// Replaces the underlying HTTP client, e.g. one built with a proxy.
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Replaces the default retry policy (5 attempts, exponential backoff
+    /// from 1s with up to 1s of jitter).
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Shares `breaker` with this client. By default each client has its
+    /// own breaker opening after 5 consecutive failures for 30s.
+    pub fn with_circuit_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = breaker;
+        self
+    }
+
+    pub fn circuit_breaker(&self) -> Arc<CircuitBreaker> {
+        Arc::clone(&self.circuit_breaker)
+    }
+
+    pub fn rate_limiter(&self) -> Arc<RateLimiter> {
+        Arc::clone(&self.rate_limiter)
+    }
+
+    /// Injects delays, 5xx responses and malformed payloads into this
+    /// client's requests. Intended for tests of error handling only.
+    pub fn with_chaos(mut self, config: ChaosConfig) -> Self {
+        self.chaos = Some(Arc::new(Chaos::new(config)));
+        self
+    }
+
+    /// Serves history and company info through `cache` in the `*_cached`
+    /// methods, so repeated calls within the TTL skip the API.
+    #[cfg(feature = "cache")]
+    pub fn with_cache(mut self, cache: Arc<DiskCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// [`Self::get_history`] read through the attached cache. `force_refresh`
+    /// skips the lookup but still stores the fresh result.
+    #[cfg(feature = "cache")]
+    pub async fn get_history_cached(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: impl AsRef<str>,
+        force_refresh: bool,
+    ) -> Result<Vec<OhlcvData>, VciError> {
+        let interval = interval.as_ref();
+        let Some(cache) = &self.cache else {
+            return self.get_history(symbol, start, end, interval).await;
+        };
+        let kind = CacheKind::for_interval(interval);
+        let key = cache::history_key("vci", symbol, &models::iso_date(start), end.map(models::iso_date).as_deref(), interval);
+        if !force_refresh {
+            if let Some(bars) = cache.get(kind, &key) {
+                return Ok(bars);
+            }
+        }
+        let bars = self.get_history(symbol, start, end, interval).await?;
+        if let Err(e) = cache.put(kind, &key, &bars) {
+            tracing::warn!("Cache write failed for {}: {:?}", key, e);
+        }
+        Ok(bars)
+    }
+
+    /// [`Self::company_info_in`] read through the attached cache.
+    #[cfg(feature = "cache")]
+    pub async fn company_info_cached(&self, symbol: &str, language: Language, force_refresh: bool) -> Result<CompanyInfo, VciError> {
+        let Some(cache) = &self.cache else {
+            return self.company_info_in(symbol, language).await;
+        };
+        let key = cache::company_key("vci", symbol, language);
+        if !force_refresh {
+            if let Some(info) = cache.get(CacheKind::CompanyInfo, &key) {
+                return Ok(info);
+            }
+        }
+        let info = self.company_info_in(symbol, language).await?;
+        if let Err(e) = cache.put(CacheKind::CompanyInfo, &key, &info) {
+            tracing::warn!("Cache write failed for {}: {:?}", key, e);
+        }
+        Ok(info)
+    }
+
+    fn requester(&self) -> Requester<'_> {
+        Requester {
+            policy: &self.retry_policy,
+            rate_limiter: &self.rate_limiter,
+            stats: &self.stats,
+            circuit_breaker: Some(&self.circuit_breaker),
+            chaos: self.chaos.as_deref(),
+        }
+    }
+
+    /// Probes the price board once to check connectivity, auth and clock
+    /// skew before a batch job starts.
+    pub async fn preflight(&self) -> PreflightReport {
+        let url = format!("{}price/symbols/getList", self.base_url);
+        let request = self.client.post(&url).header("Content-Type", "application/json").json(&serde_json::json!({ "symbols": ["VNM"] }));
+        let started = tokio::time::Instant::now();
+        let report = preflight::probe("vci", self.with_browser_headers(request, self.get_user_agent())).await;
+        self.stats.record(&stats::endpoint_key(&url), started.elapsed(), report.responded());
+        report
+    }
+
+    /// Latency histograms per endpoint for every request attempt so far.
+    pub fn stats(&self) -> std::collections::BTreeMap<String, LatencyHistogram> {
+        self.stats.snapshot()
+    }
+
+    /// Requests sent per day and endpoint, to keep under the provider's
+    /// informal quotas.
+    pub fn usage_report(&self) -> UsageReport {
+        self.stats.usage(Provider::Vci)
+    }
 
     fn get_interval_value(&self, interval: &str) -> Result<String, VciError> {
-        let interval_map = HashMap::from([
-            ("1m", "ONE_MINUTE"),
-            ("5m", "ONE_MINUTE"),
-            ("15m", "ONE_MINUTE"),
-            ("30m", "ONE_MINUTE"),
-            ("1H", "ONE_HOUR"),
-            ("1D", "ONE_DAY"),
-            ("1W", "ONE_DAY"),
-            ("1M", "ONE_DAY"),
-        ]);
-
-        interval_map.get(interval)
-            .map(|s| s.to_string())
-            .ok_or_else(|| VciError::InvalidInterval(interval.to_string()))
+        let interval: Interval = interval.parse().map_err(VciError::InvalidInterval)?;
+        Ok(interval.vci_time_frame().to_string())
     }
 
     fn get_user_agent(&self) -> String {
         if self.random_agent {
-            use rand::prelude::IndexedRandom;
-            self.user_agents.choose(&mut rand::rng())
+            use rand::seq::SliceRandom;
+            self.user_agents.choose(&mut rand::thread_rng())
                 .unwrap_or(&self.user_agents[0])
                 .clone()
         } else {
@@ -157,108 +946,60 @@ impl VciClient {
         }
     }
 
-    async fn enforce_rate_limit(&mut self) {
-        let current_time = SystemTime::now();
-        
-        // Remove timestamps older than 1 minute
-        self.request_timestamps.retain(|&timestamp| {
-            current_time.duration_since(timestamp).unwrap_or(StdDuration::from_secs(0)) < StdDuration::from_secs(60)
-        });
-
-        // If we're at the rate limit, wait
-        if self.request_timestamps.len() >= self.rate_limit_per_minute as usize {
-            if let Some(&oldest_request) = self.request_timestamps.first() {
-                let wait_time = StdDuration::from_secs(60) - current_time.duration_since(oldest_request).unwrap_or(StdDuration::from_secs(0));
-                if !wait_time.is_zero() {
-                    sleep(wait_time + StdDuration::from_millis(100)).await;
-                }
-            }
-        }
-
-        self.request_timestamps.push(current_time);
+    fn with_browser_headers(&self, request: reqwest::RequestBuilder, user_agent: String) -> reqwest::RequestBuilder {
+        request
+            .header("Accept", "application/json, text/plain, */*")
+            .header("Accept-Language", "en-US,en;q=0.9,vi-VN;q=0.8,vi;q=0.7")
+            .header("Accept-Encoding", "gzip, deflate, br")
+            .header("Connection", "keep-alive")
+            .header("Cache-Control", "no-cache")
+            .header("Pragma", "no-cache")
+            .header("DNT", "1")
+            .header("Sec-Fetch-Dest", "empty")
+            .header("Sec-Fetch-Mode", "cors")
+            .header("Sec-Fetch-Site", "same-site")
+            .header("sec-ch-ua", "\"Not_A Brand\";v=\"8\", \"Chromium\";v=\"120\", \"Google Chrome\";v=\"120\"")
+            .header("sec-ch-ua-mobile", "?0")
+            .header("sec-ch-ua-platform", "\"Windows\"")
+            .header("User-Agent", user_agent)
+            .header("Referer", "https://trading.vietcap.com.vn/")
+            .header("Origin", "https://trading.vietcap.com.vn")
+            .headers(self.extra_headers.clone())
     }
 
-    async fn make_request(&mut self, url: &str, payload: &Value) -> Result<Value, VciError> {
-        const MAX_RETRIES: u32 = 5;
-        
-        for attempt in 0..MAX_RETRIES {
-            self.enforce_rate_limit().await;
-
-            if attempt > 0 {
-                let delay = StdDuration::from_secs_f64(2.0_f64.powi(attempt as i32 - 1) + rand::random::<f64>());
-                let delay = delay.min(StdDuration::from_secs(60));
-                tracing::info!("VCI API retry backoff: attempt {}/{}, waiting {:.1}s before retry", attempt + 1, MAX_RETRIES, delay.as_secs_f64());
-                sleep(delay).await;
-            }
+    async fn make_request(&self, url: &str, payload: &Value) -> Result<Value, VciError> {
+        let stats_key = match payload.get("query").and_then(|query| query.as_str()) {
+            Some(query) => stats::graphql_key(url, query),
+            None => stats::endpoint_key(url),
+        };
+        self.send_with_retry(url, &stats_key, || self.client.post(url).header("Content-Type", "application/json").json(payload)).await
+    }
 
-            let user_agent = self.get_user_agent();
-            
-            
-            let response = self.client
-                .post(url)
-                .header("Accept", "application/json, text/plain, */*")
-                .header("Accept-Language", "en-US,en;q=0.9,vi-VN;q=0.8,vi;q=0.7")
-                .header("Accept-Encoding", "gzip, deflate, br")
-                .header("Connection", "keep-alive")
-                .header("Content-Type", "application/json")
-                .header("Cache-Control", "no-cache")
-                .header("Pragma", "no-cache")
-                .header("DNT", "1")
-                .header("Sec-Fetch-Dest", "empty")
-                .header("Sec-Fetch-Mode", "cors")
-                .header("Sec-Fetch-Site", "same-site")
-                .header("sec-ch-ua", "\"Not_A Brand\";v=\"8\", \"Chromium\";v=\"120\", \"Google Chrome\";v=\"120\"")
-                .header("sec-ch-ua-mobile", "?0")
-                .header("sec-ch-ua-platform", "\"Windows\"")
-                .header("User-Agent", user_agent)
-                .header("Referer", "https://trading.vietcap.com.vn/")
-                .header("Origin", "https://trading.vietcap.com.vn")
-                .json(payload)
-                .send()
-                .await;
-
-            match response {
-                Ok(resp) => {
-                    let status = resp.status();
-                    
-                    if status.is_success() {
-                        match resp.json::<Value>().await {
-                            Ok(data) => return Ok(data),
-                            Err(_) => continue,
-                        }
-                    } else {
-                        if status == 403 || status == 429 || status.is_server_error() {
-                            continue;
-                        } else if status.is_client_error() {
-                            break;
-                        } else {
-                            continue;
-                        }
-                    }
-                }
-                Err(_) => continue,
-            }
-        }
+    async fn make_get_request(&self, url: &str) -> Result<Value, VciError> {
+        self.send_with_retry(url, &stats::endpoint_key(url), || self.client.get(url)).await
+    }
 
-        Err(VciError::InvalidResponse("Max retries exceeded".to_string()))
+    async fn send_with_retry(&self, url: &str, stats_key: &str, build: impl Fn() -> reqwest::RequestBuilder) -> Result<Value, VciError> {
+        let endpoint = stats::endpoint_key(url);
+        Ok(self.requester().send_json(&endpoint, stats_key, || self.with_browser_headers(build(), self.get_user_agent())).await?)
     }
 
-    pub fn calculate_timestamp(&self, date_str: Option<&str>) -> i64 {
+    fn calculate_timestamp(&self, date_str: Option<&str>) -> Result<i64, VciError> {
         match date_str {
             Some(date) => {
                 let naive_date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
-                    .expect("Invalid date format");
+                    .map_err(|_| VciError::InvalidDateRange(format!("Invalid end date: {}", date)))?;
                 // Add one day to get the 'to' timestamp (exclusive end) - matching Python implementation
                 let next_day = naive_date + ChronoDuration::days(1);
                 let naive_datetime = next_day.and_hms_opt(0, 0, 0).unwrap();
                 let datetime = naive_datetime.and_utc();
-                datetime.timestamp()
+                Ok(datetime.timestamp())
             }
             None => {
                 // For current timestamp, add 1 day as well
                 let now = Utc::now();
                 let next_day = now + ChronoDuration::days(1);
-                next_day.timestamp()
+                Ok(next_day.timestamp())
             },
         }
     }
@@ -270,18 +1011,9 @@ impl VciClient {
             None => Utc::now().date_naive(),
         };
 
-        // Calculate actual business days (exclude weekends) - matching Python pd.bdate_range
-        let mut business_days = 0u32;
-        let mut current_date = start_date;
-        while current_date <= end_date {
-            // Skip weekends (Saturday = 6, Sunday = 0)
-            let weekday = current_date.weekday().num_days_from_sunday();
-            if weekday != 0 && weekday != 6 {  // Not Sunday(0) or Saturday(6)
-                business_days += 1;
-            }
-            current_date += ChronoDuration::days(1);
-        }
-        
+        // Trading days only (weekends and exchange holidays excluded)
+        let business_days = calendar::trading_days_between(start_date, end_date).len() as u32;
+
         // VCI API needs much larger buffer to reliably return historical data
         let count_back = match interval {
             "1D" => business_days + 100, // Large buffer for reliable historical data
@@ -290,21 +1022,41 @@ impl VciClient {
             _ => ((business_days as f32 * 6.5 * 60.0) as u32) + 100,
         };
 
-        tracing::debug!("Count back calculation: start={}, end={:?}, business_days={} (Python-style), count_back={}", 
+        tracing::debug!("Count back calculation: start={}, end={:?}, business_days={}, count_back={}", 
             start, end, business_days, count_back);
 
         count_back
     }
 
-    pub async fn get_history(
-        &mut self,
-        symbol: &str,
-        start: &str,
-        end: Option<&str>,
-        interval: &str,
-    ) -> Result<Vec<OhlcvData>, VciError> {
+    /// Raw gap-chart series for one symbol. Ranges longer than one request
+    /// can return are fetched in consecutive chunks and merged.
+    async fn gap_chart(&self, symbol: &str, start: &str, end: Option<&str>, interval: &str) -> Result<Value, VciError> {
+        let DateRange { start: start_date, end: end_date } = DateRange::parse(start, end).map_err(VciError::InvalidDateRange)?;
+        let chunk_days = ((MAX_BARS_PER_REQUEST - 100) as f64 / bars_per_day(interval)) as usize;
+        let chunks = calendar::chunk_range(start_date, end_date, chunk_days);
+        if chunks.len() == 1 {
+            return self.gap_chart_once(symbol, start, end, interval).await;
+        }
+
+        tracing::debug!("VCI splitting {} [{}] {}..{} into {} requests", symbol, interval, start_date, end_date, chunks.len());
+        let mut items = Vec::new();
+        for (from, to) in chunks {
+            let (from, to) = (from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string());
+            match self.gap_chart_once(symbol, &from, Some(&to), interval).await {
+                Ok(item) => items.push(item),
+                Err(VciError::NoData) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        if items.is_empty() {
+            return Err(VciError::NoData);
+        }
+        Ok(merge_series(items))
+    }
+
+    async fn gap_chart_once(&self, symbol: &str, start: &str, end: Option<&str>, interval: &str) -> Result<Value, VciError> {
         let interval_value = self.get_interval_value(interval)?;
-        let end_timestamp = self.calculate_timestamp(end);
+        let end_timestamp = self.calculate_timestamp(end)?;
         let count_back = self.calculate_count_back(start, end, interval);
 
         let url = format!("{}chart/OHLCChart/gap-chart", self.base_url);
@@ -315,27 +1067,159 @@ impl VciClient {
             "countBack": count_back
         });
 
-
         let response_data = self.make_request(&url, &payload).await?;
-
-        if !response_data.is_array() || response_data.as_array().unwrap().is_empty() {
-            return Err(VciError::NoData);
+        match response_data.as_array().and_then(|items| items.first()) {
+            Some(item) => Ok(item.clone()),
+            None => Err(VciError::NoData),
         }
+    }
 
-        let data_item = &response_data[0];
-        
-        
-        let required_keys = ["o", "h", "l", "c", "v", "t"];
-        
-        for key in &required_keys {
-            if !data_item.get(key).is_some() {
-                return Err(VciError::InvalidResponse(format!("Missing key: {}", key)));
-            }
-        }
+    /// Daily traded volume and value. Where the chart omits value, it is
+    /// estimated from the typical price and flagged. Trade counts are not
+    /// in the daily series; see `liquidity::daily_from_ticks` for those.
+    pub async fn liquidity_history(&self, symbol: &str, start: &str, end: Option<&str>) -> Result<Vec<LiquidityBar>, VciError> {
+        let item = self.gap_chart(symbol, start, end, "1D").await?;
+        let series = |key: &str| item.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+        let (times, volumes, values) = (series("t"), series("v"), series("accumulatedValue"));
+        let (highs, lows, closes) = (series("h"), series("l"), series("c"));
+        let start_date = NaiveDate::parse_from_str(start, "%Y-%m-%d").map_err(|_| VciError::InvalidResponse(format!("Invalid start date: {}", start)))?;
+        let number = |values: &[Value], i: usize| values.get(i).and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()));
+
+        let mut bars: Vec<LiquidityBar> = times.iter()
+            .enumerate()
+            .filter_map(|(i, t)| {
+                let timestamp = t.as_i64().or_else(|| t.as_str()?.parse().ok())?;
+                let time = DateTime::<Utc>::from_timestamp(timestamp, 0)?;
+                let volume = number(&volumes, i)? as u64;
+                let reported = number(&values, i).filter(|&value| value > 0.0);
+                let estimated = || {
+                    let typical = (number(&highs, i)? + number(&lows, i)? + number(&closes, i)?) / 3.0;
+                    Some(typical * volume as f64)
+                };
+                Some(LiquidityBar {
+                    time,
+                    volume,
+                    value: reported.or_else(estimated),
+                    value_estimated: reported.is_none(),
+                    trades: None,
+                })
+            })
+            .filter(|bar| bar.time.date_naive() >= start_date)
+            .collect();
+        bars.sort_by_key(|bar| bar.time);
+        Ok(bars)
+    }
 
-        let opens = data_item["o"].as_array().ok_or(VciError::InvalidResponse("Invalid opens".to_string()))?;
-        let highs = data_item["h"].as_array().ok_or(VciError::InvalidResponse("Invalid highs".to_string()))?;
-        let lows = data_item["l"].as_array().ok_or(VciError::InvalidResponse("Invalid lows".to_string()))?;
+    /// Bars for an index futures contract with open interest and settlement
+    /// price filled in when the chart carries them, from a single request.
+    /// Accepts dated codes and the rolling [`Tenor`](crate::derivatives::Tenor) codes ("VN30F1M").
+    pub async fn futures_history(&self, symbol: &str, start: &str, end: Option<&str>, interval: &str) -> Result<Vec<Ohlcv>, VciError> {
+        let item = self.gap_chart(symbol, start, end, interval).await?;
+        let series = |keys: &[&str]| keys.iter()
+            .find_map(|key| item.get(*key).and_then(|v| v.as_array()).cloned())
+            .unwrap_or_default();
+        let (times, opens, highs, lows, closes, volumes) = (series(&["t"]), series(&["o"]), series(&["h"]), series(&["l"]), series(&["c"]), series(&["v"]));
+        let open_interest = series(&["oi", "openInterest"]);
+        let settlement = series(&["settlementPrice", "sp"]);
+        let start_date = NaiveDate::parse_from_str(start, "%Y-%m-%d").map_err(|_| VciError::InvalidResponse(format!("Invalid start date: {}", start)))?;
+        let number = |values: &[Value], i: usize| values.get(i).and_then(|v| v.as_f64().or_else(|| v.as_str()?.parse().ok()));
+
+        let mut bars: Vec<Ohlcv> = times.iter()
+            .enumerate()
+            .filter_map(|(i, t)| {
+                let timestamp = t.as_i64().or_else(|| t.as_str()?.parse().ok())?;
+                let futures = crate::models::FuturesFields {
+                    open_interest: number(&open_interest, i).map(|oi| oi as u64),
+                    settlement_price: number(&settlement, i),
+                    basis: None,
+                };
+                Some(Ohlcv {
+                    time: DateTime::<Utc>::from_timestamp(timestamp, 0)?,
+                    open: number(&opens, i)?,
+                    high: number(&highs, i)?,
+                    low: number(&lows, i)?,
+                    close: number(&closes, i)?,
+                    volume: number(&volumes, i).unwrap_or(0.0) as u64,
+                    symbol: Some(symbol.to_uppercase()),
+                    breakdown: None,
+                    futures: Some(futures),
+                })
+            })
+            .filter(|bar| bar.time.date_naive() >= start_date)
+            .collect();
+        bars.sort_by_key(|bar| bar.time);
+        Ok(bars)
+    }
+
+    /// Bars for `[start, end]` at `interval`, given as an [`Interval`] or its
+    /// string form. `end` defaults to today.
+    pub async fn get_history(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: impl AsRef<str>,
+    ) -> Result<Vec<OhlcvData>, VciError> {
+        let interval = interval.as_ref();
+        let (start, end) = (models::iso_date(start), end.map(models::iso_date));
+        let key = format!("{}|{}|{}|{}", symbol.to_uppercase(), start, end.as_deref().unwrap_or(""), interval);
+        self.inflight_history
+            .run(key, async { self.fetch_history(symbol, &start, end.as_deref(), interval).await.map_err(Arc::new) })
+            .await
+            .map_err(|e| Arc::try_unwrap(e).unwrap_or_else(VciError::Shared))
+    }
+
+    /// [`get_history`](Self::get_history) with a choice of price
+    /// adjustment. Adjusted bars are back-adjusted from the symbol's corporate
+    /// actions; if that lookup fails they are returned unadjusted with a
+    /// warning. Indices are never adjusted.
+    pub async fn get_history_with(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        interval: impl AsRef<str>,
+        adjustment: PriceAdjustment,
+    ) -> Result<Vec<OhlcvData>, VciError> {
+        let mut bars = self.get_history(symbol, start, end, interval).await?;
+        if adjustment == PriceAdjustment::Unadjusted || Index::from_symbol(symbol).is_some() {
+            return Ok(bars);
+        }
+        let actions = match self.get_corporate_actions(symbol).await {
+            Ok(actions) => actions,
+            Err(e) => {
+                tracing::warn!("Corporate actions unavailable for {}, returning unadjusted bars: {:?}", symbol, e);
+                return Ok(bars);
+            }
+        };
+        corporate_actions::back_adjust(&mut bars, &actions);
+        Ok(bars)
+    }
+
+    async fn fetch_history(
+        &self,
+        symbol: &str,
+        start: &str,
+        end: Option<&str>,
+        interval: &str,
+    ) -> Result<Vec<OhlcvData>, VciError> {
+        DateRange::parse(start, end).map_err(VciError::InvalidDateRange)?;
+        let index = Index::from_symbol(symbol);
+        let code = index.map_or(symbol, |index| index.vci_code());
+        let label = index.map_or(symbol, |index| index.as_str());
+        let data_item = &self.gap_chart(code, start, end, interval).await?;
+
+        let required_keys = ["o", "h", "l", "c", "v", "t"];
+        
+        for key in &required_keys {
+            if data_item.get(key).is_none() {
+                return Err(VciError::InvalidResponse(format!("Missing key: {}", key)));
+            }
+        }
+
+        let opens = data_item["o"].as_array().ok_or(VciError::InvalidResponse("Invalid opens".to_string()))?;
+        let highs = data_item["h"].as_array().ok_or(VciError::InvalidResponse("Invalid highs".to_string()))?;
+        let lows = data_item["l"].as_array().ok_or(VciError::InvalidResponse("Invalid lows".to_string()))?;
         let closes = data_item["c"].as_array().ok_or(VciError::InvalidResponse("Invalid closes".to_string()))?;
         let volumes = data_item["v"].as_array().ok_or(VciError::InvalidResponse("Invalid volumes".to_string()))?;
         let times = data_item["t"].as_array().ok_or(VciError::InvalidResponse("Invalid times".to_string()))?;
@@ -346,7 +1230,7 @@ impl VciClient {
         }
 
         let mut result = Vec::new();
-        let start_date = NaiveDate::parse_from_str(start, "%Y-%m-%d").expect("Invalid start date");
+        let start_date = DateRange::parse(start, end).map_err(VciError::InvalidDateRange)?.start;
         
         for i in 0..length {
             // Try to get timestamp as string first, then as i64
@@ -372,12 +1256,12 @@ impl VciClient {
                     low: lows[i].as_f64().unwrap_or(0.0),
                     close: closes[i].as_f64().unwrap_or(0.0),
                     volume: volumes[i].as_u64().unwrap_or(0),
-                    symbol: Some(symbol.to_string()),
+                    symbol: Some(label.to_string()),
                 });
             }
         }
 
-        result.sort_by(|a, b| a.time.cmp(&b.time));
+        result.sort_by_key(|a| a.time);
         
         // Apply resampling if needed
         if self.resample_map.contains_key(interval) && !["1m", "1H", "1D"].contains(&interval) {
@@ -387,8 +1271,48 @@ impl VciClient {
         Ok(result)
     }
 
+    /// History of a market index, labelled with its canonical symbol.
+    pub async fn get_index_history(&self, index: Index, start: NaiveDate, end: Option<NaiveDate>, interval: impl AsRef<str>) -> Result<Vec<OhlcvData>, VciError> {
+        self.get_history(index.as_str(), start, end, interval).await
+    }
+
+    /// [`VciClient::get_history`] over a validated [`DateRange`]; build one
+    /// from strings, `NaiveDate`s or `DateTime`s with [`DateRange::new`].
+    pub async fn get_history_range(&self, symbol: &str, range: DateRange, interval: impl AsRef<str>) -> Result<Vec<OhlcvData>, VciError> {
+        self.get_history(symbol, range.start, Some(range.end), interval).await
+    }
+
+    /// Fetches several timeframes of `symbol` concurrently. Requests share the
+    /// client's rate limiter; each interval carries its own result, so one
+    /// failing does not fail the rest.
+    pub async fn get_history_multi(
+        &self,
+        symbol: &str,
+        intervals: &[Interval],
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+    ) -> Result<HashMap<Interval, Result<Vec<OhlcvData>, VciError>>, VciError> {
+        if intervals.is_empty() {
+            return Err(VciError::InvalidResponse("Intervals list cannot be empty".to_string()));
+        }
+
+        let mut unique = intervals.to_vec();
+        unique.sort();
+        unique.dedup();
+
+        let fetches = unique.iter().map(|interval| async move {
+            let result = self.get_history(symbol, start, end, interval.as_str()).await;
+            if let Err(e) = &result {
+                tracing::warn!("VCI multi-interval fetch failed for {} [{}]: {:?}", symbol, interval, e);
+            }
+            (*interval, result)
+        });
+
+        Ok(futures::future::join_all(fetches).await.into_iter().collect())
+    }
+
     pub async fn get_batch_history(
-        &mut self,
+        &self,
         symbols: &[String],
         start: &str,
         end: Option<&str>,
@@ -397,9 +1321,10 @@ impl VciClient {
         if symbols.is_empty() {
             return Err(VciError::InvalidResponse("Symbols list cannot be empty".to_string()));
         }
+        let range = DateRange::parse(start, end).map_err(VciError::InvalidDateRange)?;
 
         let interval_value = self.get_interval_value(interval)?;
-        let end_timestamp = self.calculate_timestamp(end);
+        let end_timestamp = self.calculate_timestamp(end)?;
         let count_back = self.calculate_count_back(start, end, interval);
 
         let url = format!("{}chart/OHLCChart/gap-chart", self.base_url);
@@ -425,13 +1350,13 @@ impl VciClient {
 
 
         let mut results = HashMap::new();
-        let start_date = NaiveDate::parse_from_str(start, "%Y-%m-%d").expect("Invalid start date");
+        let start_date = range.start;
 
         tracing::debug!("VCI filtering with start_date: {}, end_date: {:?}", start_date, end);
 
         // Create a mapping from response data using symbol field
         let mut response_map = HashMap::new();
-        for (_i, data_item) in response_array.iter().enumerate() {
+        for data_item in response_array.iter() {
             if let Some(obj) = data_item.as_object() {
                 // Find symbol identifier in response
                 let symbol_fields = ["symbol", "ticker", "Symbol", "Ticker", "s"];
@@ -463,7 +1388,7 @@ impl VciClient {
             
             let mut valid = true;
             for key in &required_keys {
-                if !data_item.get(key).is_some() {
+                if data_item.get(key).is_none() {
                     valid = false;
                     break;
                 }
@@ -498,11 +1423,11 @@ impl VciClient {
             
             // Debug: Show all timestamps in raw VCI response
             tracing::debug!("Symbol {}: Raw VCI timestamps from API:", symbol);
-            for j in 0..length.min(10) { // Show first 10 timestamps
-                let timestamp = if let Some(ts_str) = times[j].as_str() {
+            for (j, raw_time) in times.iter().enumerate().take(10) { // Show first 10 timestamps
+                let timestamp = if let Some(ts_str) = raw_time.as_str() {
                     ts_str.parse::<i64>().unwrap_or(0)
                 } else {
-                    times[j].as_i64().unwrap_or(0)
+                    raw_time.as_i64().unwrap_or(0)
                 };
                 let time = DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap_or_default();
                 tracing::debug!("  Raw timestamp[{}]: {} -> {}", j, timestamp, time.format("%Y-%m-%d %H:%M:%S"));
@@ -536,7 +1461,7 @@ impl VciClient {
             tracing::debug!("Symbol {}: VCI returned {} data points, filtered to {} (start_date: {})", 
                 symbol, total_data_points, filtered_data_points, start_date);
 
-            symbol_data.sort_by(|a, b| a.time.cmp(&b.time));
+            symbol_data.sort_by_key(|a| a.time);
             
             // Apply resampling if needed
             if self.resample_map.contains_key(interval) && !["1m", "1H", "1D"].contains(&interval) {
@@ -551,103 +1476,34 @@ impl VciClient {
         Ok(results)
     }
 
-    pub async fn company_info(&mut self, symbol: &str) -> Result<CompanyInfo, VciError> {
+    /// Concurrent calls for the same symbol share one request.
+    pub async fn company_info(&self, symbol: &str) -> Result<CompanyInfo, VciError> {
+        self.inflight_company
+            .run(symbol.to_uppercase(), async { self.company_info_in(symbol, Language::Vietnamese).await.map_err(Arc::new) })
+            .await
+            .map_err(|e| Arc::try_unwrap(e).unwrap_or_else(VciError::Shared))
+    }
+
+    /// Company info with profile, industry and officer titles in `language`,
+    /// falling back to Vietnamese for fields VCI has not translated.
+    pub async fn company_info_in(&self, symbol: &str, language: Language) -> Result<CompanyInfo, VciError> {
+        self.company_info_with(symbol, language, &CompanySection::ALL).await
+    }
+
+    /// Company info fetching only `sections`, which cuts the GraphQL payload
+    /// for bulk jobs. Fields of sections not requested stay `None`/empty.
+    pub async fn company_info_with(&self, symbol: &str, language: Language, sections: &[CompanySection]) -> Result<CompanyInfo, VciError> {
         let url = self.base_url.replace("/api/", "/data-mt/") + "graphql";
         
-        let graphql_query = r#"query Query($ticker: String!, $lang: String!) {
-            AnalysisReportFiles(ticker: $ticker, langCode: $lang) {
-                date
-                description
-                link
-                name
-                __typename
-            }
-            News(ticker: $ticker, langCode: $lang) {
-                id
-                organCode
-                ticker
-                newsTitle
-                newsSubTitle
-                friendlySubTitle
-                newsImageUrl
-                newsSourceLink
-                createdAt
-                publicDate
-                updatedAt
-                langCode
-                newsId
-                newsShortContent
-                newsFullContent
-                closePrice
-                referencePrice
-                floorPrice
-                ceilingPrice
-                percentPriceChange
-                __typename
-            }
-            CompanyListingInfo(ticker: $ticker) {
-                id
-                issueShare
-                history
-                companyProfile
-                icbName3
-                icbName2
-                icbName4
-                financialRatio {
-                    id
-                    ticker
-                    issueShare
-                    charterCapital
-                    __typename
-                }
-                __typename
-            }
-            TickerPriceInfo(ticker: $ticker) {
-                ticker
-                exchange
-                matchPrice
-                priceChange
-                percentPriceChange
-                totalVolume
-                highestPrice1Year
-                lowestPrice1Year
-                financialRatio {
-                    pe
-                    pb
-                    roe
-                    roa
-                    eps
-                    revenue
-                    netProfit
-                    dividend
-                    __typename
-                }
-                __typename
-            }
-            OrganizationShareHolders(ticker: $ticker) {
-                id
-                ticker
-                ownerFullName
-                percentage
-                updateDate
-                __typename
-            }
-            OrganizationManagers(ticker: $ticker) {
-                id
-                ticker
-                fullName
-                positionName
-                percentage
-                __typename
-            }
-        }"#;
+        let graphql_query = company_query(sections);
+        let mut variables = serde_json::json!({ "ticker": symbol.to_uppercase() });
+        if graphql_query.contains("$lang") {
+            variables["lang"] = serde_json::json!(language.code());
+        }
 
         let payload = serde_json::json!({
             "query": graphql_query,
-            "variables": {
-                "ticker": symbol.to_uppercase(),
-                "lang": "vi"
-            }
+            "variables": variables
         });
 
 
@@ -657,6 +1513,7 @@ impl VciClient {
 
         let mut company_info = CompanyInfo {
             symbol: symbol.to_uppercase(),
+            company_name: None,
             exchange: None,
             industry: None,
             company_type: None,
@@ -673,12 +1530,9 @@ impl VciClient {
 
         // Extract from CompanyListingInfo
         if let Some(company_listing) = data.get("CompanyListingInfo") {
-            if let Some(profile) = company_listing.get("companyProfile").and_then(|v| v.as_str()) {
-                company_info.company_profile = Some(profile.to_string());
-            }
-            if let Some(industry) = company_listing.get("icbName3").and_then(|v| v.as_str()) {
-                company_info.industry = Some(industry.to_string());
-            }
+            company_info.company_name = localized(company_listing, "organName", "enOrganName", language);
+            company_info.company_profile = localized(company_listing, "companyProfile", "en_CompanyProfile", language);
+            company_info.industry = localized(company_listing, "icbName3", "enIcbName3", language);
             if let Some(shares) = company_listing.get("issueShare").and_then(|v| v.as_u64()) {
                 company_info.outstanding_shares = Some(shares);
             }
@@ -703,11 +1557,11 @@ impl VciClient {
         if let Some(shareholders_array) = data.get("OrganizationShareHolders").and_then(|v| v.as_array()) {
             for shareholder in shareholders_array {
                 if let (Some(name), Some(percentage)) = (
-                    shareholder.get("ownerFullName").and_then(|v| v.as_str()),
+                    localized(shareholder, "ownerFullName", "en_OwnerFullName", language),
                     shareholder.get("percentage").and_then(|v| v.as_f64())
                 ) {
                     company_info.shareholders.push(ShareholderInfo {
-                        name: name.to_string(),
+                        name,
                         percentage,
                     });
                 }
@@ -719,12 +1573,12 @@ impl VciClient {
             for manager in managers_array {
                 if let (Some(name), Some(position)) = (
                     manager.get("fullName").and_then(|v| v.as_str()),
-                    manager.get("positionName").and_then(|v| v.as_str())
+                    localized(manager, "positionName", "en_PositionName", language)
                 ) {
                     let percentage = manager.get("percentage").and_then(|v| v.as_f64());
                     company_info.officers.push(OfficerInfo {
                         name: name.to_string(),
-                        position: position.to_string(),
+                        position,
                         percentage,
                     });
                 }
@@ -734,17 +1588,706 @@ impl VciClient {
         Ok(company_info)
     }
     
+    /// Raw price-board rows (`listingInfo`, `bidAsk`, `matchPrice`) for `symbols`.
+    async fn fetch_price_board(&self, symbols: &[String]) -> Result<Vec<Value>, VciError> {
+        if symbols.is_empty() {
+            return Err(VciError::InvalidResponse("Symbols list cannot be empty".to_string()));
+        }
+
+        let url = format!("{}price/symbols/getList", self.base_url);
+        let symbols: Vec<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+        let payload = serde_json::json!({ "symbols": symbols });
+
+        let response_data = self.make_request(&url, &payload).await?;
+        match response_data {
+            Value::Array(rows) if !rows.is_empty() => Ok(rows),
+            _ => Err(VciError::NoData),
+        }
+    }
+
+    /// Bid/ask levels from the price board. Empty levels (before the open,
+    /// or a locked limit) are omitted.
+    pub async fn get_price_depth(&self, symbol: &str) -> Result<PriceDepth, VciError> {
+        let rows = self.fetch_price_board(&[symbol.to_uppercase()]).await?;
+        rows.first().and_then(|row| parse_board_depth(row, Utc::now())).ok_or(VciError::NoData)
+    }
+
+    /// Every ticker listed on HOSE, HNX and UPCOM, across security types
+    /// (stocks, ETFs, covered warrants, bonds, ...). Rows the listing marks
+    /// with an unknown board keep `exchange: None`.
+    pub async fn list_symbols(&self) -> Result<Vec<ListedSymbol>, VciError> {
+        let url = format!("{}price/symbols/getAll", self.base_url);
+        let response_data = self.make_get_request(&url).await?;
+        let rows = response_data.as_array().ok_or(VciError::NoData)?;
+        Ok(rows.iter().filter_map(parse_listed_symbol).collect())
+    }
+
+    /// Tickers matching a partial ticker or company name, best first; see
+    /// [`symbol_search::rank_symbols`]. VCI has no search endpoint, so the
+    /// full listing is fetched once and reused for the builder's
+    /// `listing_ttl` (an hour by default).
+    pub async fn search_symbols(&self, query: &str) -> Result<Vec<SymbolMatch>, VciError> {
+        let listing = match self.listing_cache.get() {
+            Some(listing) => listing,
+            None => self.listing_cache.store(self.list_symbols().await?),
+        };
+        Ok(symbol_search::rank_symbols(&listing, query, usize::MAX))
+    }
+
+    /// Listed tickers of one security type.
+    pub async fn list_symbols_of(&self, kind: SecurityType) -> Result<Vec<ListedSymbol>, VciError> {
+        let mut symbols = self.list_symbols().await?;
+        symbols.retain(|symbol| symbol.kind() == kind);
+        Ok(symbols)
+    }
+
+    /// ICB classification for every listed company.
+    pub async fn industry_listings(&self) -> Result<Vec<IndustryListing>, VciError> {
+        let url = self.base_url.replace("/api/", "/data-mt/") + "graphql";
+        let payload = serde_json::json!({
+            "query": r#"{
+                CompaniesListingInfo {
+                    ticker
+                    organName
+                    icbName2
+                    icbName3
+                    icbName4
+                    icbCode1
+                    icbCode2
+                    icbCode3
+                    icbCode4
+                    __typename
+                }
+            }"#,
+            "variables": {}
+        });
+
+        let response_data = self.make_request(&url, &payload).await?;
+        let rows = response_data.get("data")
+            .and_then(|v| v.get("CompaniesListingInfo"))
+            .and_then(|v| v.as_array())
+            .ok_or(VciError::NoData)?;
+
+        let text = |row: &Value, key: &str| row.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let listings = rows.iter()
+            .filter_map(|row| {
+                Some(IndustryListing {
+                    symbol: text(row, "ticker")?.to_uppercase(),
+                    organ_name: text(row, "organName"),
+                    icb_name2: text(row, "icbName2"),
+                    icb_name3: text(row, "icbName3"),
+                    icb_name4: text(row, "icbName4"),
+                    icb_codes: [text(row, "icbCode1"), text(row, "icbCode2"), text(row, "icbCode3"), text(row, "icbCode4")],
+                })
+            })
+            .collect();
+
+        Ok(listings)
+    }
+
+    /// The ICB industry tree, levels 1 to 4.
+    pub async fn get_industries(&self) -> Result<IndustryTree, VciError> {
+        let url = self.base_url.replace("/api/", "/data-mt/") + "graphql";
+        let payload = serde_json::json!({
+            "query": r#"{
+                ListIcbCode {
+                    icbCode
+                    level
+                    icbName
+                    enIcbName
+                    __typename
+                }
+            }"#,
+            "variables": {}
+        });
+
+        let response_data = self.make_request(&url, &payload).await?;
+        let rows = response_data.get("data")
+            .and_then(|v| v.get("ListIcbCode"))
+            .and_then(|v| v.as_array())
+            .ok_or(VciError::NoData)?;
+
+        let text = |row: &Value, key: &str| row.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let industries = rows.iter()
+            .filter_map(|row| {
+                Some(IcbIndustry {
+                    code: text(row, "icbCode")?,
+                    level: row.get("level").and_then(|v| v.as_u64()).filter(|level| (1..=4).contains(level))? as u8,
+                    name: text(row, "icbName")?,
+                    en_name: text(row, "enIcbName"),
+                })
+            })
+            .collect();
+
+        Ok(IndustryTree::new(industries))
+    }
+
+    /// Companies classified under `icb_code` at any level; a level-1 code
+    /// returns the whole industry.
+    pub async fn get_symbols_by_industry(&self, icb_code: &str) -> Result<Vec<IndustryListing>, VciError> {
+        let mut listings = self.industry_listings().await?;
+        listings.retain(|listing| listing.in_icb(icb_code));
+        Ok(listings)
+    }
+
+    /// Current constituents of an index group ("VN30", "VNMidCap", "HNX30", ...).
+    pub async fn index_constituents(&self, group: &str) -> Result<Vec<String>, VciError> {
+        let url = format!("{}price/symbols/getByGroup?group={}", self.base_url, group);
+        let response_data = self.make_get_request(&url).await?;
+        let rows = response_data.as_array().ok_or(VciError::NoData)?;
+
+        let symbols: Vec<String> = rows.iter()
+            .filter_map(|row| row.as_str().or_else(|| row.get("symbol").and_then(|v| v.as_str())))
+            .map(|symbol| symbol.to_uppercase())
+            .collect();
+
+        if symbols.is_empty() {
+            return Err(VciError::NoData);
+        }
+        Ok(symbols)
+    }
+
+    /// Currently listed index futures codes (group `FU_INDEX`).
+    pub async fn futures_contracts(&self) -> Result<Vec<String>, VciError> {
+        self.index_constituents("FU_INDEX").await
+    }
+
+    /// Terms of every listed covered warrant, read from the price board in
+    /// batches of 50 symbols.
+    pub async fn covered_warrants(&self) -> Result<Vec<CoveredWarrant>, VciError> {
+        let symbols = self.index_constituents("CW").await?;
+        let mut warrants = Vec::new();
+        for batch in symbols.chunks(50) {
+            let rows = self.fetch_price_board(batch).await?;
+            warrants.extend(rows.iter().filter_map(derivatives::parse_covered_warrant));
+        }
+        Ok(warrants)
+    }
+
+    /// Terms of one covered warrant.
+    pub async fn covered_warrant(&self, symbol: &str) -> Result<CoveredWarrant, VciError> {
+        let rows = self.fetch_price_board(&[symbol.to_uppercase()]).await?;
+        rows.first().and_then(derivatives::parse_covered_warrant).ok_or(VciError::NoData)
+    }
+
+    /// Current level of each index in `indices` ("VNINDEX", "VN30", "HNXIndex", ...).
+    pub async fn index_ticks(&self, indices: &[String]) -> Result<Vec<IndexTick>, VciError> {
+        if indices.is_empty() {
+            return Err(VciError::InvalidResponse("Indices list cannot be empty".to_string()));
+        }
+
+        let url = format!("{}price/marketIndex/getList", self.base_url);
+        let payload = serde_json::json!({ "symbols": indices });
+        let response_data = self.make_request(&url, &payload).await?;
+        let rows = response_data.as_array().ok_or(VciError::NoData)?;
+
+        let now = Utc::now();
+        Ok(rows.iter().filter_map(|row| parse_index_tick(row, now)).collect())
+    }
+
+    /// Latest quotes for `symbols` from the price board.
+    pub async fn quotes(&self, symbols: &[String]) -> Result<Vec<Quote>, VciError> {
+        let rows = self.fetch_price_board(symbols).await?;
+        let now = Utc::now();
+        Ok(rows.iter().filter_map(|row| parse_board_quote(row, now)).collect())
+    }
+
+    async fn graphql(&self, query: &str, symbol: &str) -> Result<Value, VciError> {
+        let url = self.base_url.replace("/api/", "/data-mt/") + "graphql";
+        let payload = serde_json::json!({
+            "query": query,
+            "variables": {
+                "ticker": symbol.to_uppercase()
+            }
+        });
+
+        let response_data = self.make_request(&url, &payload).await?;
+        response_data.get("data").cloned().ok_or(VciError::NoData)
+    }
+
+    /// Provider-computed one-year high/low and last matched price.
+    async fn provider_range(&self, symbol: &str) -> Result<RangeStats, VciError> {
+        let query = r#"query Query($ticker: String!) {
+            TickerPriceInfo(ticker: $ticker) {
+                matchPrice
+                highestPrice1Year
+                lowestPrice1Year
+            }
+        }"#;
+        let data = self.graphql(query, symbol).await?;
+        let info = data.get("TickerPriceInfo").ok_or(VciError::NoData)?;
+        let number = |key: &str| info.get(key).and_then(|v| v.as_f64()).filter(|&v| v > 0.0);
+
+        Ok(RangeStats {
+            symbol: symbol.to_uppercase(),
+            price: number("matchPrice").ok_or(VciError::NoData)?,
+            high_52w: number("highestPrice1Year").ok_or(VciError::NoData)?,
+            low_52w: number("lowestPrice1Year").ok_or(VciError::NoData)?,
+            source: RangeSource::Provider,
+        })
+    }
+
+    /// 52-week high/low for several symbols. Uses the provider's figures and,
+    /// for symbols it can't answer, daily history from `store`. Symbols
+    /// neither can answer are left out.
+    pub async fn range_stats(&self, symbols: &[&str], store: Option<&LocalStore>) -> HashMap<String, RangeStats> {
+        let fetches = symbols.iter().map(|&symbol| async move { (symbol.to_uppercase(), self.provider_range(symbol).await) });
+        let now = Utc::now();
+
+        futures::future::join_all(fetches).await
+            .into_iter()
+            .filter_map(|(symbol, result)| {
+                let stats = match result {
+                    Ok(stats) => Some(stats),
+                    Err(e) => {
+                        tracing::debug!("Provider range unavailable for {}: {:?}", symbol, e);
+                        let bars = store.and_then(|store| store.read(&symbol, "1D").ok())?;
+                        RangeStats::from_bars(&symbol, &bars, now)
+                    }
+                };
+                stats.map(|stats| (symbol, stats))
+            })
+            .collect()
+    }
+
+    /// Latest-period valuation and profitability ratios.
+    pub async fn key_ratios(&self, symbol: &str) -> Result<FinancialRatio, VciError> {
+        let query = r#"query Query($ticker: String!) {
+            TickerPriceInfo(ticker: $ticker) {
+                financialRatio {
+                    pe
+                    pb
+                    roe
+                    roa
+                    eps
+                    revenue
+                    netProfit
+                    dividend
+                    __typename
+                }
+                __typename
+            }
+        }"#;
+
+        let data = self.graphql(query, symbol).await?;
+        let ratio = data.get("TickerPriceInfo")
+            .and_then(|v| v.get("financialRatio"))
+            .filter(|v| v.is_object())
+            .ok_or(VciError::NoData)?;
+        let field = |key: &str| ratio.get(key).and_then(|v| v.as_f64());
+
+        Ok(FinancialRatio {
+            pe: field("pe"),
+            pb: field("pb"),
+            roe: field("roe"),
+            roa: field("roa"),
+            revenue: field("revenue"),
+            net_profit: field("netProfit"),
+            dividend: field("dividend"),
+            eps: field("eps"),
+        })
+    }
+
+    /// Corporate events for `symbol`, newest first as returned by VCI.
+    pub async fn events(&self, symbol: &str) -> Result<Vec<CorporateEvent>, VciError> {
+        let query = r#"query Query($ticker: String!) {
+            OrganizationEvents(ticker: $ticker) {
+                id
+                ticker
+                eventTitle
+                eventListCode
+                eventListName
+                publicDate
+                recordDate
+                exrightDate
+                issueDate
+                ratio
+                value
+                __typename
+            }
+        }"#;
+
+        let data = self.graphql(query, symbol).await?;
+        let events_array = data.get("OrganizationEvents")
+            .and_then(|v| v.as_array())
+            .ok_or(VciError::NoData)?;
+
+        let events = parse_events(symbol, events_array);
+        Ok(events)
+    }
+
+    /// Cash dividends, stock dividends, bonus shares, splits and rights
+    /// issues for `symbol`, oldest ex-date first.
+    pub async fn get_corporate_actions(&self, symbol: &str) -> Result<Vec<CorporateAction>, VciError> {
+        Ok(corporate_actions::from_events(&self.events(symbol).await?))
+    }
+
+    /// Cash and stock dividends for `symbol`, oldest ex-date first.
+    pub async fn get_dividends(&self, symbol: &str) -> Result<Vec<CorporateAction>, VciError> {
+        let mut actions = self.get_corporate_actions(symbol).await?;
+        actions.retain(CorporateAction::is_dividend);
+        Ok(actions)
+    }
+
+    /// Charter-capital changes for `symbol`, oldest first, reconstructed
+    /// backwards from today's share count through the issuance events.
+    pub async fn capital_history(&self, symbol: &str) -> Result<CapitalHistory, VciError> {
+        let query = r#"query Query($ticker: String!) {
+            CompanyListingInfo(ticker: $ticker) {
+                issueShare
+                charterCapital
+                __typename
+            }
+            OrganizationEvents(ticker: $ticker) {
+                id
+                ticker
+                eventTitle
+                eventListCode
+                eventListName
+                publicDate
+                recordDate
+                exrightDate
+                issueDate
+                ratio
+                value
+                __typename
+            }
+        }"#;
+
+        let data = self.graphql(query, symbol).await?;
+        let listing = data.get("CompanyListingInfo");
+        let outstanding_shares = listing.and_then(|v| v.get("issueShare")).and_then(|v| v.as_f64());
+        let charter_capital = listing.and_then(|v| v.get("charterCapital")).and_then(|v| v.as_f64())
+            .or_else(|| outstanding_shares.map(|shares| shares * PAR_VALUE_VND));
+        let events = data.get("OrganizationEvents")
+            .and_then(|v| v.as_array())
+            .map(|events| parse_events(symbol, events))
+            .unwrap_or_default();
+
+        Ok(CapitalHistory {
+            symbol: symbol.to_uppercase(),
+            charter_capital,
+            outstanding_shares,
+            changes: reconstruct_capital_changes(&events, outstanding_shares),
+        })
+    }
+
+    /// Announced cash dividends for `symbol` not yet paid, soonest payment first.
+    pub async fn pending_dividends(&self, symbol: &str) -> Result<Vec<CashDividend>, VciError> {
+        let today = Utc::now().with_timezone(&vietnam_offset()).date_naive();
+        let mut dividends: Vec<CashDividend> = self.events(symbol).await?
+            .iter()
+            .filter_map(|event| event.to_pending_dividend(today))
+            .collect();
+        dividends.sort_by_key(|dividend| dividend.payment_date.or(dividend.exright_date));
+        Ok(dividends)
+    }
+
+    /// Quote, key ratios, foreign flow and upcoming events in one call. The
+    /// three underlying requests run concurrently under the shared rate limit.
+    pub async fn snapshot(&self, symbol: &str) -> Result<SymbolSnapshot, VciError> {
+        let symbol = symbol.to_uppercase();
+        let symbols = [symbol.clone()];
+
+        let (board, ratios, events) = tokio::join!(
+            self.fetch_price_board(&symbols),
+            self.key_ratios(&symbol),
+            self.events(&symbol),
+        );
+
+        let now = Utc::now();
+        let row = board.ok().and_then(|rows| rows.into_iter().next());
+        let quote = row.as_ref().and_then(|row| parse_board_quote(row, now));
+        let foreign = row.as_ref().and_then(|row| parse_board_foreign_room(row, now));
+
+        if quote.is_none() && ratios.is_err() && events.is_err() {
+            return Err(VciError::NoData);
+        }
+
+        let today = now.with_timezone(&vietnam_offset()).date_naive();
+        let mut upcoming_events: Vec<CorporateEvent> = events.unwrap_or_default()
+            .into_iter()
+            .filter(|event| event.effective_date().is_some_and(|date| date >= today))
+            .collect();
+        upcoming_events.sort_by_key(|event| event.effective_date());
+
+        Ok(SymbolSnapshot {
+            symbol,
+            quote,
+            ratios: ratios.ok(),
+            foreign,
+            upcoming_events,
+        })
+    }
+
+    /// Current exchange trading status for each symbol, taken from the price
+    /// board's listing info. The stricter of the trading and security status
+    /// codes wins, so a `NORMAL` session on a `WARNING` security is `Warning`.
+    pub async fn trading_status(&self, symbols: &[String]) -> Result<Vec<SymbolStatus>, VciError> {
+        let rows = self.fetch_price_board(symbols).await?;
+
+        let mut statuses = Vec::new();
+        for row in &rows {
+            let Some(listing) = row.get("listingInfo") else {
+                continue;
+            };
+            let Some(symbol) = listing.get("symbol").and_then(|v| v.as_str()) else {
+                continue;
+            };
+
+            let trading_status_code = listing.get("tradingStatus").and_then(|v| v.as_str()).map(str::to_string);
+            let security_status_code = listing.get("securityStatus").and_then(|v| v.as_str()).map(str::to_string);
+            let status = [&trading_status_code, &security_status_code]
+                .iter()
+                .filter_map(|code| code.as_deref().and_then(TradingStatus::classify))
+                .max()
+                .unwrap_or_else(|| {
+                    TradingStatus::Unknown(trading_status_code.clone().or(security_status_code.clone()).unwrap_or_default())
+                });
+
+            statuses.push(SymbolStatus {
+                symbol: symbol.to_string(),
+                exchange: listing.get("board").or_else(|| listing.get("exchange"))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                status,
+                trading_status_code,
+                security_status_code,
+            });
+        }
+
+        Ok(statuses)
+    }
+
+    /// Daily rows of the IQ history endpoint `path` over `range` in one page,
+    /// decoded with `parse`; rows it rejects are skipped.
+    async fn iq_post<T>(&self, path: &str, range: &DateRange, parse: impl Fn(&Value) -> Option<T>) -> Result<Vec<T>, VciError> {
+        let size = calendar::trading_days_between(range.start, range.end).len().max(1);
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let url = format!(
+            "{}{}{}timeFrame=ONE_DAY&fromDate={}&toDate={}&page=0&size={}",
+            self.iq_base_url,
+            path,
+            separator,
+            range.start.format("%Y%m%d"),
+            range.end.format("%Y%m%d"),
+            size
+        );
+        let response_data = self.make_get_request(&url).await?;
+        let rows = response_data.get("data")
+            .and_then(|data| data.get("content").or(Some(data)))
+            .and_then(|rows| rows.as_array())
+            .ok_or(VciError::NoData)?;
+        Ok(rows.iter().filter_map(parse).collect())
+    }
+
+    /// Daily foreign buy/sell volume and value with closing room for
+    /// `[start, end]`, oldest first, from the IQ price-history feed.
+    pub async fn get_foreign_trading(&self, symbol: &str, start: &str, end: Option<&str>) -> Result<Vec<ForeignTradingDay>, VciError> {
+        let range = DateRange::parse(start, end).map_err(VciError::InvalidDateRange)?;
+        let path = format!("company/{}/price-history", symbol.to_uppercase());
+        let days = self.iq_post(&path, &range, |row| foreign_room::parse_foreign_trading_day(symbol, row)).await?;
+        Ok(foreign_room::clip_foreign_trading(days, range.start, range.end))
+    }
+
+    /// Daily matched and put-through volume and value for `[start, end]`,
+    /// by exchange date, from the IQ price-history feed.
+    pub async fn get_volume_breakdown(&self, symbol: &str, start: &str, end: Option<&str>) -> Result<std::collections::BTreeMap<NaiveDate, VolumeBreakdown>, VciError> {
+        let range = DateRange::parse(start, end).map_err(VciError::InvalidDateRange)?;
+        let path = format!("company/{}/price-history", symbol.to_uppercase());
+        Ok(self.iq_post(&path, &range, parse_volume_breakdown).await?
+            .into_iter()
+            .filter(|(date, _)| *date >= range.start && *date <= range.end)
+            .collect())
+    }
+
+    /// Daily bars for `[start, end]` with the matched/put-through split
+    /// attached; see [`Self::get_volume_breakdown`].
+    pub async fn get_daily_bars_with_breakdown(&self, symbol: &str, start: &str, end: Option<&str>) -> Result<Vec<Ohlcv>, VciError> {
+        let range = DateRange::parse(start, end).map_err(VciError::InvalidDateRange)?;
+        let (history, breakdown) = tokio::join!(self.get_history(symbol, range.start, Some(range.end), "1D"), self.get_volume_breakdown(symbol, start, end));
+        let mut bars: Vec<Ohlcv> = history?.into_iter().map(Ohlcv::from).collect();
+        models::attach_breakdowns(&mut bars, &breakdown?);
+        Ok(bars)
+    }
+
+    /// Daily proprietary-desk buy/sell for a symbol or a whole exchange over
+    /// `[start, end]`, oldest first.
+    pub async fn get_proprietary_trading(&self, scope: &ProprietaryScope, start: &str, end: Option<&str>) -> Result<Vec<ProprietaryTradingDay>, VciError> {
+        let range = DateRange::parse(start, end).map_err(VciError::InvalidDateRange)?;
+        let path = match scope {
+            ProprietaryScope::Symbol(symbol) => format!("company/{}/proprietary-history", symbol.to_uppercase()),
+            ProprietaryScope::Market(exchange) => format!("market/proprietary-history?exchange={}", exchange.as_str()),
+        };
+        let label = scope.label();
+        let days = self.iq_post(&path, &range, |row| proprietary::parse_proprietary_day(&label, row)).await?;
+        Ok(proprietary::clip(days, range.start, range.end))
+    }
+
+    /// Current foreign room for each symbol from the price board. Feed the
+    /// results to a `ForeignRoomTracker` on a schedule to build history.
+    pub async fn foreign_room(&self, symbols: &[String]) -> Result<Vec<ForeignRoomSnapshot>, VciError> {
+        let rows = self.fetch_price_board(symbols).await?;
+        let now = Utc::now();
+
+        let snapshots = rows.iter()
+            .filter_map(|row| parse_board_foreign_room(row, now))
+            .collect();
+
+        Ok(snapshots)
+    }
+
+    /// Matched trades from `market-watch/LEData/getAll`, newest first as the
+    /// API returns them. `last_time` pages back from an earlier response.
+    async fn fetch_ticks(&self, symbol: &str, limit: u32, last_time: Option<&str>) -> Result<Vec<TickData>, VciError> {
+        let url = format!("{}market-watch/LEData/getAll", self.base_url);
+        let mut payload = serde_json::json!({
+            "symbol": symbol.to_uppercase(),
+            "limit": limit.min(30_000)
+        });
+        if let Some(last_time) = last_time {
+            payload["truncTime"] = Value::String(last_time.to_string());
+        }
+
+        let response_data = self.make_request(&url, &payload).await?;
+        let records = response_data.as_array().ok_or(VciError::NoData)?;
+
+        let mut ticks = Vec::with_capacity(records.len());
+        for record in records {
+            let raw_time = record.get("truncTime")
+                .and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse::<f64>().ok())));
+            let Some(raw_time) = raw_time else {
+                continue;
+            };
+            // Timestamps arrive in milliseconds on most responses; keep them,
+            // the page cursor is built from them
+            let millis = if raw_time > 1e10 { raw_time } else { raw_time * 1000.0 };
+            let Some(time) = DateTime::<Utc>::from_timestamp_millis(millis as i64) else {
+                continue;
+            };
+
+            let number = |key: &str| record.get(key)
+                .and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse::<f64>().ok())));
+
+            ticks.push(TickData {
+                time,
+                price: number("matchPrice").unwrap_or(0.0),
+                volume: number("matchVol").unwrap_or(0.0) as u64,
+                side: TradeSide::from_code(record.get("matchType").and_then(|v| v.as_str()).unwrap_or("")),
+                id: record.get("id").map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())),
+            });
+        }
+
+        Ok(ticks)
+    }
+
+    /// One page of today's matched trades, newest first. Pass the page's
+    /// `next_cursor` as `last_time` to continue further back in the session.
+    /// Consecutive pages overlap on trades sharing the boundary time;
+    /// [`Self::intraday_pages`] drops the repeats.
+    pub async fn get_intraday(&self, symbol: &str, page_size: u32, last_time: Option<&str>) -> Result<Page<TickData>, VciError> {
+        let page_size = page_size.clamp(1, 30_000);
+        let ticks = self.fetch_ticks(symbol, page_size, last_time).await?;
+        let next_cursor = next_intraday_cursor(&ticks, page_size, last_time);
+        Ok(Page { items: ticks, next_cursor, total: None })
+    }
+
+    /// Every matched trade of the session exactly once, page by page back
+    /// from the latest.
+    pub fn intraday_pages<'a>(&'a self, symbol: &'a str, page_size: u32) -> BoxStream<'a, Result<Page<TickData>, VciError>> {
+        let mut seen = HashSet::new();
+        pagination::pages(move |cursor: Option<String>| async move { self.get_intraday(symbol, page_size, cursor.as_deref()).await })
+            .map(move |page| page.map(|mut page| {
+                page.items.retain(|tick| seen.insert((tick.time, tick.price.to_bits(), tick.volume, tick.id.clone())));
+                page
+            }))
+            .boxed()
+    }
+
+    /// Today's session as `interval` bars, built from every page of matched
+    /// trades. Pass a session filter to drop auction and put-through prints.
+    pub async fn session_bars(&self, symbol: &str, interval: Interval, session: Option<&SessionFilter>) -> Result<Vec<Ohlcv>, VciError> {
+        let mut ticks = Vec::new();
+        let mut pages = self.intraday_pages(symbol, 10_000);
+        while let Some(page) = pages.next().await {
+            ticks.extend(page?.items);
+        }
+        Ok(resample::bars_from_ticks(symbol, &ticks, interval, session))
+    }
+
+    /// One price-board request sampling the auction state of `symbols`,
+    /// for [`ImbalanceTracker`](crate::auction::ImbalanceTracker).
+    pub async fn auction_snapshots(&self, symbols: &[String]) -> Result<Vec<AuctionSnapshot>, VciError> {
+        let symbols: Vec<String> = symbols.iter().map(|s| s.to_uppercase()).collect();
+        let time = Utc::now();
+        let rows = self.fetch_price_board(&symbols).await?;
+        Ok(rows.iter().map(|row| parse_auction_snapshot(row, time)).filter(|snapshot| !snapshot.symbol.is_empty()).collect())
+    }
+
+    /// Opening/closing auction data for today: the indicative price while an
+    /// auction is running (from the price board) and the final ATO/ATC prints
+    /// once matched (from the day's matched trades). Fails if either feed
+    /// does; before the open there are simply no prints yet.
+    pub async fn get_auction(&self, symbol: &str) -> Result<AuctionData, VciError> {
+        let symbol = symbol.to_uppercase();
+        let rows = self.fetch_price_board(std::slice::from_ref(&symbol)).await?;
+        let snapshot = rows.first().map(|row| parse_auction_snapshot(row, Utc::now())).ok_or(VciError::NoData)?;
+        let current_session = snapshot.session;
+        let (indicative_price, indicative_volume) = match current_session {
+            Some(_) => (snapshot.price, snapshot.volume),
+            None => (None, None),
+        };
+
+        let today = Utc::now().with_timezone(&vietnam_offset()).date_naive();
+        let ticks: Vec<TickData> = self.fetch_ticks(&symbol, 30_000, None).await?
+            .into_iter()
+            .filter(|tick| tick.time.with_timezone(&vietnam_offset()).date_naive() == today)
+            .collect();
+
+        Ok(AuctionData {
+            symbol,
+            date: today,
+            current_session,
+            indicative_price,
+            indicative_volume,
+            ato: auction::find_auction_print(&ticks, AuctionSession::Ato),
+            atc: auction::find_auction_print(&ticks, AuctionSession::Atc),
+        })
+    }
+
     fn resample_ohlcv(&self, data: Vec<OhlcvData>, interval: &str) -> Result<Vec<OhlcvData>, VciError> {
         if data.is_empty() {
             return Ok(data);
         }
         
         match interval {
+            "5m" | "15m" | "30m" => self.resample_minutes(data, interval),
             "1W" => self.resample_weekly(data),
             "1M" => self.resample_monthly(data),
             _ => Ok(data), // No resampling needed
         }
     }
+
+    /// Buckets the `ONE_MINUTE` bars VCI serves for 5m/15m/30m requests.
+    fn resample_minutes(&self, data: Vec<OhlcvData>, interval: &str) -> Result<Vec<OhlcvData>, VciError> {
+        let interval: Interval = interval.parse().map_err(VciError::InvalidInterval)?;
+        // The listing exchange isn't known here; UPCOM's session is the
+        // widest, so only lunch-break and off-hours bars are dropped
+        let session = SessionFilter::new(Exchange::Upcom);
+        let bars: Vec<Ohlcv> = data.into_iter().map(Ohlcv::from).collect();
+        Ok(resample::resample_intraday(&bars, interval, Some(&session))
+            .into_iter()
+            .map(|bar| OhlcvData {
+                time: bar.time,
+                open: bar.open,
+                high: bar.high,
+                low: bar.low,
+                close: bar.close,
+                volume: bar.volume,
+                symbol: bar.symbol,
+            })
+            .collect())
+    }
     
     fn resample_weekly(&self, data: Vec<OhlcvData>) -> Result<Vec<OhlcvData>, VciError> {
         let mut weekly_data = HashMap::new();
@@ -773,7 +2316,7 @@ impl VciClient {
         }
         
         let mut result: Vec<OhlcvData> = weekly_data.into_values().collect();
-        result.sort_by(|a, b| a.time.cmp(&b.time));
+        result.sort_by_key(|a| a.time);
         Ok(result)
     }
     
@@ -804,7 +2347,7 @@ impl VciClient {
         }
         
         let mut result: Vec<OhlcvData> = monthly_data.into_values().collect();
-        result.sort_by(|a, b| a.time.cmp(&b.time));
+        result.sort_by_key(|a| a.time);
         Ok(result)
     }
     
@@ -835,21 +2378,374 @@ impl VciClient {
     }
 }
 
+/// Reads `en_key` for English when present and non-empty, else `vi_key`.
+fn localized(obj: &Value, vi_key: &str, en_key: &str, language: Language) -> Option<String> {
+    let text = |key: &str| obj.get(key).and_then(|v| v.as_str()).map(str::trim).filter(|s| !s.is_empty());
+    let value = match language {
+        Language::English => text(en_key).or_else(|| text(vi_key)),
+        Language::Vietnamese => text(vi_key),
+    };
+    value.map(str::to_string)
+}
+
+fn parse_events(symbol: &str, events: &[Value]) -> Vec<CorporateEvent> {
+    let text = |event: &Value, key: &str| event.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    events.iter()
+        .map(|event| CorporateEvent {
+            symbol: symbol.to_uppercase(),
+            title: text(event, "eventTitle").unwrap_or_default(),
+            event_code: text(event, "eventListCode"),
+            event_name: text(event, "eventListName"),
+            public_date: text(event, "publicDate"),
+            record_date: text(event, "recordDate"),
+            exright_date: text(event, "exrightDate"),
+            issue_date: text(event, "issueDate"),
+            ratio: event.get("ratio").and_then(|v| v.as_f64()),
+            value: event.get("value").and_then(|v| v.as_f64()),
+        })
+        .collect()
+}
+
+fn board_number(obj: Option<&Value>, key: &str) -> Option<f64> {
+    obj?.get(key).and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse::<f64>().ok())))
+}
+
+/// Builds a quote from one price-board row (`listingInfo` / `matchPrice` / `bidAsk`).
+fn parse_board_quote(row: &Value, time: DateTime<Utc>) -> Option<Quote> {
+    let listing = row.get("listingInfo");
+    let matched = row.get("matchPrice");
+    let bid_ask = row.get("bidAsk");
+    let symbol = listing?.get("symbol")?.as_str()?;
+    let best = |side: &str| bid_ask?.get(side)?.as_array()?.first().and_then(|level| board_number(Some(level), "price"));
+
+    Some(Quote {
+        symbol: symbol.to_uppercase(),
+        time,
+        price: board_number(matched, "matchPrice").or_else(|| board_number(listing, "refPrice"))?,
+        reference_price: board_number(listing, "refPrice"),
+        ceiling_price: board_number(listing, "ceiling"),
+        floor_price: board_number(listing, "floor"),
+        open: board_number(matched, "openPrice"),
+        high: board_number(matched, "highest"),
+        low: board_number(matched, "lowest"),
+        volume: board_number(matched, "accumulatedVolume").unwrap_or(0.0) as u64,
+        value: board_number(matched, "accumulatedValue"),
+        best_bid: best("bidPrices").filter(|&p| p > 0.0),
+        best_ask: best("askPrices").filter(|&p| p > 0.0),
+    })
+}
+
+fn parse_listed_symbol(row: &Value) -> Option<ListedSymbol> {
+    let text = |keys: &[&str]| keys.iter().find_map(|key| row.get(*key).and_then(|v| v.as_str())).filter(|s| !s.is_empty()).map(str::to_string);
+    Some(ListedSymbol {
+        symbol: text(&["symbol"])?.to_uppercase(),
+        exchange: text(&["board", "exchange"]).and_then(|board| board.parse().ok()),
+        security_type: text(&["type"]),
+        organ_name: text(&["organName"]),
+        en_organ_name: text(&["enOrganName"]),
+        isin: text(&["isin", "isinCode", "ISIN"]).map(|isin| isin.to_uppercase()),
+    })
+}
+
+fn parse_auction_snapshot(row: &Value, time: DateTime<Utc>) -> AuctionSnapshot {
+    let match_info = row.get("matchPrice");
+    let listing = row.get("listingInfo");
+    let session = [match_info, listing, Some(row)]
+        .into_iter()
+        .flatten()
+        .find_map(|obj| obj.get("session").and_then(|v| v.as_str()).and_then(AuctionSession::from_code));
+    AuctionSnapshot {
+        symbol: listing.and_then(|l| l.get("symbol")).and_then(|v| v.as_str()).unwrap_or("").to_uppercase(),
+        time,
+        session,
+        price: board_number(match_info, "matchPrice").filter(|&p| p > 0.0),
+        volume: board_number(match_info, "matchVol").map(|v| v as u64),
+        reference_price: board_number(listing, "refPrice"),
+        depth: parse_board_depth(row, time),
+    }
+}
+
+fn parse_board_depth(row: &Value, time: DateTime<Utc>) -> Option<PriceDepth> {
+    let symbol = row.get("listingInfo")?.get("symbol")?.as_str()?;
+    let levels = |side: &str| -> Vec<DepthLevel> {
+        row.get("bidAsk")
+            .and_then(|bid_ask| bid_ask.get(side))
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|level| {
+                let price = board_number(Some(level), "price").filter(|&p| p > 0.0)?;
+                Some(DepthLevel {
+                    price,
+                    volume: board_number(Some(level), "volume").unwrap_or(0.0) as u64,
+                    orders: board_number(Some(level), "orderCount").map(|n| n as u32),
+                })
+            })
+            .collect()
+    };
+    Some(PriceDepth { symbol: symbol.to_uppercase(), time, bids: levels("bidPrices"), asks: levels("askPrices") })
+}
+
+fn parse_index_tick(row: &Value, time: DateTime<Utc>) -> Option<IndexTick> {
+    let row = Some(row);
+    let symbol = row?.get("symbol").or_else(|| row?.get("indexId"))?.as_str()?;
+    Some(IndexTick {
+        symbol: symbol.to_uppercase(),
+        time,
+        value: board_number(row, "price").or_else(|| board_number(row, "indexValue"))?,
+        reference_value: board_number(row, "refPrice").or_else(|| board_number(row, "prevIndexValue")),
+        volume: board_number(row, "totalVolume").or_else(|| board_number(row, "accumulatedVolume")).map(|v| v as u64),
+    })
+}
+
+/// Matched ("totalMatch*") and put-through ("totalDeal*") figures of one
+/// IQ price-history row.
+/// Cursor for the page after `ticks`, or `None` after a short page. The API
+/// pages by trade time in milliseconds; the cursor sits just past the oldest
+/// trade so trades sharing its time are fetched again rather than skipped.
+/// A page entirely within that millisecond steps past it instead.
+fn next_intraday_cursor(ticks: &[TickData], page_size: u32, cursor: Option<&str>) -> Option<String> {
+    if (ticks.len() as u32) < page_size {
+        return None;
+    }
+    let oldest = ticks.iter().map(|tick| tick.time.timestamp_millis()).min()?;
+    let overlapping = (oldest + 1).to_string();
+    if cursor == Some(overlapping.as_str()) {
+        Some(oldest.to_string())
+    } else {
+        Some(overlapping)
+    }
+}
+
+fn parse_volume_breakdown(row: &Value) -> Option<(NaiveDate, VolumeBreakdown)> {
+    let number = |key: &str| {
+        let value = row.get(key)?;
+        value.as_f64().or_else(|| value.as_str()?.replace(',', "").parse().ok())
+    };
+    let date_text = ["tradingDate", "date"].iter().find_map(|key| row.get(*key)?.as_str())?;
+    let date = NaiveDate::parse_from_str(date_text.split('T').next()?, "%Y-%m-%d").ok()?;
+    Some((date, VolumeBreakdown {
+        matched_volume: number("totalMatchVolume")? as u64,
+        matched_value: number("totalMatchValue"),
+        put_through_volume: number("totalDealVolume").unwrap_or(0.0) as u64,
+        put_through_value: number("totalDealValue"),
+    }))
+}
+
+fn parse_board_foreign_room(row: &Value, time: DateTime<Utc>) -> Option<ForeignRoomSnapshot> {
+    let symbol = row.get("listingInfo")?.get("symbol")?.as_str()?;
+    let matched = row.get("matchPrice");
+    let volume = |key: &str| board_number(matched, key).map(|v| v.max(0.0) as u64);
+
+    Some(ForeignRoomSnapshot {
+        symbol: symbol.to_uppercase(),
+        time,
+        current_room: volume("currentRoom")?,
+        total_room: volume("totalRoom").unwrap_or(0),
+        foreign_buy_volume: volume("foreignBuyVolume").unwrap_or(0),
+        foreign_sell_volume: volume("foreignSellVolume").unwrap_or(0),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_merge_series_dedupes_overlap() {
+        let first = serde_json::json!({"symbol": "FPT", "t": [1, 2, 3], "c": [10.0, 11.0, 12.0]});
+        let second = serde_json::json!({"symbol": "FPT", "t": ["3", 4], "c": [12.5, 13.0]});
+        let merged = merge_series(vec![first, second]);
+        assert_eq!(merged["t"].as_array().unwrap().len(), 4);
+        assert_eq!(merged["c"], serde_json::json!([10.0, 11.0, 12.5, 13.0]));
+        assert_eq!(merged["symbol"], "FPT");
+    }
+
+    #[test]
+    fn test_company_query_prunes_sections() {
+        let query = company_query(&[CompanySection::Price, CompanySection::Listing]);
+        assert!(query.contains("TickerPriceInfo") && query.contains("CompanyListingInfo"));
+        assert!(!query.contains("News(") && !query.contains("OrganizationShareHolders"));
+        assert!(query.find("CompanyListingInfo") < query.find("TickerPriceInfo"));
+        assert_eq!(company_query(&CompanySection::ALL).matches("(ticker: $ticker").count(), 6);
+        assert!(!query.contains("$lang"));
+        assert!(company_query(&[CompanySection::News]).starts_with("query Query($ticker: String!, $lang: String!)"));
+    }
+
     #[tokio::test]
     async fn test_vci_client_creation() {
         let client = VciClient::new(true, 6);
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_parse_board_quote() {
+        let row = serde_json::json!({
+            "listingInfo": { "symbol": "fpt", "refPrice": 100000.0, "ceiling": 107000.0, "floor": 93000.0 },
+            "matchPrice": { "matchPrice": 102000.0, "accumulatedVolume": 1500000, "currentRoom": 0, "totalRoom": 700000000 },
+            "bidAsk": { "bidPrices": [{ "price": 101900.0, "volume": 100 }], "askPrices": [{ "price": 0, "volume": 0 }] }
+        });
+        let quote = parse_board_quote(&row, Utc::now()).unwrap();
+        assert_eq!(quote.symbol, "FPT");
+        assert_eq!(quote.change(), Some(2000.0));
+        assert_eq!(quote.best_bid, Some(101900.0));
+        assert_eq!(quote.best_ask, None);
+
+        let room = parse_board_foreign_room(&row, quote.time).unwrap();
+        assert!(room.is_tight(0.0));
+
+        let depth = parse_board_depth(&row, quote.time).unwrap();
+        assert_eq!(depth.bids, vec![DepthLevel { price: 101900.0, volume: 100, orders: None }]);
+        assert!(depth.asks.is_empty() && depth.spread().is_none());
+    }
+
+    #[test]
+    fn test_volume_breakdown_attaches_by_exchange_date() {
+        let row = serde_json::json!({
+            "tradingDate": "2024-06-03T00:00:00",
+            "totalMatchVolume": 1_200_000, "totalMatchValue": 1.2e11,
+            "totalDealVolume": "300,000", "totalDealValue": 3.3e10
+        });
+        let (date, breakdown) = parse_volume_breakdown(&row).unwrap();
+        assert_eq!((breakdown.total_volume(), breakdown.put_through_ratio()), (1_500_000, Some(0.2)));
+
+        // Daily bars are stamped at exchange midnight, the previous UTC day
+        let bar = Ohlcv {
+            time: vietnam_offset().with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap().with_timezone(&Utc),
+            open: 1.0, high: 1.0, low: 1.0, close: 1.0, volume: 1_500_000,
+            symbol: None, breakdown: None, futures: None,
+        };
+        let mut bars = vec![bar];
+        models::attach_breakdowns(&mut bars, &std::collections::BTreeMap::from([(date, breakdown)]));
+        assert_eq!(bars[0].breakdown, Some(breakdown));
+    }
+
+    #[test]
+    fn test_parse_listed_symbol() {
+        let row = serde_json::json!({"symbol": "e1vfvn30", "board": "HSX", "type": "ETF", "organName": "Quỹ ETF DCVFMVN30", "isin": "vn0e1vfvn304"});
+        let listed = parse_listed_symbol(&row).unwrap();
+        assert_eq!((listed.symbol.as_str(), listed.exchange, listed.kind()), ("E1VFVN30", Some(Exchange::Hose), SecurityType::Etf));
+        assert_eq!(listed.isin.as_deref(), Some("VN0E1VFVN304"));
+
+        let warrant = parse_listed_symbol(&serde_json::json!({"symbol": "CFPT2401", "board": "DELISTED", "type": "CW"})).unwrap();
+        assert_eq!((warrant.exchange, warrant.kind(), warrant.isin), (None, SecurityType::CoveredWarrant, None));
+    }
+
+    #[test]
+    fn test_pending_dividend_from_event() {
+        let event = CorporateEvent {
+            symbol: "FPT".to_string(),
+            title: "FPT trả cổ tức bằng tiền 10%".to_string(),
+            event_code: Some("DIV".to_string()),
+            event_name: None,
+            public_date: Some("2024-05-20T00:00:00".to_string()),
+            record_date: Some("2024-06-12".to_string()),
+            exright_date: Some("2024-06-11".to_string()),
+            issue_date: Some("2024-06-26".to_string()),
+            ratio: Some(0.1),
+            value: None,
+        };
+        let today = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let dividend = event.to_pending_dividend(today).unwrap();
+        assert_eq!(dividend.amount_per_share, 1000.0);
+        assert_eq!(dividend.cash_for(500), 500_000.0);
+        assert_eq!(dividend.announcement_date, NaiveDate::from_ymd_opt(2024, 5, 20));
+        assert!(event.to_pending_dividend(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_localized_falls_back_to_vietnamese() {
+        let manager = serde_json::json!({ "positionName": "Chủ tịch HĐQT", "en_PositionName": "" });
+        assert_eq!(localized(&manager, "positionName", "en_PositionName", Language::English).as_deref(), Some("Chủ tịch HĐQT"));
+        let listing = serde_json::json!({ "icbName3": "Phần mềm", "enIcbName3": "Software" });
+        assert_eq!(localized(&listing, "icbName3", "enIcbName3", Language::English).as_deref(), Some("Software"));
+        assert_eq!(localized(&listing, "icbName3", "enIcbName3", Language::Vietnamese).as_deref(), Some("Phần mềm"));
+    }
+
+    #[test]
+    fn test_reconstruct_capital_changes() {
+        let event = |title: &str, code: &str, date: &str, ratio: f64| CorporateEvent {
+            symbol: "HPG".to_string(),
+            title: title.to_string(),
+            event_code: Some(code.to_string()),
+            event_name: None,
+            public_date: None,
+            record_date: None,
+            exright_date: Some(date.to_string()),
+            issue_date: None,
+            ratio: Some(ratio),
+            value: None,
+        };
+        let events = vec![
+            event("Phát hành cổ phiếu trả cổ tức bằng cổ phiếu", "ISS", "2022-06-01", 0.3),
+            event("Niêm yết bổ sung", "AIS", "2022-07-01", 0.3),
+            event("Phát hành riêng lẻ", "ISS", "2023-03-01", 0.25),
+        ];
+
+        let changes = reconstruct_capital_changes(&events, Some(1950.0));
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].kind, CapitalRaiseKind::StockDividend);
+        assert_eq!(changes[1].kind, CapitalRaiseKind::PrivatePlacement);
+        assert!((changes[1].shares_issued.unwrap() - 390.0).abs() < 1e-9);
+        assert!((changes[0].shares_issued.unwrap() - 360.0).abs() < 1e-9);
+
+        let history = CapitalHistory {
+            symbol: "HPG".to_string(),
+            charter_capital: None,
+            outstanding_shares: Some(1950.0),
+            changes,
+        };
+        assert!((history.shares_on(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap()).unwrap() - 1200.0).abs() < 1e-9);
+        assert_eq!(history.shares_on(NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()), Some(1950.0));
+    }
+
     #[tokio::test]
     async fn test_interval_mapping() {
         let client = VciClient::new(false, 6).unwrap();
         assert_eq!(client.get_interval_value("1D").unwrap(), "ONE_DAY");
         assert_eq!(client.get_interval_value("1H").unwrap(), "ONE_HOUR");
         assert!(client.get_interval_value("invalid").is_err());
+        for interval in Interval::ALL {
+            assert_eq!(client.get_interval_value(interval.as_str()).unwrap(), interval.vci_time_frame());
+        }
+
+        // 09:00-09:14 exchange time as one-minute bars
+        let minutes: Vec<OhlcvData> = (0..15)
+            .map(|minute| OhlcvData {
+                time: Utc.with_ymd_and_hms(2024, 6, 3, 2, minute, 0).unwrap(),
+                open: 10.0,
+                high: 11.0,
+                low: 9.0,
+                close: 10.5,
+                volume: 100,
+                symbol: Some("FPT".to_string()),
+            })
+            .collect();
+        let five = client.resample_ohlcv(minutes.clone(), "5m").unwrap();
+        assert_eq!(five.len(), 3);
+        assert!(five.windows(2).all(|pair| (pair[1].time - pair[0].time).num_seconds() == 300));
+        assert_eq!(five[0].volume, 500);
+        assert_eq!(client.resample_ohlcv(minutes, "15m").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_intraday_cursor_overlaps_boundary() {
+        let tick = |millis: i64| TickData { time: DateTime::from_timestamp_millis(millis).unwrap(), price: 10.0, volume: 100, side: TradeSide::Unknown, id: None };
+        let page = [tick(1_717_383_600_500), tick(1_717_383_600_000), tick(1_717_383_600_000)];
+        assert_eq!(next_intraday_cursor(&page, 3, None).as_deref(), Some("1717383600001"));
+        assert_eq!(next_intraday_cursor(&page, 4, None), None);
+
+        // Every trade on the page shares the cursor's millisecond
+        let ties = [tick(1_717_383_600_000), tick(1_717_383_600_000)];
+        assert_eq!(next_intraday_cursor(&ties, 2, Some("1717383600001")).as_deref(), Some("1717383600000"));
+    }
+
+    #[test]
+    fn test_iq_host_follows_base_url() {
+        assert_eq!(VciClient::new(false, 6).unwrap().iq_base_url, DEFAULT_IQ_BASE_URL);
+        let mock = VciClient::builder().base_url("http://127.0.0.1:8080/api/").build().unwrap();
+        assert_eq!(mock.iq_base_url, "http://127.0.0.1:8080/api/iq-insight-service/v1/");
+        let split = VciClient::builder().base_url("http://127.0.0.1:8080/api/").iq_base_url("http://127.0.0.1:9090/").build().unwrap();
+        assert_eq!(split.iq_base_url, "http://127.0.0.1:9090/");
     }
 }
\ No newline at end of file
@@ -0,0 +1,142 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::{vietnam_offset, Ohlcv};
+use crate::ta::{self, Atr};
+
+/// Sessions per year used to annualize daily volatility.
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Daily volatility scaled to a year.
+pub fn annualized(daily: f64) -> f64 {
+    daily * TRADING_DAYS_PER_YEAR.sqrt()
+}
+
+fn valid(bar: &Ohlcv) -> bool {
+    bar.open > 0.0 && bar.high > 0.0 && bar.low > 0.0 && bar.close > 0.0 && bar.high >= bar.low
+}
+
+/// Realized volatility of one session: square root of the summed squared
+/// log returns between consecutive bar closes. `None` with fewer than two bars.
+pub fn realized_volatility(bars: &[Ohlcv]) -> Option<f64> {
+    let closes: Vec<f64> = bars.iter().filter(|bar| valid(bar)).map(|bar| bar.close).collect();
+    if closes.len() < 2 {
+        return None;
+    }
+    let variance: f64 = closes.windows(2).map(|pair| (pair[1] / pair[0]).ln().powi(2)).sum();
+    Some(variance.sqrt())
+}
+
+/// Parkinson range estimator summed over the session's bars, as volatility.
+pub fn parkinson(bars: &[Ohlcv]) -> Option<f64> {
+    let ranges: Vec<f64> = bars.iter().filter(|bar| valid(bar)).map(|bar| (bar.high / bar.low).ln().powi(2)).collect();
+    if ranges.is_empty() {
+        return None;
+    }
+    Some((ranges.iter().sum::<f64>() / (4.0 * std::f64::consts::LN_2)).sqrt())
+}
+
+/// Garman-Klass estimator summed over the session's bars, as volatility.
+/// Uses the open-to-close move as well as the range, so it is less noisy
+/// than Parkinson on bars that trend.
+pub fn garman_klass(bars: &[Ohlcv]) -> Option<f64> {
+    let terms: Vec<f64> = bars.iter()
+        .filter(|bar| valid(bar))
+        .map(|bar| 0.5 * (bar.high / bar.low).ln().powi(2) - (2.0 * std::f64::consts::LN_2 - 1.0) * (bar.close / bar.open).ln().powi(2))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(terms.iter().sum::<f64>().max(0.0).sqrt())
+}
+
+/// Volatility statistics for one exchange-time session of intraday bars.
+/// Estimator values are for the whole session, not per bar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntradayVolatility {
+    pub date: NaiveDate,
+    pub bars: usize,
+    pub realized: Option<f64>,
+    pub parkinson: Option<f64>,
+    pub garman_klass: Option<f64>,
+    /// Wilder ATR of the session's bars as of its last bar, in price units.
+    pub atr: Option<f64>,
+    /// Session high minus low over the open.
+    pub range_pct: Option<f64>,
+}
+
+/// Groups intraday bars (time order) by session and computes each
+/// session's statistics. ATR restarts every session so the overnight gap
+/// is not counted as intraday range.
+pub fn intraday_volatility(bars: &[Ohlcv], atr_period: usize) -> Vec<IntradayVolatility> {
+    let mut sessions: BTreeMap<NaiveDate, Vec<Ohlcv>> = BTreeMap::new();
+    for bar in bars {
+        sessions.entry(bar.time.with_timezone(&vietnam_offset()).date_naive()).or_default().push(bar.clone());
+    }
+    sessions.into_iter()
+        .map(|(date, bars)| {
+            let high = bars.iter().map(|bar| bar.high).fold(f64::MIN, f64::max);
+            let low = bars.iter().map(|bar| bar.low).fold(f64::MAX, f64::min);
+            let open = bars[0].open;
+            IntradayVolatility {
+                date,
+                bars: bars.len(),
+                realized: realized_volatility(&bars),
+                parkinson: parkinson(&bars),
+                garman_klass: garman_klass(&bars),
+                atr: ta::compute(&mut Atr::new(atr_period), &bars).pop().flatten(),
+                range_pct: (open > 0.0).then(|| (high - low) / open),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn bar(day: u32, minute: i64, open: f64, high: f64, low: f64, close: f64) -> Ohlcv {
+        Ohlcv {
+            time: Utc.with_ymd_and_hms(2024, 6, day, 2, 15, 0).unwrap() + Duration::minutes(minute),
+            open,
+            high,
+            low,
+            close,
+            volume: 100,
+            symbol: None,
+            breakdown: None,
+            futures: None,
+        }
+    }
+
+    #[test]
+    fn test_estimators() {
+        let bars = vec![bar(3, 0, 100.0, 102.0, 99.0, 101.0), bar(3, 1, 101.0, 103.0, 100.0, 102.0)];
+        let realized = realized_volatility(&bars).unwrap();
+        assert!((realized - (102.0f64 / 101.0).ln()).abs() < 1e-12);
+
+        let ln_hl = [(102.0f64 / 99.0).ln(), (103.0f64 / 100.0).ln()];
+        let expected = ((ln_hl[0].powi(2) + ln_hl[1].powi(2)) / (4.0 * std::f64::consts::LN_2)).sqrt();
+        assert!((parkinson(&bars).unwrap() - expected).abs() < 1e-12);
+
+        let flat = vec![bar(3, 0, 100.0, 100.0, 100.0, 100.0)];
+        assert_eq!(garman_klass(&flat), Some(0.0));
+        assert_eq!(realized_volatility(&flat), None);
+        assert!((annualized(0.01) - 0.01 * 252f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_intraday_volatility_per_session() {
+        let bars = vec![
+            bar(3, 0, 100.0, 101.0, 99.0, 100.0),
+            bar(3, 1, 100.0, 101.0, 99.0, 100.5),
+            bar(4, 0, 110.0, 111.0, 109.0, 110.0),
+        ];
+        let sessions = intraday_volatility(&bars, 2);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!((sessions[0].bars, sessions[0].atr, sessions[0].range_pct), (2, Some(2.0), Some(0.02)));
+        assert_eq!((sessions[1].date, sessions[1].atr), (NaiveDate::from_ymd_opt(2024, 6, 4).unwrap(), None));
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::text;
+
+#[derive(Debug)]
+pub enum WatchlistError {
+    Io(std::io::Error),
+    Serialization(serde_json::Error),
+    InvalidCsv(String),
+}
+
+impl From<std::io::Error> for WatchlistError {
+    fn from(error: std::io::Error) -> Self {
+        WatchlistError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for WatchlistError {
+    fn from(error: serde_json::Error) -> Self {
+        WatchlistError::Serialization(error)
+    }
+}
+
+/// Header names (normalized) recognized as the symbol column in broker exports.
+const SYMBOL_HEADERS: [&str; 7] = ["symbol", "ticker", "ma ck", "ma chung khoan", "ma", "stock", "code"];
+const NOTE_HEADERS: [&str; 3] = ["note", "ghi chu", "notes"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchItem {
+    pub symbol: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub added: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Watchlist {
+    pub items: Vec<WatchItem>,
+}
+
+impl Watchlist {
+    /// Adds `symbol` unless already present; returns whether it was added.
+    pub fn add(&mut self, symbol: &str, note: Option<&str>) -> bool {
+        let symbol = symbol.trim().to_uppercase();
+        if self.contains(&symbol) {
+            return false;
+        }
+        self.items.push(WatchItem { symbol, note: note.map(str::to_string), added: Utc::now().date_naive() });
+        true
+    }
+
+    pub fn remove(&mut self, symbol: &str) -> bool {
+        let before = self.items.len();
+        self.items.retain(|item| !item.symbol.eq_ignore_ascii_case(symbol));
+        self.items.len() != before
+    }
+
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.items.iter().any(|item| item.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    pub fn symbols(&self) -> Vec<String> {
+        self.items.iter().map(|item| item.symbol.clone()).collect()
+    }
+}
+
+/// Named watchlists, persisted as one JSON document.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Watchlists {
+    pub lists: BTreeMap<String, Watchlist>,
+}
+
+impl Watchlists {
+    /// Loads from `path`; a missing file yields no watchlists.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, WatchlistError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Watchlists::default());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Writes to a temporary file first so a crash never leaves half a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), WatchlistError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn get_or_create(&mut self, name: &str) -> &mut Watchlist {
+        self.lists.entry(name.to_string()).or_default()
+    }
+
+    /// Merges a broker CSV export into the watchlist `name`. Returns how
+    /// many symbols were new.
+    pub fn import_csv(&mut self, name: &str, csv: &str) -> Result<usize, WatchlistError> {
+        let rows = parse_broker_csv(csv)?;
+        let list = self.get_or_create(name);
+        Ok(rows.iter().filter(|(symbol, note)| list.add(symbol, note.as_deref())).count())
+    }
+
+    /// `symbol,note` CSV of the watchlist `name`, empty if it doesn't exist.
+    pub fn export_csv(&self, name: &str) -> String {
+        let mut out = String::from("symbol,note\n");
+        for item in self.lists.get(name).map(|list| list.items.as_slice()).unwrap_or(&[]) {
+            let note = item.note.as_deref().unwrap_or("");
+            let note = if note.contains([',', '"']) { format!("\"{}\"", note.replace('"', "\"\"")) } else { note.to_string() };
+            out.push_str(&format!("{},{}\n", item.symbol, note));
+        }
+        out
+    }
+}
+
+/// Splits one CSV line on `delimiter`, honoring double-quoted fields.
+pub(crate) fn split_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// (symbol, note) rows from a broker watchlist or portfolio export. The
+/// symbol column is found by header name (English or Vietnamese, with or
+/// without diacritics); `,`, `;` and tab delimiters are accepted.
+pub fn parse_broker_csv(csv: &str) -> Result<Vec<(String, Option<String>)>, WatchlistError> {
+    let mut lines = csv.trim_start_matches('\u{feff}').lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next().ok_or_else(|| WatchlistError::InvalidCsv("empty file".to_string()))?;
+    let delimiter = [',', ';', '\t'].into_iter().max_by_key(|&d| header.matches(d).count()).unwrap_or(',');
+
+    let headers: Vec<String> = split_line(header, delimiter).iter().map(|h| text::normalize(h)).collect();
+    let find = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+    let symbol_col = find(&SYMBOL_HEADERS)
+        .ok_or_else(|| WatchlistError::InvalidCsv(format!("no symbol column in header: {}", header)))?;
+    let note_col = find(&NOTE_HEADERS);
+
+    let rows = lines
+        .filter_map(|line| {
+            let fields = split_line(line, delimiter);
+            let symbol = fields.get(symbol_col)?.to_uppercase();
+            let valid = (3..=10).contains(&symbol.len()) && symbol.chars().all(|c| c.is_ascii_alphanumeric());
+            let note = note_col.and_then(|col| fields.get(col)).filter(|note| !note.is_empty()).cloned();
+            valid.then_some((symbol, note))
+        })
+        .collect();
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_broker_csv_and_round_trip() {
+        let csv = "\u{feff}STT;Mã CK;Giá;Ghi chú\n1;fpt;120000;\"Công nghệ; AI\"\n2;VCB;90000;\n3;Tổng;;\n";
+        let mut lists = Watchlists::default();
+        assert_eq!(lists.import_csv("main", csv).unwrap(), 2);
+        assert_eq!(lists.import_csv("main", csv).unwrap(), 0);
+        assert_eq!(lists.lists["main"].items[0].note.as_deref(), Some("Công nghệ; AI"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("watchlists.json");
+        lists.save(&path).unwrap();
+        assert_eq!(Watchlists::load(&path).unwrap(), lists);
+
+        let mut copy = Watchlists::default();
+        copy.import_csv("main", &lists.export_csv("main")).unwrap();
+        assert_eq!(copy.lists["main"].symbols(), vec!["FPT", "VCB"]);
+    }
+}